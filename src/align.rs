@@ -0,0 +1,134 @@
+use eframe::egui::{self, Pos2, Rect};
+use serde::{Deserialize, Serialize};
+
+//
+// tidying helpers for the selected nodes (rects are in graph coordinates, the node
+// position being the top-left corner)
+//
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Grid {
+    // snap nodes moved by dragging or the tools below
+    pub snap: bool,
+    pub size: f32,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            snap: false,
+            size: 20.0,
+        }
+    }
+}
+
+impl Grid {
+    pub fn snap(&self, pos: Pos2) -> Pos2 {
+        if self.size <= 0.0 {
+            return pos;
+        }
+        ((pos.to_vec2() / self.size).round() * self.size).to_pos2()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    // on the same vertical line
+    CenterHorizontally,
+    // on the same horizontal line
+    CenterVertically,
+}
+
+impl Align {
+    pub const ALL: [Align; 6] = [
+        Align::Left,
+        Align::CenterHorizontally,
+        Align::Right,
+        Align::Top,
+        Align::CenterVertically,
+        Align::Bottom,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Align::Left => "Align left",
+            Align::Right => "Align right",
+            Align::Top => "Align top",
+            Align::Bottom => "Align bottom",
+            Align::CenterHorizontally => "Align centers horizontally",
+            Align::CenterVertically => "Align centers vertically",
+        }
+    }
+}
+
+// new positions in the order of `rects` (relative to the outermost node, or the average center)
+pub fn align(rects: &[Rect], align: Align) -> Vec<Pos2> {
+    let n = rects.len().max(1) as f32;
+    let min_x = rects.iter().map(|r| r.left()).fold(f32::INFINITY, f32::min);
+    let max_x = rects
+        .iter()
+        .map(|r| r.right())
+        .fold(f32::NEG_INFINITY, f32::max);
+    let min_y = rects.iter().map(|r| r.top()).fold(f32::INFINITY, f32::min);
+    let max_y = rects
+        .iter()
+        .map(|r| r.bottom())
+        .fold(f32::NEG_INFINITY, f32::max);
+    let center = rects
+        .iter()
+        .fold(egui::Vec2::ZERO, |sum, r| sum + r.center().to_vec2())
+        / n;
+    rects
+        .iter()
+        .map(|rect| match align {
+            Align::Left => egui::pos2(min_x, rect.top()),
+            Align::Right => egui::pos2(max_x - rect.width(), rect.top()),
+            Align::Top => egui::pos2(rect.left(), min_y),
+            Align::Bottom => egui::pos2(rect.left(), max_y - rect.height()),
+            Align::CenterHorizontally => egui::pos2(center.x - rect.width() / 2.0, rect.top()),
+            Align::CenterVertically => egui::pos2(rect.left(), center.y - rect.height() / 2.0),
+        })
+        .collect()
+}
+
+// equal gaps between neighbours along the axis, keeping the first and the last in place
+pub fn distribute(rects: &[Rect], horizontally: bool) -> Vec<Pos2> {
+    let mut positions: Vec<Pos2> = rects.iter().map(|rect| rect.min).collect();
+    if rects.len() < 3 {
+        return positions;
+    }
+    let start = |rect: &Rect| {
+        if horizontally {
+            rect.left()
+        } else {
+            rect.top()
+        }
+    };
+    let length = |rect: &Rect| {
+        if horizontally {
+            rect.width()
+        } else {
+            rect.height()
+        }
+    };
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    order.sort_by(|&a, &b| start(&rects[a]).total_cmp(&start(&rects[b])));
+    let (first, last) = (&rects[order[0]], &rects[order[order.len() - 1]]);
+    let span = start(last) + length(last) - start(first);
+    let gap = (span - rects.iter().map(length).sum::<f32>()) / (rects.len() - 1) as f32;
+    let mut next = start(first);
+    for index in order {
+        if horizontally {
+            positions[index].x = next;
+        } else {
+            positions[index].y = next;
+        }
+        next += length(&rects[index]) + gap;
+    }
+    positions
+}
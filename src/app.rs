@@ -1,144 +1,379 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    process::{Child, Command},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use eframe::egui::{self, DragValue, TextStyle};
+use eframe::egui::{
+    self,
+    plot::{Line, Plot, PlotPoints},
+    DragValue, TextStyle,
+};
 use egui_extras::{Size, TableBuilder};
 use egui_node_graph::*;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "jack")]
+use crate::jack_backend::JackBackend;
 use crate::{
+    align::{self, Align, Grid},
+    backend::Backend,
     channel::ChannelMessage,
-    pipewire_wrapper::{PipewireObject, PipewireWrapper},
+    confirm::{self, Confirmation},
+    error::PwError,
+    export::{self, SvgNode, SvgWire},
+    fuzzy,
+    history::{LinkAction, LinkHistory},
+    journal::JournalReader,
+    latency::{self, DEFAULT_QUANTUM},
+    log::{LogLevel, LOGS},
+    metrics::{self, RateMeter, METRICS},
+    mpris::{MediaCommand, MediaPlayer, MprisWatcher},
+    network::{NetworkKind, NetworkProtocol, NetworkSetup},
+    path_test::{self, Verdict},
+    pipewire_registry::{PipewireRegistry, RegistryEvent},
+    pipewire_wrapper::{
+        AudioFormat, DeviceProfiles, DeviceRoutes, LinkFlags, PipewireBackend, PipewireObject,
+        PipewireState, PipewireWrapper, VideoFormats, VirtualDevice, AUDIO_FORMATS,
+    },
+    preset::{self, Preset, PresetLink},
+    process::{self, Signal},
+    props::{PropKey, PropStore},
+    recording,
+    scope::{self, SampleRing},
+    session::SessionRecorder,
+    session_manager,
+    share::{self, Redaction, ShareHost, ShareViewer},
+    snapshot::{self, ChangeKind, Snapshot},
+    theme::{self, PortColors, Theme, ThemeMode},
+    timeline::{self, Action, Timeline},
+    toast::Toasts,
+    tone::{self, ToneSettings, Waveform},
+    troubleshoot::{self, Sandbox, SocketStatus},
+    tutorial::{self, FakeConnection},
 };
+use pipewire::{
+    prelude::ReadableDict,
+    registry::{GlobalObject, Permission},
+    spa::Direction,
+    types::ObjectType,
+};
+
+// bars, panels and windows of the app by family (all `impl PipewireGraphApp`)
+mod clients_window;
+mod confirmation_window;
+mod connection_window;
+mod core_window;
+mod diagnostics;
+mod latency_window;
+mod link_window;
+mod list_view;
+mod menu_bar;
+mod mixer_window;
+mod object_window;
+mod panels;
+mod presets_window;
+mod recording_window;
+mod scope_window;
+mod selection_toolbar;
+mod session_manager_window;
+mod share_window;
+mod snapshots_window;
+mod stream_format_window;
+mod theme_window;
+mod tutorial_window;
+mod view_bar;
+mod virtual_device_window;
 
-// ========= First, define your user data types =============
+//
+// user data types of the editor graph (see `egui_node_graph`)
+//
 
-/// The NodeData holds a custom data struct inside each node. It's useful to
-/// store additional information that doesn't live in parameters. For this
-/// example, the node data stores the template (i.e. the "type") of the node.
+/// Data of each editor node: the pipewire global it mirrors (or the template it was created
+/// from) and what its ui needs.
 #[derive(Serialize, Deserialize)]
-pub struct MyNodeData {
-    template: MyNodeTemplate,
+pub struct GraphNodeData {
+    template: GraphNodeTemplate,
+    /// global id of the corresponding pipewire node (only for `GraphNodeTemplate::PipewireNode`)
+    pipewire_id: Option<u32>,
+    /// stream node produced by this app itself (e.g. test tone)
+    own_stream: bool,
+    /// device owning the pipewire node (for profile switching)
+    device_id: Option<u32>,
+    /// `card.profile.device` of the pipewire node (for route switching)
+    route_device: Option<i32>,
+    /// `api.bluez5.codec` of a bluetooth node
+    bluez5_codec: Option<String>,
+    /// whether we have write permission on the pipewire node
+    writable: bool,
+    /// whether the close button may destroy the pipewire node (W and X permissions)
+    destroyable: bool,
+    /// `media.class` of the pipewire node (for the titlebar color)
+    media_class: Option<String>,
+    /// application of the pipewire node (for the titlebar color when grouping by client)
+    client: Option<String>,
+    /// starred by the user (always expanded)
+    pinned: bool,
+    /// ports left out while other nodes are pinned
+    collapsed: bool,
+    /// kept where the user put it by the layout and graph rebuilds
+    position_pinned: bool,
+    /// device or client the node is boxed with (the group itself for `GraphNodeTemplate::NodeGroup`)
+    group: Option<NodeGroup>,
+    /// `application.process.id` and `application.process.binary` of a client's node
+    process: Option<(u32, Option<String>)>,
+    /// API of the client behind a stream node
+    client_api: Option<ClientApi>,
+    /// sends or receives audio over the network
+    network: Option<NetworkProtocol>,
 }
 
-/// `DataType`s are what defines the possible range of connections when
-/// attaching two ports together. The graph UI will make sure to not allow
-/// attaching incompatible datatypes.
-#[derive(PartialEq, Eq, Serialize, Deserialize)]
-pub enum MyDataType {
-    Scalar,
-    Vec2,
+/// Owner of several pipewire nodes (e.g. the outputs of an HDMI card).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum NodeGroup {
+    Device(u32),
+    Client(u32),
 }
 
-/// In the graph, input parameters can optionally have a constant value. This
-/// value can be directly edited in a widget inside the node itself.
-///
-/// There will usually be a correspondence between DataTypes and ValueTypes. But
-/// this library makes no attempt to check this consistency. For instance, it is
-/// up to the user code in this example to make sure no parameter is created
-/// with a DataType of Scalar and a ValueType of Vec2.
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
-pub enum MyValueType {
-    Vec2 { value: egui::Vec2 },
-    Scalar { value: f32 },
-}
+impl NodeGroup {
+    // devices first since their nodes are owned by the session manager client
+    fn of(object: &GlobalObject<PropStore>) -> Option<Self> {
+        let client_id = || {
+            object
+                .props
+                .as_ref()?
+                .get_key(PropKey::ClientId)?
+                .parse()
+                .ok()
+        };
+        PipewireObject::get_device_id(object)
+            .map(NodeGroup::Device)
+            .or_else(|| client_id().map(NodeGroup::Client))
+    }
 
-impl Default for MyValueType {
-    fn default() -> Self {
-        // NOTE: This is just a dummy `Default` implementation. The library
-        // requires it to circumvent some internal borrow checker issues.
-        Self::Scalar { value: 0.0 }
+    fn label(self, state: &PipewireState) -> String {
+        let (id, keys) = match self {
+            NodeGroup::Device(id) => (id, [PropKey::DeviceDescription, PropKey::DeviceName]),
+            NodeGroup::Client(id) => (id, [PropKey::AppName, PropKey::ClientName]),
+        };
+        state
+            .global_objects
+            .get(&id)
+            .and_then(|object| {
+                let props = object.props.as_ref()?;
+                keys.iter().find_map(|&key| props.get_key(key))
+            })
+            .map_or_else(|| format!("{:?}", self), str::to_owned)
     }
 }
 
-impl MyValueType {
-    /// Tries to downcast this value type to a vector
-    pub fn try_to_vec2(self) -> anyhow::Result<egui::Vec2> {
-        if let MyValueType::Vec2 { value } = self {
-            Ok(value)
-        } else {
-            anyhow::bail!("Invalid cast from {:?} to vec2", self)
-        }
+/// Media type of a port. The editor only connects ports of the same type.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortType {
+    Audio,
+    Midi,
+    Video,
+    Other,
+}
+
+/// Input parameters never hold a value since connections are the only thing
+/// ports carry (the library still requires a value type).
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum PortValue {
+    /// pipewire input port which doesn't hold any value
+    #[default]
+    Port,
+}
+
+/// Kinds of editor nodes. The creatable ones are offered in the node finder popup.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum GraphNodeTemplate {
+    /// null-audio-sink as an output device
+    VirtualSink,
+    /// null-audio-sink passing whatever is linked to its inputs on to its outputs
+    Loopback,
+    /// null-audio-sink as an input device (silent unless something is linked to it)
+    NullSource,
+    /// playback stream of this app producing a test signal
+    ToneGenerator,
+    /// node mirroring a pipewire global (not offered in the node finder)
+    PipewireNode,
+    /// collapsed device/client standing in for its nodes (not offered in the node finder)
+    NodeGroup,
+}
+
+impl GraphNodeTemplate {
+    // what to request from the pipewire thread (the editor node only appears via the registry)
+    fn virtual_device(self, name: String) -> Option<VirtualDevice> {
+        let (source, duplex) = match self {
+            GraphNodeTemplate::VirtualSink => (false, false),
+            GraphNodeTemplate::Loopback => (false, true),
+            GraphNodeTemplate::NullSource => (true, false),
+            GraphNodeTemplate::ToneGenerator
+            | GraphNodeTemplate::PipewireNode
+            | GraphNodeTemplate::NodeGroup => return None,
+        };
+        Some(VirtualDevice {
+            name,
+            description: String::new(),
+            source,
+            duplex,
+            channels: CHANNEL_MAPS[1].1.to_owned(),
+            linger: false,
+        })
     }
 
-    /// Tries to downcast this value type to a scalar
-    pub fn try_to_scalar(self) -> anyhow::Result<f32> {
-        if let MyValueType::Scalar { value } = self {
-            Ok(value)
-        } else {
-            anyhow::bail!("Invalid cast from {:?} to scalar", self)
+    fn node_name_prefix(self) -> &'static str {
+        match self {
+            GraphNodeTemplate::VirtualSink => "virtual_sink",
+            GraphNodeTemplate::Loopback => "loopback",
+            GraphNodeTemplate::NullSource => "null_source",
+            GraphNodeTemplate::ToneGenerator => "tone_generator",
+            GraphNodeTemplate::PipewireNode => "node",
+            GraphNodeTemplate::NodeGroup => "group",
         }
     }
 }
 
-/// NodeTemplate is a mechanism to define node templates. It's what the graph
-/// will display in the "new node" popup. The user code needs to tell the
-/// library how to convert a NodeTemplate into a Node.
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub enum MyNodeTemplate {
-    MakeVector,
-    MakeScalar,
-    AddScalar,
-    SubtractScalar,
-    VectorTimesScalar,
-    AddVector,
-    SubtractVector,
-}
-
-/// The response type is used to encode side-effects produced when drawing a
-/// node in the graph. Most side-effects (creating new nodes, deleting existing
-/// nodes, handling connections...) are already handled by the library, but this
-/// mechanism allows creating additional side effects from user code.
+/// Actions requested by the widgets inside nodes, handled after the graph is drawn
+/// (connections and node deletion are handled by the editor itself).
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum MyResponse {
-    SetActiveNode(NodeId),
-    ClearActiveNode,
+pub enum GraphNodeResponse {
+    /// link all matching channels from the first node's outputs to the second node's inputs
+    ConnectNodes(NodeId, NodeId),
+    /// destroy the pipewire node itself
+    StopNode(NodeId),
+    /// switch device profile by index
+    SetDeviceProfile(u32, i32),
+    /// switch device route (device, route index, card profile device)
+    SetDeviceRoute(u32, i32, i32),
+    /// set route volume in percent (cubic scale like pavucontrol)
+    SetDeviceRouteVolume(u32, i32, i32, u32),
+    /// move a stream to another sink (stream, target or `None` for the default sink)
+    SetStreamTarget(u32, Option<u32>),
+    /// capture the pipewire node into a WAV file
+    Record(u32),
+    StopRecording,
+    /// change the signal of a tone generator node
+    SetTone(u32, ToneSettings),
+    /// star or unstar the pipewire node
+    TogglePin(u32),
+    /// lock or unlock the position of the pipewire node
+    TogglePinPosition(u32),
+    /// expand or collapse an unpinned pipewire node
+    SetExpanded(u32, bool),
+    /// bring back the nodes of a collapsed group
+    ExpandGroup(NodeGroup),
+    /// play/pause/skip the media player of a stream node
+    MediaControl(u32, MediaCommand),
+    /// stop the client process of a node
+    SignalProcess(u32, Signal),
+    /// destroy all the links of the node's ports (undone together)
+    DisconnectAll(NodeId),
 }
 
-/// The graph 'global' state. This state struct is passed around to the node and
-/// parameter drawing callbacks. The contents of this struct are entirely up to
-/// the user. For this example, we use it to keep track of the 'active' node.
+/// State shared with the node and port drawing callbacks.
 #[derive(Default, Serialize, Deserialize)]
-pub struct MyGraphState {
-    pub active_node: Option<NodeId>,
+pub struct GraphUserState {
+    /// copied from `PipewireState` every frame since node ui cannot access it
+    #[serde(skip)]
+    pub device_profiles: BTreeMap<u32, DeviceProfiles>,
+    #[serde(skip)]
+    pub device_routes: BTreeMap<u32, DeviceRoutes>,
+    /// formats of the first video port per node
+    #[serde(skip)]
+    pub video_formats: BTreeMap<u32, VideoFormats>,
+    /// `target.object` of streams in the "default" metadata
+    #[serde(skip)]
+    pub stream_targets: BTreeMap<u32, String>,
+    /// sinks a stream can be moved to as (id, `object.serial`, label)
+    #[serde(skip)]
+    pub sinks: Vec<(u32, Option<String>, String)>,
+    /// pipewire node being recorded
+    #[serde(skip)]
+    pub recording: Option<u32>,
+    /// tone generator nodes as node.name and current settings
+    #[serde(skip)]
+    pub tone_generators: BTreeMap<u32, (String, ToneSettings)>,
+    /// MPRIS players of stream nodes (while the media controls are on)
+    #[serde(skip)]
+    pub media_players: BTreeMap<u32, MediaPlayer>,
+    /// port names of collapsed nodes (shown on hover)
+    #[serde(skip)]
+    pub collapsed_ports: BTreeMap<u32, Vec<String>>,
+    /// whether nodes other than the pinned ones are collapsed
+    #[serde(skip)]
+    pub collapse_unpinned: bool,
+    /// copied from `ExtraState` every frame
+    pub color_by_client: bool,
+    /// copied from `Theme::wire_width` every frame (width of single channel wires)
+    pub wire_width: f32,
+    /// channels of linked output ports carrying more than one (see `port_channels`)
+    #[serde(skip)]
+    pub output_channels: HashMap<OutputId, u32>,
+    /// default sink and its upstream nodes in the monitor mode (the others are dimmed)
+    #[serde(skip)]
+    pub highlighted_nodes: Option<BTreeSet<u32>>,
+    /// nodes of the loop closed by the links waiting for the feedback confirmation
+    #[serde(skip)]
+    pub feedback_loop: Vec<u32>,
+    /// node found by the Find node window while it blinks
+    #[serde(skip)]
+    pub flashed_node: Option<NodeId>,
+    #[serde(skip)]
+    pub restricted: bool,
+    pub port_colors: PortColors,
 }
 
-// =========== Then, you need to implement some traits ============
+//
+// `egui_node_graph` traits
+//
 
 // A trait for the data types, to tell the library how to display them
-impl DataTypeTrait<MyGraphState> for MyDataType {
-    fn data_type_color(&self, _user_state: &mut MyGraphState) -> egui::Color32 {
+impl DataTypeTrait<GraphUserState> for PortType {
+    fn data_type_color(&self, user_state: &mut GraphUserState) -> egui::Color32 {
+        let port_colors = &user_state.port_colors;
         match self {
-            MyDataType::Scalar => egui::Color32::from_rgb(38, 109, 211),
-            MyDataType::Vec2 => egui::Color32::from_rgb(238, 207, 109),
+            PortType::Audio => theme::rgb(port_colors.audio),
+            PortType::Midi => theme::rgb(port_colors.midi),
+            PortType::Video => theme::rgb(port_colors.video),
+            PortType::Other => theme::rgb(port_colors.other),
         }
     }
 
     fn name(&self) -> Cow<'_, str> {
         match self {
-            MyDataType::Scalar => Cow::Borrowed("scalar"),
-            MyDataType::Vec2 => Cow::Borrowed("2d vector"),
+            PortType::Audio => Cow::Borrowed("audio"),
+            PortType::Midi => Cow::Borrowed("midi"),
+            PortType::Video => Cow::Borrowed("video"),
+            PortType::Other => Cow::Borrowed("other"),
         }
     }
 }
 
 // A trait for the node kinds, which tells the library how to build new nodes
 // from the templates in the node finder
-impl NodeTemplateTrait for MyNodeTemplate {
-    type NodeData = MyNodeData;
-    type DataType = MyDataType;
-    type ValueType = MyValueType;
-    type UserState = MyGraphState;
+impl NodeTemplateTrait for GraphNodeTemplate {
+    type NodeData = GraphNodeData;
+    type DataType = PortType;
+    type ValueType = PortValue;
+    type UserState = GraphUserState;
 
     fn node_finder_label(&self, _user_state: &mut Self::UserState) -> Cow<'_, str> {
         Cow::Borrowed(match self {
-            MyNodeTemplate::MakeVector => "New vector",
-            MyNodeTemplate::MakeScalar => "New scalar",
-            MyNodeTemplate::AddScalar => "Scalar add",
-            MyNodeTemplate::SubtractScalar => "Scalar subtract",
-            MyNodeTemplate::AddVector => "Vector add",
-            MyNodeTemplate::SubtractVector => "Vector subtract",
-            MyNodeTemplate::VectorTimesScalar => "Vector times scalar",
+            GraphNodeTemplate::VirtualSink => "Virtual Sink",
+            GraphNodeTemplate::Loopback => "Loopback",
+            GraphNodeTemplate::NullSource => "Null Source",
+            GraphNodeTemplate::ToneGenerator => "Tone Generator",
+            GraphNodeTemplate::PipewireNode => "PipeWire node",
+            GraphNodeTemplate::NodeGroup => "Node group",
         })
     }
 
@@ -149,154 +384,74 @@ impl NodeTemplateTrait for MyNodeTemplate {
     }
 
     fn user_data(&self, _user_state: &mut Self::UserState) -> Self::NodeData {
-        MyNodeData { template: *self }
+        GraphNodeData {
+            template: *self,
+            pipewire_id: None,
+            own_stream: false,
+            device_id: None,
+            route_device: None,
+            bluez5_codec: None,
+            writable: true,
+            destroyable: false,
+            media_class: None,
+            client: None,
+            pinned: false,
+            collapsed: false,
+            position_pinned: false,
+            group: None,
+            process: None,
+            client_api: None,
+            network: None,
+        }
     }
 
     fn build_node(
         &self,
-        graph: &mut Graph<Self::NodeData, Self::DataType, Self::ValueType>,
+        _graph: &mut Graph<Self::NodeData, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
-        node_id: NodeId,
+        _node_id: NodeId,
     ) {
-        // The nodes are created empty by default. This function needs to take
-        // care of creating the desired inputs and outputs based on the template
-
-        // We define some closures here to avoid boilerplate. Note that this is
-        // entirely optional.
-        let input_scalar = |graph: &mut MyGraph, name: &str| {
-            graph.add_input_param(
-                node_id,
-                name.to_string(),
-                MyDataType::Scalar,
-                MyValueType::Scalar { value: 0.0 },
-                InputParamKind::ConnectionOrConstant,
-                true,
-            );
-        };
-        let input_vector = |graph: &mut MyGraph, name: &str| {
-            graph.add_input_param(
-                node_id,
-                name.to_string(),
-                MyDataType::Vec2,
-                MyValueType::Vec2 {
-                    value: egui::vec2(0.0, 0.0),
-                },
-                InputParamKind::ConnectionOrConstant,
-                true,
-            );
-        };
-
-        let output_scalar = |graph: &mut MyGraph, name: &str| {
-            graph.add_output_param(node_id, name.to_string(), MyDataType::Scalar);
-        };
-        let output_vector = |graph: &mut MyGraph, name: &str| {
-            graph.add_output_param(node_id, name.to_string(), MyDataType::Vec2);
-        };
-
-        match self {
-            MyNodeTemplate::AddScalar => {
-                // The first input param doesn't use the closure so we can comment
-                // it in more detail.
-                graph.add_input_param(
-                    node_id,
-                    // This is the name of the parameter. Can be later used to
-                    // retrieve the value. Parameter names should be unique.
-                    "A".into(),
-                    // The data type for this input. In this case, a scalar
-                    MyDataType::Scalar,
-                    // The value type for this input. We store zero as default
-                    MyValueType::Scalar { value: 0.0 },
-                    // The input parameter kind. This allows defining whether a
-                    // parameter accepts input connections and/or an inline
-                    // widget to set its value.
-                    InputParamKind::ConnectionOrConstant,
-                    true,
-                );
-                input_scalar(graph, "B");
-                output_scalar(graph, "out");
-            }
-            MyNodeTemplate::SubtractScalar => {
-                input_scalar(graph, "A");
-                input_scalar(graph, "B");
-                output_scalar(graph, "out");
-            }
-            MyNodeTemplate::VectorTimesScalar => {
-                input_scalar(graph, "scalar");
-                input_vector(graph, "vector");
-                output_vector(graph, "out");
-            }
-            MyNodeTemplate::AddVector => {
-                input_vector(graph, "v1");
-                input_vector(graph, "v2");
-                output_vector(graph, "out");
-            }
-            MyNodeTemplate::SubtractVector => {
-                input_vector(graph, "v1");
-                input_vector(graph, "v2");
-                output_vector(graph, "out");
-            }
-            MyNodeTemplate::MakeVector => {
-                input_scalar(graph, "x");
-                input_scalar(graph, "y");
-                output_vector(graph, "out");
-            }
-            MyNodeTemplate::MakeScalar => {
-                input_scalar(graph, "value");
-                output_scalar(graph, "out");
-            }
-        }
+        // ports are added as they appear in the registry
+        // (template nodes are replaced by the created pipewire node, see `create_from_template`)
     }
 }
 
-pub struct AllMyNodeTemplates;
+// empty while creating objects is not possible (restricted mode, tutorial sandbox)
+pub struct AllMyNodeTemplates {
+    enabled: bool,
+}
+
 impl NodeTemplateIter for AllMyNodeTemplates {
-    type Item = MyNodeTemplate;
+    type Item = GraphNodeTemplate;
 
     fn all_kinds(&self) -> Vec<Self::Item> {
-        // This function must return a list of node kinds, which the node finder
-        // will use to display it to the user. Crates like strum can reduce the
-        // boilerplate in enumerating all variants of an enum.
+        if !self.enabled {
+            return vec![];
+        }
         vec![
-            MyNodeTemplate::MakeScalar,
-            MyNodeTemplate::MakeVector,
-            MyNodeTemplate::AddScalar,
-            MyNodeTemplate::SubtractScalar,
-            MyNodeTemplate::AddVector,
-            MyNodeTemplate::SubtractVector,
-            MyNodeTemplate::VectorTimesScalar,
+            GraphNodeTemplate::VirtualSink,
+            GraphNodeTemplate::Loopback,
+            GraphNodeTemplate::NullSource,
+            GraphNodeTemplate::ToneGenerator,
         ]
     }
 }
 
-impl WidgetValueTrait for MyValueType {
-    type Response = MyResponse;
-    type UserState = MyGraphState;
-    type NodeData = MyNodeData;
+impl WidgetValueTrait for PortValue {
+    type Response = GraphNodeResponse;
+    type UserState = GraphUserState;
+    type NodeData = GraphNodeData;
     fn value_widget(
         &mut self,
         param_name: &str,
         _node_id: NodeId,
         ui: &mut egui::Ui,
-        _user_state: &mut MyGraphState,
-        _node_data: &MyNodeData,
-    ) -> Vec<MyResponse> {
-        // This trait is used to tell the library which UI to display for the
-        // inline parameter widgets.
+        _user_state: &mut GraphUserState,
+        _node_data: &GraphNodeData,
+    ) -> Vec<GraphNodeResponse> {
         match self {
-            MyValueType::Vec2 { value } => {
+            PortValue::Port => {
                 ui.label(param_name);
-                ui.horizontal(|ui| {
-                    ui.label("x");
-                    ui.add(DragValue::new(&mut value.x));
-                    ui.label("y");
-                    ui.add(DragValue::new(&mut value.y));
-                });
-            }
-            MyValueType::Scalar { value } => {
-                ui.horizontal(|ui| {
-                    ui.label(param_name);
-                    ui.add(DragValue::new(value));
-                });
             }
         }
         // This allows you to return your responses from the inline widgets.
@@ -304,507 +459,3554 @@ impl WidgetValueTrait for MyValueType {
     }
 }
 
-impl UserResponseTrait for MyResponse {}
-impl NodeDataTrait for MyNodeData {
-    type Response = MyResponse;
-    type UserState = MyGraphState;
-    type DataType = MyDataType;
-    type ValueType = MyValueType;
+impl UserResponseTrait for GraphNodeResponse {}
+impl NodeDataTrait for GraphNodeData {
+    type Response = GraphNodeResponse;
+    type UserState = GraphUserState;
+    type DataType = PortType;
+    type ValueType = PortValue;
 
     // This method will be called when drawing each node. This allows adding
-    // extra ui elements inside the nodes. In this case, we create an "active"
-    // button which introduces the concept of having an active node in the
-    // graph. This is done entirely from user code with no modifications to the
-    // node graph library.
+    // extra ui elements inside the nodes.
     fn bottom_ui(
         &self,
         ui: &mut egui::Ui,
         node_id: NodeId,
-        _graph: &Graph<MyNodeData, MyDataType, MyValueType>,
+        graph: &Graph<GraphNodeData, PortType, PortValue>,
         user_state: &mut Self::UserState,
-    ) -> Vec<NodeResponse<MyResponse, MyNodeData>>
+    ) -> Vec<NodeResponse<GraphNodeResponse, GraphNodeData>>
     where
-        MyResponse: UserResponseTrait,
+        GraphNodeResponse: UserResponseTrait,
     {
-        // This logic is entirely up to the user. In this case, we check if the
-        // current node we're drawing is the active one, by comparing against
-        // the value stored in the global user state, and draw different button
-        // UIs based on that.
-
-        let mut responses = vec![];
-        let is_active = user_state
-            .active_node
-            .map(|id| id == node_id)
-            .unwrap_or(false);
-
-        // Pressing the button will emit a custom user response to either set,
-        // or clear the active node. These responses do nothing by themselves,
-        // the library only makes the responses available to you after the graph
-        // has been drawn. See below at the update method for an example.
-        if !is_active {
-            if ui.button("👁 Set active").clicked() {
-                responses.push(NodeResponse::User(MyResponse::SetActiveNode(node_id)));
+        match self.template {
+            GraphNodeTemplate::PipewireNode => {
+                pipewire_node_bottom_ui(ui, node_id, graph, user_state)
+            }
+            GraphNodeTemplate::NodeGroup => match self.group {
+                Some(group) if ui.button("⏵ Expand").clicked() => {
+                    vec![NodeResponse::User(GraphNodeResponse::ExpandGroup(group))]
+                }
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    fn titlebar_color(
+        &self,
+        ui: &egui::Ui,
+        node_id: NodeId,
+        _graph: &Graph<GraphNodeData, PortType, PortValue>,
+        user_state: &mut Self::UserState,
+    ) -> Option<egui::Color32> {
+        if user_state.flashed_node == Some(node_id) {
+            return Some(ui.visuals().selection.bg_fill);
+        }
+        if !matches!(self.template, GraphNodeTemplate::PipewireNode) {
+            return None;
+        }
+        if self
+            .pipewire_id
+            .map_or(false, |id| user_state.feedback_loop.contains(&id))
+        {
+            return Some(egui::Color32::from_rgb(180, 40, 40));
+        }
+        if let Some(highlighted) = &user_state.highlighted_nodes {
+            if !self
+                .pipewire_id
+                .map_or(false, |id| highlighted.contains(&id))
+            {
+                return Some(ui.visuals().faint_bg_color);
             }
+        }
+        let hue = if user_state.color_by_client {
+            client_hue(self.client.as_deref()?)
         } else {
-            let button =
-                egui::Button::new(egui::RichText::new("👁 Active").color(egui::Color32::BLACK))
-                    .fill(egui::Color32::GOLD);
+            media_class_hue(self.media_class.as_deref())
+        };
+        // keep the default title text readable
+        let value = if ui.visuals().dark_mode { 0.35 } else { 0.85 };
+        Some(egui::color::Hsva::new(hue, 0.5, value, 1.0).into())
+    }
+
+    // the close button destroys the pipewire node (see `delete_pipewire_node`)
+    fn can_delete(
+        &self,
+        _node_id: NodeId,
+        _graph: &Graph<GraphNodeData, PortType, PortValue>,
+        user_state: &mut Self::UserState,
+    ) -> bool {
+        self.pipewire_id.is_some() && self.destroyable && !user_state.restricted
+    }
+
+    // thicker wires for more channels and dashed ones for video
+    fn wire_style(
+        &self,
+        output: OutputId,
+        _input: InputId,
+        graph: &Graph<GraphNodeData, PortType, PortValue>,
+        color: egui::Color32,
+        user_state: &mut Self::UserState,
+    ) -> WireStyle {
+        let channels = user_state
+            .output_channels
+            .get(&output)
+            .copied()
+            .unwrap_or(1);
+        WireStyle {
+            color,
+            width: (user_state.wire_width * (channels.max(1) as f32).sqrt()).min(16.0),
+            dashed: graph[output].typ == PortType::Video,
+        }
+    }
+}
+
+// node-level "connect all matching channels" gesture
+fn pipewire_node_bottom_ui(
+    ui: &mut egui::Ui,
+    node_id: NodeId,
+    graph: &EditorGraph,
+    user_state: &GraphUserState,
+) -> Vec<NodeResponse<GraphNodeResponse, GraphNodeData>> {
+    let mut responses = vec![];
+    let pin_row = ui.scope(|ui| pin_ui(ui, &graph[node_id].user_data, user_state));
+    if let Some(response) = pin_row.inner {
+        responses.push(NodeResponse::User(response));
+    }
+    // node actions on right click of the pin row
+    pin_row
+        .response
+        .interact(egui::Sense::click())
+        .context_menu(|ui| {
+            let linked = graph.iter_connections().any(|(input, output)| {
+                graph.get_input(input).node == node_id || graph.get_output(output).node == node_id
+            });
+            let button = egui::Button::new("Disconnect all");
+            if ui.add_enabled(linked, button).clicked() {
+                responses.push(NodeResponse::User(GraphNodeResponse::DisconnectAll(
+                    node_id,
+                )));
+                ui.close_menu();
+            }
+        });
+    if graph[node_id].user_data.collapsed {
+        return responses;
+    }
+    let device_profiles = graph[node_id]
+        .user_data
+        .device_id
+        .and_then(|device_id| Some((device_id, user_state.device_profiles.get(&device_id)?)));
+    let writable = graph[node_id].user_data.writable;
+    if let Some((device_id, profiles)) = device_profiles {
+        ui.add_enabled_ui(writable, |ui| {
+            if let Some(index) = device_profile_menu(ui, profiles) {
+                responses.push(NodeResponse::User(GraphNodeResponse::SetDeviceProfile(
+                    device_id, index,
+                )));
+            }
+        });
+    }
+    let user_data = &graph[node_id].user_data;
+    if let Some(codec) = &user_data.bluez5_codec {
+        ui.horizontal(|ui| {
+            if HEADSET_CODECS.contains(&codec.as_str()) {
+                ui.colored_label(
+                    egui::Color32::from_rgb(230, 160, 60),
+                    format!("⚠ Codec {}", codec.to_uppercase()),
+                )
+                .on_hover_text(
+                    "headset profile (mono, narrowband), switch the profile to A2DP for music",
+                );
+            } else {
+                ui.label(format!("Codec {}", codec.to_uppercase()));
+            }
+            if let Some((device_id, profiles)) = device_profiles {
+                ui.add_enabled_ui(writable, |ui| {
+                    if let Some(index) = bluez5_codec_menu(ui, profiles) {
+                        responses.push(NodeResponse::User(GraphNodeResponse::SetDeviceProfile(
+                            device_id, index,
+                        )));
+                    }
+                });
+            }
+        });
+    }
+    if let (Some(device_id), Some(route_device)) = (user_data.device_id, user_data.route_device) {
+        if let Some(routes) = user_state.device_routes.get(&device_id) {
+            ui.add_enabled_ui(writable, |ui| {
+                if let Some(response) = device_route_ui(ui, device_id, routes, route_device) {
+                    responses.push(NodeResponse::User(response));
+                }
+            });
+        }
+    }
+    if let Some(formats) = user_data
+        .pipewire_id
+        .and_then(|pipewire_id| user_state.video_formats.get(&pipewire_id))
+    {
+        let text = match (&formats.negotiated, formats.offered.first()) {
+            (Some(negotiated), _) => negotiated.to_string(),
+            (None, Some(offered)) => format!("{} (idle)", offered),
+            (None, None) => "--".to_owned(),
+        };
+        let label = ui.label(format!("🎞 {}", text));
+        if !formats.offered.is_empty() {
+            label.on_hover_ui(|ui| {
+                ui.strong("Offered formats");
+                for offered in &formats.offered {
+                    ui.label(offered.to_string());
+                }
+            });
+        }
+    }
+    if let Some((pipewire_id, player)) = user_data
+        .pipewire_id
+        .and_then(|pipewire_id| Some((pipewire_id, user_state.media_players.get(&pipewire_id)?)))
+    {
+        ui.add_enabled_ui(!user_state.restricted, |ui| {
+            if let Some(command) = media_controls_ui(ui, player) {
+                responses.push(NodeResponse::User(GraphNodeResponse::MediaControl(
+                    pipewire_id,
+                    command,
+                )));
+            }
+        });
+    }
+    if let Some(protocol) = user_data.network {
+        ui.label(egui::RichText::new(format!("🌐 {}", protocol.label())).small())
+            .on_hover_text("network endpoint");
+    }
+    if let Some(api) = user_data.client_api {
+        ui.label(egui::RichText::new(api.label()).small())
+            .on_hover_text(api.description());
+    }
+    if let (Some((pid, binary)), false) = (&user_data.process, user_data.own_stream) {
+        if let Some(signal) = process_ui(ui, *pid, binary.as_deref(), user_state.restricted) {
+            responses.push(NodeResponse::User(GraphNodeResponse::SignalProcess(
+                *pid, signal,
+            )));
+        }
+    }
+    if let (Some(pipewire_id), Some("Stream/Output/Audio")) =
+        (user_data.pipewire_id, user_data.media_class.as_deref())
+    {
+        ui.add_enabled_ui(!user_state.restricted, |ui| {
+            if let Some(target) = stream_target_menu(ui, pipewire_id, user_state) {
+                responses.push(NodeResponse::User(GraphNodeResponse::SetStreamTarget(
+                    pipewire_id,
+                    target,
+                )));
+            }
+        });
+    }
+    let is_audio = user_data
+        .media_class
+        .as_deref()
+        .map_or(false, |media_class| media_class.contains("Audio"));
+    if let (Some(pipewire_id), true, false) =
+        (user_data.pipewire_id, is_audio, user_data.own_stream)
+    {
+        if user_state.recording == Some(pipewire_id) {
+            let text = egui::RichText::new("⏹ Stop recording").color(egui::Color32::WHITE);
+            let button = egui::Button::new(text).fill(egui::Color32::DARK_RED);
             if ui.add(button).clicked() {
-                responses.push(NodeResponse::User(MyResponse::ClearActiveNode));
+                responses.push(NodeResponse::User(GraphNodeResponse::StopRecording));
+            }
+        } else if ui.button("⏺ Record").clicked() {
+            responses.push(NodeResponse::User(GraphNodeResponse::Record(pipewire_id)));
+        }
+    }
+    if let Some(pipewire_id) = user_data.pipewire_id {
+        if let Some((_, settings)) = user_state.tone_generators.get(&pipewire_id) {
+            if let Some(settings) = tone_generator_ui(ui, settings) {
+                responses.push(NodeResponse::User(GraphNodeResponse::SetTone(
+                    pipewire_id,
+                    settings,
+                )));
             }
         }
+    }
+    if graph[node_id].user_data.own_stream {
+        let button = egui::Button::new(egui::RichText::new("⏹ Stop").color(egui::Color32::WHITE))
+            .fill(egui::Color32::DARK_RED);
+        if ui.add_enabled(writable, button).clicked() {
+            responses.push(NodeResponse::User(GraphNodeResponse::StopNode(node_id)));
+        }
+    }
+    if graph[node_id].outputs.is_empty() {
+        return responses;
+    }
+    ui.menu_button("Connect all ⏵", |ui| {
+        for (other_id, other) in &graph.nodes {
+            if other_id == node_id
+                || other.user_data.pipewire_id.is_none()
+                || other.inputs.is_empty()
+            {
+                continue;
+            }
+            if ui.button(&other.label).clicked() {
+                responses.push(NodeResponse::User(GraphNodeResponse::ConnectNodes(
+                    node_id, other_id,
+                )));
+                ui.close_menu();
+            }
+        }
+    });
+    responses
+}
+
+// returns the profile index to switch to
+fn device_profile_menu(ui: &mut egui::Ui, profiles: &DeviceProfiles) -> Option<i32> {
+    let mut result = None;
+    if profiles.profiles.is_empty() {
+        return result;
+    }
+    ui.menu_button("Profile ⏵", |ui| {
+        for profile in &profiles.profiles {
+            let selected = profiles.active == Some(profile.index);
+            let response = ui
+                .add_enabled(
+                    profile.available,
+                    egui::SelectableLabel::new(selected, &profile.description),
+                )
+                .on_hover_text(&profile.name);
+            if response.clicked() {
+                result = Some(profile.index);
+                ui.close_menu();
+            }
+        }
+    });
+    result
+}
+
+// codecs of the bluetooth headset profiles (HSP/HFP)
+const HEADSET_CODECS: [&str; 3] = ["cvsd", "msbc", "lc3_swb"];
+
+// bluez5 exposes one profile per codec (e.g. "a2dp-sink-aac") next to the automatic one
+// ("a2dp-sink"), so switching the codec is switching among the active profile's variants
+const BLUEZ5_PROFILE_FAMILIES: [&str; 6] = [
+    "a2dp-sink",
+    "a2dp-source",
+    "a2dp-duplex",
+    "headset-head-unit",
+    "headset-audio-gateway",
+    "bap-sink",
+];
+
+// returns the profile index to switch to
+fn bluez5_codec_menu(ui: &mut egui::Ui, profiles: &DeviceProfiles) -> Option<i32> {
+    let in_family = |family: &str, name: &str| {
+        name.strip_prefix(family)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with('-'))
+    };
+    let active = profiles
+        .profiles
+        .iter()
+        .find(|profile| profiles.active == Some(profile.index))?;
+    let family = BLUEZ5_PROFILE_FAMILIES
+        .iter()
+        .find(|family| in_family(family, &active.name))?;
+    let variants: Vec<_> = profiles
+        .profiles
+        .iter()
+        .filter(|profile| in_family(family, &profile.name))
+        .collect();
+    if variants.len() < 2 {
+        return None;
+    }
+    let mut result = None;
+    ui.menu_button("Codec ⏵", |ui| {
+        for profile in variants {
+            let selected = profile.index == active.index;
+            let response = ui
+                .add_enabled(
+                    profile.available,
+                    egui::SelectableLabel::new(selected, &profile.description),
+                )
+                .on_hover_text(&profile.name);
+            if response.clicked() {
+                result = Some(profile.index);
+                ui.close_menu();
+            }
+        }
+    });
+    result
+}
+
+fn media_controls_ui(ui: &mut egui::Ui, player: &MediaPlayer) -> Option<MediaCommand> {
+    let mut command = None;
+    ui.horizontal(|ui| {
+        if ui.small_button("⏮").on_hover_text("Previous").clicked() {
+            command = Some(MediaCommand::Previous);
+        }
+        let (icon, hover) = if player.is_playing() {
+            ("⏸", "Pause")
+        } else {
+            ("▶", "Play")
+        };
+        if ui.small_button(icon).on_hover_text(hover).clicked() {
+            command = Some(MediaCommand::PlayPause);
+        }
+        if ui.small_button("⏭").on_hover_text("Next").clicked() {
+            command = Some(MediaCommand::Next);
+        }
+        let name = player
+            .bus_name
+            .trim_start_matches("org.mpris.MediaPlayer2.");
+        ui.weak(name).on_hover_text(&player.bus_name);
+    });
+    command
+}
+
+// client binary and pid with copy/terminate actions on right click
+fn process_ui(
+    ui: &mut egui::Ui,
+    pid: u32,
+    binary: Option<&str>,
+    restricted: bool,
+) -> Option<Signal> {
+    let mut signal = None;
+    let text = format!("⚙ {} ({})", binary.unwrap_or("process"), pid);
+    ui.weak(text)
+        .on_hover_text("pipewire.sec.pid of the client (right click for actions)")
+        .context_menu(|ui| {
+            if ui.button("Copy PID").clicked() {
+                ui.output().copied_text = pid.to_string();
+                ui.close_menu();
+            }
+            // a sandbox can't signal host processes
+            ui.add_enabled_ui(!restricted, |ui| {
+                ui.separator();
+                if ui.button("Terminate (SIGTERM)").clicked() {
+                    signal = Some(Signal::Term);
+                    ui.close_menu();
+                }
+                let button = ui
+                    .button("Kill (SIGKILL)")
+                    .on_hover_text("the app can't clean up, only for one which ignores SIGTERM");
+                if button.clicked() {
+                    signal = Some(Signal::Kill);
+                    ui.close_menu();
+                }
+            });
+        });
+    signal
+}
+
+// returns the sink to move the stream to (`Some(None)` for the default sink)
+fn stream_target_menu(
+    ui: &mut egui::Ui,
+    stream_id: u32,
+    user_state: &GraphUserState,
+) -> Option<Option<u32>> {
+    // serial since `target.object`, but older clients still write the id
+    let target = user_state.stream_targets.get(&stream_id);
+    let current = target.and_then(|target| {
+        user_state
+            .sinks
+            .iter()
+            .find(|(id, serial, _)| serial.as_ref() == Some(target) || id.to_string() == *target)
+    });
+    let text = match (target, current) {
+        (None, _) => "Default".to_owned(),
+        (Some(_), Some((_, _, label))) => label.clone(),
+        (Some(target), None) => format!("{} (missing)", target),
+    };
+    let mut result = None;
+    ui.menu_button(format!("Target: {} ⏵", text), |ui| {
+        if ui.selectable_label(target.is_none(), "Default").clicked() {
+            result = Some(None);
+            ui.close_menu();
+        }
+        for (id, _, label) in &user_state.sinks {
+            let selected = current.map(|(current, _, _)| current) == Some(id);
+            if ui.selectable_label(selected, label).clicked() {
+                result = Some(Some(*id));
+                ui.close_menu();
+            }
+        }
+    });
+    result
+}
+
+// star button and the expand/collapse toggle of unpinned nodes
+fn pin_ui(
+    ui: &mut egui::Ui,
+    user_data: &GraphNodeData,
+    user_state: &GraphUserState,
+) -> Option<GraphNodeResponse> {
+    let pipewire_id = user_data.pipewire_id?;
+    let mut result = None;
+    ui.horizontal(|ui| {
+        let (text, hover) = if user_data.pinned {
+            ("★", "unpin")
+        } else {
+            (
+                "☆",
+                "pin (keeps it expanded while other nodes are collapsed)",
+            )
+        };
+        if ui.small_button(text).on_hover_text(hover).clicked() {
+            result = Some(GraphNodeResponse::TogglePin(pipewire_id));
+        }
+        let hover = if user_data.position_pinned {
+            "unpin position"
+        } else {
+            "pin position (never moved by the layout or graph rebuilds)"
+        };
+        let button = ui.selectable_label(user_data.position_pinned, "📌");
+        if button.on_hover_text(hover).clicked() {
+            result = Some(GraphNodeResponse::TogglePinPosition(pipewire_id));
+        }
+        if user_data.pinned || !user_state.collapse_unpinned {
+            return;
+        }
+        if user_data.collapsed {
+            let ports = user_state.collapsed_ports.get(&pipewire_id);
+            let text = format!("⏷ {} ports", ports.map_or(0, Vec::len));
+            let button = ui.small_button(text).on_hover_ui(|ui| {
+                for port in ports.into_iter().flatten() {
+                    ui.label(port);
+                }
+            });
+            if button.clicked() {
+                result = Some(GraphNodeResponse::SetExpanded(pipewire_id, true));
+            }
+        } else if ui.small_button("⏶ Collapse").clicked() {
+            result = Some(GraphNodeResponse::SetExpanded(pipewire_id, false));
+        }
+    });
+    result
+}
+
+// waveform, frequency and level of a tone generator node (returns the changed settings)
+fn tone_generator_ui(ui: &mut egui::Ui, settings: &ToneSettings) -> Option<ToneSettings> {
+    let mut changed = *settings;
+    ui.menu_button(format!("{} ⏵", changed.waveform.label()), |ui| {
+        for waveform in Waveform::ALL {
+            let selected = changed.waveform == waveform;
+            if ui.selectable_label(selected, waveform.label()).clicked() {
+                changed.waveform = waveform;
+                ui.close_menu();
+            }
+        }
+    });
+    ui.add_enabled(
+        changed.waveform == Waveform::Sine,
+        egui::Slider::new(
+            &mut changed.frequency,
+            tone::MIN_FREQUENCY..=tone::MAX_FREQUENCY,
+        )
+        .logarithmic(true)
+        .suffix(" Hz"),
+    );
+    ui.add(egui::Slider::new(&mut changed.level_db, tone::MIN_LEVEL_DB..=0).suffix(" dB"));
+    (changed != *settings).then_some(changed)
+}
 
-        responses
+// route menu and volume slider of the active route
+fn device_route_ui(
+    ui: &mut egui::Ui,
+    device_id: u32,
+    routes: &DeviceRoutes,
+    route_device: i32,
+) -> Option<GraphNodeResponse> {
+    let mut result = None;
+    let active = routes.get_active(route_device);
+    ui.menu_button("Route ⏵", |ui| {
+        for route in routes.get_routes(route_device) {
+            let selected = active.map(|active| active.index) == Some(route.index);
+            let response = ui
+                .add_enabled(
+                    route.available,
+                    egui::SelectableLabel::new(selected, &route.description),
+                )
+                .on_hover_text(&route.name);
+            if response.clicked() {
+                result = Some(GraphNodeResponse::SetDeviceRoute(
+                    device_id,
+                    route.index,
+                    route_device,
+                ));
+                ui.close_menu();
+            }
+        }
+    });
+    if let Some(active) = active {
+        if !active.channel_volumes.is_empty() {
+            let volume =
+                active.channel_volumes.iter().sum::<f32>() / active.channel_volumes.len() as f32;
+            let mut percent = (volume.cbrt() * 100.0).round() as u32;
+            let slider = egui::Slider::new(&mut percent, 0..=150).suffix("%");
+            if ui.add(slider).changed() {
+                result = Some(GraphNodeResponse::SetDeviceRouteVolume(
+                    device_id,
+                    active.index,
+                    route_device,
+                    percent,
+                ));
+            }
+        }
     }
+    result
 }
 
-type MyGraph = Graph<MyNodeData, MyDataType, MyValueType>;
-type MyEditorState =
-    GraphEditorState<MyNodeData, MyDataType, MyValueType, MyNodeTemplate, MyGraphState>;
+type EditorGraph = Graph<GraphNodeData, PortType, PortValue>;
+type EditorState =
+    GraphEditorState<GraphNodeData, PortType, PortValue, GraphNodeTemplate, GraphUserState>;
 
-pub struct NodeGraphExample {
-    // The `GraphEditorState` is the top-level object. You "register" all your
-    // custom types by specifying it as its generic parameters.
-    state: MyEditorState,
+/// Node graph editor of a live pipewire session.
+///
+/// Runs its own pipewire thread (`PipewireWrapper`) and keeps the editor graph in sync with the
+/// registry. Use it as an `eframe::App` or embed it by calling [`PipewireGraphApp::show`] from
+/// another app's update.
+pub struct PipewireGraphApp {
+    // editor graph built from the registry (or the tutorial sandbox)
+    state: EditorState,
 
-    user_state: MyGraphState,
+    user_state: GraphUserState,
 
     pipewire_wrapper: PipewireWrapper,
+    // repainted by the pipewire thread when it sends messages
+    egui_ctx: egui::Context,
+    // delay of those repaints (`extra_state.repaint_delay_ms` shared with the wake closure)
+    repaint_delay_ms: Arc<AtomicU32>,
+    // `remote.name` to connect to instead of the default one (see `PipewireGraphTabs`)
+    remote: Option<String>,
+    // a JACK server instead of pipewire (`--jack` or the connection error screen)
+    #[cfg(feature = "jack")]
+    jack: bool,
+    registry: PipewireRegistry,
 
     extra_state: ExtraState,
+
+    // mapping from pipewire globals to the editor graph
+    // (not derived from `Topology` since the editor graph lags behind it while paused)
+    pipewire_id_to_node_id: HashMap<u32, NodeId>,
+    pipewire_id_to_param_id: HashMap<u32, AnyParameterId>,
+    pipewire_id_to_connection: HashMap<u32, (OutputId, InputId)>,
+    param_id_to_pipewire_id: HashMap<AnyParameterId, u32>,
+    // `object.serial` of the mirrored globals as they were mirrored (the server reuses ids of
+    // removed globals, so an id alone doesn't tell whether a mapping above is still the same
+    // global, e.g. a link deleted from a paused graph must not destroy its successor)
+    pipewire_id_to_serial: HashMap<u32, Option<String>>,
+
+    // next vertical position of each layout column (sources, others, sinks)
+    next_node_y: [f32; 3],
+    // where node finder templates were dropped keyed by the requested `node.name`
+    template_positions: HashMap<String, egui::Pos2>,
+
+    // graph editor area of the last frame (used for fit-to-view and minimap)
+    editor_rect: egui::Rect,
+    // nodes moved by the ongoing drag (snapped to the grid once released)
+    dragged_nodes: HashSet<NodeId>,
+
+    // spawned while the journal panel is open
+    journal_reader: Option<JournalReader>,
+    // polls the MPRIS players while the media controls are on
+    mpris_watcher: Option<MprisWatcher>,
+    // streams whose volumes are tracked and levels metered for the Mixer window
+    mixer_nodes: Vec<u32>,
+    // output port captured for the Scope window with the samples of its stream
+    scope: Option<(u32, Arc<SampleRing>)>,
+    // the only window of this instance (a separate process so that it can go on another monitor)
+    pop_out: Option<PopOut>,
+    // processes of the windows popped out of this instance (closed with it)
+    popped_out: Vec<Child>,
+    // the graph shows the state of when it was paused
+    paused: Option<PausedGraph>,
+    // messages/sec of the ui -> pipewire and pipewire -> ui channels (Debug window)
+    message_rates: [RateMeter; 2],
+    // registry events recorded from startup (shown in the timeline panel)
+    timeline: Timeline,
+
+    // user initiated link operations for undo/redo
+    link_history: LinkHistory,
+    // Link window creates duplicate links (not persisted to avoid surprises)
+    link_force: bool,
+    // (query, highlighted match) of the source and destination in the Quick connect window
+    quick_connect: [(String, usize); 2],
+    // field of the Quick connect window to focus on the next frame (0: source, 1: destination)
+    quick_connect_focus: Option<usize>,
+    // (query, highlighted match) of the Find node window
+    node_search: (String, usize),
+    node_search_focus: bool,
+    // node centered by the Find node window and when (it blinks for a moment)
+    found_node: Option<(NodeId, Instant)>,
+    // (presets, errors of invalid files) loaded when the Presets window opens
+    presets: Option<(Vec<Preset>, Vec<String>)>,
+    // links of a created preset waiting for its nodes, and since when
+    preset_links: Vec<(PresetLink, Instant)>,
+
+    toasts: Toasts,
+
+    // monitor ports are hidden when the graph exceeds the node budget on startup
+    reduced_view: bool,
+    // the registry dump after connecting is complete (`ChannelMessage::PipewireInitialSyncDone`)
+    initial_sync_done: bool,
+    // fit the view to the complete graph on the next frame
+    fit_view_pending: bool,
+
+    // ports whose params are already tracked by the pipewire thread
+    tracked_ports: HashSet<u32>,
+    // clients whose permissions are already tracked by the pipewire thread
+    tracked_clients: HashSet<u32>,
+    // client shown in the per-object permissions editor
+    permissions_client: Option<u32>,
+    permissions_filter: String,
+
+    // nodes removed from the editor graph by the user (until "Show hidden nodes")
+    hidden_nodes: HashSet<u32>,
+
+    // groups replaced by a single node with the offsets of their nodes to it (for expanding)
+    collapsed_groups: HashMap<NodeGroup, Vec<(u32, egui::Vec2)>>,
+    group_node_ids: HashMap<NodeGroup, NodeId>,
+    // pipewire nodes of the collapsed groups
+    group_members: HashMap<u32, NodeGroup>,
+    // ports of the group nodes keyed by (group node, peer node, is input)
+    group_params: HashMap<(NodeId, NodeId, bool), AnyParameterId>,
+
+    // last format requested per stream node
+    stream_format_requests: HashMap<u32, AudioFormat>,
+
+    // registry snapshots compared in the Snapshots window (from, to or `None` for now)
+    snapshots: Vec<Snapshot>,
+    snapshot_range: (usize, Option<usize>),
+
+    // experimental read-only snapshot sharing
+    share_host: Option<ShareHost>,
+    share_viewer: Option<ShareViewer>,
+
+    // sandbox graph replaces the live one while the tutorial runs
+    tutorial: Option<tutorial::Step>,
+
+    // probed once when the pipewire thread failed to start
+    connection_diagnosis: Option<(Option<Sandbox>, SocketStatus)>,
+    // destructive operations waiting for the confirmation window
+    confirmation: Option<Confirmation>,
+    // links which would close a feedback loop (see `user_state.feedback_loop`)
+    feedback_links: Vec<LinkAction>,
+    // registry events saved for `--replay` (File > Record session or `--record`)
+    session_recorder: Option<SessionRecorder>,
+    // (summary, label) of devices and streams announced since the last `take_new_nodes`
+    #[cfg(feature = "tray")]
+    new_nodes: Vec<(&'static str, String)>,
+    // node whose props are edited in the Node properties window
+    node_props_edit: Option<NodePropsEdit>,
+    // links taken out with "Bypass" (B) until re-enabled
+    bypassed_links: Vec<BypassedLink>,
+    // for the FPS overlay (`ctx.input().time` of the frames in the last second)
+    frame_times: VecDeque<f64>,
+    last_frame_duration: Duration,
+    // detected on startup (decides the default of the restricted mode)
+    sandbox: Option<Sandbox>,
 }
 
 #[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct ExtraState {
     window_core: bool,
     window_object: bool,
     window_link: bool,
+    window_latency: bool,
+    window_path_test: bool,
+    window_recording: bool,
+    window_snapshots: bool,
+    window_session_manager: bool,
+    window_modules: bool,
+    window_factories: bool,
+    window_quick_connect: bool,
+    window_node_search: bool,
+    window_share: bool,
+    window_virtual_device: bool,
+    window_network: bool,
+    window_presets: bool,
+    window_stream_format: bool,
+    window_unconnected: bool,
+    window_mixer: bool,
+    window_scope: bool,
+    window_clients: bool,
+    window_debug: bool,
+    window_performance: bool,
+    window_log: bool,
+    window_theme: bool,
+    theme: Theme,
+    // most verbose level shown in the Log window
+    log_level: LogLevel,
+    #[serde(skip)]
+    log_search: String,
+    // only list unconnected inputs (e.g. a recorder nobody feeds)
+    unconnected_inputs_only: bool,
+    minimap: bool,
+    grid: Grid,
+    // node titlebar hue per application instead of per `media.class`
+    color_by_client: bool,
+    // boxes around the nodes of the same device/client
+    node_groups: bool,
+    // highlight the default sink and whatever is routed to it
+    monitor_mode: bool,
+    // play/pause/skip on the streams of MPRIS media players
+    media_controls: bool,
+    port_label: PortLabel,
+    node_label: NodeLabel,
+    // pipewire ids after the node and port labels (to look them up in pw-cli/pw-dump)
+    show_ids: bool,
+    // accessible alternative to the canvas
+    list_view: bool,
+    // node.name of starred nodes (the others are collapsed while any is pinned)
+    pinned_nodes: BTreeSet<String>,
+    // node.name of unpinned nodes expanded by the user
+    expanded_nodes: BTreeSet<String>,
+    // node.name of nodes with a user-chosen position
+    pinned_positions: BTreeMap<String, egui::Pos2>,
+    // client streams of these APIs are left out of the graph
+    hidden_client_apis: BTreeSet<ClientApi>,
+    panel_journal: bool,
+    panel_timeline: bool,
+    node_budget: Option<usize>,
     link_from: Option<(String, String)>,
     link_to: Option<(String, String)>,
+    // node.name narrowing down the ports of the Link window
+    link_from_node: Option<String>,
+    link_to_node: Option<String>,
+    // (from, to) of links recently created from the Link window (newest first)
+    link_recent: Vec<((String, String), (String, String))>,
+    // for every link created by this app (Link window and editor)
+    link_flags: LinkFlags,
+    // user-defined Object window columns bound to prop keys
+    object_columns: Vec<String>,
+    // (column, descending)
+    object_sort: Option<(ObjectColumn, bool)>,
+    #[serde(skip)]
+    object_column_input: String,
+    share_address: String,
+    share_redaction: Redaction,
+    share_remote_address: String,
+    #[serde(skip)]
+    share_remote_token: String,
+    virtual_device: VirtualDevice,
+    network_setup: NetworkSetup,
+    // where recordings are written (empty for the working directory like exports)
+    record_dir: String,
+    // last module loaded from the Modules window
+    module_name: String,
+    module_args: String,
+    // last object created from the Factories window
+    factory_name: String,
+    factory_props: Vec<(String, String)>,
+    stream_format: AudioFormat,
+    // finished or skipped (the tutorial starts on the first run)
+    tutorial_done: bool,
+    // hide operations a sandbox can't perform (`None` follows the detected sandbox)
+    restricted_mode: Option<bool>,
+    // send destructive operations without the confirmation window
+    skip_confirmation: bool,
+    // run interval of the pipewire thread's message handler (`None` for the default 100ms)
+    timer_interval_ms: Option<u32>,
+    // repaint after pipewire messages at most this late (0: right away)
+    repaint_delay_ms: u32,
+    // repaint every frame even when nothing changes
+    continuous_repaint: bool,
+    // a few props instead of all of them when hovering objects
+    short_props_tooltips: bool,
+    fps_overlay: bool,
+    // closing the window hides it into a tray icon (see `PipewireGraphTabs`)
+    #[cfg(feature = "tray")]
+    minimize_to_tray: bool,
 }
 
-const PERSISTENCE_KEY: &str = env!("CARGO_PKG_NAME");
+// prop shown as the port name in the graph (falls back to port.name)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum PortLabel {
+    #[default]
+    Name,
+    // e.g. "Built-in Audio Analog Stereo:playback_FL" (readable for ALSA MIDI ports)
+    Alias,
+}
 
-impl NodeGraphExample {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
-            state: Default::default(),
-            user_state: Default::default(),
-            pipewire_wrapper: PipewireWrapper::new(),
-            extra_state: cc
-                .storage
-                .and_then(|storage| eframe::get_value(storage, PERSISTENCE_KEY))
-                .unwrap_or_default(),
+impl PortLabel {
+    const ALL: [PortLabel; 2] = [PortLabel::Name, PortLabel::Alias];
+
+    fn key(self) -> PropKey {
+        match self {
+            PortLabel::Name => PropKey::PortName,
+            PortLabel::Alias => PropKey::PortAlias,
         }
     }
 }
 
-impl eframe::App for NodeGraphExample {
-    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        self.pipewire_wrapper.quit().unwrap();
-    }
+// prop shown as the node title (falls back to description, nick, name in this order)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum NodeLabel {
+    #[default]
+    Description,
+    Nick,
+    Name,
+}
 
-    /// If the persistence function is enabled,
-    /// Called by the frame work to save state before shutdown.
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, PERSISTENCE_KEY, &self.extra_state);
-    }
+impl NodeLabel {
+    const ALL: [NodeLabel; 3] = [NodeLabel::Description, NodeLabel::Nick, NodeLabel::Name];
 
-    /// Called each time the UI needs repainting, which may be many times per second.
-    /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        while let Ok(message) = self.pipewire_wrapper.channel_receiver.try_recv() {
-            dbg!(message);
+    fn key(self) -> PropKey {
+        match self {
+            NodeLabel::Description => PropKey::NodeDescription,
+            NodeLabel::Nick => PropKey::NodeNick,
+            NodeLabel::Name => PropKey::NodeName,
         }
+    }
+}
 
-        //
-        // menu bar
-        //
+// API a client created its node through (`client.api` set by the compatibility layers)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum ClientApi {
+    Native,
+    Pulse,
+    Jack,
+    Alsa,
+}
 
-        egui::TopBottomPanel::top("top").show(ctx, |ui| {
-            egui::menu::bar(ui, |ui| {
-                egui::widgets::global_dark_light_mode_switch(ui);
-                ui.toggle_value(&mut self.extra_state.window_core, "Core");
-                ui.toggle_value(&mut self.extra_state.window_object, "Object");
-                ui.toggle_value(&mut self.extra_state.window_link, "Link");
-            });
-        });
+impl ClientApi {
+    const ALL: [ClientApi; 4] = [
+        ClientApi::Native,
+        ClientApi::Pulse,
+        ClientApi::Jack,
+        ClientApi::Alsa,
+    ];
 
-        //
-        // Core window
-        //
+    // `None` for nodes other than client streams (e.g. devices)
+    fn of(object: &GlobalObject<PropStore>) -> Option<Self> {
+        let props = object.props.as_ref()?;
+        match props.get_key(PropKey::ClientApi) {
+            Some("pipewire-pulse") => Some(ClientApi::Pulse),
+            Some("jack") => Some(ClientApi::Jack),
+            Some("alsa") => Some(ClientApi::Alsa),
+            Some(_) => Some(ClientApi::Native),
+            None => props
+                .get_key(PropKey::MediaClass)
+                .filter(|media_class| media_class.starts_with("Stream/"))
+                .map(|_| ClientApi::Native),
+        }
+    }
 
-        egui::Window::new("Core")
-            .open(&mut self.extra_state.window_core)
-            .default_width(500.0)
-            .show(ctx, |ui| {
-                if let Ok(state) = self.pipewire_wrapper.state.lock().as_deref() {
-                    if let Some(core_info) = &state.core_info {
-                        egui::ScrollArea::both().show(ui, |ui| {
-                            ui.add(
-                                egui::TextEdit::multiline(&mut core_info.as_str())
-                                    .font(egui::TextStyle::Monospace)
-                                    .desired_width(f32::INFINITY),
-                            );
-                        });
-                    } else {
-                        ui.label("(initializing..)");
-                    }
-                } else {
-                    ui.label("(error)");
-                }
-            });
+    fn label(self) -> &'static str {
+        match self {
+            ClientApi::Native => "PipeWire",
+            ClientApi::Pulse => "PulseAudio",
+            ClientApi::Jack => "JACK",
+            ClientApi::Alsa => "ALSA",
+        }
+    }
 
-        //
-        // Object window
-        //
+    fn description(self) -> &'static str {
+        match self {
+            ClientApi::Native => "native PipeWire client",
+            ClientApi::Pulse => "PulseAudio client through pipewire-pulse",
+            ClientApi::Jack => "JACK client through pipewire-jack",
+            ClientApi::Alsa => "ALSA client through the PipeWire ALSA plugin",
+        }
+    }
+}
 
-        egui::Window::new("Object")
-            .open(&mut self.extra_state.window_object)
-            .show(ctx, |ui| {
-                let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
-                egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
-                    TableBuilder::new(ui)
-                        .striped(true)
-                        .column(Size::exact(20.0))
-                        .column(Size::exact(80.0))
-                        .column(Size::remainder())
-                        .header(text_height, |mut header| {
-                            header.col(|ui| {
-                                ui.strong("ID");
-                            });
-                            header.col(|ui| {
-                                ui.strong("Type");
-                            });
-                            header.col(|ui| {
-                                ui.strong("Props");
-                            });
-                        })
-                        .body(|mut body| {
-                            let state = self.pipewire_wrapper.state.lock().unwrap();
-                            for (_, object) in &state.global_objects {
-                                body.row(text_height, |mut row| {
-                                    row.col(|ui| {
-                                        ui.label(object.id.to_string());
-                                    });
-                                    row.col(|ui| {
-                                        ui.label(format!("{:?}", object.type_));
-                                    });
-                                    row.col(|ui| {
-                                        let label = ui.label(
-                                            PipewireObject::get_name(object)
-                                                .map_or("--", |(_k, v)| v),
-                                        );
-                                        if let Some(props) = &object.props {
-                                            label.on_hover_ui(|ui| {
-                                                let props_str = format!("{:#?}", props);
-                                                ui.add(
-                                                    egui::TextEdit::multiline(
-                                                        &mut props_str.as_str(),
-                                                    )
-                                                    .font(egui::TextStyle::Monospace)
-                                                    .desired_width(f32::INFINITY),
-                                                );
-                                            });
-                                        };
-                                    });
-                                });
-                            }
-                        });
-                });
-            });
+/// Window shown on its own by a popped out instance (`--pop-out <name>`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PopOut {
+    Object,
+    Mixer,
+}
 
-        //
-        // Link create/destroy window
-        //
+impl PopOut {
+    pub const ALL: [PopOut; 2] = [PopOut::Object, PopOut::Mixer];
 
-        egui::Window::new("Link")
-            .open(&mut self.extra_state.window_link)
-            .show(ctx, |ui| {
-                egui::Grid::new("link")
-                    .num_columns(2)
-                    .spacing([10.0, 5.0])
-                    .show(ui, |ui| {
-                        ui.label("From");
-                        let selected = self.extra_state.link_from.clone();
-                        egui::ComboBox::from_id_source("link-from")
-                            .width(350.0)
-                            .selected_text(
-                                selected
-                                    .as_ref()
-                                    .map_or("".to_string(), |(_k, v)| v.clone()),
-                            )
-                            .show_ui(ui, |ui| {
-                                let state = self.pipewire_wrapper.state.lock().unwrap();
-                                for (_, object) in &state.global_objects {
-                                    if PipewireObject::is_output(object) {
-                                        if let Some((k, v)) = PipewireObject::get_name(object) {
-                                            let mut response = ui.selectable_label(
-                                                selected
-                                                    .as_ref()
-                                                    .map(|(k, v)| (k.as_str(), v.as_str()))
-                                                    == Some((k, v)),
-                                                v,
-                                            );
-                                            if response.clicked() {
-                                                self.extra_state.link_from =
-                                                    Some((k.to_owned(), v.to_owned()));
-                                                response.mark_changed();
-                                            }
-                                        }
-                                    }
-                                }
-                            });
-                        ui.end_row();
-
-                        ui.label("To");
-                        let selected = self.extra_state.link_to.clone();
-                        egui::ComboBox::from_id_source("link-to")
-                            .width(350.0)
-                            .selected_text(
-                                selected
-                                    .as_ref()
-                                    .map_or("".to_string(), |(_k, v)| v.clone()),
-                            )
-                            .show_ui(ui, |ui| {
-                                let state = self.pipewire_wrapper.state.lock().unwrap();
-                                for (_, object) in &state.global_objects {
-                                    if PipewireObject::is_input(object) {
-                                        if let Some((k, v)) = PipewireObject::get_name(object) {
-                                            let mut response = ui.selectable_label(
-                                                selected
-                                                    .as_ref()
-                                                    .map(|(k, v)| (k.as_str(), v.as_str()))
-                                                    == Some((k, v)),
-                                                v,
-                                            );
-                                            if response.clicked() {
-                                                self.extra_state.link_to =
-                                                    Some((k.to_owned(), v.to_owned()));
-                                                response.mark_changed();
-                                            }
-                                        }
-                                    }
-                                }
-                            });
-                        ui.end_row();
-                    });
-                ui.add_space(5.0);
-                ui.horizontal(|ui| {
-                    if ui.button("Create Link").clicked() {
-                        match (&self.extra_state.link_from, &self.extra_state.link_to) {
-                            (Some(from), Some(to)) => {
-                                self.pipewire_wrapper
-                                    .channel_sender
-                                    .send(ChannelMessage::LinkCreate(from.clone(), to.clone()))
-                                    .unwrap();
-                            }
-                            _ => {}
+    pub fn name(self) -> &'static str {
+        match self {
+            PopOut::Object => "object",
+            PopOut::Mixer => "mixer",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|pop_out| pop_out.name() == name)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum ObjectColumn {
+    Id,
+    Serial,
+    Type,
+    Name,
+    Prop(String),
+}
+
+impl ObjectColumn {
+    fn value<'a>(&self, object: &'a GlobalObject<PropStore>) -> Cow<'a, str> {
+        match self {
+            ObjectColumn::Id => Cow::Owned(object.id.to_string()),
+            ObjectColumn::Serial => Cow::Borrowed(PipewireObject::get_serial(object).unwrap_or("")),
+            ObjectColumn::Type => Cow::Borrowed(PipewireObject::get_type_name(object)),
+            ObjectColumn::Name => {
+                Cow::Borrowed(PipewireObject::get_name(object).map_or("", |(_k, v)| v))
+            }
+            ObjectColumn::Prop(key) => Cow::Borrowed(
+                object
+                    .props
+                    .as_ref()
+                    .and_then(|props| props.get(key))
+                    .unwrap_or(""),
+            ),
+        }
+    }
+
+    // numeric values (e.g. `priority.session`) are compared as numbers
+    fn compare(
+        &self,
+        a: &GlobalObject<PropStore>,
+        b: &GlobalObject<PropStore>,
+    ) -> std::cmp::Ordering {
+        let (a, b) = (self.value(a), self.value(b));
+        match (a.parse::<i64>(), b.parse::<i64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(&b),
+        }
+    }
+}
+
+const PERSISTENCE_KEY: &str = env!("CARGO_PKG_NAME");
+
+const EXPORT_DOT_PATH: &str = "pipewire_graph.dot";
+const EXPORT_SVG_PATH: &str = "pipewire_graph.svg";
+const EXPORT_TIMELINE_CSV_PATH: &str = "pipewire_timeline.csv";
+const EXPORT_TIMELINE_JSON_PATH: &str = "pipewire_timeline.json";
+const SESSION_PATH: &str = "pipewire_session.json";
+
+fn write_export(toasts: &mut Toasts, path: &str, content: &str) {
+    match std::fs::write(path, content) {
+        Ok(()) => toasts.info(format!("Exported to {}", path)),
+        Err(e) => {
+            tracing::error!("export failed {} ({})", path, e);
+            toasts.error(format!("Export to {} failed ({})", path, e));
+        }
+    }
+}
+
+const DEFAULT_NODE_BUDGET: usize = 300;
+
+// registry events held back by a paused graph before it gets rebuilt on resume instead
+const MAX_PAUSED_EVENTS: usize = 100_000;
+
+// registry events not applied to the graph yet (see `PipewireGraphApp::resume_graph`)
+struct PausedGraph {
+    since: Instant,
+    events: Vec<RegistryEvent>,
+    // changes which aren't registry events (labels, permissions) or too many events
+    rebuild: bool,
+}
+
+// link destroyed by "Bypass" which is re-created as it was on re-enabling
+struct BypassedLink {
+    from: (String, String),
+    to: (String, String),
+    flags: LinkFlags,
+    // pipewire nodes at both ends (to find the links of the selection)
+    nodes: (u32, u32),
+}
+
+// labels editable in the Node properties window
+const EDITABLE_NODE_PROPS: [PropKey; 2] = [PropKey::NodeDescription, PropKey::NodeNick];
+
+struct NodePropsEdit {
+    node_id: u32,
+    // values of `EDITABLE_NODE_PROPS` as announced and as edited
+    current: [String; 2],
+    edited: [String; 2],
+}
+
+impl NodePropsEdit {
+    fn new(state: &PipewireState, node_id: u32) -> Self {
+        let current = Self::props(state, node_id);
+        Self {
+            node_id,
+            edited: current.clone(),
+            current,
+        }
+    }
+
+    fn props(state: &PipewireState, node_id: u32) -> [String; 2] {
+        let props = state
+            .global_objects
+            .get(&node_id)
+            .and_then(|object| object.props.as_ref());
+        EDITABLE_NODE_PROPS.map(|key| {
+            props
+                .and_then(|props| props.get_key(key))
+                .unwrap_or_default()
+                .to_owned()
+        })
+    }
+}
+
+// (label, audio.position) presets for virtual devices
+const CHANNEL_MAPS: [(&str, &str); 4] = [
+    ("Mono", "MONO"),
+    ("Stereo", "FL,FR"),
+    ("5.1", "FL,FR,FC,LFE,RL,RR"),
+    ("7.1", "FL,FR,FC,LFE,RL,RR,SL,SR"),
+];
+
+fn pipewire_node_label(object: &GlobalObject<PropStore>, style: NodeLabel) -> String {
+    let mut label = object
+        .props
+        .as_ref()
+        .and_then(|props| props.get_key(style.key()))
+        .or_else(|| PipewireObject::get_node_label(object))
+        .unwrap_or("--")
+        .to_owned();
+    if PipewireObject::is_own(object) {
+        label = format!("{}: {}", env!("CARGO_PKG_NAME"), label);
+    }
+    if !PipewireObject::is_writable(object) {
+        label = format!("🔒 {}", label);
+    }
+    label
+}
+
+// markers after the port label in the graph (see `port_flags_text`)
+fn port_flag_icons(object: &GlobalObject<PropStore>) -> String {
+    [
+        (PipewireObject::is_physical(object), "🔌"),
+        (PipewireObject::is_terminal(object), "⚓"),
+        (PipewireObject::is_control(object), "⚙"),
+        (
+            PipewireObject::is_passthrough(object) && !PipewireObject::is_control(object),
+            "⏭",
+        ),
+    ]
+    .iter()
+    .filter(|(flag, _)| *flag)
+    .map(|(_, icon)| *icon)
+    .collect()
+}
+
+// e.g. "physical, terminal, dsp 32 bit float mono audio"
+fn port_flags_text(object: &GlobalObject<PropStore>) -> String {
+    let mut flags = vec![];
+    if PipewireObject::is_physical(object) {
+        flags.push("🔌 physical".to_owned());
+    }
+    if PipewireObject::is_terminal(object) {
+        flags.push("⚓ terminal".to_owned());
+    }
+    if PipewireObject::is_control(object) {
+        flags.push("⚙ control (not auto-connected)".to_owned());
+    } else if let Some(format) = PipewireObject::get_format_dsp(object) {
+        flags.push(format!("dsp {}", format));
+    } else {
+        flags.push("⏭ passthrough (no dsp format)".to_owned());
+    }
+    flags.join(", ")
+}
+
+// e.g. "Built-in Audio #42"
+fn with_id(label: String, id: u32, show_ids: bool) -> String {
+    if show_ids {
+        format!("{} #{}", label, id)
+    } else {
+        label
+    }
+}
+
+// names survive the ids if the nodes are re-created meanwhile (e.g. for undo)
+fn port_key(port: &GlobalObject<PropStore>) -> (String, String) {
+    PipewireObject::get_name(port).map_or_else(
+        || PipewireObject::id_key(port.id),
+        |(k, v)| (k.to_owned(), v.to_owned()),
+    )
+}
+
+fn pipewire_port_label(object: &GlobalObject<PropStore>, style: PortLabel) -> &str {
+    object
+        .props
+        .as_ref()
+        .and_then(|props| props.get_key(style.key()))
+        .or_else(|| PipewireObject::get_port_name(object))
+        .unwrap_or("--")
+}
+
+// e.g. "rwx-"
+fn permissions_label(permissions: Permission) -> String {
+    [
+        (Permission::R, 'r'),
+        (Permission::W, 'w'),
+        (Permission::X, 'x'),
+        (Permission::M, 'm'),
+    ]
+    .iter()
+    .map(|&(flag, c)| if permissions.contains(flag) { c } else { '-' })
+    .collect()
+}
+
+// hue of the node titlebar by `media.class` (filters e.g. `pw_filter` nodes often have none)
+fn media_class_hue(media_class: Option<&str>) -> f32 {
+    match media_class {
+        Some(class) if class.starts_with("Stream/Output") => 0.08,
+        Some(class) if class.starts_with("Stream/Input") => 0.8,
+        Some(class) if class.contains("Sink") => 0.6,
+        Some(class) if class.contains("Source") => 0.35,
+        _ => 0.15,
+    }
+}
+
+// stable hue per application so that its nodes stand out together
+fn client_hue(client: &str) -> f32 {
+    let hash = client.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    });
+    (hash % 360) as f32 / 360.0
+}
+
+fn port_data_type(state: &PipewireState, port: &GlobalObject<PropStore>) -> PortType {
+    // cameras and screencasts negotiate raw formats without a dsp format
+    let is_video_node = PipewireObject::get_parent_node_id(port)
+        .and_then(|node_id| state.global_objects.get(&node_id))
+        .and_then(PipewireObject::get_media_class)
+        .map_or(false, |media_class| media_class.contains("Video"));
+    match PipewireObject::get_format_dsp(port) {
+        Some(format) if format.ends_with("audio") => PortType::Audio,
+        Some(format) if format.ends_with("midi") => PortType::Midi,
+        Some(format) if format.ends_with("video") => PortType::Video,
+        None if is_video_node => PortType::Video,
+        _ => PortType::Other,
+    }
+}
+
+fn is_link_port(port: &GlobalObject<PropStore>, output: bool) -> bool {
+    if output {
+        PipewireObject::is_output(port)
+    } else {
+        PipewireObject::is_input(port)
+    }
+}
+
+// channels carried by a port: the negotiated format when it's tracked, otherwise all of the
+// node's for a passthrough port (dsp ports are mono)
+fn port_channels(state: &PipewireState, port_id: u32) -> Option<u32> {
+    if let Some(format) = state.port_formats.get(&port_id) {
+        if format.channels > 0 {
+            return Some(format.channels as u32);
+        }
+    }
+    let port = state.global_objects.get(&port_id)?;
+    if !PipewireObject::is_passthrough(port) {
+        return Some(1);
+    }
+    let node = state
+        .global_objects
+        .get(&PipewireObject::get_parent_node_id(port)?)?;
+    node.props
+        .as_ref()?
+        .get_key(PropKey::AudioChannels)?
+        .parse()
+        .ok()
+}
+
+// egui_node_graph doesn't expose node sizes, so approximate them from the number of ports
+fn estimate_node_size(node: &Node<GraphNodeData>) -> egui::Vec2 {
+    let rows = node.inputs.len() + node.outputs.len();
+    egui::vec2(200.0, 60.0 + 20.0 * rows as f32)
+}
+
+// pipewire thread which repaints the ui on its messages (the graph stays live while idle)
+fn connect(
+    ctx: &egui::Context,
+    backend: impl Backend,
+    repaint_delay_ms: &Arc<AtomicU32>,
+    timer_interval_ms: Option<u32>,
+) -> PipewireWrapper {
+    let pipewire_wrapper = PipewireWrapper::with_backend(backend);
+    let ctx = ctx.clone();
+    let repaint_delay_ms = repaint_delay_ms.clone();
+    pipewire_wrapper.channel_receiver.set_wake(move || {
+        match repaint_delay_ms.load(Ordering::Relaxed) {
+            0 => ctx.request_repaint(),
+            ms => ctx.request_repaint_after(Duration::from_millis(ms.into())),
+        }
+    });
+    if let Some(ms) = timer_interval_ms {
+        pipewire_wrapper
+            .channel_sender
+            .send_or_log(ChannelMessage::PipewireTimerIntervalSet(ms));
+    }
+    pipewire_wrapper
+}
+
+impl PipewireGraphApp {
+    /// Connects to pipewire and restores the settings saved by `eframe::App::save`.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::with_storage(&cc.egui_ctx, cc.storage)
+    }
+
+    /// Same as [`PipewireGraphApp::new`] for a host app which owns the eframe setup.
+    pub fn with_storage(ctx: &egui::Context, storage: Option<&dyn eframe::Storage>) -> Self {
+        let extra_state: ExtraState = storage
+            .and_then(|storage| eframe::get_value(storage, PERSISTENCE_KEY))
+            .unwrap_or_default();
+        Self::with_connection(ctx, extra_state, None)
+    }
+
+    /// One window of the app filling the native window of its own process, e.g. the Mixer on
+    /// another monitor while the graph stays full screen (eframe has a single viewport).
+    ///
+    /// It has its own connection and starts from the saved settings without saving them.
+    pub fn popped_out(cc: &eframe::CreationContext<'_>, pop_out: PopOut) -> Self {
+        let extra_state: ExtraState = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, PERSISTENCE_KEY))
+            .unwrap_or_default();
+        let extra_state = ExtraState {
+            tutorial_done: true,
+            window_object: pop_out == PopOut::Object,
+            window_mixer: pop_out == PopOut::Mixer,
+            ..extra_state
+        };
+        let mut app = Self::with_connection(&cc.egui_ctx, extra_state, None);
+        app.pop_out = Some(pop_out);
+        app
+    }
+
+    /// Graph of a plain JACK server (`--jack`) with the settings of the pipewire one.
+    #[cfg(feature = "jack")]
+    pub fn with_jack(cc: &eframe::CreationContext<'_>) -> Self {
+        let extra_state: ExtraState = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, PERSISTENCE_KEY))
+            .unwrap_or_default();
+        let mut app = Self::with_wrapper(&cc.egui_ctx, extra_state, None, JackBackend);
+        app.jack = true;
+        app
+    }
+
+    /// Graph of another backend (e.g. [`crate::backend::MockBackend`] replaying a fixture).
+    ///
+    /// Its settings start from the defaults and are not persisted.
+    pub fn with_backend(ctx: &egui::Context, backend: impl Backend) -> Self {
+        let extra_state = ExtraState {
+            tutorial_done: true,
+            ..Default::default()
+        };
+        Self::with_wrapper(ctx, extra_state, None, backend)
+    }
+
+    /// Graph of another pipewire instance (e.g. "tcp:192.168.1.2:4656") with this app's theme.
+    ///
+    /// Its settings start from the defaults and are not persisted.
+    pub fn open_remote(&self, ctx: &egui::Context, remote: String) -> Self {
+        let extra_state = ExtraState {
+            theme: self.extra_state.theme.clone(),
+            tutorial_done: true,
+            ..Default::default()
+        };
+        Self::with_connection(ctx, extra_state, Some(remote))
+    }
+
+    /// Records the registry events from now on into `path` (saved on stop or exit),
+    /// which `--replay <path>` replays offline.
+    pub fn record_session(&mut self, path: String) {
+        let mut recorder = SessionRecorder::new(path);
+        // otherwise the pending registry messages announce everything
+        if self.initial_sync_done {
+            recorder.snapshot(&metrics::lock_ui(&self.pipewire_wrapper.state));
+        }
+        self.session_recorder = Some(recorder);
+    }
+
+    fn stop_session_recording(&mut self) {
+        let recorder = match self.session_recorder.take() {
+            Some(recorder) => recorder,
+            None => return,
+        };
+        match recorder.save() {
+            Ok(()) => self.toasts.info(format!(
+                "Saved {} ticks to {} (replay with --replay)",
+                recorder.num_ticks(),
+                recorder.path
+            )),
+            Err(e) => {
+                tracing::error!("session save failed {} ({})", recorder.path, e);
+                self.toasts
+                    .error(format!("Saving {} failed ({})", recorder.path, e));
+            }
+        }
+    }
+
+    /// `remote.name` given to [`PipewireGraphApp::open_remote`].
+    pub fn remote(&self) -> Option<&str> {
+        self.remote.as_deref()
+    }
+
+    fn with_connection(
+        ctx: &egui::Context,
+        extra_state: ExtraState,
+        remote: Option<String>,
+    ) -> Self {
+        let backend = PipewireBackend {
+            remote: remote.clone(),
+        };
+        Self::with_wrapper(ctx, extra_state, remote, backend)
+    }
+
+    fn with_wrapper(
+        ctx: &egui::Context,
+        extra_state: ExtraState,
+        remote: Option<String>,
+        backend: impl Backend,
+    ) -> Self {
+        let repaint_delay_ms = Arc::new(AtomicU32::new(extra_state.repaint_delay_ms));
+        let pipewire_wrapper = connect(
+            ctx,
+            backend,
+            &repaint_delay_ms,
+            extra_state.timer_interval_ms,
+        );
+        let mut app = Self {
+            state: EditorState::new(1.0),
+            user_state: Default::default(),
+            pipewire_wrapper,
+            egui_ctx: ctx.clone(),
+            repaint_delay_ms,
+            remote,
+            #[cfg(feature = "jack")]
+            jack: false,
+            registry: Default::default(),
+            extra_state,
+            pipewire_id_to_node_id: Default::default(),
+            pipewire_id_to_param_id: Default::default(),
+            pipewire_id_to_connection: Default::default(),
+            param_id_to_pipewire_id: Default::default(),
+            pipewire_id_to_serial: Default::default(),
+            next_node_y: Default::default(),
+            template_positions: Default::default(),
+            editor_rect: egui::Rect::NOTHING,
+            dragged_nodes: Default::default(),
+            journal_reader: None,
+            mpris_watcher: None,
+            mixer_nodes: vec![],
+            scope: None,
+            pop_out: None,
+            popped_out: vec![],
+            paused: None,
+            message_rates: Default::default(),
+            timeline: Default::default(),
+            link_history: Default::default(),
+            link_force: false,
+            quick_connect: Default::default(),
+            quick_connect_focus: None,
+            node_search: Default::default(),
+            node_search_focus: false,
+            found_node: None,
+            presets: None,
+            preset_links: vec![],
+            toasts: Default::default(),
+            reduced_view: false,
+            initial_sync_done: false,
+            fit_view_pending: false,
+            tracked_ports: Default::default(),
+            tracked_clients: Default::default(),
+            permissions_client: None,
+            permissions_filter: String::new(),
+            hidden_nodes: Default::default(),
+            collapsed_groups: Default::default(),
+            group_node_ids: Default::default(),
+            group_members: Default::default(),
+            group_params: Default::default(),
+            stream_format_requests: Default::default(),
+            snapshots: vec![],
+            snapshot_range: (0, None),
+            share_host: None,
+            share_viewer: None,
+            tutorial: None,
+            connection_diagnosis: None,
+            confirmation: None,
+            feedback_links: vec![],
+            session_recorder: None,
+            #[cfg(feature = "tray")]
+            new_nodes: vec![],
+            node_props_edit: None,
+            bypassed_links: vec![],
+            sandbox: Sandbox::detect(),
+            frame_times: Default::default(),
+            last_frame_duration: Duration::ZERO,
+        };
+        app.extra_state.theme.apply(ctx);
+        if !app.extra_state.tutorial_done {
+            app.start_tutorial();
+        }
+        app
+    }
+
+    //
+    // sync editor graph with registry events
+    //
+
+    fn pause_graph(&mut self) {
+        self.paused = Some(PausedGraph {
+            since: Instant::now(),
+            events: vec![],
+            rebuild: false,
+        });
+    }
+
+    // catch up with what happened meanwhile
+    fn resume_graph(&mut self) {
+        let paused = match self.paused.take() {
+            Some(paused) => paused,
+            None => return,
+        };
+        for event in paused.events {
+            self.handle_registry_event(event);
+        }
+        if paused.rebuild {
+            let state = self.pipewire_wrapper.state.clone();
+            self.rebuild_graph(&metrics::lock_ui(&state));
+        }
+    }
+
+    fn handle_registry_event(&mut self, event: RegistryEvent) {
+        let id = event.id();
+        match event {
+            RegistryEvent::NodeRemoved(_) => self.remove_pipewire_node(id),
+            RegistryEvent::PortRemoved(_) => self.remove_pipewire_port(id),
+            RegistryEvent::LinkRemoved(_) => self.remove_pipewire_link(id),
+            RegistryEvent::GlobalRemoved(_) => {}
+            added => return self.handle_registry_global(added),
+        }
+        // nothing keyed by this id may carry over to a new global reusing it
+        self.pipewire_id_to_serial.remove(&id);
+        self.tracked_ports.remove(&id);
+        self.tracked_clients.remove(&id);
+        self.hidden_nodes.remove(&id);
+        self.stream_format_requests.remove(&id);
+        if self.permissions_client == Some(id) {
+            self.permissions_client = None;
+        }
+        if matches!(&self.node_props_edit, Some(edit) if edit.node_id == id) {
+            self.node_props_edit = None;
+        }
+    }
+
+    fn handle_registry_global(&mut self, event: RegistryEvent) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        if !self.initial_sync_done && !self.reduced_view {
+            let budget = self.extra_state.node_budget.unwrap_or(DEFAULT_NODE_BUDGET);
+            let num_nodes = state
+                .global_objects
+                .values()
+                .filter(|object| object.type_ == ObjectType::Node)
+                .count();
+            if num_nodes > budget {
+                self.reduced_view = true;
+                self.rebuild_graph(&state);
+                return;
+            }
+        }
+        let id = event.id();
+        if let Some(object) = state.global_objects.get(&id) {
+            match event {
+                RegistryEvent::NodeAdded(_) => self.add_pipewire_node(&state, object),
+                RegistryEvent::PortAdded { .. } => self.add_pipewire_port(&state, object),
+                RegistryEvent::LinkAdded { .. } => self.add_pipewire_link(&state, object),
+                _ => {}
+            }
+            self.add_known_children(&state, id);
+        }
+    }
+
+    // ports and links which arrived before their node or port
+    // (anything already in the editor graph is skipped by `is_mirrored`)
+    fn add_known_children(&mut self, state: &PipewireState, id: u32) {
+        let topology = self.registry.topology();
+        let mut port_ids: Vec<u32> = topology.ports_of(id).map(|port| port.id).collect();
+        if topology.port(id).is_some() {
+            port_ids.push(id);
+        }
+        let link_ids: BTreeSet<u32> = port_ids
+            .iter()
+            .flat_map(|&port_id| topology.links_of(port_id).map(|link| link.id))
+            .collect();
+        for id in port_ids.into_iter().chain(link_ids) {
+            if let Some(object) = state.global_objects.get(&id) {
+                match object.type_ {
+                    ObjectType::Port => self.add_pipewire_port(state, object),
+                    ObjectType::Link => self.add_pipewire_link(state, object),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // re-create the editor graph from the registry snapshot
+    // (nodes which still exist keep their positions)
+    fn rebuild_graph(&mut self, state: &PipewireState) {
+        let positions: HashMap<u32, egui::Pos2> = self
+            .pipewire_id_to_node_id
+            .iter()
+            .filter_map(|(&pipewire_id, &node_id)| {
+                Some((pipewire_id, *self.state.node_positions.get(node_id)?))
+            })
+            .collect();
+        let group_positions: HashMap<NodeGroup, egui::Pos2> = self
+            .group_node_ids
+            .iter()
+            .filter_map(|(&group, &node_id)| {
+                Some((group, *self.state.node_positions.get(node_id)?))
+            })
+            .collect();
+        let pan_zoom = self.state.pan_zoom;
+        self.state = EditorState::new(1.0);
+        self.state.pan_zoom = pan_zoom;
+        self.group_node_ids.clear();
+        self.group_members.clear();
+        self.group_params.clear();
+        self.pipewire_id_to_node_id.clear();
+        self.pipewire_id_to_param_id.clear();
+        self.pipewire_id_to_connection.clear();
+        self.param_id_to_pipewire_id.clear();
+        self.pipewire_id_to_serial.clear();
+        self.next_node_y = Default::default();
+        // nodes before their ports before their links
+        let topology = self.registry.topology();
+        let ids: Vec<u32> = (topology.nodes().map(|node| node.id))
+            .chain(topology.ports().map(|port| port.id))
+            .chain(topology.links().map(|link| link.id))
+            .collect();
+        for id in ids {
+            if let Some(object) = state.global_objects.get(&id) {
+                match object.type_ {
+                    ObjectType::Node => self.add_pipewire_node(state, object),
+                    ObjectType::Port => self.add_pipewire_port(state, object),
+                    ObjectType::Link => self.add_pipewire_link(state, object),
+                    _ => {}
+                }
+            }
+        }
+        for (pipewire_id, position) in positions {
+            if let Some(&node_id) = self.pipewire_id_to_node_id.get(&pipewire_id) {
+                if !self.state.graph[node_id].user_data.position_pinned {
+                    self.state.node_positions.insert(node_id, position);
+                }
+            }
+        }
+        for (group, position) in group_positions {
+            if let Some(&node_id) = self.group_node_ids.get(&group) {
+                self.state.node_positions.insert(node_id, position);
+            }
+        }
+    }
+
+    // resync the editor graph when it drifted from the registry (e.g. after errors)
+    fn refresh_graph(&mut self) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let num_nodes = self.pipewire_id_to_node_id.len();
+        let num_links = self.pipewire_id_to_connection.len();
+        self.rebuild_graph(&state);
+        self.toasts.info(format!(
+            "Graph refreshed (nodes {} -> {}, links {} -> {})",
+            num_nodes,
+            self.pipewire_id_to_node_id.len(),
+            num_links,
+            self.pipewire_id_to_connection.len()
+        ));
+    }
+
+    fn update_node_label(&mut self, id: u32) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        self.registry.node_props_changed(&state, id);
+        if let Some(recorder) = &mut self.session_recorder {
+            recorder.record(&state, &[ChannelMessage::PipewireNodePropsChanged(id)]);
+        }
+        if let (Some(&node_id), Some(object)) = (
+            self.pipewire_id_to_node_id.get(&id),
+            state.global_objects.get(&id),
+        ) {
+            let label = pipewire_node_label(object, self.extra_state.node_label);
+            self.state.graph[node_id].label = with_id(label, id, self.extra_state.show_ids);
+        }
+        if let Some(edit) = self
+            .node_props_edit
+            .as_mut()
+            .filter(|edit| edit.node_id == id)
+        {
+            edit.current = NodePropsEdit::props(&state, id);
+        }
+    }
+
+    fn handle_permission_changed(&mut self, id: u32, old_permissions: u32) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let object = match state.global_objects.get(&id) {
+            Some(object) => object,
+            None => return,
+        };
+        let writable = PipewireObject::is_writable(object);
+        if let Some(&node_id) = self.pipewire_id_to_node_id.get(&id) {
+            let node = &mut self.state.graph[node_id];
+            let label = pipewire_node_label(object, self.extra_state.node_label);
+            node.label = with_id(label, id, self.extra_state.show_ids);
+            node.user_data.writable = writable;
+            node.user_data.destroyable = PipewireObject::is_destroyable(object);
+        }
+        let was_writable = Permission::from_bits_truncate(old_permissions).contains(Permission::W);
+        if writable != was_writable {
+            let name = PipewireObject::get_name(object).map_or("--", |(_k, v)| v);
+            let text = format!("{} {} ({})", object.type_, id, name);
+            if writable {
+                self.toasts
+                    .info(format!("Gained write permission on {}", text));
+            } else {
+                self.toasts
+                    .warning(format!("Lost write permission on {}", text));
+            }
+        }
+    }
+
+    fn remove_pipewire_node(&mut self, id: u32) {
+        if let Some(group) = self.group_members.remove(&id) {
+            // the group node goes away with its last node
+            if !self.group_members.values().any(|&other| other == group) {
+                if let Some(node_id) = self.group_node_ids.remove(&group) {
+                    self.remove_graph_node(node_id);
+                }
+            }
+            return;
+        }
+        if let Some(node_id) = self.pipewire_id_to_node_id.remove(&id) {
+            self.remove_graph_node(node_id);
+        }
+    }
+
+    // also drops the port mappings and the ports of group nodes peering with it
+    fn remove_graph_node(&mut self, node_id: NodeId) {
+        let graph = &mut self.state.graph;
+        if !graph.nodes.contains_key(node_id) {
+            return;
+        }
+        let param_ids: Vec<AnyParameterId> = graph[node_id]
+            .input_ids()
+            .map(AnyParameterId::from)
+            .chain(graph[node_id].output_ids().map(AnyParameterId::from))
+            .collect();
+        for param_id in param_ids {
+            if let Some(port_id) = self.param_id_to_pipewire_id.remove(&param_id) {
+                self.pipewire_id_to_param_id.remove(&port_id);
+            }
+        }
+        graph.remove_node(node_id);
+        self.state.node_positions.remove(node_id);
+        self.state.node_order.retain(|&other| other != node_id);
+        self.state.selected_nodes.retain(|&other| other != node_id);
+        let mut stale = vec![];
+        self.group_params
+            .retain(|&(group_node, peer, _), &mut param_id| {
+                if peer == node_id {
+                    stale.push(param_id);
+                }
+                group_node != node_id && peer != node_id
+            });
+        for param_id in stale {
+            match param_id {
+                AnyParameterId::Input(input) if graph.inputs.contains_key(input) => {
+                    graph.remove_input_param(input);
+                }
+                AnyParameterId::Output(output) if graph.outputs.contains_key(output) => {
+                    graph.remove_output_param(output);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn remove_pipewire_port(&mut self, id: u32) {
+        let graph = &mut self.state.graph;
+        if let Some(param_id) = self.pipewire_id_to_param_id.remove(&id) {
+            self.param_id_to_pipewire_id.remove(&param_id);
+            match param_id {
+                AnyParameterId::Input(input) if graph.inputs.contains_key(input) => {
+                    graph.remove_input_param(input);
+                }
+                AnyParameterId::Output(output) if graph.outputs.contains_key(output) => {
+                    graph.remove_output_param(output);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn remove_pipewire_link(&mut self, id: u32) {
+        let graph = &mut self.state.graph;
+        if let Some((output, input)) = self.pipewire_id_to_connection.remove(&id) {
+            // links of a collapsed group can share the connection of its node
+            let shared = self
+                .pipewire_id_to_connection
+                .values()
+                .any(|&connection| connection == (output, input));
+            if graph.connection(input) == Some(output) && !shared {
+                graph.remove_connection(input);
+            }
+            let port_ids: Vec<u32> = [output.into(), input.into()]
+                .iter()
+                .filter_map(|param_id| self.param_id_to_pipewire_id.get(param_id).copied())
+                .collect();
+            let state = self.pipewire_wrapper.state.clone();
+            let state = metrics::lock_ui(&state);
+            self.update_port_badges(&state, &port_ids);
+        }
+    }
+
+    // number of links on the port label e.g. "playback_FL [2]"
+    fn update_port_badges(&mut self, state: &PipewireState, port_ids: &[u32]) {
+        let counts = state.link_counts();
+        let graph = &mut self.state.graph;
+        for port_id in port_ids {
+            let (param_id, port) = match (
+                self.pipewire_id_to_param_id.get(port_id),
+                state.global_objects.get(port_id),
+            ) {
+                (Some(&param_id), Some(port)) => (param_id, port),
+                _ => continue,
+            };
+            let name = pipewire_port_label(port, self.extra_state.port_label);
+            let icons = port_flag_icons(port);
+            let label = match counts.get(port_id) {
+                Some(count) => format!("{} [{}]", name, count),
+                None => name.to_owned(),
+            };
+            let label = if icons.is_empty() {
+                label
+            } else {
+                format!("{} {}", label, icons)
+            };
+            let label = with_id(label, port.id, self.extra_state.show_ids);
+            match param_id {
+                AnyParameterId::Input(input) => {
+                    if let Some(param) = graph.inputs.get(input) {
+                        let node = &mut graph.nodes[param.node];
+                        for (name, _) in node.inputs.iter_mut().filter(|(_, id)| *id == input) {
+                            *name = label.clone();
+                        }
+                    }
+                }
+                AnyParameterId::Output(output) => {
+                    if let Some(param) = graph.outputs.get(output) {
+                        let node = &mut graph.nodes[param.node];
+                        for (name, _) in node.outputs.iter_mut().filter(|(_, id)| *id == output) {
+                            *name = label.clone();
                         }
                     }
-                    if ui.button("Destroy Link").clicked() {
-                        match (&self.extra_state.link_from, &self.extra_state.link_to) {
-                            (Some(from), Some(to)) => {
-                                self.pipewire_wrapper
-                                    .channel_sender
-                                    .send(ChannelMessage::LinkDestroy(from.clone(), to.clone()))
-                                    .unwrap();
+                }
+            }
+        }
+    }
+
+    // whether the global is already in the editor graph
+    // (a mapping of another serial is a removed global whose removal was missed, so drop it)
+    fn is_mirrored(&mut self, object: &GlobalObject<PropStore>) -> bool {
+        let id = object.id;
+        if !self.pipewire_id_to_node_id.contains_key(&id)
+            && !self.pipewire_id_to_param_id.contains_key(&id)
+            && !self.pipewire_id_to_connection.contains_key(&id)
+        {
+            return false;
+        }
+        let serial = PipewireObject::get_serial(object);
+        match self.pipewire_id_to_serial.get(&id) {
+            Some(known) if known.as_deref() == serial => true,
+            known => {
+                tracing::warn!(
+                    "id {} reused (object.serial {} -> {})",
+                    id,
+                    known.and_then(Option::as_deref).unwrap_or("--"),
+                    serial.unwrap_or("--")
+                );
+                // not `remove_pipewire_link` which locks the state already held by the caller
+                if let Some((output, input)) = self.pipewire_id_to_connection.remove(&id) {
+                    if self.state.graph.connection(input) == Some(output) {
+                        self.state.graph.remove_connection(input);
+                    }
+                }
+                self.remove_pipewire_port(id);
+                self.remove_pipewire_node(id);
+                false
+            }
+        }
+    }
+
+    fn is_pinned(&self, node: &GlobalObject<PropStore>) -> bool {
+        node.props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::NodeName))
+            .map_or(false, |name| self.extra_state.pinned_nodes.contains(name))
+    }
+
+    // nothing is collapsed until something is pinned
+    fn is_collapsed(&self, node: &GlobalObject<PropStore>) -> bool {
+        let name = node
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::NodeName));
+        !self.extra_state.pinned_nodes.is_empty()
+            && name.map_or(true, |name| {
+                !self.extra_state.pinned_nodes.contains(name)
+                    && !self.extra_state.expanded_nodes.contains(name)
+            })
+    }
+
+    // star/expand state is kept by node.name across sessions
+    fn update_pin(&mut self, pipewire_id: u32, update: impl FnOnce(&mut ExtraState, String)) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let name = state
+            .global_objects
+            .get(&pipewire_id)
+            .and_then(|object| object.props.as_ref()?.get_key(PropKey::NodeName));
+        if let Some(name) = name {
+            update(&mut self.extra_state, name.to_owned());
+            // pinning the first node collapses every other one
+            self.rebuild_graph(&state);
+        }
+    }
+
+    fn serial(&self, pipewire_id: u32) -> Option<String> {
+        self.pipewire_id_to_serial
+            .get(&pipewire_id)
+            .cloned()
+            .flatten()
+    }
+
+    fn add_pipewire_node(&mut self, state: &PipewireState, object: &GlobalObject<PropStore>) {
+        if self.is_mirrored(object) || self.hidden_nodes.contains(&object.id) {
+            return;
+        }
+        if ClientApi::of(object).map_or(false, |api| {
+            self.extra_state.hidden_client_apis.contains(&api)
+        }) {
+            return;
+        }
+        if let Some(group) =
+            NodeGroup::of(object).filter(|group| self.collapsed_groups.contains_key(group))
+        {
+            self.group_members.insert(object.id, group);
+            if !self.group_node_ids.contains_key(&group) {
+                self.add_group_node(state, group);
+            }
+            return;
+        }
+        let pinned_position = object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::NodeName))
+            .and_then(|name| self.extra_state.pinned_positions.get(name).copied());
+        let label = pipewire_node_label(object, self.extra_state.node_label);
+        let node_id = self.state.graph.add_node(
+            with_id(label, object.id, self.extra_state.show_ids),
+            GraphNodeData {
+                template: GraphNodeTemplate::PipewireNode,
+                pipewire_id: Some(object.id),
+                own_stream: PipewireObject::is_own(object),
+                device_id: PipewireObject::get_device_id(object),
+                route_device: PipewireObject::get_card_profile_device(object),
+                bluez5_codec: PipewireObject::get_bluez5_codec(object).map(str::to_owned),
+                writable: PipewireObject::is_writable(object),
+                destroyable: PipewireObject::is_destroyable(object),
+                media_class: PipewireObject::get_media_class(object).map(str::to_owned),
+                client: PipewireObject::get_client_label(object).map(str::to_owned),
+                pinned: self.is_pinned(object),
+                collapsed: self.is_collapsed(object),
+                position_pinned: pinned_position.is_some(),
+                group: NodeGroup::of(object),
+                process: PipewireObject::get_process(state, object),
+                client_api: ClientApi::of(object),
+                network: NetworkProtocol::of(object),
+            },
+            |_, _| {},
+        );
+
+        // sources on the left, sinks on the right
+        let column = match PipewireObject::get_media_class(object) {
+            Some(c) if c.contains("Source") || c.contains("Output") => 0,
+            Some(c) if c.contains("Sink") || c.contains("Input") => 2,
+            _ => 1,
+        };
+        let template_position = object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::NodeName))
+            .and_then(|name| self.template_positions.remove(name));
+        let position = pinned_position.or(template_position).unwrap_or_else(|| {
+            let position = egui::pos2(
+                50.0 + 350.0 * column as f32,
+                50.0 + self.next_node_y[column],
+            );
+            self.next_node_y[column] += 150.0;
+            position
+        });
+
+        self.state.node_positions.insert(node_id, position);
+        self.state.node_order.push(node_id);
+        self.pipewire_id_to_node_id.insert(object.id, node_id);
+        self.pipewire_id_to_serial.insert(
+            object.id,
+            PipewireObject::get_serial(object).map(str::to_owned),
+        );
+    }
+
+    // single node with the external links of the group's nodes
+    fn add_group_node(&mut self, state: &PipewireState, group: NodeGroup) {
+        let node_id = self.state.graph.add_node(
+            format!("🗀 {}", group.label(state)),
+            GraphNodeData {
+                template: GraphNodeTemplate::NodeGroup,
+                pipewire_id: None,
+                own_stream: false,
+                device_id: None,
+                route_device: None,
+                bluez5_codec: None,
+                writable: false,
+                destroyable: false,
+                media_class: None,
+                client: None,
+                pinned: false,
+                collapsed: false,
+                position_pinned: false,
+                group: Some(group),
+                process: None,
+                client_api: None,
+                network: None,
+            },
+            |_, _| {},
+        );
+        let position = egui::pos2(400.0, 50.0 + self.next_node_y[1]);
+        self.next_node_y[1] += 150.0;
+        self.state.node_positions.insert(node_id, position);
+        self.state.node_order.push(node_id);
+        self.group_node_ids.insert(group, node_id);
+    }
+
+    fn add_pipewire_port(&mut self, state: &PipewireState, object: &GlobalObject<PropStore>) {
+        if (self.reduced_view && PipewireObject::is_monitor(object)) || self.is_mirrored(object) {
+            return;
+        }
+        let parent_node = match PipewireObject::get_parent_node_id(object) {
+            Some(parent_node) => parent_node,
+            None => {
+                tracing::error!("invalid port (node.id not found) {}", object.id);
+                return;
+            }
+        };
+        // links of collapsed groups are added to the group node instead
+        if self.group_members.contains_key(&parent_node) {
+            return;
+        }
+        // like hidden nodes, links to these ports stay pending
+        if state
+            .global_objects
+            .get(&parent_node)
+            .map_or(false, |node| self.is_collapsed(node))
+        {
+            return;
+        }
+        let node_id = match self.pipewire_id_to_node_id.get(&parent_node) {
+            Some(&node_id) => node_id,
+            None if self.hidden_nodes.contains(&parent_node) => return,
+            // added together with the node (see `add_known_children`)
+            None => {
+                tracing::debug!("port {} waiting for node {}", object.id, parent_node);
+                return;
+            }
+        };
+        let name = pipewire_port_label(object, self.extra_state.port_label);
+        let icons = port_flag_icons(object);
+        let name = if icons.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{} {}", name, icons)
+        };
+        let name = with_id(name, object.id, self.extra_state.show_ids);
+        let typ = port_data_type(state, object);
+        // resolution and framerate are shown on the node
+        if typ == PortType::Video && self.tracked_ports.insert(object.id) {
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::PortParamsQuery(vec![object.id]));
+        }
+        let graph = &mut self.state.graph;
+        let param_id = if PipewireObject::is_input(object) {
+            AnyParameterId::Input(graph.add_input_param(
+                node_id,
+                name,
+                typ,
+                PortValue::Port,
+                InputParamKind::ConnectionOnly,
+                true,
+            ))
+        } else if PipewireObject::is_output(object) {
+            AnyParameterId::Output(graph.add_output_param(node_id, name, typ))
+        } else {
+            return;
+        };
+        self.pipewire_id_to_param_id.insert(object.id, param_id);
+        self.param_id_to_pipewire_id.insert(param_id, object.id);
+        self.pipewire_id_to_serial.insert(
+            object.id,
+            PipewireObject::get_serial(object).map(str::to_owned),
+        );
+
+        // group ports by channel (e.g. "playback_FL" next to "monitor_FL")
+        let param_id_to_pipewire_id = &self.param_id_to_pipewire_id;
+        let sort_key = |name: &String, param_id: AnyParameterId| {
+            let channel = param_id_to_pipewire_id
+                .get(&param_id)
+                .and_then(|id| state.global_objects.get(id))
+                .and_then(PipewireObject::get_channel);
+            (PipewireObject::channel_sort_key(channel), name.clone())
+        };
+        let node = &mut graph.nodes[node_id];
+        node.inputs
+            .sort_by_cached_key(|(name, input)| sort_key(name, (*input).into()));
+        node.outputs
+            .sort_by_cached_key(|(name, output)| sort_key(name, (*output).into()));
+    }
+
+    fn add_pipewire_link(&mut self, state: &PipewireState, object: &GlobalObject<PropStore>) {
+        let (output_port, input_port) = match PipewireObject::get_link_ports(object) {
+            Some(ports) => ports,
+            None => return,
+        };
+        if self.is_mirrored(object) {
+            return;
+        }
+        let port_group = |port_id: u32| {
+            let port = state.global_objects.get(&port_id)?;
+            let node_id = PipewireObject::get_parent_node_id(port)?;
+            self.group_members.get(&node_id).copied()
+        };
+        let groups = (port_group(output_port), port_group(input_port));
+        if groups != (None, None) {
+            self.add_group_link(state, object, groups);
+            return;
+        }
+        self.update_port_badges(state, &[output_port, input_port]);
+        match (
+            self.pipewire_id_to_param_id.get(&output_port),
+            self.pipewire_id_to_param_id.get(&input_port),
+        ) {
+            (Some(&AnyParameterId::Output(output)), Some(&AnyParameterId::Input(input))) => {
+                self.state.graph.add_connection(output, input);
+                self.pipewire_id_to_connection
+                    .insert(object.id, (output, input));
+                self.pipewire_id_to_serial.insert(
+                    object.id,
+                    PipewireObject::get_serial(object).map(str::to_owned),
+                );
+            }
+            (Some(_), Some(_)) => {
+                tracing::error!("invalid link (port direction mismatch) {}", object.id);
+            }
+            // the port might not have arrived yet (see `add_known_children`)
+            // (ports of hidden nodes or monitor ports in the reduced view are never added)
+            _ => {}
+        }
+    }
+
+    // aggregated per peer node on the group node (links within the group are not shown)
+    fn add_group_link(
+        &mut self,
+        state: &PipewireState,
+        object: &GlobalObject<PropStore>,
+        (output_group, input_group): (Option<NodeGroup>, Option<NodeGroup>),
+    ) {
+        let (output_port, input_port) = match PipewireObject::get_link_ports(object) {
+            Some(ports) => ports,
+            None => return,
+        };
+        if output_group.is_some() && output_group == input_group {
+            return;
+        }
+        let typ = state
+            .global_objects
+            .get(&output_port)
+            .map_or(PortType::Other, |port| port_data_type(state, port));
+        // (graph node, port param of the visible side) for each end
+        let mut ends =
+            [(output_group, output_port), (input_group, input_port)].map(|(group, port_id)| {
+                match group {
+                    Some(group) => Some((*self.group_node_ids.get(&group)?, None)),
+                    None => {
+                        let param_id = *self.pipewire_id_to_param_id.get(&port_id)?;
+                        let node_id = match param_id {
+                            AnyParameterId::Input(input) => self.state.graph.get_input(input).node,
+                            AnyParameterId::Output(output) => {
+                                self.state.graph.get_output(output).node
                             }
-                            _ => {}
+                        };
+                        Some((node_id, Some(param_id)))
+                    }
+                }
+            });
+        let (output_node, input_node) = match ends {
+            [Some((output_node, _)), Some((input_node, _))] => (output_node, input_node),
+            // the port of the visible side might not have arrived yet
+            _ => return,
+        };
+        for (i, (node_id, param_id)) in ends.iter_mut().flatten().enumerate() {
+            if param_id.is_some() {
+                continue;
+            }
+            let is_input = i == 1;
+            let peer = if is_input { output_node } else { input_node };
+            let name = self.state.graph[peer].label.clone();
+            let graph = &mut self.state.graph;
+            let group_param = *self
+                .group_params
+                .entry((*node_id, peer, is_input))
+                .or_insert_with(|| {
+                    if is_input {
+                        AnyParameterId::Input(graph.add_input_param(
+                            *node_id,
+                            name,
+                            typ,
+                            PortValue::Port,
+                            InputParamKind::ConnectionOnly,
+                            true,
+                        ))
+                    } else {
+                        AnyParameterId::Output(graph.add_output_param(*node_id, name, typ))
+                    }
+                });
+            *param_id = Some(group_param);
+        }
+        let params = ends.map(|end| end.and_then(|(_, param_id)| param_id));
+        if let [Some(AnyParameterId::Output(output)), Some(AnyParameterId::Input(input))] = params {
+            self.state.graph.add_connection(output, input);
+            self.pipewire_id_to_connection
+                .insert(object.id, (output, input));
+            self.pipewire_id_to_serial.insert(
+                object.id,
+                PipewireObject::get_serial(object).map(str::to_owned),
+            );
+        }
+    }
+
+    // the editor already removed the node (close button), so put it back until `global_remove`
+    // confirms that the pipewire node was destroyed
+    fn delete_pipewire_node(&mut self, node: &Node<GraphNodeData>, position: Option<egui::Pos2>) {
+        let pipewire_id = match node.user_data.pipewire_id {
+            Some(pipewire_id) => pipewire_id,
+            None => return,
+        };
+        let message = self.destroy_message(pipewire_id);
+        let state = self.pipewire_wrapper.state.clone();
+        self.send_or_confirm(&metrics::lock_ui(&state), vec![message]);
+
+        self.pipewire_id_to_node_id.remove(&pipewire_id);
+        for param_id in node
+            .input_ids()
+            .map(AnyParameterId::from)
+            .chain(node.output_ids().map(AnyParameterId::from))
+        {
+            if let Some(port_id) = self.param_id_to_pipewire_id.remove(&param_id) {
+                self.pipewire_id_to_param_id.remove(&port_id);
+            }
+        }
+        let graph = &self.state.graph;
+        self.pipewire_id_to_connection.retain(|_, (output, input)| {
+            graph.outputs.contains_key(*output) && graph.inputs.contains_key(*input)
+        });
+        self.add_pipewire_nodes(&HashSet::from([pipewire_id]));
+        if let (Some(&node_id), Some(position)) =
+            (self.pipewire_id_to_node_id.get(&pipewire_id), position)
+        {
+            self.state.node_positions.insert(node_id, position);
+        }
+    }
+
+    // connection dragged in the editor for a link which is not going to be created
+    fn revert_editor_connection(&mut self, action: &LinkAction) {
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        if state.find_link(&action.from, &action.to).is_some() {
+            return;
+        }
+        let param_of = |(k, v): &(String, String)| {
+            let port = state.find_object_by_prop(k, v)?;
+            self.pipewire_id_to_param_id.get(&port.id).copied()
+        };
+        if let (Some(AnyParameterId::Output(output)), Some(AnyParameterId::Input(input))) =
+            (param_of(&action.from), param_of(&action.to))
+        {
+            if self.state.graph.connection(input) == Some(output) {
+                self.state.graph.remove_connection(input);
+            }
+        }
+    }
+
+    // tone generators are stopped by dropping their stream rather than destroying the global
+    fn destroy_message(&self, pipewire_id: u32) -> ChannelMessage {
+        match self.user_state.tone_generators.get(&pipewire_id) {
+            Some((name, _)) => ChannelMessage::ToneGeneratorDestroy(name.clone()),
+            None => ChannelMessage::ObjectDestroy(pipewire_id, self.serial(pipewire_id)),
+        }
+    }
+
+    // replace the placeholder created by the node finder with a request to create the real node
+    fn create_from_template(&mut self, node_id: NodeId) {
+        let template = self.state.graph[node_id].user_data.template;
+        let position = self.state.node_positions.remove(node_id);
+        self.state.graph.remove_node(node_id);
+        self.state.node_order.retain(|&other| other != node_id);
+        self.state.selected_nodes.retain(|&other| other != node_id);
+
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let prefix = template.node_name_prefix();
+        let name = (1..)
+            .map(|i| format!("{}_{}", prefix, i))
+            .find(|name| {
+                // the stream of a tone generator may not be registered yet
+                state
+                    .find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+                    .is_none()
+                    && !state.tone_generators.contains_key(name)
+            })
+            .unwrap();
+        drop(state);
+        let message = match template {
+            GraphNodeTemplate::ToneGenerator => {
+                ChannelMessage::ToneGeneratorCreate(name.clone(), ToneSettings::default())
+            }
+            _ => match template.virtual_device(name.clone()) {
+                Some(device) => ChannelMessage::VirtualDeviceCreate(device),
+                None => return,
+            },
+        };
+        if let Some(position) = position {
+            self.template_positions.insert(name, position);
+        }
+        self.pipewire_wrapper.channel_sender.send_or_log(message);
+    }
+
+    //
+    // bulk operations on the selected nodes
+    //
+
+    // add nodes together with their ports and links
+    fn add_pipewire_nodes(&mut self, node_ids: &HashSet<u32>) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let is_node_port = |port_id: u32| {
+            state
+                .global_objects
+                .get(&port_id)
+                .and_then(PipewireObject::get_parent_node_id)
+                .map_or(false, |node| node_ids.contains(&node))
+        };
+        for object in state.global_objects.values() {
+            if node_ids.contains(&object.id) {
+                self.add_pipewire_node(&state, object);
+            }
+        }
+        for object in state.global_objects.values() {
+            if object.type_ == ObjectType::Port && is_node_port(object.id) {
+                self.add_pipewire_port(&state, object);
+            }
+        }
+        for object in state.global_objects.values() {
+            if let Some((output_port, input_port)) = PipewireObject::get_link_ports(object) {
+                if is_node_port(output_port) || is_node_port(input_port) {
+                    self.add_pipewire_link(&state, object);
+                }
+            }
+        }
+    }
+
+    // the editor moves the other selected nodes along with the dragged one
+    fn finish_node_drag(&mut self, ctx: &egui::Context) {
+        if self.dragged_nodes.is_empty() || ctx.input().pointer.primary_down() {
+            return;
+        }
+        let mut dragged = std::mem::take(&mut self.dragged_nodes);
+        if dragged
+            .iter()
+            .any(|node_id| self.state.selected_nodes.contains(node_id))
+        {
+            dragged.extend(self.state.selected_nodes.iter().copied());
+        }
+        for node_id in dragged {
+            if let Some(pos) = self.state.node_positions.get_mut(node_id) {
+                if self.extra_state.grid.snap {
+                    *pos = self.extra_state.grid.snap(*pos);
+                }
+                self.update_pinned_position(node_id);
+            }
+        }
+    }
+
+    // moving a pinned node by hand chooses its new position
+    fn update_pinned_position(&mut self, node_id: NodeId) {
+        let node = &self.state.graph[node_id];
+        if !node.user_data.position_pinned {
+            return;
+        }
+        let name = node
+            .user_data
+            .pipewire_id
+            .and_then(|pipewire_id| self.node_name(pipewire_id));
+        if let (Some(name), Some(&pos)) = (name, self.state.node_positions.get(node_id)) {
+            self.extra_state.pinned_positions.insert(name, pos);
+        }
+    }
+
+    fn node_name(&self, pipewire_id: u32) -> Option<String> {
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        let object = state.global_objects.get(&pipewire_id)?;
+        Some(
+            object
+                .props
+                .as_ref()?
+                .get_key(PropKey::NodeName)?
+                .to_owned(),
+        )
+    }
+
+    // pinned positions are kept by node.name across sessions
+    fn toggle_pinned_position(&mut self, pipewire_id: u32) {
+        let node_id = match self.pipewire_id_to_node_id.get(&pipewire_id) {
+            Some(&node_id) => node_id,
+            None => return,
+        };
+        let name = match self.node_name(pipewire_id) {
+            Some(name) => name,
+            None => {
+                self.toasts
+                    .warning("Only nodes with a node.name can be pinned");
+                return;
+            }
+        };
+        let user_data = &mut self.state.graph[node_id].user_data;
+        user_data.position_pinned = !user_data.position_pinned;
+        if user_data.position_pinned {
+            self.update_pinned_position(node_id);
+        } else {
+            self.extra_state.pinned_positions.remove(&name);
+        }
+    }
+
+    //
+    // device/client group boxes
+    //
+
+    // box around the nodes of each device/client with more than one node
+    // (returns the group whose collapse button was clicked)
+    fn show_node_groups(&mut self, ui: &mut egui::Ui) -> Option<NodeGroup> {
+        let mut bounds: BTreeMap<NodeGroup, (egui::Rect, usize)> = BTreeMap::new();
+        for &node_id in self.pipewire_id_to_node_id.values() {
+            let (node, position) = match (
+                self.state.graph.nodes.get(node_id),
+                self.state.node_positions.get(node_id),
+            ) {
+                (Some(node), Some(&position)) => (node, position),
+                _ => continue,
+            };
+            if let Some(group) = node.user_data.group {
+                let rect = egui::Rect::from_min_size(position, estimate_node_size(node));
+                let entry = bounds.entry(group).or_insert((rect, 0));
+                entry.0 = entry.0.union(rect);
+                entry.1 += 1;
+            }
+        }
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let offset = self.state.pan_zoom.pan + self.editor_rect.min.to_vec2();
+        let painter = ui.painter().clone();
+        let mut collapse = None;
+        for (group, (rect, count)) in bounds.into_iter().filter(|(_, (_, count))| *count > 1) {
+            let label = group.label(&state);
+            let color = egui::color::Hsva::new(client_hue(&label), 0.4, 0.5, 0.12);
+            let rect = rect.translate(offset).expand(12.0);
+            let rect = egui::Rect::from_min_max(rect.min - egui::vec2(0.0, 22.0), rect.max);
+            painter.rect(
+                rect,
+                6.0,
+                color,
+                egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+            );
+            let header = egui::Rect::from_min_size(
+                rect.min + egui::vec2(6.0, 2.0),
+                egui::vec2(rect.width() - 12.0, 18.0),
+            );
+            let button = egui::Button::new(format!("⏷ {} ({} nodes)", label, count))
+                .small()
+                .frame(false);
+            if ui
+                .put(header, button)
+                .on_hover_text("Collapse into a single node")
+                .clicked()
+            {
+                collapse = Some(group);
+            }
+        }
+        collapse
+    }
+
+    fn set_group_collapsed(&mut self, group: NodeGroup, collapsed: bool) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        if collapsed {
+            let members: Vec<(u32, egui::Pos2)> = self
+                .pipewire_id_to_node_id
+                .iter()
+                .filter(|(_, &node_id)| self.state.graph[node_id].user_data.group == Some(group))
+                .filter_map(|(&pipewire_id, &node_id)| {
+                    Some((pipewire_id, *self.state.node_positions.get(node_id)?))
+                })
+                .collect();
+            let origin = members
+                .iter()
+                .map(|(_, position)| *position)
+                .reduce(|a, b| a.min(b))
+                .unwrap_or_default();
+            let offsets = members
+                .into_iter()
+                .map(|(pipewire_id, position)| (pipewire_id, position - origin))
+                .collect();
+            self.collapsed_groups.insert(group, offsets);
+            self.rebuild_graph(&state);
+            if let Some(&node_id) = self.group_node_ids.get(&group) {
+                self.state.node_positions.insert(node_id, origin);
+            }
+        } else {
+            let origin = self
+                .group_node_ids
+                .get(&group)
+                .and_then(|&node_id| self.state.node_positions.get(node_id).copied());
+            let offsets = self.collapsed_groups.remove(&group).unwrap_or_default();
+            self.rebuild_graph(&state);
+            // unfold around where the group node was moved to
+            if let Some(origin) = origin {
+                for (pipewire_id, offset) in offsets {
+                    if let Some(&node_id) = self.pipewire_id_to_node_id.get(&pipewire_id) {
+                        if !self.state.graph[node_id].user_data.position_pinned {
+                            self.state.node_positions.insert(node_id, origin + offset);
                         }
                     }
+                }
+            }
+        }
+    }
+
+    // select the graph node of a pipewire object (or the node owning a port/link) and center it
+    fn focus_pipewire_object(&mut self, id: u32) {
+        let pipewire_node_id = {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            let object = match state.global_objects.get(&id) {
+                Some(object) => object,
+                None => return,
+            };
+            let port_id = PipewireObject::get_link_ports(object).map_or(id, |(output, _)| output);
+            state
+                .global_objects
+                .get(&port_id)
+                .and_then(PipewireObject::get_parent_node_id)
+                .unwrap_or(id)
+        };
+        if let Some(&node_id) = self.pipewire_id_to_node_id.get(&pipewire_node_id) {
+            let center = self.state.node_positions[node_id]
+                + estimate_node_size(&self.state.graph[node_id]) / 2.0;
+            self.center_view(center, self.state.pan_zoom.zoom);
+            self.state.selected_nodes = vec![node_id];
+        }
+    }
+
+    // players of the output streams matched by `application.process.id`
+    fn update_media_players(&mut self) {
+        self.user_state.media_players.clear();
+        if !self.extra_state.media_controls {
+            self.mpris_watcher = None;
+            return;
+        }
+        let mpris_watcher = self.mpris_watcher.get_or_insert_with(MprisWatcher::spawn);
+        if let Some(e) = mpris_watcher.take_error() {
+            tracing::error!("{}", e);
+            self.toasts
+                .error(format!("Cannot list media players ({})", e));
+            self.extra_state.media_controls = false;
+            self.mpris_watcher = None;
+            return;
+        }
+        let players = mpris_watcher.players.lock().unwrap();
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        for node in self.registry.topology().nodes() {
+            let object = state.global_objects.get(&node.id);
+            let is_output_stream = object
+                .and_then(PipewireObject::get_media_class)
+                .map_or(false, |media_class| {
+                    media_class.starts_with("Stream/Output")
                 });
-            });
+            let player = object
+                .and_then(|object| object.props.as_ref()?.get_key(PropKey::AppProcessId))
+                .and_then(|pid| players.get(&pid.parse().ok()?));
+            if let (true, Some(player)) = (is_output_stream, player) {
+                self.user_state
+                    .media_players
+                    .insert(node.id, player.clone());
+            }
+        }
+    }
 
-        //
-        // node graph
-        //
+    // fills the native window of a popped out instance
+    fn window(&self, ctx: &egui::Context, title: &str) -> egui::Window<'static> {
+        let window = egui::Window::new(title);
+        match self.pop_out {
+            Some(_) => window.title_bar(false).fixed_rect(ctx.available_rect()),
+            None => window,
+        }
+    }
 
-        let graph_response = egui::CentralPanel::default()
-            .show(ctx, |ui| {
-                self.state
-                    .draw_graph_editor(ui, AllMyNodeTemplates, &mut self.user_state)
-            })
-            .inner;
-        for node_response in graph_response.node_responses {
-            // Here, we ignore all other graph events. But you may find
-            // some use for them. For example, by playing a sound when a new
-            // connection is created
-            if let NodeResponse::User(user_event) = node_response {
-                match user_event {
-                    MyResponse::SetActiveNode(node) => self.user_state.active_node = Some(node),
-                    MyResponse::ClearActiveNode => self.user_state.active_node = None,
-                }
-            }
-        }
-
-        if let Some(node) = self.user_state.active_node {
-            if self.state.graph.nodes.contains_key(node) {
-                let text = match evaluate_node(&self.state.graph, node, &mut HashMap::new()) {
-                    Ok(value) => format!("The result is: {:?}", value),
-                    Err(err) => format!("Execution error: {}", err),
-                };
-                ctx.debug_painter().text(
-                    egui::pos2(10.0, 35.0),
-                    egui::Align2::LEFT_TOP,
-                    text,
-                    TextStyle::Button.resolve(&ctx.style()),
-                    egui::Color32::WHITE,
+    // the window moves to a new process of this app (see `PipewireGraphApp::popped_out`)
+    fn spawn_pop_out(&mut self, pop_out: PopOut) {
+        self.popped_out
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        let child = std::env::current_exe().and_then(|exe| {
+            Command::new(exe)
+                .args(["--pop-out", pop_out.name()])
+                .spawn()
+        });
+        match child {
+            Ok(child) => {
+                self.popped_out.push(child);
+                match pop_out {
+                    PopOut::Object => self.extra_state.window_object = false,
+                    PopOut::Mixer => self.extra_state.window_mixer = false,
+                }
+            }
+            Err(e) => self
+                .toasts
+                .error(format!("Cannot pop out the window ({})", e)),
+        }
+    }
+
+    // render the canvas with the same approximated layout as the minimap
+    fn export_svg(&mut self) -> String {
+        let graph = &self.state.graph;
+        let mut port_positions = HashMap::new();
+        let mut nodes = vec![];
+        for (node_id, &pos) in &self.state.node_positions {
+            let node = &graph[node_id];
+            let rect = egui::Rect::from_min_size(pos, estimate_node_size(node));
+            let params = node
+                .inputs
+                .iter()
+                .map(|(name, input)| (name, AnyParameterId::from(*input), rect.left()))
+                .chain(
+                    node.outputs
+                        .iter()
+                        .map(|(name, output)| (name, AnyParameterId::from(*output), rect.right())),
+                );
+            let mut ports = vec![];
+            for (i, (name, param_id, x)) in params.enumerate() {
+                let port_pos = egui::pos2(x, rect.top() + 40.0 + 20.0 * i as f32);
+                port_positions.insert(param_id, port_pos);
+                ports.push((port_pos, name.clone()));
+            }
+            nodes.push(SvgNode {
+                rect,
+                label: node.label.clone(),
+                ports,
+            });
+        }
+        let mut wires = vec![];
+        for (input, output) in graph.iter_connections() {
+            let positions = (
+                port_positions.get(&output.into()),
+                port_positions.get(&input.into()),
+            );
+            if let (Some(&from), Some(&to)) = positions {
+                let color = graph[output].typ.data_type_color(&mut self.user_state);
+                let style = graph[graph[output].node].user_data.wire_style(
+                    output,
+                    input,
+                    graph,
+                    color,
+                    &mut self.user_state,
                 );
+                wires.push(SvgWire { from, to, style });
+            }
+        }
+        export::to_svg(&nodes, &wires)
+    }
+
+    //
+    // translate editor actions into pipewire requests
+    //
+
+    // only what the sandbox exposes is visible (e.g. portal camera nodes of a Flatpak)
+    fn restricted(&self) -> bool {
+        self.extra_state.restricted_mode.unwrap_or(matches!(
+            self.sandbox,
+            Some(Sandbox::Flatpak | Sandbox::Snap)
+        ))
+    }
+
+    // revert the editor instead of issuing an operation the server would reject
+    fn is_link_action_permitted(&self, output: OutputId, input: InputId, create: bool) -> bool {
+        if !self.restricted() {
+            return true;
+        }
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        if create {
+            state.can_create_links()
+        } else {
+            self.pipewire_id_to_connection
+                .iter()
+                .find(|(_, &connection)| connection == (output, input))
+                .and_then(|(id, _)| state.global_objects.get(id))
+                .map_or(false, PipewireObject::is_destroyable)
+        }
+    }
+
+    fn send_link_message(&mut self, output: OutputId, input: InputId, create: bool) {
+        if !self.is_link_action_permitted(output, input, create) {
+            if create {
+                self.state.graph.remove_connection(input);
+                self.toasts
+                    .warning("Creating links is not permitted in restricted mode");
             } else {
-                self.user_state.active_node = None;
+                self.state.graph.add_connection(output, input);
+                self.toasts
+                    .warning("Removing this link is not permitted in restricted mode");
             }
+            return;
+        }
+        let ports = (
+            self.param_id_to_pipewire_id.get(&output.into()),
+            self.param_id_to_pipewire_id.get(&input.into()),
+        );
+        if let (Some(&output_port), Some(&input_port)) = ports {
+            if create {
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                if let Some(warning) =
+                    session_manager::link_warning(&state, output_port, input_port)
+                {
+                    self.toasts.warning(warning);
+                }
+            }
+            let from = PipewireObject::id_key(output_port);
+            let to = PipewireObject::id_key(input_port);
+            let flags = self.link_flags(&from, &to, create);
+            self.perform_link_action(LinkAction {
+                create,
+                from,
+                to,
+                flags,
+                force: false,
+            });
         }
     }
-}
 
-type OutputsCache = HashMap<OutputId, MyValueType>;
+    // flags of the link to destroy so that undo recreates it as it was
+    fn link_flags(
+        &self,
+        from: &(String, String),
+        to: &(String, String),
+        create: bool,
+    ) -> LinkFlags {
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        let existing = state
+            .find_link(from, to)
+            .and_then(|link_id| state.link_flags.get(&link_id));
+        match existing {
+            Some(&flags) if !create => flags,
+            _ => self.extra_state.link_flags,
+        }
+    }
 
-/// Recursively evaluates all dependencies of this node, then evaluates the node itself.
-pub fn evaluate_node(
-    graph: &MyGraph,
-    node_id: NodeId,
-    outputs_cache: &mut OutputsCache,
-) -> anyhow::Result<MyValueType> {
-    // To solve a similar problem as creating node types above, we define an
-    // Evaluator as a convenience. It may be overkill for this small example,
-    // but something like this makes the code much more readable when the
-    // number of nodes starts growing.
-
-    struct Evaluator<'a> {
-        graph: &'a MyGraph,
-        outputs_cache: &'a mut OutputsCache,
-        node_id: NodeId,
+    // links closing a loop (e.g. monitor -> effect -> same sink) wait for the confirmation
+    fn perform_link_action(&mut self, action: LinkAction) {
+        if action.create {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            let topology = self.registry.topology();
+            let node_of = |(k, v): &(String, String)| {
+                let port = state.find_object_by_prop(k, v)?;
+                Some(topology.port(port.id)?.node_id)
+            };
+            let feedback_loop = node_of(&action.from)
+                .zip(node_of(&action.to))
+                .and_then(|(output, input)| topology.feedback_loop(output, input));
+            drop(state);
+            if let Some(nodes) = feedback_loop {
+                for node in nodes {
+                    if !self.user_state.feedback_loop.contains(&node) {
+                        self.user_state.feedback_loop.push(node);
+                    }
+                }
+                self.feedback_links.push(action);
+                return;
+            }
+        }
+        self.send_link_action(action);
     }
-    impl<'a> Evaluator<'a> {
-        fn new(graph: &'a MyGraph, outputs_cache: &'a mut OutputsCache, node_id: NodeId) -> Self {
-            Self {
-                graph,
-                outputs_cache,
-                node_id,
+
+    fn send_link_action(&mut self, action: LinkAction) {
+        // rejected by the pipewire thread, and undoing it would destroy the existing link
+        let duplicate = action.create
+            && !action.force
+            && metrics::lock_ui(&self.pipewire_wrapper.state)
+                .find_link(&action.from, &action.to)
+                .is_some();
+        self.pipewire_wrapper
+            .channel_sender
+            .send_or_log(action.to_message());
+        if !duplicate {
+            self.link_history.push(action);
+        }
+    }
+
+    //
+    // undo/redo
+    //
+
+    fn undo_link_action(&mut self) {
+        for action in self.link_history.undo() {
+            self.reconcile_link_action(action);
+        }
+    }
+
+    fn redo_link_action(&mut self) {
+        for action in self.link_history.redo() {
+            self.reconcile_link_action(action);
+        }
+    }
+
+    // destroy the links of a node in one message, undone together by Ctrl+Z
+    fn disconnect_all(&mut self, node_id: NodeId) {
+        let links = self.links_of_nodes(&[node_id]);
+        if links.is_empty() {
+            return;
+        }
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let actions: Vec<LinkAction> = links
+            .iter()
+            .filter_map(|&(link_id, _)| {
+                let (output, input) = state
+                    .global_objects
+                    .get(&link_id)
+                    .and_then(PipewireObject::get_link_ports)?;
+                Some(LinkAction {
+                    create: false,
+                    from: port_key(state.global_objects.get(&output)?),
+                    to: port_key(state.global_objects.get(&input)?),
+                    flags: state.link_flags.get(&link_id).copied().unwrap_or_default(),
+                    force: false,
+                })
+            })
+            .collect();
+        // otherwise the confirmation window lists them
+        if self.extra_state.skip_confirmation {
+            self.toasts.info(format!(
+                "Disconnected {} links (Ctrl+Z to undo)",
+                links.len()
+            ));
+        }
+        self.send_or_confirm(&state, vec![ChannelMessage::ObjectsDestroy(links)]);
+        drop(state);
+        // undoing a cancelled one finds the links still there and does nothing
+        self.link_history.push_all(actions);
+    }
+
+    // only issue the operation if the registry doesn't already reflect it
+    fn reconcile_link_action(&mut self, action: LinkAction) {
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        let ports_exist = state
+            .find_object_by_prop(&action.from.0, &action.from.1)
+            .is_some()
+            && state
+                .find_object_by_prop(&action.to.0, &action.to.1)
+                .is_some();
+        if !ports_exist {
+            tracing::error!("undo/redo ports not found {:?}", action);
+            self.toasts
+                .error("Cannot undo/redo since the ports no longer exist");
+            return;
+        }
+        if state.find_link(&action.from, &action.to).is_some() == action.create {
+            tracing::info!("undo/redo already applied {:?}", action);
+            return;
+        }
+        self.pipewire_wrapper
+            .channel_sender
+            .send_or_log(action.to_message());
+    }
+
+    fn set_device_route_volume(&self, device_id: u32, index: i32, route_device: i32, percent: u32) {
+        let channels = self
+            .user_state
+            .device_routes
+            .get(&device_id)
+            .and_then(|routes| routes.get_active(route_device))
+            .map_or(0, |route| route.channel_volumes.len());
+        let volume = (percent as f32 / 100.0).powi(3);
+        self.pipewire_wrapper
+            .channel_sender
+            .send_or_log(ChannelMessage::DeviceSetRoute(
+                device_id,
+                index,
+                route_device,
+                Some(vec![volume; channels]),
+            ));
+    }
+
+    fn connect_nodes(&mut self, output_node: NodeId, input_node: NodeId) {
+        let graph = &self.state.graph;
+        let pipewire_ids = (
+            graph[output_node].user_data.pipewire_id,
+            graph[input_node].user_data.pipewire_id,
+        );
+        if let (Some(output_node), Some(input_node)) = pipewire_ids {
+            let state = self.pipewire_wrapper.state.clone();
+            let state = metrics::lock_ui(&state);
+            let pairs = state.match_ports_by_channel(output_node, input_node);
+            if pairs.is_empty() {
+                tracing::error!("ConnectNodes no matching channels");
+                self.toasts
+                    .error("No matching channels between the nodes to connect");
+            }
+            // links in one shot, skipping pairs which are already linked
+            let pairs: Vec<_> = pairs
+                .into_iter()
+                .map(|(output_port, input_port)| {
+                    (
+                        PipewireObject::id_key(output_port),
+                        PipewireObject::id_key(input_port),
+                    )
+                })
+                .filter(|(from, to)| state.find_link(from, to).is_none())
+                .collect();
+            drop(state);
+            for (from, to) in pairs {
+                self.perform_link_action(LinkAction {
+                    create: true,
+                    from,
+                    to,
+                    flags: self.extra_state.link_flags,
+                    force: false,
+                });
             }
         }
-        fn evaluate_input(&mut self, name: &str) -> anyhow::Result<MyValueType> {
-            // Calling `evaluate_input` recursively evaluates other nodes in the
-            // graph until the input value for a paramater has been computed.
-            evaluate_input(self.graph, self.node_id, name, self.outputs_cache)
-        }
-        fn populate_output(
-            &mut self,
-            name: &str,
-            value: MyValueType,
-        ) -> anyhow::Result<MyValueType> {
-            // After computing an output, we don't just return it, but we also
-            // populate the outputs cache with it. This ensures the evaluation
-            // only ever computes an output once.
-            //
-            // The return value of the function is the "final" output of the
-            // node, the thing we want to get from the evaluation. The example
-            // would be slightly more contrived when we had multiple output
-            // values, as we would need to choose which of the outputs is the
-            // one we want to return. Other outputs could be used as
-            // intermediate values.
-            //
-            // Note that this is just one possible semantic interpretation of
-            // the graphs, you can come up with your own evaluation semantics!
-            populate_output(self.graph, self.outputs_cache, self.node_id, name, value)
-        }
-        fn input_vector(&mut self, name: &str) -> anyhow::Result<egui::Vec2> {
-            self.evaluate_input(name)?.try_to_vec2()
-        }
-        fn input_scalar(&mut self, name: &str) -> anyhow::Result<f32> {
-            self.evaluate_input(name)?.try_to_scalar()
-        }
-        fn output_vector(&mut self, name: &str, value: egui::Vec2) -> anyhow::Result<MyValueType> {
-            self.populate_output(name, MyValueType::Vec2 { value })
-        }
-        fn output_scalar(&mut self, name: &str, value: f32) -> anyhow::Result<MyValueType> {
-            self.populate_output(name, MyValueType::Scalar { value })
-        }
-    }
-
-    let node = &graph[node_id];
-    let mut evaluator = Evaluator::new(graph, outputs_cache, node_id);
-    match node.user_data.template {
-        MyNodeTemplate::AddScalar => {
-            let a = evaluator.input_scalar("A")?;
-            let b = evaluator.input_scalar("B")?;
-            evaluator.output_scalar("out", a + b)
-        }
-        MyNodeTemplate::SubtractScalar => {
-            let a = evaluator.input_scalar("A")?;
-            let b = evaluator.input_scalar("B")?;
-            evaluator.output_scalar("out", a - b)
-        }
-        MyNodeTemplate::VectorTimesScalar => {
-            let scalar = evaluator.input_scalar("scalar")?;
-            let vector = evaluator.input_vector("vector")?;
-            evaluator.output_vector("out", vector * scalar)
-        }
-        MyNodeTemplate::AddVector => {
-            let v1 = evaluator.input_vector("v1")?;
-            let v2 = evaluator.input_vector("v2")?;
-            evaluator.output_vector("out", v1 + v2)
-        }
-        MyNodeTemplate::SubtractVector => {
-            let v1 = evaluator.input_vector("v1")?;
-            let v2 = evaluator.input_vector("v2")?;
-            evaluator.output_vector("out", v1 - v2)
-        }
-        MyNodeTemplate::MakeVector => {
-            let x = evaluator.input_scalar("x")?;
-            let y = evaluator.input_scalar("y")?;
-            evaluator.output_vector("out", egui::vec2(x, y))
-        }
-        MyNodeTemplate::MakeScalar => {
-            let value = evaluator.input_scalar("value")?;
-            evaluator.output_scalar("out", value)
-        }
-    }
-}
-
-fn populate_output(
-    graph: &MyGraph,
-    outputs_cache: &mut OutputsCache,
-    node_id: NodeId,
-    param_name: &str,
-    value: MyValueType,
-) -> anyhow::Result<MyValueType> {
-    let output_id = graph[node_id].get_output(param_name)?;
-    outputs_cache.insert(output_id, value);
-    Ok(value)
+    }
 }
 
-// Evaluates the input value of
-fn evaluate_input(
-    graph: &MyGraph,
-    node_id: NodeId,
-    param_name: &str,
-    outputs_cache: &mut OutputsCache,
-) -> anyhow::Result<MyValueType> {
-    let input_id = graph[node_id].get_input(param_name)?;
+impl eframe::App for PipewireGraphApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.stop_session_recording();
+        // logged rather than panicking so that the rest still shuts down
+        // (only to the terminal since the Log window is gone by now, see `reconnect` for a toast)
+        if let Err(e) = self.pipewire_wrapper.quit() {
+            tracing::error!("{:#}", e);
+        }
+        for child in &mut self.popped_out {
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+
+    /// If the persistence function is enabled,
+    /// Called by the frame work to save state before shutdown.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if self.pop_out.is_none() {
+            eframe::set_value(storage, PERSISTENCE_KEY, &self.extra_state);
+        }
+    }
+
+    // a popped out instance leaves the storage of the main one alone
+    fn persist_native_window(&self) -> bool {
+        self.pop_out.is_none()
+    }
+
+    fn persist_egui_memory(&self) -> bool {
+        self.pop_out.is_none()
+    }
+
+    /// Called each time the UI needs repainting, which may be many times per second.
+    /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.show(ctx, frame.info().native_pixels_per_point.unwrap_or(1.0));
+    }
+}
+
+impl PipewireGraphApp {
+    /// Whether the objects which existed when connecting are all in the graph.
+    pub fn initial_sync_done(&self) -> bool {
+        self.initial_sync_done
+    }
+
+    /// Handles pending pipewire messages (`show` does it too, call it for an app not shown).
+    pub fn poll(&mut self) {
+        while let Ok(message) = self.pipewire_wrapper.channel_receiver.try_recv() {
+            match &message {
+                ChannelMessage::Batch(messages) => {
+                    tracing::debug!(len = messages.len(), "ui message batch")
+                }
+                message => tracing::debug!(?message, "ui message"),
+            }
+            match message {
+                ChannelMessage::Batch(messages) => self.handle_registry_messages(messages),
+                ChannelMessage::PipewireInitialSyncDone => {
+                    if let Some(recorder) = &mut self.session_recorder {
+                        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                        recorder.record(&state, &[ChannelMessage::PipewireInitialSyncDone]);
+                    }
+                    self.initial_sync_done = true;
+                    self.fit_view_pending = self.tutorial.is_none();
+                }
+                ChannelMessage::PipewireNodePropsChanged(_) if self.paused.is_some() => {
+                    self.paused.as_mut().unwrap().rebuild = true;
+                }
+                ChannelMessage::PipewireNodePropsChanged(id) => self.update_node_label(id),
+                ChannelMessage::PipewireError(PwError::AlreadyLinked(link_id)) => {
+                    self.toasts.warning(format!(
+                        "Already linked (link {}), tick Force in the Link window to add another",
+                        link_id
+                    ))
+                }
+                ChannelMessage::PipewireError(e) => self.toasts.error(e.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Devices and streams which appeared since the last call as (summary, label)
+    /// e.g. ("New device", "Built-in Audio Analog Stereo").
+    #[cfg(feature = "tray")]
+    pub fn take_new_nodes(&mut self) -> Vec<(&'static str, String)> {
+        std::mem::take(&mut self.new_nodes)
+    }
+
+    /// Whether "Minimize to tray" is on.
+    #[cfg(feature = "tray")]
+    pub fn minimize_to_tray(&self) -> bool {
+        self.extra_state.minimize_to_tray
+    }
+
+    // nodes which weren't there on startup (before `PipewireRegistry::event` knows them)
+    #[cfg(feature = "tray")]
+    fn collect_new_node(&mut self, state: &PipewireState, message: &ChannelMessage) {
+        let id = match *message {
+            ChannelMessage::PipewireRegistryGlobal(id) => id,
+            _ => return,
+        };
+        // a known node is announced again when its permissions change
+        if !self.initial_sync_done || self.registry.topology().node(id).is_some() {
+            return;
+        }
+        let object = match state.global_objects.get(&id) {
+            Some(object) if object.type_ == ObjectType::Node => object,
+            _ => return,
+        };
+        let summary = match PipewireObject::get_media_class(object) {
+            Some(class) if class.starts_with("Stream/") => "New stream",
+            Some(class) if class.starts_with("Audio/") || class.starts_with("Video/") => {
+                "New device"
+            }
+            _ => return,
+        };
+        let label = pipewire_node_label(object, self.extra_state.node_label);
+        self.new_nodes.push((summary, label));
+    }
+
+    // registry messages of one pipewire tick (the state is locked once for all of them)
+    fn handle_registry_messages(&mut self, messages: Vec<ChannelMessage>) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let mut events = Vec::with_capacity(messages.len());
+        for message in &messages {
+            self.timeline.record(&state, message);
+            #[cfg(feature = "tray")]
+            self.collect_new_node(&state, message);
+            events.extend(self.registry.event(&state, message));
+        }
+        if let Some(recorder) = &mut self.session_recorder {
+            recorder.record(&state, &messages);
+        }
+        drop(state);
+        if let Some(paused) = &mut self.paused {
+            paused.events.extend(events);
+            paused.rebuild |= messages
+                .iter()
+                .any(|message| matches!(message, ChannelMessage::PipewirePermissionChanged(..)));
+            if paused.events.len() > MAX_PAUSED_EVENTS {
+                METRICS
+                    .paused_events_dropped
+                    .fetch_add(paused.events.len() as u64, Ordering::Relaxed);
+                paused.events.clear();
+                paused.rebuild = true;
+            }
+            return;
+        }
+        // the live graph is rebuilt after the tutorial
+        if self.tutorial.is_none() {
+            for event in events {
+                self.handle_registry_event(event);
+            }
+        }
+        // after the events so that new nodes are already in the graph
+        for message in messages {
+            if let ChannelMessage::PipewirePermissionChanged(id, old_permissions) = message {
+                self.handle_permission_changed(id, old_permissions);
+            }
+        }
+    }
+
+    /// Handles pending pipewire messages and draws the menu bar, the graph and all windows.
+    ///
+    /// `native_pixels_per_point` is the scale the zoom is relative to (`eframe::IntegrationInfo`).
+    pub fn show(&mut self, ctx: &egui::Context, native_pixels_per_point: f32) {
+        let frame_start = Instant::now();
+        self.poll();
+
+        // the pipewire thread is gone (e.g. connection failed) so nothing else can work
+        let error = metrics::lock_ui(&self.pipewire_wrapper.state).error.clone();
+        if let Some(error) = error {
+            self.show_connection_error(ctx, &error);
+            self.toasts.show(ctx);
+            return;
+        }
+
+        if let Some(pop_out) = self.pop_out {
+            match pop_out {
+                PopOut::Object => self.show_object_window(ctx),
+                PopOut::Mixer => self.show_mixer_window(ctx),
+            }
+            self.toasts.show(ctx);
+            return;
+        }
+
+        self.handle_shortcuts(ctx);
+        self.show_menu_bar(ctx);
+        self.show_banners(ctx);
+        self.show_windows(ctx);
+        self.show_panels(ctx);
+
+        //
+        // node graph
+        //
+
+        self.update_user_state(ctx);
+        let node_positions = self.node_positions();
+        let node_responses = if self.extra_state.list_view {
+            egui::CentralPanel::default()
+                .show(ctx, |ui| self.show_list_view(ui))
+                .inner
+        } else {
+            let node_responses = egui::CentralPanel::default()
+                .show(ctx, |ui| self.graph_editor_ui(ui))
+                .inner;
+            if self.extra_state.minimap {
+                self.show_minimap(ctx);
+            }
+            self.show_selection_toolbar(ctx);
+            self.handle_zoom_and_pan(ctx, native_pixels_per_point);
+            node_responses
+        };
+        self.show_confirmation_window(ctx);
+        self.show_feedback_window(ctx);
+        self.toasts.show(ctx);
+        if let Some(step) = self.tutorial {
+            // connections are only kept in the sandbox graph
+            let step = step.next(&self.fake_connections());
+            self.tutorial = Some(step);
+            self.show_tutorial_window(ctx, step);
+            return;
+        }
+        self.handle_node_responses(ctx, node_responses, &node_positions);
+        self.last_frame_duration = frame_start.elapsed();
+    }
+
+    // windows toggled in the menu bar (each one closes itself, `open` is its flag)
+    fn show_windows(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        self.show_core_window(ctx);
+        self.show_object_window(ctx);
+        self.show_link_window(ctx);
+        if self.extra_state.window_latency {
+            self.show_latency_window(ctx);
+        }
+        if self.extra_state.window_path_test {
+            self.show_path_test_window(ctx);
+        }
+        if self.extra_state.window_recording {
+            self.show_recording_window(ctx);
+        }
+        if self.extra_state.window_snapshots {
+            self.show_snapshots_window(ctx);
+        }
+        if self.extra_state.window_session_manager {
+            self.show_session_manager_window(ctx);
+        }
+        if self.extra_state.window_modules {
+            self.show_modules_window(ctx);
+        }
+        if self.extra_state.window_factories {
+            self.show_factories_window(ctx);
+        }
+        if self.extra_state.window_quick_connect {
+            self.show_quick_connect_window(ctx);
+        }
+        if self.extra_state.window_node_search {
+            self.show_node_search_window(ctx);
+        }
+        if self.extra_state.window_network && !restricted {
+            self.show_network_window(ctx);
+        }
+        if self.extra_state.window_presets && !restricted {
+            self.show_presets_window(ctx);
+        }
+        self.show_virtual_device_window(ctx);
+        if self.extra_state.window_stream_format {
+            self.show_stream_format_window(ctx);
+        }
+        if self.extra_state.window_clients && !restricted {
+            self.show_clients_window(ctx);
+        }
+        if self.extra_state.window_unconnected {
+            self.show_unconnected_window(ctx);
+        }
+        // stop metering and sampling for closed windows
+        if self.extra_state.window_mixer {
+            self.show_mixer_window(ctx);
+        } else if !self.mixer_nodes.is_empty() {
+            self.set_mixer_nodes(vec![]);
+        }
+        if self.extra_state.window_scope {
+            self.show_scope_window(ctx);
+        } else if self.scope.is_some() {
+            self.set_scope_port(None);
+        }
+        if self.extra_state.window_debug {
+            self.show_debug_window(ctx);
+        }
+        if self.extra_state.window_performance {
+            self.show_performance_window(ctx);
+        }
+        if self.extra_state.fps_overlay {
+            self.show_fps_overlay(ctx);
+        }
+        if self.extra_state.continuous_repaint {
+            ctx.request_repaint();
+        }
+        if self.extra_state.window_theme {
+            self.show_theme_window(ctx);
+        }
+        if self.extra_state.window_log {
+            self.show_log_window(ctx);
+        }
+        if self.extra_state.window_share {
+            self.show_share_window(ctx);
+        }
+    }
+
+    /// Draws only the graph editor into `ui` (e.g. a panel of another egui app).
+    ///
+    /// Handles pending pipewire messages like `show`, but leaves out the menu bar, the windows
+    /// and the shortcuts except the confirmation of destructive operations.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+        let error = metrics::lock_ui(&self.pipewire_wrapper.state).error.clone();
+        if let Some(error) = error {
+            ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+            return;
+        }
+        let ctx = ui.ctx().clone();
+        self.update_user_state(&ctx);
+        let node_positions = self.node_positions();
+        let node_responses = self.graph_editor_ui(ui);
+        self.show_confirmation_window(&ctx);
+        self.show_feedback_window(&ctx);
+        self.toasts.show(&ctx);
+        self.handle_node_responses(&ctx, node_responses, &node_positions);
+    }
 
-    // The output of another node is connected.
-    if let Some(other_output_id) = graph.connection(input_id) {
-        // The value was already computed due to the evaluation of some other
-        // node. We simply return value from the cache.
-        if let Some(other_value) = outputs_cache.get(&other_output_id) {
-            Ok(*other_value)
+    // what the node ui callbacks read from `GraphUserState`
+    fn update_user_state(&mut self, ctx: &egui::Context) {
+        self.update_media_players();
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        self.user_state.device_profiles = state.device_profiles.clone();
+        self.user_state.output_channels = self
+            .pipewire_id_to_connection
+            .values()
+            .filter_map(|&(output, _)| {
+                let port_id = *self.param_id_to_pipewire_id.get(&output.into())?;
+                Some((output, port_channels(&state, port_id)?))
+            })
+            .filter(|&(_, channels)| channels > 1)
+            .collect();
+        self.user_state.device_routes = state.device_routes.clone();
+        self.user_state.video_formats.clear();
+        for (port_id, formats) in &state.port_video_formats {
+            if let Some(node_id) = state
+                .global_objects
+                .get(port_id)
+                .and_then(PipewireObject::get_parent_node_id)
+            {
+                let video_formats = &mut self.user_state.video_formats;
+                video_formats
+                    .entry(node_id)
+                    .or_insert_with(|| formats.clone());
+            }
+        }
+        self.user_state.stream_targets = state
+            .metadata
+            .iter()
+            .filter_map(|(&subject, properties)| {
+                Some((subject, properties.get("target.object")?.clone()))
+            })
+            .collect();
+        self.user_state.sinks = state
+            .global_objects
+            .values()
+            .filter(|object| PipewireObject::get_media_class(object) == Some("Audio/Sink"))
+            .map(|object| {
+                let serial = PipewireObject::get_serial(object).map(str::to_owned);
+                (
+                    object.id,
+                    serial,
+                    pipewire_node_label(object, self.extra_state.node_label),
+                )
+            })
+            .collect();
+        self.user_state.collapse_unpinned = !self.extra_state.pinned_nodes.is_empty();
+        self.user_state.collapsed_ports.clear();
+        if self.user_state.collapse_unpinned {
+            for port in state
+                .global_objects
+                .values()
+                .filter(|object| object.type_ == ObjectType::Port)
+            {
+                let node = PipewireObject::get_parent_node_id(port)
+                    .and_then(|node_id| state.global_objects.get(&node_id));
+                if let Some(node) = node.filter(|node| self.is_collapsed(node)) {
+                    self.user_state
+                        .collapsed_ports
+                        .entry(node.id)
+                        .or_default()
+                        .push(with_id(
+                            pipewire_port_label(port, self.extra_state.port_label).to_owned(),
+                            port.id,
+                            self.extra_state.show_ids,
+                        ));
+                }
+            }
+        }
+        self.user_state.tone_generators = state
+            .tone_generators
+            .iter()
+            .filter_map(|(name, settings)| {
+                let object = state.find_object_by_prop(*pipewire::keys::NODE_NAME, name)?;
+                Some((object.id, (name.clone(), *settings)))
+            })
+            .collect();
+        drop(state);
+        self.user_state.color_by_client = self.extra_state.color_by_client;
+        self.user_state.wire_width = self.extra_state.theme.wire_width;
+        self.link_preset_nodes(ctx);
+        // blinks three times
+        self.user_state.flashed_node = None;
+        if let Some((node_id, since)) = self.found_node {
+            let elapsed = since.elapsed();
+            if elapsed < Duration::from_millis(1500) {
+                let on = elapsed.as_millis() / 250 % 2 == 0;
+                self.user_state.flashed_node = on.then_some(node_id);
+                ctx.request_repaint_after(Duration::from_millis(50));
+            } else {
+                self.found_node = None;
+            }
         }
-        // This is the first time encountering this node, so we need to
-        // recursively evaluate it.
-        else {
-            // Calling this will populate the cache
-            evaluate_node(graph, graph[other_output_id].node, outputs_cache)?;
+        // follows changes of the default sink and of the links
+        self.user_state.highlighted_nodes = self.extra_state.monitor_mode.then(|| {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            state
+                .default_sink()
+                .map(|sink| self.registry.topology().upstream(sink))
+                .unwrap_or_default()
+        });
+        self.user_state.port_colors = self.extra_state.theme.port_colors;
+        self.user_state.restricted = self.restricted();
+        self.user_state.recording = metrics::lock_ui(&self.pipewire_wrapper.state)
+            .recording
+            .as_ref()
+            .filter(|report| report.running)
+            .map(|report| report.node_id);
+    }
 
-            // Now that we know the value is cached, return it
-            Ok(*outputs_cache
-                .get(&other_output_id)
-                .expect("Cache should be populated"))
+    fn graph_editor_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+    ) -> Vec<NodeResponse<GraphNodeResponse, GraphNodeData>> {
+        let templates = AllMyNodeTemplates {
+            enabled: !self.restricted() && self.tutorial.is_none(),
+        };
+        self.editor_rect = ui.max_rect();
+        // painted first so that the nodes stay on top
+        let collapse_group = self
+            .extra_state
+            .node_groups
+            .then(|| self.show_node_groups(ui))
+            .flatten();
+        let graph_response = self
+            .state
+            .draw_graph_editor(ui, templates, &mut self.user_state);
+        if let Some(group) = collapse_group {
+            self.set_group_collapsed(group, true);
         }
+        // positions of the complete graph are known after drawing it once
+        if std::mem::take(&mut self.fit_view_pending) {
+            self.fit_view();
+        }
+        graph_response.node_responses
+    }
+
+    // the editor forgets the position of a deleted node before returning the response,
+    // so they are taken before drawing it for `handle_node_responses`
+    fn node_positions(&self) -> HashMap<NodeId, egui::Pos2> {
+        (self.state.node_positions.iter())
+            .map(|(node_id, &position)| (node_id, position))
+            .collect()
     }
-    // No existing connection, take the inline value instead.
-    else {
-        Ok(graph[input_id].value)
+
+    fn handle_node_responses(
+        &mut self,
+        ctx: &egui::Context,
+        node_responses: Vec<NodeResponse<GraphNodeResponse, GraphNodeData>>,
+        node_positions: &HashMap<NodeId, egui::Pos2>,
+    ) {
+        let deleted_params: HashSet<AnyParameterId> = node_responses
+            .iter()
+            .flat_map(|response| match response {
+                NodeResponse::DeleteNodeFull { node, .. } => node
+                    .input_ids()
+                    .map(AnyParameterId::from)
+                    .chain(node.output_ids().map(AnyParameterId::from))
+                    .collect(),
+                _ => vec![],
+            })
+            .collect();
+        for node_response in node_responses {
+            match node_response {
+                NodeResponse::CreatedNode(node_id) => self.create_from_template(node_id),
+                NodeResponse::ConnectEventEnded { output, input } => {
+                    self.send_link_message(output, input, true);
+                }
+                // links go away together with the node
+                NodeResponse::DisconnectEvent { output, input }
+                    if deleted_params.contains(&output.into())
+                        || deleted_params.contains(&input.into()) => {}
+                NodeResponse::DisconnectEvent { output, input } => {
+                    self.send_link_message(output, input, false);
+                }
+                NodeResponse::DeleteNodeFull { node_id, node } => {
+                    self.delete_pipewire_node(&node, node_positions.get(&node_id).copied());
+                }
+                NodeResponse::User(user_event) => match user_event {
+                    GraphNodeResponse::ConnectNodes(output_node, input_node) => {
+                        self.connect_nodes(output_node, input_node);
+                    }
+                    GraphNodeResponse::SetDeviceProfile(device_id, index) => {
+                        self.pipewire_wrapper
+                            .channel_sender
+                            .send_or_log(ChannelMessage::DeviceSetProfile(device_id, index));
+                    }
+                    GraphNodeResponse::SetDeviceRoute(device_id, index, route_device) => {
+                        self.pipewire_wrapper.channel_sender.send_or_log(
+                            ChannelMessage::DeviceSetRoute(device_id, index, route_device, None),
+                        );
+                    }
+                    GraphNodeResponse::SetDeviceRouteVolume(
+                        device_id,
+                        index,
+                        route_device,
+                        percent,
+                    ) => {
+                        self.set_device_route_volume(device_id, index, route_device, percent);
+                    }
+                    GraphNodeResponse::Record(pipewire_id) => self.start_recording(pipewire_id),
+                    GraphNodeResponse::StopRecording => {
+                        self.pipewire_wrapper
+                            .channel_sender
+                            .send_or_log(ChannelMessage::RecordStop);
+                    }
+                    GraphNodeResponse::TogglePin(pipewire_id) => {
+                        self.update_pin(pipewire_id, |extra_state, name| {
+                            if !extra_state.pinned_nodes.remove(&name) {
+                                extra_state.expanded_nodes.remove(&name);
+                                extra_state.pinned_nodes.insert(name);
+                            }
+                        });
+                    }
+                    GraphNodeResponse::TogglePinPosition(pipewire_id) => {
+                        self.toggle_pinned_position(pipewire_id);
+                    }
+                    GraphNodeResponse::SetExpanded(pipewire_id, expanded) => {
+                        self.update_pin(pipewire_id, |extra_state, name| {
+                            if expanded {
+                                extra_state.expanded_nodes.insert(name);
+                            } else {
+                                extra_state.expanded_nodes.remove(&name);
+                            }
+                        });
+                    }
+                    GraphNodeResponse::SetTone(pipewire_id, settings) => {
+                        if let Some((name, _)) = self.user_state.tone_generators.get(&pipewire_id) {
+                            self.pipewire_wrapper.channel_sender.send_or_log(
+                                ChannelMessage::ToneGeneratorUpdate(name.clone(), settings),
+                            );
+                        }
+                    }
+                    GraphNodeResponse::SetStreamTarget(stream_id, target_id) => {
+                        self.pipewire_wrapper
+                            .channel_sender
+                            .send_or_log(ChannelMessage::StreamSetTarget(stream_id, target_id));
+                    }
+                    GraphNodeResponse::ExpandGroup(group) => self.set_group_collapsed(group, false),
+                    GraphNodeResponse::MediaControl(pipewire_id, command) => {
+                        if let Some(player) = self.user_state.media_players.get(&pipewire_id) {
+                            player.send(command);
+                        }
+                    }
+                    GraphNodeResponse::SignalProcess(pid, signal) => {
+                        let state = self.pipewire_wrapper.state.clone();
+                        self.send_or_confirm(
+                            &metrics::lock_ui(&state),
+                            vec![ChannelMessage::ProcessSignal(pid, signal)],
+                        );
+                    }
+                    GraphNodeResponse::DisconnectAll(node) => self.disconnect_all(node),
+                    GraphNodeResponse::StopNode(node) => {
+                        if let Some(pipewire_id) = self.state.graph[node].user_data.pipewire_id {
+                            let message = self.destroy_message(pipewire_id);
+                            let state = self.pipewire_wrapper.state.clone();
+                            self.send_or_confirm(&metrics::lock_ui(&state), vec![message]);
+                        }
+                    }
+                },
+                NodeResponse::MoveNode { node, .. } => {
+                    self.dragged_nodes.insert(node);
+                }
+                _ => {}
+            }
+        }
+        self.finish_node_drag(ctx);
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,212 @@
+use super::*;
+
+//
+// clients and their permissions on each object
+//
+
+impl PipewireGraphApp {
+    // clients with their default permissions (`PW_ID_ANY` entry) and disconnect action
+    pub(super) fn show_clients_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let clients: Vec<&GlobalObject<PropStore>> = state
+            .global_objects
+            .values()
+            .filter(|object| object.type_ == ObjectType::Client)
+            .collect();
+        let new_clients: Vec<u32> = clients
+            .iter()
+            .map(|client| client.id)
+            .filter(|&id| self.tracked_clients.insert(id))
+            .collect();
+        for client_id in new_clients {
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::ClientPermissionsQuery(client_id));
+        }
+
+        let mut messages = vec![];
+        let mut permissions_client = self.permissions_client;
+        egui::Window::new("Clients")
+            .open(&mut self.extra_state.window_clients)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("clients")
+                        .num_columns(7)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("ID");
+                            ui.strong("PID");
+                            ui.strong("Application");
+                            ui.strong("Access");
+                            ui.strong("Default permissions");
+                            ui.label("");
+                            ui.label("");
+                            ui.end_row();
+                            for client in &clients {
+                                let props = client.props.as_ref();
+                                let prop = |key: PropKey| {
+                                    props.and_then(|props| props.get_key(key)).unwrap_or("--")
+                                };
+                                ui.label(client.id.to_string());
+                                ui.label(prop(PropKey::AppProcessId));
+                                ui.label(prop(PropKey::AppName));
+                                ui.monospace(permissions_label(client.permissions))
+                                    .on_hover_text("our permissions on the client");
+                                let default =
+                                    state
+                                        .client_permissions
+                                        .get(&client.id)
+                                        .and_then(|entries| {
+                                            entries
+                                                .iter()
+                                                .find(|(id, _)| *id == u32::MAX)
+                                                .map(|(_, permissions)| *permissions)
+                                        });
+                                match default {
+                                    Some(default) => {
+                                        let mut edited = default;
+                                        ui.horizontal(|ui| {
+                                            permissions_checkboxes(ui, &mut edited);
+                                        });
+                                        if edited != default {
+                                            messages.push(ChannelMessage::ClientUpdatePermissions(
+                                                client.id,
+                                                vec![(u32::MAX, edited.bits())],
+                                            ));
+                                        }
+                                    }
+                                    None => {
+                                        ui.weak("--");
+                                    }
+                                }
+                                let button = egui::Button::new("Objects...");
+                                if ui.add_enabled(default.is_some(), button).clicked() {
+                                    permissions_client = Some(client.id);
+                                }
+                                let button = egui::Button::new("Disconnect");
+                                if ui
+                                    .add_enabled(PipewireObject::is_destroyable(client), button)
+                                    .clicked()
+                                {
+                                    messages.push(ChannelMessage::ObjectDestroy(
+                                        client.id,
+                                        PipewireObject::get_serial(client).map(str::to_owned),
+                                    ));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.permissions_client = permissions_client;
+        if let Some(client_id) = permissions_client {
+            if state.global_objects.contains_key(&client_id) {
+                messages.extend(self.show_permissions_window(ctx, &state, client_id));
+            } else {
+                self.permissions_client = None;
+            }
+        }
+        self.send_or_confirm(&state, messages);
+        // permissions arrive without notifying the ui
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    // permissions of a client on each object (objects without an explicit entry use the default)
+    pub(super) fn show_permissions_window(
+        &mut self,
+        ctx: &egui::Context,
+        state: &PipewireState,
+        client_id: u32,
+    ) -> Vec<ChannelMessage> {
+        let mut messages = vec![];
+        let entries = match state.client_permissions.get(&client_id) {
+            Some(entries) => entries,
+            None => return messages,
+        };
+        let default = entries
+            .iter()
+            .find(|(id, _)| *id == u32::MAX)
+            .map_or(Permission::empty(), |(_, permissions)| *permissions);
+        let client_label = state
+            .global_objects
+            .get(&client_id)
+            .and_then(|client| client.props.as_ref()?.get_key(PropKey::AppName))
+            .unwrap_or("--");
+        let filter = &mut self.permissions_filter;
+        let mut open = true;
+        egui::Window::new(format!(
+            "Permissions of client {} ({})",
+            client_id, client_label
+        ))
+        .id(egui::Id::new("permissions"))
+        .open(&mut open)
+        .show(ctx, |ui| {
+            ui.add(egui::TextEdit::singleline(filter).hint_text("filter by type or name"));
+            ui.weak("entries in bold are set explicitly, others follow the default");
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("permissions")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for object in state.global_objects.values() {
+                                let type_name = PipewireObject::get_type_name(object);
+                                let name =
+                                    PipewireObject::get_name(object).map_or("--", |(_k, v)| v);
+                                let filter = filter.to_lowercase();
+                                if !filter.is_empty()
+                                    && !type_name.to_lowercase().contains(&filter)
+                                    && !name.to_lowercase().contains(&filter)
+                                {
+                                    continue;
+                                }
+                                let explicit = entries
+                                    .iter()
+                                    .find(|(id, _)| *id == object.id)
+                                    .map(|(_, permissions)| *permissions);
+                                let label = format!("{} {}", object.id, type_name);
+                                if explicit.is_some() {
+                                    ui.strong(label);
+                                } else {
+                                    ui.label(label);
+                                }
+                                ui.label(name);
+                                let current = explicit.unwrap_or(default);
+                                let mut edited = current;
+                                ui.horizontal(|ui| {
+                                    permissions_checkboxes(ui, &mut edited);
+                                });
+                                if edited != current {
+                                    messages.push(ChannelMessage::ClientUpdatePermissions(
+                                        client_id,
+                                        vec![(object.id, edited.bits())],
+                                    ));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+        if !open {
+            self.permissions_client = None;
+        }
+        messages
+    }
+}
+
+fn permissions_checkboxes(ui: &mut egui::Ui, permissions: &mut Permission) {
+    for (flag, label) in [
+        (Permission::R, "R"),
+        (Permission::W, "W"),
+        (Permission::X, "X"),
+        (Permission::M, "M"),
+    ] {
+        let mut checked = permissions.contains(flag);
+        if ui.checkbox(&mut checked, label).changed() {
+            permissions.set(flag, checked);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+//
+// confirmation of destructive operations and of links closing a feedback loop
+//
+
+impl PipewireGraphApp {
+    // destructive messages wait for the confirmation window (unless turned off)
+    pub(super) fn send_or_confirm(&mut self, state: &PipewireState, messages: Vec<ChannelMessage>) {
+        let (destructive, others): (Vec<_>, Vec<_>) = messages.into_iter().partition(|message| {
+            !self.extra_state.skip_confirmation && confirm::is_destructive(message)
+        });
+        for message in others {
+            self.send_confirmed(message);
+        }
+        if destructive.is_empty() {
+            return;
+        }
+        match &mut self.confirmation {
+            Some(confirmation) => confirmation.merge(state, destructive),
+            None => self.confirmation = Some(Confirmation::new(state, destructive)),
+        }
+    }
+
+    pub(super) fn show_confirmation_window(&mut self, ctx: &egui::Context) {
+        let confirmation = match &self.confirmation {
+            Some(confirmation) => confirmation,
+            None => return,
+        };
+        let (mut confirmed, mut cancelled) = (false, false);
+        egui::Window::new("Confirm")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                for (pid, signal) in &confirmation.signals {
+                    ui.label(format!("{} will be sent to process {}", signal.name(), pid));
+                }
+                for module in &confirmation.modules {
+                    ui.label(format!("module {} will be unloaded", module));
+                }
+                if !confirmation.objects.is_empty()
+                    || (confirmation.signals.is_empty() && confirmation.modules.is_empty())
+                {
+                    ui.label(format!(
+                        "{} objects will be destroyed:",
+                        confirmation.objects.len()
+                    ));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("confirmation")
+                            .num_columns(4)
+                            .spacing([10.0, 5.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for object in &confirmation.objects {
+                                    ui.label(object.id.to_string());
+                                    ui.label(&object.type_);
+                                    ui.label(&object.label);
+                                    match (object.with, object.own_stream) {
+                                        (Some(with), _) => ui.weak(format!("with {}", with)),
+                                        (None, true) => ui.weak("stream of this app"),
+                                        (None, false) => ui.label(""),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if confirmation.objects.is_empty()
+                    && confirmation.signals.is_empty()
+                    && confirmation.modules.is_empty()
+                {
+                    ui.weak("(the objects are already gone)");
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Copy pw-cli commands")
+                        .on_hover_text(confirmation.pw_cli_commands())
+                        .clicked()
+                    {
+                        ui.output().copied_text = confirmation.pw_cli_commands();
+                    }
+                    cancelled = ui.button("Cancel").clicked();
+                    let text = if confirmation.objects.is_empty() {
+                        "Confirm"
+                    } else {
+                        "Destroy"
+                    };
+                    confirmed = ui.button(text).clicked();
+                });
+                ui.checkbox(&mut self.extra_state.skip_confirmation, "Don't ask again")
+                    .on_hover_text("can be turned back on from the Edit menu");
+            });
+        if confirmed {
+            for message in self.confirmation.take().unwrap().messages {
+                self.send_confirmed(message);
+            }
+        } else if cancelled {
+            self.confirmation = None;
+        }
+    }
+
+    // signals are sent from here rather than by the pipewire thread
+    fn send_confirmed(&mut self, message: ChannelMessage) {
+        match message {
+            ChannelMessage::ProcessSignal(pid, signal) => match process::send_signal(pid, signal) {
+                Ok(()) => self
+                    .toasts
+                    .info(format!("Sent {} to {}", signal.name(), pid)),
+                Err(e) => self.toasts.error(format!(
+                    "Cannot send {} to {} ({:#})",
+                    signal.name(),
+                    pid,
+                    e
+                )),
+            },
+            message => self.pipewire_wrapper.channel_sender.send_or_log(message),
+        }
+    }
+
+    pub(super) fn show_feedback_window(&mut self, ctx: &egui::Context) {
+        if self.feedback_links.is_empty() {
+            return;
+        }
+        let (mut confirmed, mut cancelled) = (false, false);
+        egui::Window::new("Feedback loop")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} links would route the audio back into itself:",
+                    self.feedback_links.len()
+                ));
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                let chain: Vec<_> = self
+                    .user_state
+                    .feedback_loop
+                    .iter()
+                    .map(|id| {
+                        state
+                            .global_objects
+                            .get(id)
+                            .and_then(PipewireObject::get_node_label)
+                            .unwrap_or("--")
+                            .to_owned()
+                    })
+                    .collect();
+                drop(state);
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 80, 80),
+                    format!("{} ↩", chain.join(" → ")),
+                );
+                ui.weak("(the nodes of the loop are highlighted in red)");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    cancelled = ui.button("Cancel").clicked();
+                    confirmed = ui.button("Link anyway").clicked();
+                });
+            });
+        if confirmed || cancelled {
+            self.user_state.feedback_loop.clear();
+            for action in std::mem::take(&mut self.feedback_links) {
+                if confirmed {
+                    self.send_link_action(action);
+                } else {
+                    self.revert_editor_connection(&action);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+//
+// what is shown instead of the graph when the pipewire thread is gone
+//
+
+impl PipewireGraphApp {
+    // full-screen state with remediation steps instead of the graph
+    pub(super) fn show_connection_error(&mut self, ctx: &egui::Context, error: &PwError) {
+        let (sandbox, socket) = self
+            .connection_diagnosis
+            .get_or_insert_with(|| (Sandbox::detect(), troubleshoot::probe_socket()));
+        // the local socket has nothing to do with a remote connection
+        let steps = match &self.remote {
+            #[cfg(feature = "jack")]
+            _ if self.jack => vec![
+                "Start the JACK server (e.g. `jackd -d alsa`) or go back to PipeWire.".to_owned(),
+            ],
+            Some(remote) => vec![format!(
+                "Check that \"{}\" is reachable and that the server listens on it (e.g. module-protocol-native with `sockets = [ {{ name = \"tcp:4656\" }} ]`).",
+                remote
+            )],
+            None => troubleshoot::remediation(*sandbox, socket),
+        };
+        let sandbox = *sandbox;
+        let mut retry = false;
+        #[cfg(feature = "jack")]
+        let (jack, mut switch_backend) = (self.jack, false);
+        #[cfg(not(feature = "jack"))]
+        let jack = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.0);
+                ui.heading(if jack {
+                    "Cannot connect to JACK"
+                } else {
+                    "Cannot connect to PipeWire"
+                });
+                ui.add_space(10.0);
+                ui.label(error.to_string());
+                if let Some(sandbox) = sandbox {
+                    ui.label(format!("Running inside {}.", sandbox.name()));
+                }
+            });
+            ui.add_space(20.0);
+            ui.strong("What you can try:");
+            for (i, step) in steps.iter().enumerate() {
+                ui.label(format!("{}. {}", i + 1, step));
+            }
+            ui.add_space(20.0);
+            ui.vertical_centered(|ui| {
+                retry = ui.button("Retry").clicked();
+                // degraded graph of a plain JACK server (linking only)
+                #[cfg(feature = "jack")]
+                {
+                    let label = if jack {
+                        "Back to PipeWire"
+                    } else {
+                        "Show a JACK server instead"
+                    };
+                    switch_backend = ui.button(label).clicked();
+                }
+            });
+        });
+        #[cfg(feature = "jack")]
+        if switch_backend {
+            self.jack = !self.jack;
+            retry = true;
+        }
+        if retry {
+            self.reconnect();
+        }
+    }
+
+    // respawn the pipewire thread and rebuild the graph from scratch
+    pub(super) fn reconnect(&mut self) {
+        if let Err(e) = self.pipewire_wrapper.quit() {
+            tracing::error!("{:#}", e);
+            self.toasts.error(e.to_string());
+        }
+        let remote = self.remote.clone();
+        let (ctx, interval) = (&self.egui_ctx, self.extra_state.timer_interval_ms);
+        self.pipewire_wrapper = match () {
+            #[cfg(feature = "jack")]
+            _ if self.jack => connect(ctx, JackBackend, &self.repaint_delay_ms, interval),
+            _ => connect(
+                ctx,
+                PipewireBackend { remote },
+                &self.repaint_delay_ms,
+                interval,
+            ),
+        };
+        self.connection_diagnosis = None;
+        self.initial_sync_done = false;
+        self.fit_view_pending = false;
+        self.tracked_ports.clear();
+        self.tracked_clients.clear();
+        self.mixer_nodes.clear();
+        self.scope = None;
+        self.paused = None;
+        self.registry.clear();
+        self.rebuild_graph(&PipewireState::default());
+    }
+}
@@ -0,0 +1,292 @@
+use super::*;
+
+//
+// the pipewire server: core info, loaded modules and object factories
+//
+
+// (label, module name, args) offered in the Modules window
+const MODULE_PRESETS: [(&str, &str, &str); 4] = [
+    (
+        "Discover PulseAudio servers (zeroconf)",
+        "libpipewire-module-zeroconf-discover",
+        "",
+    ),
+    (
+        "Discover AirPlay receivers",
+        "libpipewire-module-raop-discover",
+        "",
+    ),
+    (
+        "PulseAudio TCP server",
+        "libpipewire-module-protocol-pulse",
+        "{ server.address = [ \"tcp:4713\" ] }",
+    ),
+    (
+        "Pulse tunnel to a remote server",
+        "libpipewire-module-pulse-tunnel",
+        "{ tunnel.mode = sink pulse.server.address = \"tcp:192.168.1.2:4713\" }",
+    ),
+];
+
+// properties filled in when picking one of these factories in the Factories window
+const FACTORY_TEMPLATES: [(&str, &[(&str, &str)]); 3] = [
+    (
+        "adapter",
+        &[
+            ("factory.name", "support.null-audio-sink"),
+            ("node.name", "my-sink"),
+            ("media.class", "Audio/Sink"),
+            ("audio.position", "FL,FR"),
+            ("object.linger", "true"),
+        ],
+    ),
+    (
+        "link-factory",
+        &[
+            ("link.output.port", ""),
+            ("link.input.port", ""),
+            ("object.linger", "true"),
+        ],
+    ),
+    ("metadata", &[("metadata.name", "my-metadata")]),
+];
+
+impl PipewireGraphApp {
+    // info of the server this app is connected to
+    pub(super) fn show_core_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Core")
+            .open(&mut self.extra_state.window_core)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                if let Some(core_info) = &state.core_info {
+                    egui::ScrollArea::both().show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut core_info.as_str())
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                } else {
+                    ui.label("(initializing..)");
+                }
+            });
+    }
+
+    // server modules with their arguments and modules loaded into this app
+    pub(super) fn show_modules_window(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let mut messages = vec![];
+        egui::Window::new("Modules")
+            .open(&mut self.extra_state.window_modules)
+            .show(ctx, |ui| {
+                ui.strong("Server");
+                egui::ScrollArea::vertical()
+                    .id_source("server-modules")
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("server-modules")
+                            .num_columns(4)
+                            .spacing([10.0, 5.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for module in state
+                                    .global_objects
+                                    .values()
+                                    .filter(|object| object.type_ == ObjectType::Module)
+                                {
+                                    ui.label(module.id.to_string());
+                                    ui.label(
+                                        PipewireObject::get_name(module).map_or("--", |(_k, v)| v),
+                                    );
+                                    ui.monospace(
+                                        state
+                                            .module_args
+                                            .get(&module.id)
+                                            .map_or("", String::as_str),
+                                    );
+                                    // needs X permission (usually only the session manager has it)
+                                    let button = egui::Button::new("Unload");
+                                    let enabled =
+                                        !restricted && PipewireObject::is_destroyable(module);
+                                    if ui.add_enabled(enabled, button).clicked() {
+                                        let serial =
+                                            PipewireObject::get_serial(module).map(str::to_owned);
+                                        messages
+                                            .push(ChannelMessage::ObjectDestroy(module.id, serial));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if restricted {
+                    return;
+                }
+                ui.separator();
+                ui.strong("This app");
+                ui.label("Modules run in this process and are unloaded when it exits.");
+                egui::Grid::new("loaded-modules")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for module in &state.loaded_modules {
+                            ui.label(&module.name);
+                            ui.monospace(&module.args);
+                            if ui.button("Unload").clicked() {
+                                messages.push(ChannelMessage::ModuleUnload(module.key));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.menu_button("Presets ⏵", |ui| {
+                        for (label, name, args) in MODULE_PRESETS {
+                            if ui.button(label).clicked() {
+                                self.extra_state.module_name = name.to_owned();
+                                self.extra_state.module_args = args.to_owned();
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.extra_state.module_name)
+                            .hint_text("libpipewire-module-..."),
+                    );
+                });
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.extra_state.module_args)
+                        .code_editor()
+                        .desired_rows(2)
+                        .hint_text("arguments (SPA-JSON)"),
+                );
+                let name = self.extra_state.module_name.trim();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new("Load"))
+                    .clicked()
+                {
+                    messages.push(ChannelMessage::ModuleLoad(
+                        name.to_owned(),
+                        self.extra_state.module_args.clone(),
+                    ));
+                }
+            });
+        self.send_or_confirm(&state, messages);
+    }
+
+    pub(super) fn show_factories_window(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let mut message = None;
+        egui::Window::new("Factories")
+            .open(&mut self.extra_state.window_factories)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("factories")
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("factories")
+                            .num_columns(4)
+                            .spacing([10.0, 5.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Id");
+                                ui.strong("Name");
+                                ui.strong("Type");
+                                ui.strong("Version");
+                                ui.end_row();
+                                for factory in state.factories() {
+                                    ui.label(factory.id.to_string());
+                                    ui.label(factory.name);
+                                    ui.label(
+                                        factory.type_name.trim_start_matches("PipeWire:Interface:"),
+                                    );
+                                    ui.label(factory.type_version.unwrap_or("--"));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if restricted {
+                    return;
+                }
+                ui.separator();
+                ui.strong("Create object");
+                let label = if self.extra_state.factory_name.is_empty() {
+                    "Factory"
+                } else {
+                    self.extra_state.factory_name.as_str()
+                };
+                ui.menu_button(format!("{} ⏵", label), |ui| {
+                    for factory in state.factories() {
+                        if ui.button(factory.name).clicked() {
+                            self.extra_state.factory_name = factory.name.to_owned();
+                            if let Some((_, props)) = FACTORY_TEMPLATES
+                                .iter()
+                                .find(|(name, _)| *name == factory.name)
+                            {
+                                self.extra_state.factory_props = props
+                                    .iter()
+                                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                                    .collect();
+                            }
+                            ui.close_menu();
+                        }
+                    }
+                });
+                let mut remove = None;
+                egui::Grid::new("factory-props")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (i, (key, value)) in
+                            self.extra_state.factory_props.iter_mut().enumerate()
+                        {
+                            ui.add(
+                                egui::TextEdit::singleline(key)
+                                    .hint_text("key")
+                                    .desired_width(150.0),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(value)
+                                    .hint_text("value")
+                                    .desired_width(150.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = remove {
+                    self.extra_state.factory_props.remove(i);
+                }
+                if ui.button("+ Property").clicked() {
+                    self.extra_state.factory_props.push(Default::default());
+                }
+                ui.label("Objects without object.linger are destroyed when this app exits.");
+                let enabled = !self.extra_state.factory_name.is_empty();
+                if ui
+                    .add_enabled(enabled, egui::Button::new("Create"))
+                    .clicked()
+                {
+                    let props = self
+                        .extra_state
+                        .factory_props
+                        .iter()
+                        .filter(|(key, _)| !key.trim().is_empty())
+                        .map(|(key, value)| (key.trim().to_owned(), value.clone()))
+                        .collect();
+                    message = Some(ChannelMessage::ObjectCreate(
+                        self.extra_state.factory_name.clone(),
+                        props,
+                    ));
+                }
+            });
+        drop(state);
+        if let Some(message) = message {
+            self.pipewire_wrapper.channel_sender.send_or_log(message);
+        }
+    }
+}
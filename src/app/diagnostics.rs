@@ -0,0 +1,251 @@
+use super::*;
+
+//
+// windows about the app itself (logs, frame times and metrics of the pipewire thread)
+//
+
+impl PipewireGraphApp {
+    // tracing events captured by `LogLayer`
+    pub(super) fn show_log_window(&mut self, ctx: &egui::Context) {
+        let extra_state = &mut self.extra_state;
+        egui::Window::new("Log")
+            .open(&mut extra_state.window_log)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("log_level")
+                        .selected_text(extra_state.log_level.as_str())
+                        .show_ui(ui, |ui| {
+                            for level in LogLevel::ALL {
+                                ui.selectable_value(
+                                    &mut extra_state.log_level,
+                                    level,
+                                    level.as_str(),
+                                );
+                            }
+                        });
+                    ui.add(
+                        egui::TextEdit::singleline(&mut extra_state.log_search).hint_text("search"),
+                    );
+                    if ui.button("Clear").clicked() {
+                        LOGS.lock().unwrap().clear();
+                    }
+                });
+                ui.separator();
+                // formatted up front so that no event logged while drawing waits for the lock
+                let search = extra_state.log_search.to_lowercase();
+                let rows: Vec<(LogLevel, String)> = LOGS
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|entry| entry.level <= extra_state.log_level)
+                    .filter(|entry| {
+                        search.is_empty()
+                            || entry.message.to_lowercase().contains(&search)
+                            || entry.target.to_lowercase().contains(&search)
+                    })
+                    .map(|entry| {
+                        let text = format!(
+                            "{:>9.3} {:<5} {} {}",
+                            entry.elapsed.as_secs_f32(),
+                            entry.level.as_str(),
+                            entry.target,
+                            entry.message
+                        );
+                        (entry.level, text)
+                    })
+                    .collect();
+                let row_height = ui.text_style_height(&TextStyle::Monospace);
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .show_rows(ui, row_height, rows.len(), |ui, range| {
+                        for (level, text) in &rows[range] {
+                            let color = match level {
+                                LogLevel::Error => ui.visuals().error_fg_color,
+                                LogLevel::Warn => ui.visuals().warn_fg_color,
+                                LogLevel::Info => ui.visuals().text_color(),
+                                LogLevel::Debug | LogLevel::Trace => ui.visuals().weak_text_color(),
+                            };
+                            ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(text.as_str()).monospace().color(color),
+                                )
+                                .wrap(false),
+                            );
+                        }
+                    });
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    pub(super) fn show_performance_window(&mut self, ctx: &egui::Context) {
+        let mut window_performance = self.extra_state.window_performance;
+        egui::Window::new("Performance")
+            .open(&mut window_performance)
+            .show(ctx, |ui| {
+                egui::Grid::new("performance")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Pipewire timer")
+                            .on_hover_text("how often the pipewire thread handles ui commands");
+                        let mut interval = self.extra_state.timer_interval_ms.unwrap_or(100);
+                        let slider = egui::Slider::new(&mut interval, 1..=1000)
+                            .logarithmic(true)
+                            .suffix(" ms");
+                        if ui.add(slider).changed() {
+                            self.extra_state.timer_interval_ms = Some(interval);
+                            self.pipewire_wrapper
+                                .channel_sender
+                                .send_or_log(ChannelMessage::PipewireTimerIntervalSet(interval));
+                        }
+                        ui.end_row();
+                        ui.label("Repaint delay")
+                            .on_hover_text("coalesce pipewire updates into fewer repaints");
+                        let slider =
+                            egui::Slider::new(&mut self.extra_state.repaint_delay_ms, 0..=1000)
+                                .suffix(" ms");
+                        if ui.add(slider).changed() {
+                            self.repaint_delay_ms
+                                .store(self.extra_state.repaint_delay_ms, Ordering::Relaxed);
+                        }
+                        ui.end_row();
+                    });
+                ui.checkbox(
+                    &mut self.extra_state.continuous_repaint,
+                    "Continuous repaint",
+                )
+                .on_hover_text("repaint every frame even when idle (uses more cpu)");
+                ui.checkbox(
+                    &mut self.extra_state.short_props_tooltips,
+                    "Short props tooltips",
+                )
+                .on_hover_text("a few props instead of all of them when hovering objects");
+                ui.checkbox(&mut self.extra_state.fps_overlay, "FPS overlay");
+                if ui.button("Reset").clicked() {
+                    self.extra_state.timer_interval_ms = None;
+                    self.extra_state.repaint_delay_ms = 0;
+                    self.extra_state.continuous_repaint = false;
+                    self.extra_state.short_props_tooltips = false;
+                    self.repaint_delay_ms.store(0, Ordering::Relaxed);
+                    self.pipewire_wrapper
+                        .channel_sender
+                        .send_or_log(ChannelMessage::PipewireTimerIntervalSet(100));
+                }
+            });
+        self.extra_state.window_performance = window_performance;
+    }
+
+    // frames drawn in the last second (only while something repaints) and cpu usage
+    pub(super) fn show_fps_overlay(&mut self, ctx: &egui::Context) {
+        let now = ctx.input().time;
+        self.frame_times.push_back(now);
+        while self
+            .frame_times
+            .front()
+            .map_or(false, |&time| time < now - 1.0)
+        {
+            self.frame_times.pop_front();
+        }
+        egui::Area::new("fps_overlay")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 40.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} fps | ui {:.1} ms | pipewire {:.1} %",
+                        self.frame_times.len(),
+                        self.last_frame_duration.as_secs_f32() * 1000.0,
+                        METRICS.cpu_permille.load(Ordering::Relaxed) as f32 / 10.0
+                    ))
+                    .monospace()
+                    .background_color(ui.visuals().extreme_bg_color),
+                );
+            });
+        // the count drops to zero once idle
+        ctx.request_repaint_after(Duration::from_secs(1));
+    }
+
+    // self-metrics of the pipewire thread (e.g. to spot a listener or proxy doing too much work)
+    pub(super) fn show_debug_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Debug")
+            .open(&mut self.extra_state.window_debug)
+            .show(ctx, |ui| {
+                egui::Grid::new("debug")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("pipewire thread cpu");
+                        ui.label(format!(
+                            "{:.1} %",
+                            METRICS.cpu_permille.load(Ordering::Relaxed) as f32 / 10.0
+                        ));
+                        ui.end_row();
+                        ui.label("process rss");
+                        ui.label(format!(
+                            "{:.1} MiB",
+                            METRICS.rss_kb.load(Ordering::Relaxed) as f32 / 1024.0
+                        ));
+                        ui.end_row();
+                        for ((label, stats), rate) in [
+                            ("ui -> pipewire queue", &METRICS.ui_to_pw),
+                            ("pipewire -> ui queue", &METRICS.pw_to_ui),
+                        ]
+                        .into_iter()
+                        .zip(&mut self.message_rates)
+                        {
+                            let total = stats.total.load(Ordering::Relaxed);
+                            ui.label(label);
+                            ui.label(format!(
+                                "{} (max {}, total {}, {:.0}/s, superseded {}, dropped {})",
+                                stats.queued.load(Ordering::Relaxed),
+                                stats.max_queued.load(Ordering::Relaxed),
+                                total,
+                                rate.sample(total),
+                                stats.superseded.load(Ordering::Relaxed),
+                                stats.dropped.load(Ordering::Relaxed)
+                            ));
+                            ui.end_row();
+                        }
+                        ui.label("message handler max");
+                        ui.label(format!(
+                            "{} us",
+                            METRICS.timer_max_us.load(Ordering::Relaxed)
+                        ));
+                        ui.end_row();
+                        ui.label("state lock contended")
+                            .on_hover_text("pipewire thread waiting for the ui thread");
+                        ui.label(format!(
+                            "{} / {} (waited {} us, max {} us)",
+                            METRICS.lock_contended.load(Ordering::Relaxed),
+                            METRICS.lock_acquired.load(Ordering::Relaxed),
+                            METRICS.lock_wait_us.load(Ordering::Relaxed),
+                            METRICS.lock_max_wait_us.load(Ordering::Relaxed)
+                        ));
+                        ui.end_row();
+                        ui.label("state lock held by ui")
+                            .on_hover_text("the pipewire thread can't update the state meanwhile");
+                        ui.label(format!(
+                            "{} times (total {} us, max {} us)",
+                            METRICS.ui_lock_acquired.load(Ordering::Relaxed),
+                            METRICS.ui_lock_held_us.load(Ordering::Relaxed),
+                            METRICS.ui_lock_max_held_us.load(Ordering::Relaxed)
+                        ));
+                        ui.end_row();
+                        ui.label("paused events dropped")
+                            .on_hover_text("the graph was rebuilt on resume instead");
+                        ui.label(
+                            METRICS
+                                .paused_events_dropped
+                                .load(Ordering::Relaxed)
+                                .to_string(),
+                        );
+                        ui.end_row();
+                    });
+                if ui.button("Reset max").clicked() {
+                    METRICS.reset_max();
+                }
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,231 @@
+use super::*;
+
+//
+// latency and an audible test of the path between the selected nodes
+//
+
+impl PipewireGraphApp {
+    // per-hop and total latency along the links between the selected nodes
+    pub(super) fn show_latency_window(&mut self, ctx: &egui::Context) {
+        let node_ids: Vec<u32> = self
+            .state
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| self.state.graph[node_id].user_data.pipewire_id)
+            .collect();
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let path = latency::find_path(&state, &node_ids);
+
+        let new_ports: Vec<u32> = path
+            .iter()
+            .flat_map(|hop| [hop.output_port, hop.input_port])
+            .filter(|port_id| self.tracked_ports.insert(*port_id))
+            .collect();
+        if !new_ports.is_empty() {
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::PortParamsQuery(new_ports));
+        }
+
+        let node_label = |id: u32| {
+            state
+                .global_objects
+                .get(&id)
+                .and_then(PipewireObject::get_node_label)
+                .unwrap_or("--")
+        };
+        let format_ms = |(min, max): (f32, f32)| {
+            if max - min < 0.01 {
+                format!("{:.2} ms", min)
+            } else {
+                format!("{:.2} - {:.2} ms", min, max)
+            }
+        };
+
+        egui::Window::new("Latency")
+            .open(&mut self.extra_state.window_latency)
+            .show(ctx, |ui| {
+                if path.is_empty() {
+                    ui.label("Select linked nodes in the graph to show the latency along them.");
+                    return;
+                }
+                let path_quantum = latency::path_quantum(&state, &path);
+                let (quantum, rate) = path_quantum.unwrap_or(DEFAULT_QUANTUM);
+                ui.label(format!(
+                    "Quantum {}/{} ({:.2} ms){}",
+                    quantum,
+                    rate,
+                    quantum as f32 / rate as f32 * 1000.0,
+                    if path_quantum.is_none() {
+                        " (assumed)"
+                    } else {
+                        ""
+                    }
+                ));
+                ui.add_space(5.0);
+                let port_ms = |port_id: u32, direction: Direction| {
+                    latency::port_latency(&state, port_id, direction)
+                        .map(|latency| latency.to_ms(quantum, rate))
+                };
+                egui::Grid::new("latency")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Hop");
+                        ui.strong("Hop latency");
+                        ui.strong("Cumulative");
+                        ui.end_row();
+
+                        // capture latency reported on output ports accumulates along the path
+                        let mut previous = Some((0.0, 0.0));
+                        for hop in &path {
+                            let capture = port_ms(hop.output_port, Direction::Input);
+                            ui.label(format!(
+                                "{} -> {}",
+                                node_label(hop.from_node),
+                                node_label(hop.to_node)
+                            ));
+                            match (capture, previous) {
+                                (Some(capture), Some(previous)) => ui.label(format_ms((
+                                    capture.0 - previous.0,
+                                    capture.1 - previous.1,
+                                ))),
+                                _ => ui.label("?"),
+                            };
+                            ui.label(capture.map_or("?".to_owned(), format_ms));
+                            ui.end_row();
+                            previous = capture;
+                        }
+
+                        let last = path.last().unwrap();
+                        let playback = port_ms(last.input_port, Direction::Output);
+                        ui.label(format!("{} -> (output)", node_label(last.to_node)));
+                        ui.label(playback.map_or("?".to_owned(), format_ms));
+                        ui.label("");
+                        ui.end_row();
+
+                        ui.strong("Total");
+                        ui.label("");
+                        match (previous, playback) {
+                            (Some(capture), Some(playback)) => ui.strong(format_ms((
+                                capture.0 + playback.0,
+                                capture.1 + playback.1,
+                            ))),
+                            _ => ui.strong("?"),
+                        };
+                        ui.end_row();
+                    });
+            });
+        // latency params arrive without notifying the ui
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    // play a tone through the linked selected nodes and check the level after every node
+    pub(super) fn show_path_test_window(&mut self, ctx: &egui::Context) {
+        let node_ids: Vec<u32> = self
+            .state
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| self.state.graph[node_id].user_data.pipewire_id)
+            .collect();
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let path = latency::find_path(&state, &node_ids);
+        let path_nodes: Vec<u32> = path
+            .first()
+            .map(|hop| hop.from_node)
+            .into_iter()
+            .chain(path.iter().map(|hop| hop.to_node))
+            .collect();
+        let report = state.path_test.as_ref();
+        let running = report.map_or(false, |report| report.running);
+        let node_label = |id: u32| {
+            state
+                .global_objects
+                .get(&id)
+                .and_then(PipewireObject::get_node_label)
+                .unwrap_or("--")
+        };
+
+        let mut message = None;
+        egui::Window::new("Path test")
+            .open(&mut self.extra_state.window_path_test)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Plays a {} Hz tone at {} dBFS into the first node with inputs for {} s and captures it after every node.",
+                    path_test::TONE_FREQUENCY,
+                    path_test::TONE_LEVEL_DB,
+                    path_test::DURATION.as_secs()
+                ));
+                ui.horizontal(|ui| {
+                    let button = egui::Button::new("Run");
+                    if ui
+                        .add_enabled(!path_nodes.is_empty() && !running, button)
+                        .on_hover_text("the tone is audible wherever the path ends")
+                        .clicked()
+                    {
+                        message = Some(ChannelMessage::PathTestStart(path_nodes.clone()));
+                    }
+                    if ui.add_enabled(running, egui::Button::new("Stop")).clicked() {
+                        message = Some(ChannelMessage::PathTestStop);
+                    }
+                    if path_nodes.is_empty() {
+                        ui.label("Select linked nodes in the graph.");
+                    }
+                });
+                let report = match report {
+                    Some(report) => report,
+                    None => return,
+                };
+                if let Some(error) = &report.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.add_space(5.0);
+                egui::Grid::new("path-test")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Node");
+                        ui.strong("Peak");
+                        ui.strong("Result");
+                        ui.end_row();
+
+                        for hop in &report.hops {
+                            ui.label(node_label(hop.node_id));
+                            if hop.peak > 0.0 {
+                                ui.label(format!("{:.1} dBFS", path_test::to_db(hop.peak)));
+                            } else {
+                                ui.label("--");
+                            }
+                            let visuals = ui.visuals();
+                            let (color, text) = match (&hop.error, Verdict::of(hop.peak)) {
+                                (Some(e), _) => (visuals.error_fg_color, e.as_str()),
+                                (None, _) if running => (visuals.text_color(), "running..."),
+                                (None, Verdict::Pass) => {
+                                    (visuals.strong_text_color(), Verdict::Pass.as_str())
+                                }
+                                (None, Verdict::Attenuated) => {
+                                    (visuals.warn_fg_color, Verdict::Attenuated.as_str())
+                                }
+                                (None, Verdict::Silent) => {
+                                    (visuals.error_fg_color, Verdict::Silent.as_str())
+                                }
+                            };
+                            ui.colored_label(color, text);
+                            ui.end_row();
+                        }
+                    });
+            });
+        drop(state);
+        if let Some(message) = message {
+            self.pipewire_wrapper.channel_sender.send_or_log(message);
+        }
+        // levels arrive without notifying the ui
+        if running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+    }
+}
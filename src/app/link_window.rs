@@ -0,0 +1,413 @@
+use super::*;
+
+//
+// linking ports picked from lists or typed by name, and finding nodes by name
+//
+
+const MAX_RECENT_LINKS: usize = 5;
+
+impl PipewireGraphApp {
+    // create/destroy links by picking ports
+    pub(super) fn show_link_window(&mut self, ctx: &egui::Context) {
+        let mut link_action = None;
+        egui::Window::new("Link")
+            .open(&mut self.extra_state.window_link)
+            .show(ctx, |ui| {
+                let state = self.pipewire_wrapper.state.clone();
+                let state = metrics::lock_ui(&state);
+                egui::Grid::new("link")
+                    .num_columns(3)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        let sides = [
+                            (
+                                "From",
+                                true,
+                                &mut self.extra_state.link_from_node,
+                                &mut self.extra_state.link_from,
+                            ),
+                            (
+                                "To",
+                                false,
+                                &mut self.extra_state.link_to_node,
+                                &mut self.extra_state.link_to,
+                            ),
+                        ];
+                        for (label, output, node, port) in sides {
+                            ui.label(label);
+                            link_node_combo(ui, &state, output, node);
+                            link_port_combo(ui, &state, output, node.as_deref(), port);
+                            ui.end_row();
+                        }
+                    });
+                // e.g. an audio output to a midi input
+                let types = [&self.extra_state.link_from, &self.extra_state.link_to].map(|port| {
+                    let (k, v) = port.as_ref()?;
+                    let port = state.find_object_by_prop(k, v)?;
+                    Some(port_data_type(&state, port))
+                });
+                let compatible = match types {
+                    [Some(from), Some(to)] if from != to => {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ {} output to {} input", from.name(), to.name()),
+                        );
+                        false
+                    }
+                    [Some(_), Some(_)] => true,
+                    _ => false,
+                };
+                if !self.extra_state.link_recent.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.weak("Recent");
+                        for (from, to) in &self.extra_state.link_recent {
+                            let text = format!("{} → {}", from.1, to.1);
+                            if ui.small_button(text).clicked() {
+                                self.extra_state.link_from = Some(from.clone());
+                                self.extra_state.link_to = Some(to.clone());
+                                // the ports might be on other nodes than the filters
+                                self.extra_state.link_from_node = None;
+                                self.extra_state.link_to_node = None;
+                            }
+                        }
+                    });
+                }
+                drop(state);
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    let flags = &mut self.extra_state.link_flags;
+                    ui.checkbox(&mut flags.linger, "Linger")
+                        .on_hover_text("keep the link after this app exits (object.linger)");
+                    ui.checkbox(&mut flags.passive, "Passive").on_hover_text(
+                        "doesn't keep the nodes running, and session managers \
+                         don't treat it as a routing choice (link.passive)",
+                    );
+                    ui.weak("(also for links drawn in the graph)");
+                });
+                ui.checkbox(&mut self.link_force, "Force")
+                    .on_hover_text("create another link even if the ports are already linked");
+                ui.horizontal(|ui| {
+                    for (create, text) in [(true, "Create Link"), (false, "Destroy Link")] {
+                        // destroying stays possible for links which shouldn't exist
+                        let enabled = compatible || !create;
+                        if ui.add_enabled(enabled, egui::Button::new(text)).clicked() {
+                            if let (Some(from), Some(to)) =
+                                (&self.extra_state.link_from, &self.extra_state.link_to)
+                            {
+                                link_action = Some((create, from.clone(), to.clone()));
+                            }
+                        }
+                    }
+                });
+                ui.separator();
+                egui::CollapsingHeader::new("Existing links").show(ui, |ui| {
+                    let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                    egui::ScrollArea::vertical()
+                        .max_height(250.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("existing-links")
+                                .num_columns(3)
+                                .spacing([10.0, 5.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for link in state
+                                        .global_objects
+                                        .values()
+                                        .filter(|object| object.type_ == ObjectType::Link)
+                                    {
+                                        ui.label(link.id.to_string());
+                                        ui.label(snapshot::label(&state, link));
+                                        match state.link_flags.get(&link.id) {
+                                            Some(flags) => {
+                                                let text = match (flags.linger, flags.passive) {
+                                                    (true, true) => "linger, passive",
+                                                    (true, false) => "linger",
+                                                    (false, true) => "passive",
+                                                    (false, false) => "",
+                                                };
+                                                ui.label(text);
+                                            }
+                                            None => {
+                                                ui.weak("--");
+                                            }
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                });
+            });
+        if let Some((create, from, to)) = link_action {
+            if create {
+                let recent = &mut self.extra_state.link_recent;
+                recent.retain(|other| *other != (from.clone(), to.clone()));
+                recent.insert(0, (from.clone(), to.clone()));
+                recent.truncate(MAX_RECENT_LINKS);
+            }
+            let flags = self.link_flags(&from, &to, create);
+            self.perform_link_action(LinkAction {
+                create,
+                from,
+                to,
+                flags,
+                force: self.link_force,
+            });
+        }
+    }
+
+    // keyboard-only linking by fuzzy matching "node.name:port.name" of output and input ports
+    pub(super) fn show_quick_connect_window(&mut self, ctx: &egui::Context) {
+        let mut candidates: [Vec<(String, (String, String))>; 2] = Default::default();
+        {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            for port in state.global_objects.values() {
+                let side = if PipewireObject::is_output(port) {
+                    0
+                } else if PipewireObject::is_input(port) {
+                    1
+                } else {
+                    continue;
+                };
+                let (key, value) = match PipewireObject::get_name(port) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let node_name = PipewireObject::get_parent_node_id(port)
+                    .and_then(|node_id| state.global_objects.get(&node_id))
+                    .and_then(|node| node.props.as_ref()?.get_key(PropKey::NodeName))
+                    .unwrap_or("--");
+                let port_name = port
+                    .props
+                    .as_ref()
+                    .and_then(|props| props.get_key(PropKey::PortName))
+                    .unwrap_or(value);
+                candidates[side].push((
+                    format!("{}:{}", node_name, port_name),
+                    (key.to_owned(), value.to_owned()),
+                ));
+            }
+        }
+        for candidates in &mut candidates {
+            candidates.sort();
+        }
+
+        let mut link = None;
+        let focus = self.quick_connect_focus.take();
+        egui::Window::new("Quick connect")
+            .open(&mut self.extra_state.window_quick_connect)
+            .show(ctx, |ui| {
+                ui.weak("type parts of node.name:port.name, ↑↓ to choose, enter to continue");
+                let mut chosen: [Option<&(String, String)>; 2] = [None, None];
+                for (side, label) in ["From", "To"].into_iter().enumerate() {
+                    let (query, highlighted) = &mut self.quick_connect[side];
+                    let ranked = fuzzy::rank(
+                        query,
+                        candidates[side].iter().map(|(text, _)| text.as_str()),
+                    );
+                    *highlighted = (*highlighted).min(ranked.len().saturating_sub(1));
+                    let response = ui
+                        .horizontal(|ui| {
+                            ui.label(label);
+                            ui.add(
+                                egui::TextEdit::singleline(query)
+                                    .id_source(("quick-connect", side))
+                                    .desired_width(350.0),
+                            )
+                        })
+                        .inner;
+                    if focus == Some(side) {
+                        response.request_focus();
+                    }
+                    if response.changed() {
+                        *highlighted = 0;
+                    }
+                    if response.has_focus() {
+                        let mut input = ui.input_mut();
+                        let last = ranked.len().saturating_sub(1);
+                        if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                            *highlighted = (*highlighted + 1).min(last);
+                        }
+                        if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                            *highlighted = highlighted.saturating_sub(1);
+                        }
+                    }
+                    for (i, &index) in ranked.iter().enumerate().take(8) {
+                        let (text, _) = &candidates[side][index];
+                        if ui.selectable_label(i == *highlighted, text).clicked() {
+                            *highlighted = i;
+                        }
+                    }
+                    if ranked.len() > 8 {
+                        ui.weak(format!("… {} more", ranked.len() - 8));
+                    }
+                    chosen[side] = ranked
+                        .get(*highlighted)
+                        .map(|&index| &candidates[side][index].1);
+                    let entered = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                    if entered && side == 0 {
+                        self.quick_connect_focus = Some(1);
+                    }
+                    if entered && side == 1 {
+                        if let [Some(from), Some(to)] = chosen {
+                            link = Some((from.clone(), to.clone()));
+                        }
+                    }
+                    ui.separator();
+                }
+                ui.horizontal(|ui| {
+                    let enabled = chosen.iter().all(Option::is_some);
+                    if ui.add_enabled(enabled, egui::Button::new("Link")).clicked() {
+                        if let [Some(from), Some(to)] = chosen {
+                            link = Some((from.clone(), to.clone()));
+                        }
+                    }
+                    ui.weak("with the flags of the Link window");
+                });
+            });
+        if let Some((from, to)) = link {
+            let flags = self.link_flags(&from, &to, true);
+            self.perform_link_action(LinkAction {
+                create: true,
+                from,
+                to,
+                flags,
+                force: self.link_force,
+            });
+            self.quick_connect = Default::default();
+            self.quick_connect_focus = Some(0);
+        }
+    }
+
+    // jump to a graph node by typing parts of its label
+    pub(super) fn show_node_search_window(&mut self, ctx: &egui::Context) {
+        let mut candidates: Vec<(String, NodeId)> = self
+            .state
+            .graph
+            .iter_nodes()
+            .filter(|&node_id| self.state.node_positions.contains_key(node_id))
+            .map(|node_id| (self.state.graph[node_id].label.clone(), node_id))
+            .collect();
+        candidates.sort();
+
+        let mut found = None;
+        let focus = std::mem::take(&mut self.node_search_focus);
+        egui::Window::new("Find node")
+            .open(&mut self.extra_state.window_node_search)
+            .show(ctx, |ui| {
+                ui.weak("type parts of the node label, ↑↓ to choose, enter to show it");
+                let (query, highlighted) = &mut self.node_search;
+                let ranked = fuzzy::rank(query, candidates.iter().map(|(text, _)| text.as_str()));
+                *highlighted = (*highlighted).min(ranked.len().saturating_sub(1));
+                let response = ui.add(
+                    egui::TextEdit::singleline(query)
+                        .id_source("node-search")
+                        .desired_width(350.0),
+                );
+                if focus {
+                    response.request_focus();
+                }
+                if response.changed() {
+                    *highlighted = 0;
+                }
+                if response.has_focus() {
+                    let mut input = ui.input_mut();
+                    let last = ranked.len().saturating_sub(1);
+                    if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                        *highlighted = (*highlighted + 1).min(last);
+                    }
+                    if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                        *highlighted = highlighted.saturating_sub(1);
+                    }
+                }
+                for (i, &index) in ranked.iter().enumerate().take(8) {
+                    let (text, node_id) = &candidates[index];
+                    if ui.selectable_label(i == *highlighted, text).clicked() {
+                        *highlighted = i;
+                        found = Some(*node_id);
+                    }
+                }
+                if ranked.len() > 8 {
+                    ui.weak(format!("… {} more", ranked.len() - 8));
+                }
+                let entered = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                if entered {
+                    found = ranked.get(*highlighted).map(|&index| candidates[index].1);
+                    // keep typing for the next one
+                    self.node_search_focus = true;
+                }
+            });
+        if let Some(node_id) = found {
+            self.focus_node(node_id);
+            self.found_node = Some((node_id, Instant::now()));
+        }
+    }
+}
+
+// nodes with output (or input) ports (`None` shows the ports of all nodes)
+fn link_node_combo(
+    ui: &mut egui::Ui,
+    state: &PipewireState,
+    output: bool,
+    selected: &mut Option<String>,
+) {
+    let node_ids: BTreeSet<u32> = state
+        .global_objects
+        .values()
+        .filter(|port| is_link_port(port, output))
+        .filter_map(PipewireObject::get_parent_node_id)
+        .collect();
+    let mut names: Vec<&str> = node_ids
+        .iter()
+        .filter_map(|id| {
+            state
+                .global_objects
+                .get(id)?
+                .props
+                .as_ref()?
+                .get_key(PropKey::NodeName)
+        })
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    egui::ComboBox::from_id_source(("link-node", output))
+        .width(200.0)
+        .selected_text(selected.as_deref().unwrap_or("all nodes"))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, "all nodes");
+            for name in names {
+                ui.selectable_value(selected, Some(name.to_owned()), name);
+            }
+        });
+}
+
+// ports as (key, value) of `PipewireObject::get_name` (like `find_link`)
+fn link_port_combo(
+    ui: &mut egui::Ui,
+    state: &PipewireState,
+    output: bool,
+    node: Option<&str>,
+    selected: &mut Option<(String, String)>,
+) {
+    let node_id = node.and_then(|name| {
+        state
+            .find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+            .map(|node| node.id)
+    });
+    egui::ComboBox::from_id_source(("link-port", output))
+        .width(350.0)
+        .selected_text(selected.as_ref().map_or("", |(_k, v)| v.as_str()))
+        .show_ui(ui, |ui| {
+            for port in state.global_objects.values() {
+                if !is_link_port(port, output)
+                    || (node.is_some() && PipewireObject::get_parent_node_id(port) != node_id)
+                {
+                    continue;
+                }
+                if let Some((k, v)) = PipewireObject::get_name(port) {
+                    let value = Some((k.to_owned(), v.to_owned()));
+                    ui.selectable_value(selected, value, v)
+                        .on_hover_text(port_flags_text(port));
+                }
+            }
+        });
+}
@@ -0,0 +1,148 @@
+use super::*;
+
+//
+// nodes and their connections as a tree instead of the graph editor
+//
+
+impl PipewireGraphApp {
+    // nodes and their connections as a keyboard navigable (Tab / Space) tree
+    // emitting the same responses as the graph editor
+    pub(super) fn show_list_view(
+        &self,
+        ui: &mut egui::Ui,
+    ) -> Vec<NodeResponse<GraphNodeResponse, GraphNodeData>> {
+        let graph = &self.state.graph;
+        let mut responses = vec![];
+        let mut nodes: Vec<(NodeId, &Node<GraphNodeData>)> = graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.user_data.pipewire_id.is_some())
+            .collect();
+        nodes.sort_by(|a, b| a.1.label.cmp(&b.1.label));
+        let output_label = |output: OutputId| {
+            let node = &graph[graph.get_output(output).node];
+            let name = node.outputs.iter().find(|(_, id)| *id == output);
+            format!("{}:{}", node.label, name.map_or("--", |(name, _)| name))
+        };
+        let input_label = |input: InputId| {
+            let node = &graph[graph.get_input(input).node];
+            let name = node.inputs.iter().find(|(_, id)| *id == input);
+            format!("{}:{}", node.label, name.map_or("--", |(name, _)| name))
+        };
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for (node_id, node) in nodes {
+                    egui::CollapsingHeader::new(&node.label)
+                        .id_source(node_id)
+                        .show(ui, |ui| {
+                            for (name, input) in &node.inputs {
+                                let input = *input;
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("⏵ {}", name));
+                                    ui.menu_button("Connect from...", |ui| {
+                                        for (other_id, other) in &graph.nodes {
+                                            if other.user_data.pipewire_id.is_none()
+                                                || other_id == node_id
+                                            {
+                                                continue;
+                                            }
+                                            for (other_name, output) in &other.outputs {
+                                                let text =
+                                                    format!("{}:{}", other.label, other_name);
+                                                if ui.button(text).clicked() {
+                                                    responses.push(
+                                                        NodeResponse::ConnectEventEnded {
+                                                            output: *output,
+                                                            input,
+                                                        },
+                                                    );
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        }
+                                    });
+                                });
+                                if let Some(output) = graph.connection(input) {
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(20.0);
+                                        ui.label(format!("from {}", output_label(output)));
+                                        if ui.small_button("Disconnect").clicked() {
+                                            responses.push(NodeResponse::DisconnectEvent {
+                                                output,
+                                                input,
+                                            });
+                                        }
+                                    });
+                                }
+                            }
+                            for (name, output) in &node.outputs {
+                                let output = *output;
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{} ⏵", name));
+                                    ui.menu_button("Connect to...", |ui| {
+                                        for (other_id, other) in &graph.nodes {
+                                            if other.user_data.pipewire_id.is_none()
+                                                || other_id == node_id
+                                            {
+                                                continue;
+                                            }
+                                            for (other_name, input) in &other.inputs {
+                                                let text =
+                                                    format!("{}:{}", other.label, other_name);
+                                                if ui.button(text).clicked() {
+                                                    responses.push(
+                                                        NodeResponse::ConnectEventEnded {
+                                                            output,
+                                                            input: *input,
+                                                        },
+                                                    );
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        }
+                                    });
+                                });
+                                for (input, _) in graph
+                                    .iter_connections()
+                                    .filter(|(_, other)| *other == output)
+                                {
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(20.0);
+                                        ui.label(format!("to {}", input_label(input)));
+                                        if ui.small_button("Disconnect").clicked() {
+                                            responses.push(NodeResponse::DisconnectEvent {
+                                                output,
+                                                input,
+                                            });
+                                        }
+                                    });
+                                }
+                            }
+                            let object = node
+                                .user_data
+                                .pipewire_id
+                                .and_then(|id| state.global_objects.get(&id));
+                            if let Some(props) = object.and_then(|object| object.props.as_ref()) {
+                                egui::CollapsingHeader::new("Properties")
+                                    .id_source((node_id, "props"))
+                                    .show(ui, |ui| {
+                                        for (key, value) in props.iter() {
+                                            ui.label(format!("{} = {}", key, value));
+                                        }
+                                    });
+                            }
+                            responses.extend(pipewire_node_bottom_ui(
+                                ui,
+                                node_id,
+                                graph,
+                                &self.user_state,
+                            ));
+                        });
+                }
+            });
+        responses
+    }
+}
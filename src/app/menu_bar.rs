@@ -0,0 +1,255 @@
+use super::*;
+
+//
+// menu bar with the File/Edit menus and the window toggles, and the keyboard shortcuts
+//
+
+impl PipewireGraphApp {
+    // undo/redo, bypass and the window shortcuts
+    pub(super) fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // left to text edits (their own undo) while one has the focus
+        let (undo, redo) = if ctx.memory().focus().is_some() {
+            (false, false)
+        } else {
+            let mut input = ctx.input_mut();
+            let redo = input.consume_key(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::Z,
+            );
+            (
+                input.consume_key(egui::Modifiers::COMMAND, egui::Key::Z),
+                redo,
+            )
+        };
+        if ctx
+            .input_mut()
+            .consume_key(egui::Modifiers::COMMAND, egui::Key::K)
+        {
+            self.extra_state.window_quick_connect = !self.extra_state.window_quick_connect;
+            self.quick_connect_focus = Some(0);
+        }
+        if ctx
+            .input_mut()
+            .consume_key(egui::Modifiers::COMMAND, egui::Key::F)
+        {
+            self.extra_state.window_node_search = true;
+            self.node_search_focus = true;
+        }
+        // single key shortcut unless typing somewhere
+        let bypass = ctx.memory().focus().is_none()
+            && ctx
+                .input_mut()
+                .consume_key(egui::Modifiers::NONE, egui::Key::B);
+        if bypass && !self.restricted() {
+            self.toggle_bypass();
+        }
+        if undo {
+            self.undo_link_action();
+        }
+        if redo {
+            self.redo_link_action();
+        }
+    }
+
+    pub(super) fn show_menu_bar(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let (icon, mode) = match self.extra_state.theme.mode {
+                    ThemeMode::Dark => ("☀", ThemeMode::Light),
+                    ThemeMode::Light => ("🌙", ThemeMode::Dark),
+                };
+                if ui.button(icon).on_hover_text("toggle light/dark").clicked() {
+                    self.extra_state.theme.mode = mode;
+                    self.extra_state.theme.apply(ctx);
+                }
+                if !self.initial_sync_done {
+                    ui.spinner();
+                    let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                    let num_objects = state.global_objects.len();
+                    ui.weak(format!("loading ({} objects)", num_objects));
+                }
+                ui.menu_button("File", |ui| self.file_menu_ui(ui));
+                ui.menu_button("Edit", |ui| self.edit_menu_ui(ui));
+                ui.menu_button("Help", |ui| {
+                    let button = egui::Button::new("Tutorial");
+                    if ui.add_enabled(self.tutorial.is_none(), button).clicked() {
+                        self.start_tutorial();
+                        ui.close_menu();
+                    }
+                });
+                ui.toggle_value(&mut self.extra_state.window_core, "Core");
+                ui.toggle_value(&mut self.extra_state.window_object, "Object");
+                if !restricted {
+                    ui.toggle_value(&mut self.extra_state.window_clients, "Clients");
+                }
+                ui.toggle_value(&mut self.extra_state.window_link, "Link");
+                if ui
+                    .toggle_value(&mut self.extra_state.window_quick_connect, "Quick connect")
+                    .on_hover_text("link ports by typing (Ctrl+K)")
+                    .clicked()
+                {
+                    self.quick_connect_focus = Some(0);
+                }
+                if ui
+                    .toggle_value(&mut self.extra_state.window_node_search, "Find node")
+                    .on_hover_text("center a node by typing its name (Ctrl+F)")
+                    .clicked()
+                {
+                    self.node_search_focus = true;
+                }
+                ui.toggle_value(&mut self.extra_state.window_latency, "Latency");
+                ui.toggle_value(&mut self.extra_state.window_path_test, "Path test");
+                ui.toggle_value(&mut self.extra_state.window_recording, "Recording");
+                ui.toggle_value(&mut self.extra_state.window_snapshots, "Snapshots");
+                ui.toggle_value(&mut self.extra_state.window_session_manager, "Session");
+                ui.toggle_value(&mut self.extra_state.window_modules, "Modules");
+                ui.toggle_value(&mut self.extra_state.window_factories, "Factories");
+                ui.toggle_value(&mut self.extra_state.window_stream_format, "Format");
+                ui.toggle_value(&mut self.extra_state.window_unconnected, "Unconnected");
+                ui.toggle_value(&mut self.extra_state.window_mixer, "Mixer");
+                ui.toggle_value(&mut self.extra_state.window_scope, "Scope");
+                // keeps sharing while the window is closed
+                let share_label = if self.share_host.is_some() {
+                    "Share (on)"
+                } else {
+                    "Share"
+                };
+                ui.toggle_value(&mut self.extra_state.window_share, share_label);
+                ui.toggle_value(&mut self.extra_state.panel_journal, "Journal");
+                ui.toggle_value(&mut self.extra_state.panel_timeline, "Timeline");
+                ui.toggle_value(&mut self.extra_state.window_log, "Log");
+                ui.toggle_value(&mut self.extra_state.window_theme, "Theme");
+                ui.toggle_value(&mut self.extra_state.window_debug, "Debug");
+                ui.toggle_value(&mut self.extra_state.window_performance, "Performance");
+                ui.separator();
+                self.view_bar_ui(ui);
+            });
+        });
+    }
+
+    // export, session recording and tray options
+    fn file_menu_ui(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Export", |ui| {
+            if ui.button("GraphViz (.dot)").clicked() {
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                let dot = export::to_dot(&state);
+                drop(state);
+                write_export(&mut self.toasts, EXPORT_DOT_PATH, &dot);
+                ui.close_menu();
+            }
+            if ui.button("Canvas (.svg)").clicked() {
+                let svg = self.export_svg();
+                write_export(&mut self.toasts, EXPORT_SVG_PATH, &svg);
+                ui.close_menu();
+            }
+        });
+        match &self.session_recorder {
+            Some(recorder) => {
+                let text = format!("⏹ Stop recording ({} ticks)", recorder.num_ticks());
+                if ui.button(text).clicked() {
+                    self.stop_session_recording();
+                    ui.close_menu();
+                }
+            }
+            None => {
+                if ui
+                    .button("⏺ Record session")
+                    .on_hover_text(format!(
+                        "registry events into {} (replay with --replay)",
+                        SESSION_PATH
+                    ))
+                    .clicked()
+                {
+                    self.record_session(SESSION_PATH.to_owned());
+                    ui.close_menu();
+                }
+            }
+        }
+        // the tray belongs to the window (not to a remote tab)
+        #[cfg(feature = "tray")]
+        if self.remote.is_none() && self.pop_out.is_none() {
+            ui.checkbox(&mut self.extra_state.minimize_to_tray, "Minimize to tray")
+                .on_hover_text(
+                    "closing the window hides it into a tray icon, \
+                     and new devices and streams show a notification meanwhile",
+                );
+        }
+    }
+
+    // undo/redo and creating or cleaning up objects
+    fn edit_menu_ui(&mut self, ui: &mut egui::Ui) {
+        let restricted = self.restricted();
+        let button = egui::Button::new("Undo (Ctrl+Z)");
+        if ui
+            .add_enabled(self.link_history.can_undo(), button)
+            .clicked()
+        {
+            self.undo_link_action();
+            ui.close_menu();
+        }
+        let button = egui::Button::new("Redo (Ctrl+Shift+Z)");
+        if ui
+            .add_enabled(self.link_history.can_redo(), button)
+            .clicked()
+        {
+            self.redo_link_action();
+            ui.close_menu();
+        }
+        ui.separator();
+        let button = egui::Button::new("Refresh graph");
+        if ui.add_enabled(self.tutorial.is_none(), button).clicked() {
+            self.refresh_graph();
+            ui.close_menu();
+        }
+        if !restricted && ui.button("Create virtual device...").clicked() {
+            self.extra_state.window_virtual_device = true;
+            ui.close_menu();
+        }
+        if !restricted && ui.button("Create from preset...").clicked() {
+            self.extra_state.window_presets = true;
+            ui.close_menu();
+        }
+        if !restricted && ui.button("Set up network audio...").clicked() {
+            self.extra_state.window_network = true;
+            ui.close_menu();
+        }
+        ui.separator();
+        let button = egui::Button::new(format!("Show hidden nodes ({})", self.hidden_nodes.len()));
+        if ui
+            .add_enabled(!self.hidden_nodes.is_empty(), button)
+            .clicked()
+        {
+            self.show_hidden_nodes();
+            ui.close_menu();
+        }
+        if restricted {
+            return;
+        }
+        ui.separator();
+        let num_created = {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            state.created_objects.len() + state.loaded_modules.len()
+        };
+        let button = egui::Button::new(format!("Clean up everything I created ({})", num_created));
+        if ui
+            .add_enabled(num_created > 0, button)
+            .on_hover_text(
+                "destroy links and virtual devices created by this app, \
+                 and unload its modules",
+            )
+            .clicked()
+        {
+            let state = self.pipewire_wrapper.state.clone();
+            self.send_or_confirm(
+                &metrics::lock_ui(&state),
+                vec![ChannelMessage::CreatedObjectsDestroy],
+            );
+            ui.close_menu();
+        }
+        ui.checkbox(
+            &mut self.extra_state.skip_confirmation,
+            "Destroy without confirmation",
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+//
+// playback streams with their volumes and meters (also popped out)
+//
+
+impl PipewireGraphApp {
+    // track the volumes of new streams and meter exactly these ones
+    pub(super) fn set_mixer_nodes(&mut self, node_ids: Vec<u32>) {
+        let new: Vec<u32> = node_ids
+            .iter()
+            .copied()
+            .filter(|node_id| !self.mixer_nodes.contains(node_id))
+            .collect();
+        let sender = &self.pipewire_wrapper.channel_sender;
+        if !new.is_empty() {
+            sender.send_or_log(ChannelMessage::NodeParamsQuery(new));
+        }
+        sender.send_or_log(ChannelMessage::NodeMetersSet(node_ids.clone()));
+        self.mixer_nodes = node_ids;
+    }
+
+    // playback streams like pavucontrol's Playback tab
+    pub(super) fn show_mixer_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let node_label = self.extra_state.node_label;
+        let mut streams: Vec<(String, u32)> = self
+            .registry
+            .topology()
+            .nodes()
+            .filter_map(|node| state.global_objects.get(&node.id))
+            .filter(|object| PipewireObject::get_media_class(object) == Some("Stream/Output/Audio"))
+            .map(|object| (pipewire_node_label(object, node_label), object.id))
+            .collect();
+        streams.sort();
+
+        let mut volume_changed = None;
+        let mut mute_changed = None;
+        let mut focus = None;
+        let mut pop_out = false;
+        self.window(ctx, "Mixer")
+            .open(&mut self.extra_state.window_mixer)
+            .show(ctx, |ui| {
+                if self.pop_out.is_none() && ui.button("⧉ Pop out").clicked() {
+                    pop_out = true;
+                }
+                if streams.is_empty() {
+                    ui.label("No application is playing.");
+                    return;
+                }
+                egui::Grid::new("mixer")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, node_id) in &streams {
+                            if ui.link(label).on_hover_text("show in graph").clicked() {
+                                focus = Some(*node_id);
+                            }
+                            let volume = state.node_volumes.get(node_id);
+                            match volume.filter(|volume| !volume.channel_volumes.is_empty()) {
+                                Some(volume) => {
+                                    let mut mute = volume.mute;
+                                    if ui.toggle_value(&mut mute, "🔇").changed() {
+                                        mute_changed = Some((*node_id, mute));
+                                    }
+                                    // cubic scale like pavucontrol
+                                    let channels = &volume.channel_volumes;
+                                    let average =
+                                        channels.iter().sum::<f32>() / channels.len() as f32;
+                                    let mut percent = (average.cbrt() * 100.0).round() as u32;
+                                    let slider =
+                                        egui::Slider::new(&mut percent, 0..=150).suffix("%");
+                                    if ui.add(slider).changed() {
+                                        let volume = (percent as f32 / 100.0).powi(3);
+                                        volume_changed =
+                                            Some((*node_id, vec![volume; channels.len()]));
+                                    }
+                                }
+                                None => {
+                                    ui.label("");
+                                    ui.weak("--");
+                                }
+                            }
+                            // -60 dBFS .. 0 dBFS
+                            let peak = state.node_peaks.get(node_id).copied().unwrap_or(0.0);
+                            let db = path_test::to_db(peak);
+                            let fill = ((db + 60.0) / 60.0).clamp(0.0, 1.0);
+                            ui.add(egui::ProgressBar::new(fill).desired_width(100.0))
+                                .on_hover_text(format!("{:.1} dBFS", db));
+                            ui.end_row();
+                        }
+                    });
+            });
+        drop(state);
+        let node_ids: Vec<u32> = streams.iter().map(|(_, node_id)| *node_id).collect();
+        if node_ids != self.mixer_nodes {
+            self.set_mixer_nodes(node_ids);
+        }
+        let sender = &self.pipewire_wrapper.channel_sender;
+        if let Some((node_id, channel_volumes)) = volume_changed {
+            sender.send_or_log(ChannelMessage::NodeSetVolume(node_id, channel_volumes));
+        }
+        if let Some((node_id, mute)) = mute_changed {
+            sender.send_or_log(ChannelMessage::NodeSetMute(node_id, mute));
+        }
+        if let Some(id) = focus {
+            self.focus_pipewire_object(id);
+        }
+        if pop_out {
+            self.spawn_pop_out(PopOut::Mixer);
+        }
+        // meters
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,307 @@
+use super::*;
+
+//
+// properties of the selected object (also popped out into its own process)
+//
+
+impl PipewireGraphApp {
+    pub(super) fn show_object_window(&mut self, ctx: &egui::Context) {
+        let mut destroyed = None;
+        let mut edited = None;
+        let short_props_tooltips = self.extra_state.short_props_tooltips;
+        let mut pop_out = false;
+        self.window(ctx, "Object")
+            .open(&mut self.extra_state.window_object)
+            .show(ctx, |ui| {
+                if self.pop_out.is_none() && ui.button("⧉ Pop out").clicked() {
+                    pop_out = true;
+                }
+                if ui.button("Copy as JSON").clicked() {
+                    let dump = metrics::lock_ui(&self.pipewire_wrapper.state).dump();
+                    match serde_json::to_string_pretty(&dump) {
+                        Ok(json) => ui.output().copied_text = json,
+                        Err(e) => tracing::error!("json serialization failed ({})", e),
+                    }
+                }
+                ui.horizontal(|ui| {
+                    let input = ui.add(
+                        egui::TextEdit::singleline(&mut self.extra_state.object_column_input)
+                            .hint_text("prop key e.g. media.class")
+                            .desired_width(200.0),
+                    );
+                    let submitted = input.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                    if ui.button("Add column").clicked() || submitted {
+                        let key = self.extra_state.object_column_input.trim().to_owned();
+                        if !key.is_empty() && !self.extra_state.object_columns.contains(&key) {
+                            self.extra_state.object_columns.push(key);
+                        }
+                        self.extra_state.object_column_input.clear();
+                    }
+                });
+                let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+                let object_columns = &self.extra_state.object_columns;
+                let object_sort = &mut self.extra_state.object_sort;
+                let mut removed_column = None;
+                egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
+                    let mut table = TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .column(Size::exact(20.0))
+                        .column(Size::exact(40.0))
+                        .column(Size::exact(80.0))
+                        .column(Size::exact(40.0));
+                    for _ in object_columns {
+                        table = table.column(Size::initial(120.0).at_least(40.0));
+                    }
+                    table
+                        .column(Size::remainder())
+                        .header(text_height, |mut header| {
+                            let sort = &mut *object_sort;
+                            header.col(|ui| {
+                                object_column_header(ui, "ID", ObjectColumn::Id, sort);
+                            });
+                            header.col(|ui| {
+                                object_column_header(ui, "Serial", ObjectColumn::Serial, sort)
+                                    .on_hover_text("object.serial (never reused unlike the ID)");
+                            });
+                            header.col(|ui| {
+                                object_column_header(ui, "Type", ObjectColumn::Type, sort);
+                            });
+                            header.col(|ui| {
+                                ui.strong("Perms")
+                                    .on_hover_text("permissions of this app on the object");
+                            });
+                            for key in object_columns {
+                                header.col(|ui| {
+                                    let column = ObjectColumn::Prop(key.clone());
+                                    object_column_header(ui, key, column, sort).context_menu(
+                                        |ui| {
+                                            if ui.button("Remove column").clicked() {
+                                                removed_column = Some(key.clone());
+                                                ui.close_menu();
+                                            }
+                                        },
+                                    );
+                                });
+                            }
+                            header.col(|ui| {
+                                object_column_header(ui, "Props", ObjectColumn::Name, sort);
+                            });
+                        })
+                        .body(|mut body| {
+                            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                            let mut objects: Vec<_> = state.global_objects.values().collect();
+                            if let Some((column, descending)) = object_sort {
+                                objects.sort_by(|a, b| column.compare(a, b));
+                                if *descending {
+                                    objects.reverse();
+                                }
+                            }
+                            for object in objects {
+                                body.row(text_height, |mut row| {
+                                    row.col(|ui| {
+                                        ui.label(object.id.to_string());
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(ObjectColumn::Serial.value(object));
+                                    });
+                                    row.col(|ui| {
+                                        let type_name = PipewireObject::get_type_name(object);
+                                        if let ObjectType::Other(raw) = &object.type_ {
+                                            ui.label(egui::RichText::new(type_name).italics())
+                                                .on_hover_text(raw);
+                                        } else {
+                                            ui.label(type_name);
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        ui.monospace(permissions_label(object.permissions));
+                                    });
+                                    for key in object_columns {
+                                        row.col(|ui| {
+                                            ui.label(ObjectColumn::Prop(key.clone()).value(object));
+                                        });
+                                    }
+                                    row.col(|ui| {
+                                        let label = ui.add(
+                                            egui::Label::new(
+                                                PipewireObject::get_name(object)
+                                                    .map_or("--", |(_k, v)| v),
+                                            )
+                                            .sense(egui::Sense::click()),
+                                        );
+                                        let profiles = state.device_profiles.get(&object.id);
+                                        let writable = PipewireObject::is_writable(object);
+                                        let destroyable = PipewireObject::is_destroyable(object);
+                                        let label = label.context_menu(|ui| {
+                                            if let Some(profiles) = profiles {
+                                                ui.add_enabled_ui(writable, |ui| {
+                                                    if let Some(index) =
+                                                        device_profile_menu(ui, profiles)
+                                                    {
+                                                        self.pipewire_wrapper
+                                                            .channel_sender
+                                                            .send_or_log(ChannelMessage::DeviceSetProfile(
+                                                                object.id, index,
+                                                            ));
+                                                    }
+                                                });
+                                            }
+                                            if object.type_ == ObjectType::Node {
+                                                let button = egui::Button::new("Edit properties…");
+                                                if ui.add_enabled(writable, button).clicked() {
+                                                    edited = Some(object.id);
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                            let button = egui::Button::new("Destroy");
+                                            if ui.add_enabled(destroyable, button).clicked() {
+                                                destroyed = Some(ChannelMessage::ObjectDestroy(
+                                                    object.id,
+                                                    PipewireObject::get_serial(object)
+                                                        .map(str::to_owned),
+                                                ));
+                                                ui.close_menu();
+                                            }
+                                        });
+                                        if let Some(props) = &object.props {
+                                            label.on_hover_ui(|ui| {
+                                                let props_str = if short_props_tooltips {
+                                                    short_props(props)
+                                                } else {
+                                                    format!("{:#?}", props)
+                                                };
+                                                ui.add(
+                                                    egui::TextEdit::multiline(
+                                                        &mut props_str.as_str(),
+                                                    )
+                                                    .font(egui::TextStyle::Monospace)
+                                                    .desired_width(f32::INFINITY),
+                                                );
+                                            });
+                                        };
+                                    });
+                                });
+                            }
+                        });
+                });
+                if let Some(key) = removed_column {
+                    let column = ObjectColumn::Prop(key.clone());
+                    if matches!(&self.extra_state.object_sort, Some((sorted, _)) if *sorted == column) {
+                        self.extra_state.object_sort = None;
+                    }
+                    self.extra_state.object_columns.retain(|other| *other != key);
+                }
+            });
+        if let Some(message) = destroyed {
+            let state = self.pipewire_wrapper.state.clone();
+            self.send_or_confirm(&metrics::lock_ui(&state), vec![message]);
+        }
+        if let Some(node_id) = edited {
+            let state = self.pipewire_wrapper.state.clone();
+            self.node_props_edit = Some(NodePropsEdit::new(&metrics::lock_ui(&state), node_id));
+        }
+        self.show_node_props_window(ctx);
+        if pop_out {
+            self.spawn_pop_out(PopOut::Object);
+        }
+    }
+
+    // node.description/node.nick via `SPA_PROP_params` (only applied by some nodes e.g. adapters)
+    fn show_node_props_window(&mut self, ctx: &egui::Context) {
+        let edit = match &mut self.node_props_edit {
+            Some(edit) => edit,
+            None => return,
+        };
+        let mut open = true;
+        let mut apply = false;
+        egui::Window::new("Node properties")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("node {}", edit.node_id));
+                egui::Grid::new("node-props")
+                    .num_columns(2)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        for (key, edited) in EDITABLE_NODE_PROPS.iter().zip(&mut edit.edited) {
+                            ui.label(key.as_str());
+                            ui.text_edit_singleline(edited);
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    let changed = edit.edited != edit.current;
+                    if ui
+                        .add_enabled(changed, egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui
+                        .add_enabled(changed, egui::Button::new("Reset"))
+                        .clicked()
+                    {
+                        edit.edited = edit.current.clone();
+                    }
+                });
+                ui.weak("the node might ignore the update (e.g. nodes which aren't adapters)");
+            });
+        if apply {
+            let props = EDITABLE_NODE_PROPS
+                .iter()
+                .zip(edit.edited.iter().zip(&edit.current))
+                .filter(|(_, (edited, current))| edited != current)
+                .map(|(key, (edited, _))| (key.as_str().to_owned(), edited.clone()))
+                .collect();
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::NodeUpdateProps(edit.node_id, props));
+        }
+        if !open {
+            self.node_props_edit = None;
+        }
+    }
+}
+
+// clickable header cycling ascending -> descending -> unsorted
+fn object_column_header(
+    ui: &mut egui::Ui,
+    text: &str,
+    column: ObjectColumn,
+    sort: &mut Option<(ObjectColumn, bool)>,
+) -> egui::Response {
+    let text = match sort {
+        Some((sorted, false)) if *sorted == column => format!("{} ⏶", text),
+        Some((sorted, true)) if *sorted == column => format!("{} ⏷", text),
+        _ => text.to_owned(),
+    };
+    let response =
+        ui.add(egui::Label::new(egui::RichText::new(text).strong()).sense(egui::Sense::click()));
+    if response.clicked() {
+        *sort = match sort.take() {
+            Some((sorted, false)) if sorted == column => Some((column, true)),
+            Some((sorted, true)) if sorted == column => None,
+            _ => Some((column, false)),
+        };
+    }
+    response
+}
+
+// identifying props for hover tooltips (formatting all of them each frame adds up)
+fn short_props(props: &PropStore) -> String {
+    let keys = [
+        PropKey::ObjectSerial,
+        PropKey::NodeName,
+        PropKey::NodeDescription,
+        PropKey::MediaClass,
+        PropKey::AppName,
+    ];
+    let mut out = String::new();
+    for key in keys {
+        if let Some(value) = props.get_key(key) {
+            writeln!(out, "{} = {}", key.as_str(), value).unwrap();
+        }
+    }
+    write!(out, "({} props)", props.iter().count()).unwrap();
+    out
+}
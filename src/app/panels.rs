@@ -0,0 +1,193 @@
+use super::*;
+
+//
+// notices and panels around the graph (journal, timeline)
+//
+
+impl PipewireGraphApp {
+    // notices above the graph (restricted mode, paused graph, reduced view)
+    pub(super) fn show_banners(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        if restricted {
+            egui::TopBottomPanel::top("restricted_mode").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let reason = match self.sandbox {
+                        Some(sandbox) => format!("Running inside {}", sandbox.name()),
+                        None => "Restricted mode".to_owned(),
+                    };
+                    ui.label(format!(
+                        "{}: only objects exposed to this app are shown and operations needing more permissions are hidden.",
+                        reason
+                    ));
+                    if ui.button("Disable").clicked() {
+                        self.extra_state.restricted_mode = Some(false);
+                    }
+                });
+            });
+        }
+
+        if let Some(paused) = &self.paused {
+            let mut resume = false;
+            egui::TopBottomPanel::top("paused").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "⏸ Graph paused {}s ago, {} registry events held back{}.",
+                        paused.since.elapsed().as_secs(),
+                        paused.events.len(),
+                        if paused.rebuild {
+                            " (rebuilt on resume)"
+                        } else {
+                            ""
+                        }
+                    ));
+                    resume = ui.button("Resume").clicked();
+                });
+            });
+            if resume {
+                self.resume_graph();
+            }
+        }
+
+        if self.reduced_view {
+            egui::TopBottomPanel::top("reduced_view").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "More than {} nodes, so monitor ports are hidden to keep the graph responsive.",
+                        self.extra_state.node_budget.unwrap_or(DEFAULT_NODE_BUDGET)
+                    ));
+                    if ui.button("Show all").clicked() {
+                        self.reduced_view = false;
+                        let state = self.pipewire_wrapper.state.clone();
+                        self.rebuild_graph(&metrics::lock_ui(&state));
+                    }
+                });
+            });
+        }
+    }
+
+    // side and bottom panels around the graph
+    pub(super) fn show_panels(&mut self, ctx: &egui::Context) {
+        if self.extra_state.panel_journal {
+            self.show_journal_panel(ctx);
+        } else {
+            self.journal_reader = None;
+        }
+        if self.extra_state.panel_timeline {
+            self.show_timeline_panel(ctx);
+        }
+    }
+
+    fn show_journal_panel(&mut self, ctx: &egui::Context) {
+        if self.journal_reader.is_none() {
+            match JournalReader::new() {
+                Ok(journal_reader) => self.journal_reader = Some(journal_reader),
+                Err(e) => {
+                    tracing::error!("{:#}", e);
+                    self.toasts
+                        .error(format!("Cannot read the journal ({:#})", e));
+                    self.extra_state.panel_journal = false;
+                    return;
+                }
+            }
+        }
+        let mut focus = None;
+        egui::TopBottomPanel::bottom("journal")
+            .resizable(true)
+            .default_height(150.0)
+            .show(ctx, |ui| {
+                ui.strong("Journal (pipewire / wireplumber warnings)");
+                let entries = self.journal_reader.as_ref().unwrap().entries.clone();
+                let entries = entries.lock().unwrap();
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                egui::ScrollArea::both()
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for entry in entries.iter() {
+                            ui.horizontal(|ui| {
+                                for &id in &entry.object_ids {
+                                    if let Some(object) = state.global_objects.get(&id) {
+                                        let text = format!("{} {}", object.type_, id);
+                                        if ui.small_button(text).clicked() {
+                                            focus = Some(id);
+                                        }
+                                    }
+                                }
+                                ui.monospace(&entry.line);
+                            });
+                        }
+                    });
+            });
+        if let Some(id) = focus {
+            self.focus_pipewire_object(id);
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    // registry events with wall clock time (UTC) to correlate with glitches
+    fn show_timeline_panel(&mut self, ctx: &egui::Context) {
+        let mut focus = None;
+        egui::TopBottomPanel::bottom("timeline")
+            .resizable(true)
+            .default_height(150.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("Timeline (registry events)");
+                    let label = if self.timeline.paused {
+                        "⏵ Resume"
+                    } else {
+                        "⏸ Pause"
+                    };
+                    ui.toggle_value(&mut self.timeline.paused, label);
+                    if ui.button("Clear").clicked() {
+                        self.timeline.events.clear();
+                    }
+                    if ui.button("Export CSV").clicked() {
+                        let csv = self.timeline.to_csv();
+                        write_export(&mut self.toasts, EXPORT_TIMELINE_CSV_PATH, &csv);
+                    }
+                    if ui.button("Export JSON").clicked() {
+                        match self.timeline.to_json() {
+                            Ok(json) => {
+                                write_export(&mut self.toasts, EXPORT_TIMELINE_JSON_PATH, &json)
+                            }
+                            Err(e) => tracing::error!("json serialization failed ({})", e),
+                        }
+                    }
+                    ui.label(format!("{} events", self.timeline.events.len()));
+                });
+                let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, false])
+                    .show_rows(ui, text_height, self.timeline.events.len(), |ui, rows| {
+                        for event in self.timeline.events.range(rows) {
+                            ui.horizontal(|ui| {
+                                ui.monospace(timeline::format_time(event.time_ms));
+                                let color = match event.action {
+                                    Action::Added => egui::Color32::from_rgb(90, 180, 90),
+                                    Action::Updated => ui.visuals().warn_fg_color,
+                                    Action::Removed => ui.visuals().error_fg_color,
+                                };
+                                ui.colored_label(color, event.action.as_str());
+                                ui.label(&event.type_);
+                                // only objects which still exist can be focused
+                                let id = event.id.to_string();
+                                if state.global_objects.contains_key(&event.id) {
+                                    if ui.small_button(id).clicked() {
+                                        focus = Some(event.id);
+                                    }
+                                } else {
+                                    ui.label(id);
+                                }
+                                ui.label(&event.name);
+                            });
+                        }
+                    });
+            });
+        if let Some(id) = focus {
+            self.focus_pipewire_object(id);
+        }
+    }
+}
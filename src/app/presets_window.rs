@@ -0,0 +1,127 @@
+use super::*;
+
+//
+// one-click setups of virtual devices linked together (see `preset`)
+//
+
+// presets give up on linking nodes which don't show up by then
+const PRESET_LINK_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl PipewireGraphApp {
+    pub(super) fn show_presets_window(&mut self, ctx: &egui::Context) {
+        let (presets, errors) = self.presets.get_or_insert_with(preset::load);
+        let mut created = None;
+        let mut reload = false;
+        egui::Window::new("Presets")
+            .open(&mut self.extra_state.window_presets)
+            .show(ctx, |ui| {
+                for preset in presets.iter() {
+                    ui.horizontal(|ui| {
+                        let button = ui.button("Create").on_hover_text(preset.summary());
+                        if button.clicked() {
+                            created = Some(preset.clone());
+                        }
+                        ui.strong(&preset.name);
+                    });
+                    if !preset.description.is_empty() {
+                        ui.weak(&preset.description);
+                    }
+                }
+                for error in errors.iter() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    reload = ui.button("Reload").clicked();
+                    if let Some(dir) = preset::user_dir() {
+                        ui.weak(format!("your presets: {}/*.json", dir.display()));
+                    }
+                });
+            });
+        if reload {
+            self.presets = None;
+        }
+        if let Some(preset) = created {
+            self.create_preset(&preset);
+        }
+    }
+
+    // devices whose node.name is taken are reused
+    pub(super) fn create_preset(&mut self, preset: &Preset) {
+        {
+            let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+            for device in &preset.devices {
+                if state
+                    .find_object_by_prop(*pipewire::keys::NODE_NAME, &device.name)
+                    .is_none()
+                {
+                    self.pipewire_wrapper
+                        .channel_sender
+                        .send_or_log(ChannelMessage::VirtualDeviceCreate(device.clone()));
+                }
+            }
+        }
+        let now = Instant::now();
+        self.preset_links
+            .extend(preset.links.iter().map(|link| (link.clone(), now)));
+        self.toasts.info(format!("Creating {}", preset.name));
+    }
+
+    // link the nodes of created presets as soon as their ports show up
+    pub(super) fn link_preset_nodes(&mut self, ctx: &egui::Context) {
+        if self.preset_links.is_empty() {
+            return;
+        }
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let (mut ready, mut missing) = (vec![], vec![]);
+        self.preset_links.retain(|(link, since)| {
+            let nodes = link.resolve(&state);
+            let complete = nodes.map_or(false, |(output, input)| {
+                preset::has_all_ports(&state, output, true)
+                    && preset::has_all_ports(&state, input, false)
+            });
+            let timed_out = since.elapsed() > PRESET_LINK_TIMEOUT;
+            if !complete && !timed_out {
+                return true;
+            }
+            // whatever matches by the timeout
+            let pairs = nodes
+                .map(|(output, input)| state.match_ports_by_channel(output, input))
+                .unwrap_or_default();
+            if pairs.is_empty() {
+                missing.push(format!("{} → {}", link.from, link.to));
+            }
+            ready.extend(pairs);
+            false
+        });
+        let pairs: Vec<_> = ready
+            .into_iter()
+            .map(|(output, input)| {
+                (
+                    PipewireObject::id_key(output),
+                    PipewireObject::id_key(input),
+                )
+            })
+            .filter(|(from, to)| state.find_link(from, to).is_none())
+            .collect();
+        drop(state);
+        for (from, to) in pairs {
+            self.perform_link_action(LinkAction {
+                create: true,
+                from,
+                to,
+                flags: self.extra_state.link_flags,
+                force: false,
+            });
+        }
+        for link in missing {
+            tracing::error!("preset link {} timed out", link);
+            self.toasts
+                .error(format!("Cannot link {} (no matching ports)", link));
+        }
+        if !self.preset_links.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+}
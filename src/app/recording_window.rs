@@ -0,0 +1,78 @@
+use super::*;
+
+//
+// capture of a node into a WAV file
+//
+
+impl PipewireGraphApp {
+    // into the record directory, named after the node
+    pub(super) fn start_recording(&mut self, pipewire_id: u32) {
+        let node_name = metrics::lock_ui(&self.pipewire_wrapper.state)
+            .global_objects
+            .get(&pipewire_id)
+            .and_then(|object| object.props.as_ref()?.get_key(PropKey::NodeName))
+            .unwrap_or("recording")
+            .to_owned();
+        let path = recording::file_path(&self.extra_state.record_dir, &node_name);
+        self.pipewire_wrapper
+            .channel_sender
+            .send_or_log(ChannelMessage::RecordStart(
+                pipewire_id,
+                path.to_string_lossy().into_owned(),
+            ));
+        self.extra_state.window_recording = true;
+    }
+
+    pub(super) fn show_recording_window(&mut self, ctx: &egui::Context) {
+        let report = metrics::lock_ui(&self.pipewire_wrapper.state)
+            .recording
+            .clone();
+        let running = report.as_ref().map_or(false, |report| report.running);
+        let mut stop = false;
+        egui::Window::new("Recording")
+            .open(&mut self.extra_state.window_recording)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Directory");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.extra_state.record_dir)
+                            .hint_text("working directory"),
+                    );
+                });
+                let report = match &report {
+                    Some(report) => report,
+                    None => {
+                        ui.label("Start with \"⏺ Record\" on an audio node.");
+                        return;
+                    }
+                };
+                ui.label(report.path.display().to_string());
+                let seconds = report.duration().as_secs();
+                ui.label(format!(
+                    "{:02}:{:02} ({} Hz, 32 bit float)",
+                    seconds / 60,
+                    seconds % 60,
+                    recording::RATE
+                ));
+                if running {
+                    // -60 dBFS .. 0 dBFS
+                    let db = path_test::to_db(report.level);
+                    let fill = ((db + 60.0) / 60.0).clamp(0.0, 1.0);
+                    ui.add(egui::ProgressBar::new(fill).text(format!("{:.1} dBFS", db)));
+                }
+                if let Some(error) = &report.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                stop = ui.add_enabled(running, egui::Button::new("Stop")).clicked();
+            });
+        if stop {
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::RecordStop);
+        }
+        // duration and level arrive without notifying the ui
+        if running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+}
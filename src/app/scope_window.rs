@@ -0,0 +1,148 @@
+use super::*;
+
+//
+// waveform and spectrum of an output port
+//
+
+impl PipewireGraphApp {
+    pub(super) fn set_scope_port(&mut self, port_id: Option<u32>) {
+        let message = match port_id {
+            Some(port_id) => {
+                let ring = Arc::new(SampleRing::new(2 * scope::FFT_SIZE));
+                self.scope = Some((port_id, ring.clone()));
+                ChannelMessage::ScopeStart(port_id, ring)
+            }
+            None => {
+                self.scope = None;
+                ChannelMessage::ScopeStop
+            }
+        };
+        self.pipewire_wrapper.channel_sender.send_or_log(message);
+    }
+
+    // waveform and spectrum of an output port
+    pub(super) fn show_scope_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let (node_label, port_label) = (self.extra_state.node_label, self.extra_state.port_label);
+        let mut ports: Vec<(String, u32)> = self
+            .registry
+            .topology()
+            .ports()
+            .filter(|port| port.direction == Some(Direction::Output))
+            .filter_map(|port| {
+                let object = state.global_objects.get(&port.id)?;
+                let node = state.global_objects.get(&port.node_id)?;
+                let format = object.props.as_ref()?.get_key(PropKey::FormatDsp)?;
+                format.contains("audio").then(|| {
+                    let label = format!(
+                        "{}: {}",
+                        pipewire_node_label(node, node_label),
+                        pipewire_port_label(object, port_label)
+                    );
+                    (label, port.id)
+                })
+            })
+            .collect();
+        ports.sort();
+        let error = state.scope_error.clone();
+        drop(state);
+
+        let current = self.scope.as_ref().map(|(port_id, _)| *port_id);
+        let mut selected = current;
+        let (waveform, spectrum) = match &self.scope {
+            Some((_, ring)) => (
+                ring.latest(scope::WAVEFORM_SIZE),
+                scope::spectrum(&ring.latest(scope::FFT_SIZE)),
+            ),
+            None => (vec![], vec![]),
+        };
+        egui::Window::new("Scope")
+            .open(&mut self.extra_state.window_scope)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let text = ports
+                        .iter()
+                        .find(|(_, port_id)| Some(*port_id) == selected)
+                        .map_or("Select an output port", |(label, _)| label.as_str());
+                    egui::ComboBox::from_id_source("scope_port")
+                        .selected_text(text)
+                        .width(300.0)
+                        .show_ui(ui, |ui| {
+                            for (label, port_id) in &ports {
+                                ui.selectable_value(&mut selected, Some(*port_id), label);
+                            }
+                        });
+                    if ui
+                        .add_enabled(selected.is_some(), egui::Button::new("Stop"))
+                        .clicked()
+                    {
+                        selected = None;
+                    }
+                });
+                if let Some(error) = &error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                if waveform.is_empty() {
+                    return;
+                }
+                let rate = scope::RATE as f64;
+                let line = Line::new(
+                    waveform
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &sample)| [i as f64 * 1000.0 / rate, sample as f64])
+                        .collect::<PlotPoints>(),
+                );
+                ui.label("Waveform");
+                Plot::new("scope_waveform")
+                    .height(150.0)
+                    .include_y(-1.0)
+                    .include_y(1.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .x_axis_formatter(|x, _| format!("{} ms", x))
+                    .show(ui, |plot_ui| plot_ui.line(line));
+                // log frequency axis (the dc bin left out)
+                let bin_width = rate / scope::FFT_SIZE as f64;
+                let line = Line::new(
+                    spectrum
+                        .iter()
+                        .enumerate()
+                        .skip(1)
+                        .map(|(i, &db)| [(i as f64 * bin_width).log10(), db as f64])
+                        .collect::<PlotPoints>(),
+                );
+                ui.label("Spectrum");
+                Plot::new("scope_spectrum")
+                    .height(150.0)
+                    .include_x(20f64.log10())
+                    .include_x((rate / 2.0).log10())
+                    .include_y(scope::MIN_DB)
+                    .include_y(0.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .x_axis_formatter(|x, _| {
+                        let hz = 10f64.powf(x);
+                        if hz >= 1000.0 {
+                            format!("{:.1}k", hz / 1000.0)
+                        } else {
+                            format!("{:.0}", hz)
+                        }
+                    })
+                    .y_axis_formatter(|y, _| format!("{} dB", y))
+                    .label_formatter(|_, point| {
+                        format!("{:.0} Hz\n{:.1} dBFS", 10f64.powf(point.x), point.y)
+                    })
+                    .show(ui, |plot_ui| plot_ui.line(line));
+            });
+        if selected != current {
+            self.set_scope_port(selected);
+        }
+        if self.scope.is_some() {
+            ctx.request_repaint();
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use super::*;
+
+//
+// actions on the selected nodes (toolbar above the selection, bypass with B)
+//
+
+#[derive(Clone, Copy, PartialEq)]
+enum Arrange {
+    Align(Align),
+    // horizontally or vertically
+    Distribute(bool),
+    Snap,
+}
+
+impl PipewireGraphApp {
+    // egui_node_graph clears the selection on clicks outside of the editor (e.g. menu bar),
+    // so actions on the selection are shown over the editor
+    pub(super) fn show_selection_toolbar(&mut self, ctx: &egui::Context) {
+        let num_selected = self.state.selected_nodes.len();
+        if num_selected == 0 {
+            return;
+        }
+        let selected_links = self.selected_links();
+        // (output node, input node) when exactly two nodes are selected
+        let pair = match self.state.selected_nodes[..] {
+            [a, b] => self.pair_direction(a, b),
+            _ => None,
+        };
+        let pair_links = pair.map_or_else(Vec::new, |(a, b)| self.links_between(a, b));
+        let num_bypassed = self.selected_bypassed_links();
+        let mut connect_pair = false;
+        let mut arrange = None;
+        egui::Area::new("selection")
+            .order(egui::Order::Foreground)
+            .fixed_pos(self.editor_rect.min + egui::vec2(10.0, 10.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} selected", num_selected));
+                        if ui.button("Hide").clicked() {
+                            self.hide_selected_nodes();
+                        }
+                        ui.menu_button("Arrange ⏷", |ui| {
+                            for align in Align::ALL {
+                                let button = egui::Button::new(align.label());
+                                if ui.add_enabled(num_selected >= 2, button).clicked() {
+                                    arrange = Some(Arrange::Align(align));
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            for (horizontally, label) in [
+                                (true, "Distribute horizontally"),
+                                (false, "Distribute vertically"),
+                            ] {
+                                let button = egui::Button::new(label);
+                                if ui.add_enabled(num_selected >= 3, button).clicked() {
+                                    arrange = Some(Arrange::Distribute(horizontally));
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Snap to grid").clicked() {
+                                arrange = Some(Arrange::Snap);
+                                ui.close_menu();
+                            }
+                        });
+                        let button = egui::Button::new(format!(
+                            "Disconnect ({} links)",
+                            selected_links.len()
+                        ));
+                        let bypass_enabled = !selected_links.is_empty() || num_bypassed > 0;
+                        if ui.add_enabled(!selected_links.is_empty(), button).clicked() {
+                            let state = self.pipewire_wrapper.state.clone();
+                            self.send_or_confirm(
+                                &metrics::lock_ui(&state),
+                                vec![ChannelMessage::ObjectsDestroy(selected_links)],
+                            );
+                        }
+                        let text = if num_bypassed > 0 {
+                            format!("Re-enable ({} links)", num_bypassed)
+                        } else {
+                            "Bypass".to_owned()
+                        };
+                        if ui
+                            .add_enabled(bypass_enabled, egui::Button::new(text))
+                            .on_hover_text("take the links out and put them back later (B)")
+                            .clicked()
+                        {
+                            self.toggle_bypass();
+                        }
+                        if pair.is_some() {
+                            ui.separator();
+                            connect_pair = ui
+                                .button("Connect")
+                                .on_hover_text("link the pair by audio.channel, then in port order")
+                                .clicked();
+                            let button = egui::Button::new(format!(
+                                "Disconnect pair ({} links)",
+                                pair_links.len()
+                            ));
+                            if ui.add_enabled(!pair_links.is_empty(), button).clicked() {
+                                let state = self.pipewire_wrapper.state.clone();
+                                self.send_or_confirm(
+                                    &metrics::lock_ui(&state),
+                                    vec![ChannelMessage::ObjectsDestroy(pair_links)],
+                                );
+                            }
+                        }
+                    });
+                });
+            });
+        if let (true, Some((output_node, input_node))) = (connect_pair, pair) {
+            self.connect_nodes(output_node, input_node);
+        }
+        if let Some(arrange) = arrange {
+            self.arrange_selected_nodes(arrange);
+        }
+    }
+
+    fn arrange_selected_nodes(&mut self, arrange: Arrange) {
+        let nodes: Vec<(NodeId, egui::Rect)> = self
+            .state
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| {
+                let pos = *self.state.node_positions.get(node_id)?;
+                let size = estimate_node_size(self.state.graph.nodes.get(node_id)?);
+                Some((node_id, egui::Rect::from_min_size(pos, size)))
+            })
+            .collect();
+        let rects: Vec<egui::Rect> = nodes.iter().map(|(_, rect)| *rect).collect();
+        let positions = match arrange {
+            Arrange::Align(align) => align::align(&rects, align),
+            Arrange::Distribute(horizontally) => align::distribute(&rects, horizontally),
+            Arrange::Snap => rects.iter().map(|rect| rect.min).collect(),
+        };
+        let snap = self.extra_state.grid.snap || arrange == Arrange::Snap;
+        for ((node_id, _), pos) in nodes.into_iter().zip(positions) {
+            let pos = if snap {
+                self.extra_state.grid.snap(pos)
+            } else {
+                pos
+            };
+            self.state.node_positions.insert(node_id, pos);
+            self.update_pinned_position(node_id);
+        }
+    }
+
+    fn hide_selected_nodes(&mut self) {
+        for node_id in std::mem::take(&mut self.state.selected_nodes) {
+            if let Some(pipewire_id) = self.state.graph[node_id].user_data.pipewire_id {
+                self.hidden_nodes.insert(pipewire_id);
+                self.remove_pipewire_node(pipewire_id);
+            }
+        }
+        // connections were removed together with the nodes
+        let graph = &self.state.graph;
+        self.pipewire_id_to_connection.retain(|_, (output, input)| {
+            graph.outputs.contains_key(*output) && graph.inputs.contains_key(*input)
+        });
+    }
+
+    pub(super) fn show_hidden_nodes(&mut self) {
+        let hidden_nodes = std::mem::take(&mut self.hidden_nodes);
+        self.add_pipewire_nodes(&hidden_nodes);
+    }
+
+    // the selection order unless only the other direction has matching ports
+    fn pair_direction(&self, a: NodeId, b: NodeId) -> Option<(NodeId, NodeId)> {
+        let graph = &self.state.graph;
+        let (a_id, b_id) = (
+            graph.nodes.get(a)?.user_data.pipewire_id?,
+            graph.nodes.get(b)?.user_data.pipewire_id?,
+        );
+        let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+        if state.match_ports_by_channel(a_id, b_id).is_empty()
+            && !state.match_ports_by_channel(b_id, a_id).is_empty()
+        {
+            Some((b, a))
+        } else {
+            Some((a, b))
+        }
+    }
+
+    // links between the two nodes in either direction
+    fn links_between(&self, a: NodeId, b: NodeId) -> Vec<(u32, Option<String>)> {
+        let graph = &self.state.graph;
+        self.pipewire_id_to_connection
+            .iter()
+            .filter(|(_, &(output, input))| {
+                let nodes = (
+                    graph.outputs.get(output).map(|o| o.node),
+                    graph.inputs.get(input).map(|i| i.node),
+                );
+                nodes == (Some(a), Some(b)) || nodes == (Some(b), Some(a))
+            })
+            .map(|(&link_id, _)| (link_id, self.serial(link_id)))
+            .collect()
+    }
+
+    // bypassed links with either end on a selected node
+    fn selected_bypassed_links(&self) -> usize {
+        let selected = self.selected_pipewire_nodes();
+        self.bypassed_links
+            .iter()
+            .filter(|link| selected.contains(&link.nodes.0) || selected.contains(&link.nodes.1))
+            .count()
+    }
+
+    fn selected_pipewire_nodes(&self) -> HashSet<u32> {
+        self.state
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| self.state.graph.nodes.get(node_id)?.user_data.pipewire_id)
+            .collect()
+    }
+
+    // re-create the bypassed links of the selection, or else destroy its links and keep them
+    // (e.g. A/B comparison of a processing chain)
+    pub(super) fn toggle_bypass(&mut self) {
+        let selected = self.selected_pipewire_nodes();
+        if self.selected_bypassed_links() > 0 {
+            let (restored, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.bypassed_links)
+                .into_iter()
+                .partition(|link| {
+                    selected.contains(&link.nodes.0) || selected.contains(&link.nodes.1)
+                });
+            self.bypassed_links = kept;
+            for link in &restored {
+                self.pipewire_wrapper
+                    .channel_sender
+                    .send_or_log(ChannelMessage::LinkCreate(
+                        link.from.clone(),
+                        link.to.clone(),
+                        link.flags,
+                        false,
+                    ));
+            }
+            self.toasts
+                .info(format!("Re-enabled {} links", restored.len()));
+            return;
+        }
+        let links = self.selected_links();
+        if links.is_empty() {
+            return;
+        }
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        for &(link_id, _) in &links {
+            let ports = state
+                .global_objects
+                .get(&link_id)
+                .and_then(PipewireObject::get_link_ports)
+                .and_then(|(output, input)| {
+                    Some((
+                        state.global_objects.get(&output)?,
+                        state.global_objects.get(&input)?,
+                    ))
+                });
+            let (output, input) = match ports {
+                Some(ports) => ports,
+                None => continue,
+            };
+            self.bypassed_links.push(BypassedLink {
+                from: port_key(output),
+                to: port_key(input),
+                flags: state.link_flags.get(&link_id).copied().unwrap_or_default(),
+                nodes: (
+                    PipewireObject::get_parent_node_id(output).unwrap_or_default(),
+                    PipewireObject::get_parent_node_id(input).unwrap_or_default(),
+                ),
+            });
+        }
+        drop(state);
+        // no confirmation since nothing is lost
+        self.toasts
+            .info(format!("Bypassed {} links (B to re-enable)", links.len()));
+        self.pipewire_wrapper
+            .channel_sender
+            .send_or_log(ChannelMessage::ObjectsDestroy(links));
+    }
+
+    // links with either end on a selected node
+    fn selected_links(&self) -> Vec<(u32, Option<String>)> {
+        self.links_of_nodes(&self.state.selected_nodes)
+    }
+
+    pub(super) fn links_of_nodes(&self, nodes: &[NodeId]) -> Vec<(u32, Option<String>)> {
+        let graph = &self.state.graph;
+        self.pipewire_id_to_connection
+            .iter()
+            .filter(|(_, &(output, input))| {
+                let selected = |node| nodes.contains(&node);
+                graph
+                    .outputs
+                    .get(output)
+                    .map_or(false, |o| selected(o.node))
+                    || graph.inputs.get(input).map_or(false, |i| selected(i.node))
+            })
+            .map(|(&link_id, _)| (link_id, self.serial(link_id)))
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+//
+// session manager detection and its policy on stream links
+//
+
+impl PipewireGraphApp {
+    // detected session manager and the stream links its policy may revert
+    pub(super) fn show_session_manager_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let detected = session_manager::detect(&state);
+        egui::Window::new("Session manager")
+            .open(&mut self.extra_state.window_session_manager)
+            .show(ctx, |ui| {
+                let manager = match &detected {
+                    Some(manager) => manager,
+                    None => {
+                        ui.label("No session manager detected.");
+                        ui.label("Manual links stay as they are (nothing re-routes streams).");
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "{} {}(client {})",
+                    manager.kind.label(),
+                    manager
+                        .version
+                        .as_ref()
+                        .map_or_else(String::new, |version| format!("{} ", version)),
+                    manager.client_id
+                ));
+                ui.label("It links the streams below by its policy and can revert a manual link");
+                ui.label("when the stream reconnects or the default device changes.");
+                if manager.kind == session_manager::Kind::WirePlumber {
+                    ui.label("A copied rule in ~/.config/wireplumber/wireplumber.conf.d/*.conf");
+                    ui.label("(WirePlumber 0.5) makes the target persistent.");
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("session-manager")
+                            .num_columns(3)
+                            .spacing([10.0, 5.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Stream");
+                                ui.strong("Linked to");
+                                ui.end_row();
+
+                                // one row per (stream, node) pair rather than per port link
+                                let mut pairs = BTreeSet::new();
+                                for (output_port, input_port) in state
+                                    .global_objects
+                                    .values()
+                                    .filter_map(PipewireObject::get_link_ports)
+                                {
+                                    let (stream, target) = match session_manager::policy_stream(
+                                        &state,
+                                        output_port,
+                                        input_port,
+                                    ) {
+                                        Some(pair) => pair,
+                                        None => continue,
+                                    };
+                                    if !pairs.insert((stream.id, target.id)) {
+                                        continue;
+                                    }
+                                    ui.label(
+                                        PipewireObject::get_node_label(stream).unwrap_or("--"),
+                                    );
+                                    ui.label(
+                                        PipewireObject::get_node_label(target).unwrap_or("--"),
+                                    );
+                                    let rule = session_manager::wireplumber_rule(stream, target)
+                                        .filter(|_| {
+                                            manager.kind == session_manager::Kind::WirePlumber
+                                        });
+                                    if let Some(rule) = rule {
+                                        let button = ui.button("Copy rule").on_hover_ui(|ui| {
+                                            ui.monospace(&rule);
+                                        });
+                                        if button.clicked() {
+                                            ui.output().copied_text = rule;
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+//
+// read-only graph shared over tcp, and viewers of the graph of another machine
+//
+
+impl PipewireGraphApp {
+    pub(super) fn show_share_window(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("Share (experimental)")
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.strong("Host");
+                ui.label("Let others view this graph (read-only, not encrypted).");
+                let extra_state = &mut self.extra_state;
+                match &self.share_host {
+                    Some(host) => {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Listening on {} with token", host.address));
+                            ui.monospace(&host.token);
+                            if ui.small_button("Copy").clicked() {
+                                ui.output().copied_text = host.token.clone();
+                            }
+                        });
+                        ui.label(format!(
+                            "{} viewer(s) connected",
+                            host.num_viewers.load(std::sync::atomic::Ordering::Relaxed)
+                        ));
+                        if ui.button("Stop sharing").clicked() {
+                            self.share_host = None;
+                        }
+                    }
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.label("Address");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut extra_state.share_address)
+                                    .hint_text(share::DEFAULT_ADDRESS),
+                            )
+                            .on_hover_text("e.g. 0.0.0.0:7878 for every network interface");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Redact");
+                            let redaction = &mut extra_state.share_redaction;
+                            ui.checkbox(&mut redaction.process, "process info");
+                            ui.checkbox(&mut redaction.media, "media titles");
+                            ui.checkbox(&mut redaction.device, "device identifiers");
+                        });
+                        if ui.button("Start sharing").clicked() {
+                            let address = match extra_state.share_address.trim() {
+                                "" => share::DEFAULT_ADDRESS,
+                                address => address,
+                            };
+                            match ShareHost::new(
+                                address,
+                                extra_state.share_redaction,
+                                self.pipewire_wrapper.state.clone(),
+                            ) {
+                                Ok(host) => self.share_host = Some(host),
+                                Err(e) => self.toasts.warning(format!("{:#}", e)),
+                            }
+                        }
+                    }
+                }
+
+                ui.separator();
+                ui.strong("Viewer");
+                match &self.share_viewer {
+                    Some(viewer) => {
+                        let snapshot = viewer.snapshot.lock().unwrap();
+                        if let Some(error) = &snapshot.error {
+                            ui.colored_label(ui.visuals().warn_fg_color, error);
+                        } else if let Some(received) = snapshot.received {
+                            ui.label(format!(
+                                "{} objects (updated {:.0}s ago)",
+                                snapshot.objects.len(),
+                                received.elapsed().as_secs_f32()
+                            ));
+                        } else {
+                            ui.label("(waiting for snapshot..)");
+                        }
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for (output, input) in share::remote_links(&snapshot.objects) {
+                                    ui.monospace(format!("{} -> {}", output, input));
+                                }
+                            });
+                        drop(snapshot);
+                        if ui.button("Disconnect").clicked() {
+                            self.share_viewer = None;
+                        }
+                    }
+                    None => {
+                        egui::Grid::new("share-viewer")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label("Address");
+                                ui.add(
+                                    egui::TextEdit::singleline(
+                                        &mut extra_state.share_remote_address,
+                                    )
+                                    .hint_text("host:7878"),
+                                );
+                                ui.end_row();
+                                ui.label("Token");
+                                ui.text_edit_singleline(&mut extra_state.share_remote_token);
+                                ui.end_row();
+                            });
+                        if ui.button("Connect").clicked() {
+                            match ShareViewer::new(
+                                extra_state.share_remote_address.trim(),
+                                &extra_state.share_remote_token,
+                            ) {
+                                Ok(viewer) => self.share_viewer = Some(viewer),
+                                Err(e) => self.toasts.warning(format!("{:#}", e)),
+                            }
+                        }
+                    }
+                }
+            });
+        self.extra_state.window_share = open;
+        // snapshots and viewer count change without notifying the ui
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+//
+// registry snapshots and what changed since
+//
+
+impl PipewireGraphApp {
+    // "what did launching this app change?" (take a snapshot before and compare with now)
+    pub(super) fn show_snapshots_window(&mut self, ctx: &egui::Context) {
+        let now = Snapshot::take(&metrics::lock_ui(&self.pipewire_wrapper.state));
+        let snapshots = &mut self.snapshots;
+        let (from, to) = &mut self.snapshot_range;
+        egui::Window::new("Snapshots")
+            .open(&mut self.extra_state.window_snapshots)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    // a partial registry would show everything else as added later
+                    let button = egui::Button::new("Take snapshot");
+                    if ui.add_enabled(self.initial_sync_done, button).clicked() {
+                        snapshots.push(Snapshot::take(&metrics::lock_ui(
+                            &self.pipewire_wrapper.state,
+                        )));
+                        // compare the new one with now
+                        *from = snapshots.len() - 1;
+                        *to = None;
+                    }
+                    if ui.button("Clear").clicked() {
+                        snapshots.clear();
+                    }
+                });
+                if snapshots.is_empty() {
+                    ui.label("Take a snapshot, change something and compare it with now.");
+                    return;
+                }
+                *from = (*from).min(snapshots.len() - 1);
+                *to = to.filter(|&to| to < snapshots.len());
+                let label = |index: usize, snapshot: &Snapshot| {
+                    format!(
+                        "#{} ({} s ago, {} objects)",
+                        index + 1,
+                        snapshot.taken.elapsed().as_secs(),
+                        snapshot.num_objects()
+                    )
+                };
+                ui.horizontal(|ui| {
+                    ui.label("From");
+                    egui::ComboBox::from_id_source("snapshot-from")
+                        .selected_text(label(*from, &snapshots[*from]))
+                        .show_ui(ui, |ui| {
+                            for (index, snapshot) in snapshots.iter().enumerate() {
+                                ui.selectable_value(from, index, label(index, snapshot));
+                            }
+                        });
+                    ui.label("To");
+                    let selected = match *to {
+                        Some(index) => label(index, &snapshots[index]),
+                        None => "Now".to_owned(),
+                    };
+                    egui::ComboBox::from_id_source("snapshot-to")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(to, None, "Now");
+                            for (index, snapshot) in snapshots.iter().enumerate() {
+                                ui.selectable_value(to, Some(index), label(index, snapshot));
+                            }
+                        });
+                });
+                let after = to.map_or(&now, |index| &snapshots[index]);
+                let changes = snapshot::diff(&snapshots[*from], after);
+                let count =
+                    |kind: ChangeKind| changes.iter().filter(|change| change.kind == kind).count();
+                ui.label(format!(
+                    "{} added, {} removed, {} changed",
+                    count(ChangeKind::Added),
+                    count(ChangeKind::Removed),
+                    count(ChangeKind::Changed)
+                ));
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("snapshot-diff")
+                        .num_columns(4)
+                        .spacing([10.0, 5.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for change in &changes {
+                                let (color, symbol) = match change.kind {
+                                    ChangeKind::Added => {
+                                        (egui::Color32::from_rgb(90, 180, 90), "+")
+                                    }
+                                    ChangeKind::Removed => (ui.visuals().error_fg_color, "−"),
+                                    ChangeKind::Changed => (ui.visuals().warn_fg_color, "~"),
+                                };
+                                ui.colored_label(color, symbol);
+                                ui.label(change.type_);
+                                ui.label(change.id.to_string());
+                                if change.props.is_empty() {
+                                    ui.label(&change.label);
+                                } else {
+                                    ui.collapsing(&change.label, |ui| {
+                                        for (key, before, after) in &change.props {
+                                            ui.label(format!(
+                                                "{}: {} → {}",
+                                                key,
+                                                before.as_deref().unwrap_or("(unset)"),
+                                                after.as_deref().unwrap_or("(unset)")
+                                            ));
+                                        }
+                                    });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+    }
+}
@@ -0,0 +1,171 @@
+use super::*;
+
+//
+// formats requested from streams, and ports left without links
+//
+
+impl PipewireGraphApp {
+    // request a format for the selected stream node and check what its ports ended up with
+    pub(super) fn show_stream_format_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let node_label = self.extra_state.node_label;
+        let stream = self
+            .state
+            .selected_nodes
+            .iter()
+            .filter_map(|&node_id| self.state.graph[node_id].user_data.pipewire_id)
+            .filter_map(|id| state.global_objects.get(&id))
+            .find(|object| {
+                PipewireObject::get_media_class(object)
+                    .map_or(false, |class| class.starts_with("Stream/"))
+            });
+
+        let mut current_formats: Vec<AudioFormat> = vec![];
+        if let Some(stream) = stream {
+            let port_ids: Vec<u32> = state.get_ports(stream.id).map(|port| port.id).collect();
+            let new_ports: Vec<u32> = port_ids
+                .iter()
+                .copied()
+                .filter(|port_id| self.tracked_ports.insert(*port_id))
+                .collect();
+            if !new_ports.is_empty() {
+                self.pipewire_wrapper
+                    .channel_sender
+                    .send_or_log(ChannelMessage::PortParamsQuery(new_ports));
+            }
+            for port_id in port_ids {
+                if let Some(&format) = state.port_formats.get(&port_id) {
+                    if !current_formats.contains(&format) {
+                        current_formats.push(format);
+                    }
+                }
+            }
+        }
+
+        let mut request = None;
+        egui::Window::new("Stream format")
+            .open(&mut self.extra_state.window_stream_format)
+            .show(ctx, |ui| {
+                let stream = match stream {
+                    Some(stream) => stream,
+                    None => {
+                        ui.label("Select a stream node (media.class \"Stream/*\") in the graph.");
+                        return;
+                    }
+                };
+                ui.strong(pipewire_node_label(stream, node_label));
+                ui.horizontal(|ui| {
+                    ui.label("Current");
+                    if current_formats.is_empty() {
+                        ui.label("(not negotiated)");
+                    }
+                    for format in &current_formats {
+                        ui.monospace(format.to_string());
+                    }
+                });
+                ui.separator();
+                let form = &mut self.extra_state.stream_format;
+                egui::Grid::new("stream-format")
+                    .num_columns(2)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label("Format");
+                        let selected = AUDIO_FORMATS
+                            .iter()
+                            .find(|(_, format)| *format == form.format)
+                            .map_or("", |(name, _)| name);
+                        egui::ComboBox::from_id_source("stream-format-format")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (name, format) in AUDIO_FORMATS {
+                                    ui.selectable_value(&mut form.format, format, name);
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("Rate");
+                        ui.add(DragValue::new(&mut form.rate).clamp_range(8000..=384000));
+                        ui.end_row();
+                        ui.label("Channels");
+                        ui.add(DragValue::new(&mut form.channels).clamp_range(1..=64));
+                        ui.end_row();
+                    });
+                let writable = PipewireObject::is_writable(stream);
+                if ui
+                    .add_enabled(writable, egui::Button::new("Request"))
+                    .clicked()
+                {
+                    request = Some((stream.id, *form));
+                }
+                if let Some(requested) = self.stream_format_requests.get(&stream.id) {
+                    if current_formats.contains(requested) {
+                        ui.label(format!("✔ {} was honored", requested));
+                    } else {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("✖ {} was not honored", requested),
+                        );
+                    }
+                }
+            });
+        drop(state);
+        if let Some((node_id, format)) = request {
+            self.stream_format_requests.insert(node_id, format);
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::StreamFormatRequest(node_id, format));
+        }
+        // port formats are updated without notifying the ui
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    // ports without any link grouped by node
+    pub(super) fn show_unconnected_window(&mut self, ctx: &egui::Context) {
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        let counts = state.link_counts();
+        let inputs_only = self.extra_state.unconnected_inputs_only;
+        let (port_label, node_label) = (self.extra_state.port_label, self.extra_state.node_label);
+        let mut nodes: Vec<(String, u32, Vec<&str>)> = state
+            .global_objects
+            .values()
+            .filter(|object| object.type_ == ObjectType::Node)
+            .filter_map(|node| {
+                let ports: Vec<&str> = state
+                    .get_ports(node.id)
+                    .filter(|port| !counts.contains_key(&port.id))
+                    .filter(|port| !inputs_only || PipewireObject::is_input(port))
+                    .map(|port| pipewire_port_label(port, port_label))
+                    .collect();
+                (!ports.is_empty()).then(|| (pipewire_node_label(node, node_label), node.id, ports))
+            })
+            .collect();
+        nodes.sort();
+
+        let mut focus = None;
+        egui::Window::new("Unconnected")
+            .open(&mut self.extra_state.window_unconnected)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.extra_state.unconnected_inputs_only, "Inputs only");
+                ui.separator();
+                if nodes.is_empty() {
+                    ui.label("Every port is connected.");
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (label, node_id, ports) in &nodes {
+                        ui.horizontal(|ui| {
+                            if ui.link(label).on_hover_text("show in graph").clicked() {
+                                focus = Some(*node_id);
+                            }
+                            ui.weak(ports.join(", "));
+                        });
+                    }
+                });
+            });
+        drop(state);
+        if let Some(id) = focus {
+            self.focus_pipewire_object(id);
+        }
+    }
+}
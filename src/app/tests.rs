@@ -0,0 +1,114 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::*;
+use crate::backend::MockBackend;
+
+//
+// graph building from registry fixtures replayed by `MockBackend` (no pipewire daemon)
+//
+
+fn wait_until(mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn replay(fixture: &str) -> (PipewireGraphApp, Arc<Mutex<Vec<ChannelMessage>>>) {
+    let backend = MockBackend::from_json(fixture).unwrap();
+    let commands = backend.commands();
+    let mut app = PipewireGraphApp::with_backend(&egui::Context::default(), backend);
+    wait_until(|| {
+        app.poll();
+        app.initial_sync_done()
+    });
+    (app, commands)
+}
+
+fn graph_node(app: &PipewireGraphApp, pipewire_id: u32) -> NodeId {
+    app.pipewire_id_to_node_id[&pipewire_id]
+}
+
+fn port_names(app: &PipewireGraphApp, pipewire_id: u32) -> Vec<String> {
+    let node = &app.state.graph[graph_node(app, pipewire_id)];
+    let inputs = node.inputs.iter().map(|(name, _)| name.clone());
+    inputs
+        .chain(node.outputs.iter().map(|(name, _)| name.clone()))
+        .collect()
+}
+
+#[test]
+fn builds_graph() {
+    let (mut app, _) = replay(include_str!("../../tests/fixtures/basic.json"));
+    assert_eq!(app.state.graph.nodes.len(), 2);
+    assert_eq!(app.state.graph.iter_connections().count(), 1);
+    assert!(app.pipewire_id_to_connection.contains_key(&50));
+    assert_eq!(port_names(&app, 40), ["playback_FL [1]", "playback_FR"]);
+    app.pipewire_wrapper.quit().unwrap();
+}
+
+#[test]
+fn builds_graph_from_out_of_order_events() {
+    let (mut app, _) = replay(include_str!("../../tests/fixtures/out_of_order.json"));
+    assert_eq!(app.state.graph.nodes.len(), 2);
+    assert_eq!(app.state.graph.iter_connections().count(), 1);
+    assert_eq!(port_names(&app, 30).len(), 2);
+    assert_eq!(port_names(&app, 40).len(), 2);
+    app.pipewire_wrapper.quit().unwrap();
+}
+
+#[test]
+fn removes_node_with_its_ports_and_links() {
+    let (mut app, _) = replay(include_str!("../../tests/fixtures/node_removed.json"));
+    assert_eq!(app.state.graph.nodes.len(), 1);
+    assert_eq!(app.state.graph.iter_connections().count(), 0);
+    assert!(app.registry.topology().node(30).is_none());
+    assert_eq!(app.registry.topology().ports().count(), 2);
+    app.pipewire_wrapper.quit().unwrap();
+}
+
+#[test]
+fn connects_unlinked_channels() {
+    let (mut app, commands) = replay(include_str!("../../tests/fixtures/basic.json"));
+    app.connect_nodes(graph_node(&app, 30), graph_node(&app, 40));
+    wait_until(|| !commands.lock().unwrap().is_empty());
+    let commands = commands.lock().unwrap();
+    // FL is already linked
+    assert!(matches!(
+        &commands[..],
+        [ChannelMessage::LinkCreate(from, to, _, false)]
+            if *from == PipewireObject::id_key(32) && *to == PipewireObject::id_key(42)
+    ));
+    drop(commands);
+    app.pipewire_wrapper.quit().unwrap();
+}
+
+#[test]
+fn holds_link_closing_feedback_loop() {
+    let (mut app, commands) = replay(include_str!("../../tests/fixtures/feedback_loop.json"));
+    // speaker monitor -> echo -> speaker
+    app.connect_nodes(graph_node(&app, 40), graph_node(&app, 60));
+    assert_eq!(app.feedback_links.len(), 1);
+    assert_eq!(app.user_state.feedback_loop, [60, 40]);
+    // a wrongly sent link would reach the backend before this marker (same priority, fifo)
+    app.pipewire_wrapper
+        .channel_sender
+        .send_or_log(ChannelMessage::PipewireRoundtrip);
+    wait_until(|| {
+        commands
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|command| matches!(command, ChannelMessage::PipewireRoundtrip))
+    });
+    assert!(!commands
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|command| matches!(command, ChannelMessage::LinkCreate(..))));
+    app.pipewire_wrapper.quit().unwrap();
+}
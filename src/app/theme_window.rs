@@ -0,0 +1,79 @@
+use super::*;
+
+//
+// colors, wire width and font size
+//
+
+impl PipewireGraphApp {
+    pub(super) fn show_theme_window(&mut self, ctx: &egui::Context) {
+        let theme = &mut self.extra_state.theme;
+        let old_theme = theme.clone();
+        egui::Window::new("Theme")
+            .open(&mut self.extra_state.window_theme)
+            .show(ctx, |ui| {
+                egui::Grid::new("theme").num_columns(2).show(ui, |ui| {
+                    ui.label("Mode");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut theme.mode, ThemeMode::Dark, "Dark");
+                        ui.radio_value(&mut theme.mode, ThemeMode::Light, "Light");
+                    });
+                    ui.end_row();
+                    ui.label("Accent");
+                    ui.horizontal(|ui| {
+                        let mut custom = theme.accent.is_some();
+                        if ui.checkbox(&mut custom, "Custom").changed() {
+                            theme.accent = custom.then_some([0, 160, 160]);
+                        }
+                        if let Some(accent) = &mut theme.accent {
+                            ui.color_edit_button_srgb(accent);
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("Port colors");
+                    ui.horizontal(|ui| {
+                        let port_colors = &mut theme.port_colors;
+                        for (label, color) in [
+                            ("audio", &mut port_colors.audio),
+                            ("midi", &mut port_colors.midi),
+                            ("video", &mut port_colors.video),
+                            ("other", &mut port_colors.other),
+                        ] {
+                            ui.color_edit_button_srgb(color);
+                            ui.label(label);
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        if ui.button("Default").clicked() {
+                            theme.port_colors = PortColors::DEFAULT;
+                        }
+                        if ui
+                            .button("Colorblind friendly")
+                            .on_hover_text("Okabe-Ito palette")
+                            .clicked()
+                        {
+                            theme.port_colors = PortColors::COLORBLIND;
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("Wire width");
+                    ui.add(egui::Slider::new(&mut theme.wire_width, 1.0..=10.0))
+                        .on_hover_text("of single channel wires (more channels are thicker)");
+                    ui.end_row();
+                    ui.label("Font size");
+                    ui.add(
+                        egui::Slider::new(&mut theme.font_scale, 0.75..=2.0)
+                            .custom_formatter(|scale, _| format!("{:.0} %", scale * 100.0)),
+                    );
+                    ui.end_row();
+                });
+                if ui.button("Reset").clicked() {
+                    *theme = Theme::default();
+                }
+            });
+        if *theme != old_theme {
+            theme.apply(ctx);
+        }
+    }
+}
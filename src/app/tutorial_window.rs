@@ -0,0 +1,139 @@
+use super::*;
+
+//
+// interactive tutorial on a sandbox graph (see `tutorial`)
+//
+
+impl PipewireGraphApp {
+    pub(super) fn start_tutorial(&mut self) {
+        let pan_zoom = self.state.pan_zoom;
+        self.state = EditorState::new(1.0);
+        self.state.pan_zoom = pan_zoom;
+        self.pipewire_id_to_node_id.clear();
+        self.pipewire_id_to_param_id.clear();
+        self.pipewire_id_to_connection.clear();
+        self.param_id_to_pipewire_id.clear();
+        self.pipewire_id_to_serial.clear();
+        self.next_node_y = Default::default();
+        for fake_node in &tutorial::FAKE_NODES {
+            self.add_fake_node(fake_node);
+        }
+        self.tutorial = Some(tutorial::Step::FIRST);
+    }
+
+    // back to the live graph
+    fn finish_tutorial(&mut self) {
+        self.tutorial = None;
+        self.extra_state.tutorial_done = true;
+        let state = self.pipewire_wrapper.state.clone();
+        let state = metrics::lock_ui(&state);
+        self.rebuild_graph(&state);
+    }
+
+    fn add_fake_node(&mut self, fake_node: &tutorial::FakeNode) {
+        let graph = &mut self.state.graph;
+        let node_id = graph.add_node(
+            fake_node.label.to_owned(),
+            GraphNodeData {
+                template: GraphNodeTemplate::PipewireNode,
+                pipewire_id: None,
+                own_stream: false,
+                device_id: None,
+                route_device: None,
+                bluez5_codec: None,
+                writable: true,
+                destroyable: false,
+                media_class: Some(fake_node.media_class.to_owned()),
+                client: None,
+                pinned: false,
+                collapsed: false,
+                position_pinned: false,
+                group: None,
+                process: None,
+                client_api: None,
+                network: None,
+            },
+            |_, _| {},
+        );
+        for &name in fake_node.inputs {
+            graph.add_input_param(
+                node_id,
+                name.to_owned(),
+                PortType::Audio,
+                PortValue::Port,
+                InputParamKind::ConnectionOnly,
+                true,
+            );
+        }
+        for &name in fake_node.outputs {
+            graph.add_output_param(node_id, name.to_owned(), PortType::Audio);
+        }
+        let column = if fake_node.outputs.is_empty() { 2 } else { 0 };
+        let position = egui::pos2(
+            50.0 + 350.0 * column as f32,
+            50.0 + self.next_node_y[column],
+        );
+        self.next_node_y[column] += 150.0;
+        self.state.node_positions.insert(node_id, position);
+        self.state.node_order.push(node_id);
+    }
+
+    pub(super) fn fake_connections(&self) -> Vec<FakeConnection> {
+        let graph = &self.state.graph;
+        let param_label = |node_id: NodeId, name: Option<&String>| {
+            format!(
+                "{}:{}",
+                graph[node_id].label,
+                name.map_or("--", |name| name)
+            )
+        };
+        graph
+            .iter_connections()
+            .map(|(input, output)| {
+                let output_node = graph.get_output(output).node;
+                let input_node = graph.get_input(input).node;
+                let output_name = graph[output_node]
+                    .outputs
+                    .iter()
+                    .find(|(_, id)| *id == output)
+                    .map(|(name, _)| name);
+                let input_name = graph[input_node]
+                    .inputs
+                    .iter()
+                    .find(|(_, id)| *id == input)
+                    .map(|(name, _)| name);
+                (
+                    param_label(output_node, output_name),
+                    param_label(input_node, input_name),
+                )
+            })
+            .collect()
+    }
+
+    pub(super) fn show_tutorial_window(&mut self, ctx: &egui::Context, step: tutorial::Step) {
+        let mut finish = false;
+        egui::Window::new("Tutorial")
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(400.0);
+                let (current, total) = step.progress();
+                ui.weak(format!(
+                    "Step {}/{} (sandbox, your session is not touched)",
+                    current, total
+                ));
+                ui.label(step.instruction());
+                ui.horizontal(|ui| {
+                    if step == tutorial::Step::Done {
+                        finish = ui.button("Start live mode").clicked();
+                    } else {
+                        finish = ui.small_button("Skip").clicked();
+                    }
+                });
+            });
+        if finish {
+            self.finish_tutorial();
+        }
+    }
+}
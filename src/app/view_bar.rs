@@ -0,0 +1,259 @@
+use super::*;
+
+//
+// view options at the end of the menu bar, and moving around the graph (zoom, pan, minimap)
+//
+
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 2.0;
+
+impl PipewireGraphApp {
+    // pause, layout and label options (on the right of the menu bar)
+    pub(super) fn view_bar_ui(&mut self, ui: &mut egui::Ui) {
+        match &self.paused {
+            Some(paused) => {
+                let text = format!("▶ Resume ({})", paused.events.len());
+                if ui
+                    .button(text)
+                    .on_hover_text("apply the registry events held back")
+                    .clicked()
+                {
+                    self.resume_graph();
+                }
+            }
+            None => {
+                let button = egui::Button::new("⏸ Pause");
+                if ui
+                    .add_enabled(self.tutorial.is_none(), button)
+                    .on_hover_text("freeze the graph (e.g. to catch a short-lived node)")
+                    .clicked()
+                {
+                    self.pause_graph();
+                }
+            }
+        }
+        if ui.button("Fit view").clicked() {
+            self.fit_view();
+        }
+        ui.checkbox(&mut self.extra_state.minimap, "Minimap");
+        ui.checkbox(&mut self.extra_state.grid.snap, "Snap to grid");
+        if self.extra_state.grid.snap {
+            ui.add(
+                DragValue::new(&mut self.extra_state.grid.size)
+                    .clamp_range(5.0..=100.0)
+                    .suffix(" px"),
+            )
+            .on_hover_text("grid size");
+        }
+        ui.checkbox(&mut self.extra_state.list_view, "List view");
+        ui.checkbox(&mut self.extra_state.color_by_client, "Color by client");
+        ui.checkbox(&mut self.extra_state.media_controls, "Media controls")
+            .on_hover_text("play/pause/skip on the streams of media players (MPRIS)");
+        ui.checkbox(&mut self.extra_state.monitor_mode, "Monitor default sink")
+            .on_hover_text("highlight the default sink and everything routed to it");
+        if ui
+            .checkbox(&mut self.extra_state.node_groups, "Group boxes")
+            .on_hover_text("Boxes around the nodes of the same device or client")
+            .changed()
+            && !self.extra_state.node_groups
+            && !self.collapsed_groups.is_empty()
+        {
+            self.collapsed_groups.clear();
+            let state = self.pipewire_wrapper.state.clone();
+            self.rebuild_graph(&metrics::lock_ui(&state));
+        }
+        ui.menu_button("Labels ⏵", |ui| {
+            let (port_label, node_label, show_ids) = (
+                self.extra_state.port_label,
+                self.extra_state.node_label,
+                self.extra_state.show_ids,
+            );
+            ui.label("Ports");
+            for style in PortLabel::ALL {
+                let text = style.key().as_str();
+                ui.radio_value(&mut self.extra_state.port_label, style, text);
+            }
+            ui.separator();
+            ui.label("Nodes");
+            for style in NodeLabel::ALL {
+                let text = style.key().as_str();
+                ui.radio_value(&mut self.extra_state.node_label, style, text);
+            }
+            ui.separator();
+            ui.checkbox(&mut self.extra_state.show_ids, "Object IDs")
+                .on_hover_text("pipewire id after the labels (as in pw-cli/pw-dump)");
+            let changed = (port_label, node_label, show_ids)
+                != (
+                    self.extra_state.port_label,
+                    self.extra_state.node_label,
+                    self.extra_state.show_ids,
+                );
+            // the sandbox graph is rebuilt after the tutorial anyway
+            if changed && self.tutorial.is_none() {
+                let state = self.pipewire_wrapper.state.clone();
+                self.rebuild_graph(&metrics::lock_ui(&state));
+            }
+        });
+        ui.menu_button("Clients ⏵", |ui| {
+            let mut changed = false;
+            for api in ClientApi::ALL {
+                let mut shown = !self.extra_state.hidden_client_apis.contains(&api);
+                let checkbox = ui.checkbox(&mut shown, api.label());
+                if checkbox.on_hover_text(api.description()).changed() {
+                    changed = true;
+                    if shown {
+                        self.extra_state.hidden_client_apis.remove(&api);
+                    } else {
+                        self.extra_state.hidden_client_apis.insert(api);
+                    }
+                }
+            }
+            if changed && self.tutorial.is_none() {
+                let state = self.pipewire_wrapper.state.clone();
+                self.rebuild_graph(&metrics::lock_ui(&state));
+            }
+        })
+        .response
+        .on_hover_text("client streams shown by API (native or compatibility layer)");
+        let mut restricted_mode = self.restricted();
+        if ui
+            .checkbox(&mut restricted_mode, "Restricted")
+            .on_hover_text(
+                "hide operations a sandbox can't perform (on by default inside Flatpak/Snap)",
+            )
+            .changed()
+        {
+            self.extra_state.restricted_mode = Some(restricted_mode);
+        }
+        ui.separator();
+        ui.label("Node budget");
+        let mut budget = self.extra_state.node_budget.unwrap_or(DEFAULT_NODE_BUDGET);
+        if ui
+            .add(egui::DragValue::new(&mut budget).clamp_range(1..=10000))
+            .on_hover_text("hide monitor ports on startup when there are more nodes")
+            .changed()
+        {
+            self.extra_state.node_budget = Some(budget);
+        }
+    }
+
+    // egui_node_graph ignores `pan_zoom.zoom` when drawing,
+    // so zoom is applied by scaling the whole ui via pixels per point
+    pub(super) fn handle_zoom_and_pan(
+        &mut self,
+        ctx: &egui::Context,
+        native_pixels_per_point: f32,
+    ) {
+        let pointer_over_graph = ctx.input().pointer.hover_pos().map_or(false, |pos| {
+            self.editor_rect.contains(pos)
+                && ctx
+                    .layer_id_at(pos)
+                    .map_or(false, |layer| layer.order == egui::Order::Background)
+        });
+        if pointer_over_graph {
+            let (zoom_delta, scroll_delta) = {
+                let input = ctx.input();
+                (input.zoom_delta(), input.scroll_delta)
+            };
+            self.state.pan_zoom.pan += scroll_delta;
+            if zoom_delta != 1.0 {
+                self.set_zoom(self.state.pan_zoom.zoom * zoom_delta);
+            }
+        }
+        ctx.set_pixels_per_point(native_pixels_per_point * self.state.pan_zoom.zoom);
+    }
+
+    fn set_zoom(&mut self, zoom: f32) {
+        // keep the center of the editor fixed while zooming
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let size = self.editor_rect.size();
+        let center = size / 2.0 - self.state.pan_zoom.pan;
+        let new_size = size * self.state.pan_zoom.zoom / zoom;
+        self.state.pan_zoom.pan = new_size / 2.0 - center;
+        self.state.pan_zoom.zoom = zoom;
+    }
+
+    // put `center` (in graph coordinates) in the middle of the editor at `zoom`
+    pub(super) fn center_view(&mut self, center: egui::Pos2, zoom: f32) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        // the editor gets bigger or smaller in points with the zoom (see `handle_zoom_and_pan`)
+        let size = self.editor_rect.size() * self.state.pan_zoom.zoom / zoom;
+        self.state.pan_zoom.zoom = zoom;
+        self.state.pan_zoom.pan = size / 2.0 - center.to_vec2();
+    }
+
+    // center a graph node, zooming in enough to read it
+    pub(super) fn focus_node(&mut self, node_id: NodeId) {
+        let position = match self.state.node_positions.get(node_id) {
+            Some(&position) => position,
+            None => return,
+        };
+        let center = position + estimate_node_size(&self.state.graph[node_id]) / 2.0;
+        self.center_view(center, self.state.pan_zoom.zoom.max(1.0));
+        self.state.selected_nodes = vec![node_id];
+    }
+
+    // bounding box of all nodes in graph coordinates
+    fn graph_bounds(&self) -> Option<egui::Rect> {
+        self.state
+            .node_positions
+            .iter()
+            .map(|(node_id, &pos)| {
+                egui::Rect::from_min_size(pos, estimate_node_size(&self.state.graph[node_id]))
+            })
+            .reduce(|a, b| a.union(b))
+    }
+
+    pub(super) fn fit_view(&mut self) {
+        if let Some(bounds) = self.graph_bounds() {
+            let bounds = bounds.expand(20.0);
+            let size = self.editor_rect.size() * self.state.pan_zoom.zoom;
+            let zoom = (size.x / bounds.width()).min(size.y / bounds.height());
+            self.center_view(bounds.center(), zoom);
+        }
+    }
+
+    pub(super) fn show_minimap(&mut self, ctx: &egui::Context) {
+        let editor_rect = self.editor_rect;
+        let viewport =
+            egui::Rect::from_min_size((-self.state.pan_zoom.pan).to_pos2(), editor_rect.size());
+        let bounds = match self.graph_bounds() {
+            Some(bounds) => bounds.union(viewport),
+            None => return,
+        };
+        let minimap_size = egui::vec2(200.0, 150.0);
+        let scale = (minimap_size.x / bounds.width()).min(minimap_size.y / bounds.height());
+
+        egui::Area::new("minimap")
+            .order(egui::Order::Foreground)
+            .fixed_pos(editor_rect.max - minimap_size - egui::vec2(10.0, 10.0))
+            .show(ctx, |ui| {
+                let (response, painter) =
+                    ui.allocate_painter(minimap_size, egui::Sense::click_and_drag());
+                let rect = response.rect;
+                let to_minimap = |pos: egui::Pos2| rect.min + (pos - bounds.min) * scale;
+                painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(150));
+                for (node_id, &pos) in &self.state.node_positions {
+                    let size = estimate_node_size(&self.state.graph[node_id]);
+                    painter.rect_filled(
+                        egui::Rect::from_min_max(to_minimap(pos), to_minimap(pos + size)),
+                        0.0,
+                        egui::Color32::GRAY,
+                    );
+                }
+                painter.rect_stroke(
+                    egui::Rect::from_min_max(to_minimap(viewport.min), to_minimap(viewport.max)),
+                    0.0,
+                    egui::Stroke::new(1.0, egui::Color32::WHITE),
+                );
+
+                // center the view on the clicked point
+                if response.clicked() || response.dragged() {
+                    if let Some(pointer) = response.interact_pointer_pos() {
+                        let target = bounds.min + (pointer - rect.min) / scale;
+                        self.state.pan_zoom.pan = editor_rect.size() / 2.0 - target.to_vec2();
+                    }
+                }
+            });
+    }
+}
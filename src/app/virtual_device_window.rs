@@ -0,0 +1,156 @@
+use super::*;
+
+//
+// devices created by this app (null-audio-sink and network audio)
+//
+
+impl PipewireGraphApp {
+    // null-audio-sink devices created by this app
+    pub(super) fn show_virtual_device_window(&mut self, ctx: &egui::Context) {
+        let restricted = self.restricted();
+        egui::Window::new("Virtual device")
+            .open(&mut self.extra_state.window_virtual_device)
+            .enabled(!restricted)
+            .show(ctx, |ui| {
+                let device = &mut self.extra_state.virtual_device;
+                if device.channels.is_empty() {
+                    device.channels = CHANNEL_MAPS[1].1.to_owned();
+                }
+                egui::Grid::new("virtual-device")
+                    .num_columns(2)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label("Name");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut device.name)
+                                .hint_text("node.name e.g. virtual_sink"),
+                        );
+                        ui.end_row();
+                        ui.label("Description");
+                        ui.text_edit_singleline(&mut device.description);
+                        ui.end_row();
+                        ui.label("Type");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut device.source, false, "Sink");
+                            ui.radio_value(&mut device.source, true, "Source");
+                        });
+                        ui.end_row();
+                        ui.label("Channels");
+                        egui::ComboBox::from_id_source("virtual-device-channels")
+                            .selected_text(&device.channels)
+                            .show_ui(ui, |ui| {
+                                for (label, channels) in CHANNEL_MAPS {
+                                    ui.selectable_value(
+                                        &mut device.channels,
+                                        channels.to_owned(),
+                                        label,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("");
+                        ui.checkbox(&mut device.linger, "Keep after exit");
+                        ui.end_row();
+                    });
+                let state = metrics::lock_ui(&self.pipewire_wrapper.state);
+                let name = device.name.trim();
+                let exists = state
+                    .find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+                    .is_some();
+                let button = egui::Button::new("Create");
+                let response = ui.add_enabled(!name.is_empty() && !exists, button);
+                if exists {
+                    response.on_hover_text("node.name is already used");
+                } else if response.clicked() {
+                    let device = VirtualDevice {
+                        name: name.to_owned(),
+                        ..device.clone()
+                    };
+                    self.pipewire_wrapper
+                        .channel_sender
+                        .send_or_log(ChannelMessage::VirtualDeviceCreate(device));
+                }
+                if !state.virtual_devices.is_empty() {
+                    ui.separator();
+                    for device in &state.virtual_devices {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{} ({}{})",
+                                device.name,
+                                device.kind(),
+                                if device.linger {
+                                    ", kept after exit"
+                                } else {
+                                    ""
+                                }
+                            ));
+                            if ui.small_button("Remove").clicked() {
+                                self.pipewire_wrapper.channel_sender.send_or_log(
+                                    ChannelMessage::VirtualDeviceDestroy(device.name.clone()),
+                                );
+                            }
+                        });
+                    }
+                }
+            });
+    }
+
+    // RTP or tunnel modules loaded into this app from a few fields
+    pub(super) fn show_network_window(&mut self, ctx: &egui::Context) {
+        let mut load = None;
+        egui::Window::new("Network audio")
+            .open(&mut self.extra_state.window_network)
+            .show(ctx, |ui| {
+                let setup = &mut self.extra_state.network_setup;
+                let kind = setup.kind;
+                for option in NetworkKind::ALL {
+                    ui.radio_value(&mut setup.kind, option, option.label())
+                        .on_hover_text(option.description());
+                }
+                if setup.kind != kind {
+                    setup.port = setup.kind.default_port();
+                }
+                if setup.kind.has_address() {
+                    egui::Grid::new("network-setup")
+                        .num_columns(2)
+                        .spacing([10.0, 5.0])
+                        .show(ui, |ui| {
+                            ui.label("Address");
+                            let hint = match setup.kind {
+                                NetworkKind::RtpSink | NetworkKind::RtpSource => {
+                                    "e.g. 224.0.0.56 (multicast)"
+                                }
+                                _ => "server e.g. 192.168.1.2",
+                            };
+                            ui.add(egui::TextEdit::singleline(&mut setup.address).hint_text(hint));
+                            ui.end_row();
+                            ui.label("Port");
+                            ui.add(DragValue::new(&mut setup.port).clamp_range(1..=65535));
+                            ui.end_row();
+                            ui.label("Name");
+                            ui.text_edit_singleline(&mut setup.name);
+                            ui.end_row();
+                        });
+                }
+                ui.weak(setup.kind.description());
+                let (name, args) = setup.module();
+                ui.monospace(format!("{} {}", name, args));
+                let validation = setup.validate();
+                let button = ui.add_enabled(validation.is_ok(), egui::Button::new("Load"));
+                match validation {
+                    Err(e) => {
+                        button.on_disabled_hover_text(e);
+                    }
+                    Ok(()) if button.clicked() => load = Some((name, args)),
+                    Ok(()) => {}
+                }
+                ui.label("Runs in this app until it exits (see the Modules window).");
+                ui.label("The nodes show up tagged 🌐 in the graph.");
+            });
+        if let Some((name, args)) = load {
+            self.pipewire_wrapper
+                .channel_sender
+                .send_or_log(ChannelMessage::ModuleLoad(name, args));
+        }
+    }
+}
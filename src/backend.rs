@@ -0,0 +1,243 @@
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc::RecvTimeoutError, Arc, Mutex},
+    time::Duration,
+};
+
+use pipewire::{
+    prelude::{ReadableDict, WritableDict},
+    registry::{GlobalObject, Permission},
+    types::ObjectType,
+    Properties,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    channel::{ChannelMessage, ChannelReceiver, ChannelSender},
+    error::PwError,
+    metrics,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    props::PropStore,
+};
+
+/// Pipewire side of a [`crate::pipewire_wrapper::PipewireWrapper`], run on its own thread.
+///
+/// It keeps `state` up to date, sends events with `sender` (e.g. a `ChannelMessage::Batch` of
+/// `PipewireRegistryGlobal`) and handles the commands from `receiver` until
+/// `ChannelMessage::PipewireMainLoopStopRequest`.
+pub trait Backend: Send + 'static {
+    fn run(
+        self,
+        state: Arc<Mutex<PipewireState>>,
+        sender: ChannelSender,
+        receiver: ChannelReceiver,
+    ) -> Result<(), PwError>;
+}
+
+/// Registry change of a [`MockBackend`] fixture, e.g.
+/// `{"global": {"id": 40, "type": "Node", "props": {"node.name": "sink"}}}` or
+/// `{"global_remove": 40}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockEvent {
+    Global {
+        id: u32,
+        // e.g. "Node" for "PipeWire:Interface:Node"
+        #[serde(rename = "type")]
+        type_: String,
+        // `Permission` bits (all when omitted), announcing a known global with other
+        // permissions is a permission change
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        permissions: Option<u32>,
+        #[serde(default)]
+        props: BTreeMap<String, String>,
+    },
+    GlobalRemove(u32),
+    // props of a bound node updated via its info (`ChannelMessage::PipewireNodePropsChanged`)
+    NodeProps {
+        id: u32,
+        props: BTreeMap<String, String>,
+    },
+    // sent after the last tick when a fixture doesn't have it
+    InitialSyncDone,
+}
+
+impl MockEvent {
+    // the global as announced by the registry (`None` if it's already gone from `state`)
+    pub fn global(state: &PipewireState, id: u32) -> Option<Self> {
+        let object = state.global_objects.get(&id)?;
+        Some(MockEvent::Global {
+            id,
+            type_: PipewireObject::get_type_name(object).to_owned(),
+            permissions: Some(object.permissions.bits()),
+            props: props_map(object),
+        })
+    }
+
+    pub fn node_props(state: &PipewireState, id: u32) -> Option<Self> {
+        let object = state.global_objects.get(&id)?;
+        Some(MockEvent::NodeProps {
+            id,
+            props: props_map(object),
+        })
+    }
+}
+
+fn props_map(object: &GlobalObject<PropStore>) -> BTreeMap<String, String> {
+    object.props.as_ref().map_or_else(BTreeMap::new, |props| {
+        props
+            .iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect()
+    })
+}
+
+/// Backend replaying recorded registry events without a pipewire daemon.
+///
+/// The fixture is a JSON array of ticks, each an array of [`MockEvent`]s sent as one batch
+/// (like the registry events of one pipewire loop iteration). The commands sent by the ui
+/// are recorded instead of being executed.
+pub struct MockBackend {
+    ticks: Vec<Vec<MockEvent>>,
+    commands: Arc<Mutex<Vec<ChannelMessage>>>,
+    // pause after each tick so that the ui draws in between (as in a live session)
+    tick_interval: Duration,
+}
+
+impl MockBackend {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        Ok(Self {
+            ticks: serde_json::from_str(json)?,
+            commands: Default::default(),
+            tick_interval: Duration::ZERO,
+        })
+    }
+
+    pub fn with_tick_interval(self, tick_interval: Duration) -> Self {
+        Self {
+            tick_interval,
+            ..self
+        }
+    }
+
+    /// Commands received so far (keep it before handing the backend to the wrapper).
+    pub fn commands(&self) -> Arc<Mutex<Vec<ChannelMessage>>> {
+        self.commands.clone()
+    }
+}
+
+impl Backend for MockBackend {
+    fn run(
+        self,
+        state: Arc<Mutex<PipewireState>>,
+        sender: ChannelSender,
+        receiver: ChannelReceiver,
+    ) -> Result<(), PwError> {
+        let mut initial_sync_done = false;
+        for tick in self.ticks {
+            // registry messages go in a batch like the ones of the pipewire thread
+            let (mut batch, mut others) = (vec![], vec![]);
+            let mut state = metrics::lock(&state);
+            for event in tick {
+                match event {
+                    MockEvent::Global {
+                        id,
+                        type_,
+                        permissions,
+                        props,
+                    } => {
+                        let object = mock_global(id, &type_, permissions, &props);
+                        let permissions = object.permissions;
+                        batch.push(ChannelMessage::PipewireRegistryGlobal(id));
+                        match state.global_objects.insert(id, object) {
+                            Some(old) if old.permissions != permissions => {
+                                batch.push(ChannelMessage::PipewirePermissionChanged(
+                                    id,
+                                    old.permissions.bits(),
+                                ))
+                            }
+                            _ => {}
+                        }
+                    }
+                    MockEvent::GlobalRemove(id) => {
+                        state.global_objects.remove(&id);
+                        batch.push(ChannelMessage::PipewireRegistryGlobalRemove(id));
+                    }
+                    MockEvent::NodeProps { id, props } => {
+                        if let Some(object) = state.global_objects.get_mut(&id) {
+                            object.props = Some(PropStore::new(&mock_props(id, &props)));
+                            others.push(ChannelMessage::PipewireNodePropsChanged(id));
+                        }
+                    }
+                    MockEvent::InitialSyncDone => {
+                        initial_sync_done = true;
+                        others.push(ChannelMessage::PipewireInitialSyncDone);
+                    }
+                }
+            }
+            drop(state);
+            if !batch.is_empty() {
+                sender.send(ChannelMessage::Batch(batch)).ok();
+            }
+            for message in others {
+                sender.send(message).ok();
+            }
+            std::thread::sleep(self.tick_interval);
+        }
+        if !initial_sync_done {
+            sender.send(ChannelMessage::PipewireInitialSyncDone).ok();
+        }
+
+        loop {
+            match receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(ChannelMessage::PipewireMainLoopStopRequest) => return Ok(()),
+                Ok(message) => self.commands.lock().unwrap().push(message),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+    }
+}
+
+// the registry types used by the app (anything else keeps the given name)
+const MOCK_TYPES: [ObjectType; 9] = [
+    ObjectType::Client,
+    ObjectType::Core,
+    ObjectType::Device,
+    ObjectType::Factory,
+    ObjectType::Link,
+    ObjectType::Metadata,
+    ObjectType::Module,
+    ObjectType::Node,
+    ObjectType::Port,
+];
+
+// also used for the objects of `JackBackend`
+pub(crate) fn mock_global(
+    id: u32,
+    type_: &str,
+    permissions: Option<u32>,
+    props: &BTreeMap<String, String>,
+) -> GlobalObject<PropStore> {
+    let type_ = MOCK_TYPES
+        .iter()
+        .find(|known| known.to_str().strip_prefix("PipeWire:Interface:") == Some(type_))
+        .cloned()
+        .unwrap_or_else(|| ObjectType::Other(type_.to_owned()));
+    GlobalObject {
+        id,
+        permissions: permissions.map_or_else(Permission::all, Permission::from_bits_truncate),
+        type_,
+        version: 0,
+        props: Some(PropStore::new(&mock_props(id, props))),
+    }
+}
+
+fn mock_props(id: u32, props: &BTreeMap<String, String>) -> Properties {
+    let mut dict = Properties::new();
+    dict.insert("object.id", id.to_string().as_str());
+    for (key, value) in props {
+        dict.insert(key.as_str(), value.as_str());
+    }
+    dict
+}
@@ -1,9 +1,353 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        mpsc::{RecvTimeoutError, SendError, TryRecvError},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::PwError,
+    metrics::ChannelStats,
+    pipewire_wrapper::{AudioFormat, LinkFlags, VirtualDevice},
+    process::Signal,
+    scope::SampleRing,
+    tone::ToneSettings,
+};
+
 #[derive(Clone, Debug)]
 pub enum ChannelMessage {
-    PipewireRegistryGlobal,
-    PipewireRegistryGlobalRemove,
+    PipewireRegistryGlobal(u32),
+    PipewireRegistryGlobalRemove(u32),
+    PipewirePermissionChanged(u32, u32), // (id, old permission bits)
+    PipewireNodePropsChanged(u32),       // props of a bound node were updated
     PipewireMainLoopReady,
+    PipewireInitialSyncDone,
+    PipewireRoundtrip,
+    PipewireRoundtripDone,
     PipewireMainLoopStopRequest,
-    LinkCreate((String, String), (String, String)),
+    PipewireTimerIntervalSet(u32), // ms between runs of the message handler
+    PipewireError(PwError),
+    LinkCreate((String, String), (String, String), LinkFlags, bool), // (.., force even if already linked)
     LinkDestroy((String, String), (String, String)),
+    ObjectDestroy(u32, Option<String>), // (id, object.serial expected at that id)
+    ObjectsDestroy(Vec<(u32, Option<String>)>), // e.g. links of the selected nodes
+    DeviceSetProfile(u32, i32),
+    DeviceSetRoute(u32, i32, i32, Option<Vec<f32>>), // (device, route index, card profile device, channel volumes)
+    PortParamsQuery(Vec<u32>), // start tracking SPA_PARAM_Latency / SPA_PARAM_Format of the ports
+    VirtualDeviceCreate(VirtualDevice),
+    VirtualDeviceDestroy(String), // node.name
+    StreamFormatRequest(u32, AudioFormat),
+    StreamSetTarget(u32, Option<u32>), // (stream node, target node or `None` for the default)
+    CreatedObjectsDestroy,             // everything in `PipewireState::created_objects`
+    ClientPermissionsQuery(u32),       // start tracking the permissions of the client
+    ClientUpdatePermissions(u32, Vec<(u32, u32)>), // (client, [(global id or PW_ID_ANY, permission bits)])
+    PathTestStart(Vec<u32>),                       // node ids in the order of the path
+    PathTestStop,
+    RecordStart(u32, String), // (node, WAV file path)
+    RecordStop,
+    ToneGeneratorCreate(String, ToneSettings), // (node.name, settings)
+    ToneGeneratorUpdate(String, ToneSettings),
+    ToneGeneratorDestroy(String),
+    ModuleLoad(String, String), // (name e.g. "libpipewire-module-zeroconf-discover", args)
+    ModuleUnload(u32),          // `LoadedModule::key`
+    ObjectCreate(String, Vec<(String, String)>), // (factory.name, properties)
+    NodeUpdateProps(u32, Vec<(String, String)>), // (node, [(key, value)]) via `SPA_PROP_params`
+    NodeParamsQuery(Vec<u32>),  // start tracking SPA_PARAM_Props (volumes) of the nodes
+    NodeSetVolume(u32, Vec<f32>), // (node, channel volumes)
+    NodeSetMute(u32, bool),
+    NodeMetersSet(Vec<u32>), // nodes whose peak levels are captured (replaces the previous ones)
+    ScopeStart(u32, Arc<SampleRing>), // (output port, samples read by the ui) replacing a running one
+    ScopeStop,
+    ProcessSignal(u32, Signal), // (pid, signal) sent by the ui itself once confirmed
+    Batch(Vec<ChannelMessage>), // registry messages of one pipewire loop tick
+}
+
+// delivery order between queued messages (fifo within the same priority)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    // stop/quit and errors
+    Control,
+    // user-initiated operations
+    User,
+    // background queries which can wait
+    Bulk,
+}
+
+const NUM_PRIORITIES: usize = 3;
+
+// queued messages with the same key are replaced by the newer one (e.g. rapid volume drags)
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SupersedeKey {
+    DeviceProfile(u32),
+    DeviceRoute(u32, i32, i32),
+    StreamFormat(u32),
+    ToneGenerator(String),
+    NodeVolume(u32),
+}
+
+impl ChannelMessage {
+    pub fn priority(&self) -> Priority {
+        match self {
+            ChannelMessage::PipewireMainLoopStopRequest | ChannelMessage::PipewireError(_) => {
+                Priority::Control
+            }
+            ChannelMessage::PortParamsQuery(_)
+            | ChannelMessage::ClientPermissionsQuery(_)
+            | ChannelMessage::NodeParamsQuery(_) => Priority::Bulk,
+            _ => Priority::User,
+        }
+    }
+
+    fn supersede_key(&self) -> Option<SupersedeKey> {
+        match self {
+            ChannelMessage::DeviceSetProfile(device_id, _) => {
+                Some(SupersedeKey::DeviceProfile(*device_id))
+            }
+            ChannelMessage::DeviceSetRoute(device_id, index, route_device, _) => {
+                Some(SupersedeKey::DeviceRoute(*device_id, *index, *route_device))
+            }
+            ChannelMessage::StreamFormatRequest(node_id, _) => {
+                Some(SupersedeKey::StreamFormat(*node_id))
+            }
+            // e.g. dragging a slider of the Mixer window
+            ChannelMessage::NodeSetVolume(node_id, _) => Some(SupersedeKey::NodeVolume(*node_id)),
+            // e.g. dragging the frequency slider
+            ChannelMessage::ToneGeneratorUpdate(name, _) => {
+                Some(SupersedeKey::ToneGenerator(name.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+//
+// prioritized channel with deduplication and queue metrics (replaces mpsc but keeps its error types)
+//
+
+struct Queue {
+    // indexed by `Priority as usize`
+    messages: [VecDeque<ChannelMessage>; NUM_PRIORITIES],
+    num_senders: usize,
+    receiver_alive: bool,
+    // called after each send (e.g. to wake up an idle ui)
+    wake: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl Queue {
+    fn pop(&mut self) -> Option<ChannelMessage> {
+        self.messages.iter_mut().find_map(VecDeque::pop_front)
+    }
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    available: Condvar,
+    stats: &'static ChannelStats,
+}
+
+pub fn channel(stats: &'static ChannelStats) -> (ChannelSender, ChannelReceiver) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            messages: Default::default(),
+            num_senders: 1,
+            receiver_alive: true,
+            wake: None,
+        }),
+        available: Condvar::new(),
+        stats,
+    });
+    (
+        ChannelSender {
+            shared: shared.clone(),
+        },
+        ChannelReceiver { shared },
+    )
+}
+
+pub struct ChannelSender {
+    shared: Arc<Shared>,
+}
+
+impl ChannelSender {
+    pub fn send(&self, message: ChannelMessage) -> Result<(), SendError<ChannelMessage>> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        if !queue.receiver_alive {
+            self.shared.stats.on_drop();
+            return Err(SendError(message));
+        }
+        let messages = &mut queue.messages[message.priority() as usize];
+        let superseded = message.supersede_key().and_then(|key| {
+            messages
+                .iter_mut()
+                .find(|queued| queued.supersede_key().as_ref() == Some(&key))
+        });
+        match superseded {
+            // keep the position so that the update isn't delayed by later messages
+            Some(queued) => {
+                *queued = message;
+                self.shared.stats.on_supersede();
+            }
+            None => {
+                messages.push_back(message);
+                self.shared.stats.on_send();
+            }
+        }
+        self.shared.available.notify_one();
+        // outside of the lock since the callback might take its own locks
+        let wake = queue.wake.clone();
+        drop(queue);
+        if let Some(wake) = wake {
+            wake();
+        }
+        Ok(())
+    }
+
+    // for the ui, whose pipewire thread may be gone (died or abandoned by `PipewireWrapper::quit`)
+    pub fn send_or_log(&self, message: ChannelMessage) {
+        if let Err(SendError(message)) = self.send(message) {
+            tracing::error!(?message, "not sent, the pipewire thread is gone");
+        }
+    }
+}
+
+impl Clone for ChannelSender {
+    fn clone(&self) -> Self {
+        self.shared.queue.lock().unwrap().num_senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for ChannelSender {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().num_senders -= 1;
+        self.shared.available.notify_all();
+    }
+}
+
+pub struct ChannelReceiver {
+    shared: Arc<Shared>,
+}
+
+impl ChannelReceiver {
+    pub fn try_recv(&self) -> Result<ChannelMessage, TryRecvError> {
+        let mut queue = self.shared.queue.lock().unwrap();
+        match queue.pop() {
+            Some(message) => {
+                self.shared.stats.on_receive();
+                Ok(message)
+            }
+            None if queue.num_senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<ChannelMessage, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.queue.lock().unwrap();
+        loop {
+            if let Some(message) = queue.pop() {
+                self.shared.stats.on_receive();
+                return Ok(message);
+            }
+            if queue.num_senders == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            queue = self
+                .shared
+                .available
+                .wait_timeout(queue, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    // e.g. `egui::Context::request_repaint` so that messages are handled without user input
+    pub fn set_wake(&self, wake: impl Fn() + Send + Sync + 'static) {
+        self.shared.queue.lock().unwrap().wake = Some(Arc::new(wake));
+    }
+
+    // highest priority first, including messages sent while iterating
+    pub fn try_iter(&self) -> impl Iterator<Item = ChannelMessage> + '_ {
+        std::iter::from_fn(move || self.try_recv().ok())
+    }
+}
+
+impl Drop for ChannelReceiver {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().receiver_alive = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::METRICS;
+
+    #[test]
+    fn higher_priority_first() {
+        let (sender, receiver) = channel(&METRICS.ui_to_pw);
+        sender
+            .send(ChannelMessage::PortParamsQuery(vec![1]))
+            .unwrap();
+        sender.send(ChannelMessage::PipewireRoundtrip).unwrap();
+        sender
+            .send(ChannelMessage::NodeSetVolume(1, vec![0.5]))
+            .unwrap();
+        sender
+            .send(ChannelMessage::PipewireMainLoopStopRequest)
+            .unwrap();
+        let received: Vec<_> = receiver.try_iter().collect();
+        assert!(matches!(
+            received[..],
+            [
+                ChannelMessage::PipewireMainLoopStopRequest,
+                ChannelMessage::PipewireRoundtrip,
+                ChannelMessage::NodeSetVolume(1, _),
+                ChannelMessage::PortParamsQuery(_),
+            ]
+        ));
+    }
+
+    #[test]
+    fn superseded_in_place() {
+        let (sender, receiver) = channel(&METRICS.ui_to_pw);
+        sender
+            .send(ChannelMessage::NodeSetVolume(1, vec![0.1]))
+            .unwrap();
+        sender
+            .send(ChannelMessage::NodeSetVolume(2, vec![0.2]))
+            .unwrap();
+        sender.send(ChannelMessage::PipewireRoundtrip).unwrap();
+        sender
+            .send(ChannelMessage::NodeSetVolume(1, vec![0.3]))
+            .unwrap();
+        let received: Vec<_> = receiver.try_iter().collect();
+        match &received[..] {
+            [ChannelMessage::NodeSetVolume(1, first), ChannelMessage::NodeSetVolume(2, _), ChannelMessage::PipewireRoundtrip] =>
+            {
+                assert_eq!(first, &[0.3])
+            }
+            _ => panic!("{:?}", received),
+        }
+    }
+
+    #[test]
+    fn send_after_receiver_dropped() {
+        let (sender, receiver) = channel(&METRICS.ui_to_pw);
+        sender.send(ChannelMessage::PipewireRoundtrip).unwrap();
+        drop(receiver);
+        let result = sender.send(ChannelMessage::PipewireRoundtrip);
+        assert!(matches!(
+            result,
+            Err(SendError(ChannelMessage::PipewireRoundtrip))
+        ));
+    }
 }
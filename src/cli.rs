@@ -0,0 +1,159 @@
+use std::{path::PathBuf, sync::mpsc::RecvTimeoutError, time::Duration};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    channel::ChannelMessage,
+    ipc::{self, IpcServer},
+    pipewire_registry::PipewireRegistry,
+    pipewire_wrapper::{LinkFlags, PipewireObject, PipewireState, PipewireWrapper},
+};
+
+pub const USAGE: &str = "\
+usage:
+  pipewire_graph_egui                             (start gui)
+  pipewire_graph_egui --record <session.json>     (start gui recording the registry events)
+  pipewire_graph_egui --replay <session.json>     (start gui replaying a recorded session)
+  pipewire_graph_egui --pop-out <object|mixer>   (start gui showing only that window)
+  pipewire_graph_egui --jack                      (start gui on a JACK server, `jack` feature)
+  pipewire_graph_egui link create <output> <input>
+  pipewire_graph_egui link destroy <output> <input>
+  pipewire_graph_egui dump [--json]
+  pipewire_graph_egui --dump-json                 (same as dump --json)
+  pipewire_graph_egui serve [<socket>]            (json requests over a unix socket, see ipc.rs)
+
+ports can be given as global id, port.alias, object.path or <node.name>:<port.name>";
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const SERVE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// run a subcommand without gui
+pub fn run(args: &[String]) -> Result<()> {
+    let mut pipewire_wrapper = PipewireWrapper::new();
+    let result = run_command(&pipewire_wrapper, args);
+    pipewire_wrapper.quit()?;
+    result
+}
+
+fn run_command(pipewire_wrapper: &PipewireWrapper, args: &[String]) -> Result<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    // the registry is followed from the start instead
+    match args.as_slice() {
+        ["serve"] => return serve(pipewire_wrapper, IpcServer::default_path()),
+        ["serve", path] => return serve(pipewire_wrapper, path.into()),
+        _ => {}
+    }
+
+    // every command works on the complete registry snapshot
+    wait_for(pipewire_wrapper, ChannelMessage::PipewireInitialSyncDone)?;
+
+    match args.as_slice() {
+        ["link", action @ ("create" | "destroy"), output, input] => {
+            let message = {
+                let state = pipewire_wrapper.state.lock().unwrap();
+                link_message(&state, *action == "create", output, input)?
+            };
+            pipewire_wrapper.channel_sender.send(message)?;
+
+            // make sure the request reached the server before disconnecting
+            pipewire_wrapper
+                .channel_sender
+                .send(ChannelMessage::PipewireRoundtrip)?;
+            wait_for(pipewire_wrapper, ChannelMessage::PipewireRoundtripDone)?;
+        }
+        ["dump"] => {
+            let state = pipewire_wrapper.state.lock().unwrap();
+            for object in state.global_objects.values() {
+                println!(
+                    "{}\t{}\t{}",
+                    object.id,
+                    object.type_,
+                    PipewireObject::get_name(object).map_or("--", |(_k, v)| v)
+                );
+            }
+        }
+        ["dump", "--json"] | ["--dump-json"] => {
+            let dump = pipewire_wrapper.state.lock().unwrap().dump();
+            println!("{}", serde_json::to_string_pretty(&dump)?);
+        }
+        _ => bail!("invalid arguments\n{}", USAGE),
+    }
+    Ok(())
+}
+
+// `LinkCreate` or `LinkDestroy` between ports given as in `USAGE`
+pub(crate) fn link_message(
+    state: &PipewireState,
+    create: bool,
+    output: &str,
+    input: &str,
+) -> Result<ChannelMessage> {
+    let output = state
+        .find_port(output, true)
+        .with_context(|| format!("output port not found '{}'", output))?;
+    let input = state
+        .find_port(input, false)
+        .with_context(|| format!("input port not found '{}'", input))?;
+    let (from, to) = (
+        PipewireObject::id_key(output.id),
+        PipewireObject::id_key(input.id),
+    );
+    Ok(if create {
+        ChannelMessage::LinkCreate(from, to, LinkFlags::default(), false)
+    } else {
+        ChannelMessage::LinkDestroy(from, to)
+    })
+}
+
+// answer ipc requests until the pipewire thread exits
+fn serve(pipewire_wrapper: &PipewireWrapper, path: PathBuf) -> Result<()> {
+    let server = IpcServer::new(path)?;
+    eprintln!("listening on {}", server.path.display());
+    let mut registry = PipewireRegistry::default();
+    loop {
+        match pipewire_wrapper
+            .channel_receiver
+            .recv_timeout(SERVE_POLL_INTERVAL)
+        {
+            Ok(ChannelMessage::Batch(messages)) => {
+                let state = pipewire_wrapper.state.lock().unwrap();
+                for message in &messages {
+                    registry.event(&state, message);
+                }
+            }
+            Ok(ChannelMessage::PipewireNodePropsChanged(id)) => {
+                let state = pipewire_wrapper.state.lock().unwrap();
+                registry.node_props_changed(&state, id);
+            }
+            Ok(ChannelMessage::PipewireError(e)) => tracing::error!("{}", e),
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => bail!("pipewire thread exited"),
+        }
+        for pending in server.requests.try_iter() {
+            tracing::debug!(request = ?pending.request, "ipc request");
+            let state = pipewire_wrapper.state.lock().unwrap();
+            let reply = ipc::handle(
+                &state,
+                registry.topology(),
+                &pipewire_wrapper.channel_sender,
+                &pending.request,
+            );
+            drop(state);
+            pending.reply(reply);
+        }
+    }
+}
+
+fn wait_for(pipewire_wrapper: &PipewireWrapper, expected: ChannelMessage) -> Result<()> {
+    let expected = std::mem::discriminant(&expected);
+    loop {
+        match pipewire_wrapper.channel_receiver.recv_timeout(TIMEOUT) {
+            Ok(message) if std::mem::discriminant(&message) == expected => return Ok(()),
+            Ok(ChannelMessage::PipewireError(e)) => return Err(e.into()),
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => bail!("timeout waiting for pipewire"),
+            Err(RecvTimeoutError::Disconnected) => bail!("pipewire thread exited"),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use pipewire::types::ObjectType;
+
+use crate::{
+    channel::ChannelMessage,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    process::Signal,
+    props::PropKey,
+    snapshot,
+};
+
+//
+// preview of destructive operations before they are sent to the pipewire thread
+//
+
+pub fn is_destructive(message: &ChannelMessage) -> bool {
+    matches!(
+        message,
+        ChannelMessage::ObjectDestroy(..)
+            | ChannelMessage::ObjectsDestroy(_)
+            | ChannelMessage::CreatedObjectsDestroy
+            | ChannelMessage::ToneGeneratorDestroy(_)
+            | ChannelMessage::ProcessSignal(..)
+    )
+}
+
+pub struct PlannedObject {
+    pub id: u32,
+    pub type_: String,
+    pub label: String,
+    // id of the requested object this one goes away with (e.g. links of a destroyed node)
+    pub with: Option<u32>,
+    // stream of this app which is stopped rather than destroyed (no pw-cli equivalent)
+    pub own_stream: bool,
+}
+
+// destructive messages waiting for the user
+pub struct Confirmation {
+    pub messages: Vec<ChannelMessage>,
+    pub objects: Vec<PlannedObject>,
+    // (pid, signal) of the processes to stop
+    pub signals: Vec<(u32, Signal)>,
+    // names of the modules loaded by this app to unload
+    pub modules: Vec<String>,
+}
+
+impl Confirmation {
+    pub fn new(state: &PipewireState, messages: Vec<ChannelMessage>) -> Self {
+        let mut requested: Vec<(u32, bool)> = vec![];
+        let mut signals = vec![];
+        let mut modules = vec![];
+        for message in &messages {
+            match message {
+                ChannelMessage::ProcessSignal(pid, signal) => signals.push((*pid, *signal)),
+                ChannelMessage::ObjectDestroy(id, _) => requested.push((*id, false)),
+                ChannelMessage::ObjectsDestroy(objects) => {
+                    requested.extend(objects.iter().map(|(id, _)| (*id, false)))
+                }
+                // same filter as the pipewire thread
+                ChannelMessage::CreatedObjectsDestroy => {
+                    requested.extend(state.created_objects.iter().filter_map(|(id, created)| {
+                        let object = state.global_objects.get(id)?;
+                        (PipewireObject::get_serial(object) == created.serial.as_deref())
+                            .then_some((*id, false))
+                    }));
+                    modules.extend(
+                        state
+                            .loaded_modules
+                            .iter()
+                            .map(|module| module.name.clone()),
+                    );
+                }
+                ChannelMessage::ToneGeneratorDestroy(name) => {
+                    if let Some(object) =
+                        state.find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+                    {
+                        requested.push((object.id, true));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut objects = vec![];
+        let mut plan = |id: u32, with: Option<u32>, own_stream: bool| {
+            if !seen.insert(id) {
+                return;
+            }
+            if let Some(object) = state.global_objects.get(&id) {
+                objects.push(PlannedObject {
+                    id,
+                    type_: PipewireObject::get_type_name(object).to_owned(),
+                    label: snapshot::label(state, object),
+                    with,
+                    own_stream,
+                });
+            }
+        };
+        for &(id, own_stream) in &requested {
+            plan(id, None, own_stream);
+        }
+        for &(id, _) in &requested {
+            for dependent in dependents(state, id) {
+                plan(dependent, Some(id), false);
+            }
+        }
+        Self {
+            messages,
+            objects,
+            signals,
+            modules,
+        }
+    }
+
+    pub fn merge(&mut self, state: &PipewireState, messages: Vec<ChannelMessage>) {
+        let mut merged = std::mem::take(&mut self.messages);
+        merged.extend(messages);
+        *self = Self::new(state, merged);
+    }
+
+    // equivalent commands for the requested objects (the others go away with them)
+    pub fn pw_cli_commands(&self) -> String {
+        let mut out = String::new();
+        for (pid, signal) in &self.signals {
+            writeln!(
+                out,
+                "kill -{} {}",
+                signal.name().trim_start_matches("SIG"),
+                pid
+            )
+            .unwrap();
+        }
+        for module in &self.modules {
+            writeln!(out, "# module {} is unloaded within this app", module).unwrap();
+        }
+        for object in self.objects.iter().filter(|object| object.with.is_none()) {
+            if object.own_stream {
+                writeln!(out, "# {} is a stream of this app", object.label).unwrap();
+            } else {
+                writeln!(out, "pw-cli destroy {}  # {}", object.id, object.label).unwrap();
+            }
+        }
+        out
+    }
+}
+
+// objects the server removes together with the given one
+fn dependents(state: &PipewireState, id: u32) -> Vec<u32> {
+    let object = match state.global_objects.get(&id) {
+        Some(object) => object,
+        None => return vec![],
+    };
+    let nodes: Vec<u32> = match object.type_ {
+        ObjectType::Node => vec![id],
+        // nodes owned by the client
+        ObjectType::Client => state
+            .global_objects
+            .values()
+            .filter(|other| {
+                other.type_ == ObjectType::Node
+                    && other
+                        .props
+                        .as_ref()
+                        .and_then(|props| props.get_key(PropKey::ClientId))
+                        == Some(id.to_string().as_str())
+            })
+            .map(|node| node.id)
+            .collect(),
+        _ => return vec![],
+    };
+    let ports: BTreeSet<u32> = state
+        .global_objects
+        .values()
+        .filter(|port| {
+            PipewireObject::get_parent_node_id(port).map_or(false, |node| nodes.contains(&node))
+        })
+        .map(|port| port.id)
+        .collect();
+    let links = state.global_objects.values().filter_map(|link| {
+        let (output_port, input_port) = PipewireObject::get_link_ports(link)?;
+        (ports.contains(&output_port) || ports.contains(&input_port)).then_some(link.id)
+    });
+    nodes
+        .iter()
+        .copied()
+        .filter(|&node| node != id)
+        .chain(links)
+        .collect()
+}
@@ -0,0 +1,114 @@
+use std::{fmt, io, time::Duration};
+
+// failures in the pipewire thread (sent to the ui via `ChannelMessage::PipewireError`)
+#[derive(Clone, Debug)]
+pub enum PwError {
+    MainLoopNew(String),
+    ContextNew(String),
+    Connect(String),
+    GetRegistry(String),
+    Timer(String),
+    Sync(String),
+    // object type name
+    FactoryNotFound(String),
+    // what was looked up e.g. "LinkCreate object.id=42 -> object.id=57"
+    ObjectNotFound(String),
+    CreateObject(String),
+    // id of the link between the same ports (see `ChannelMessage::LinkCreate`)
+    AlreadyLinked(u32),
+    DestroyGlobal(u32, String),
+    Bind(u32, String),
+    // e.g. a JACK server gone (see `JackBackend`)
+    Jack(String),
+    // command the backend can't carry out e.g. "NodeSetVolume with JACK"
+    Unsupported(String),
+    // `error` event of the core (e.g. failed create_object reported asynchronously)
+    Core {
+        id: u32,
+        seq: i32,
+        res: i32,
+        message: String,
+    },
+}
+
+impl PwError {
+    // failures which can succeed by simply trying again later
+    // e.g. daemon not started yet, ports of a new node not registered yet
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PwError::Connect(_) | PwError::ObjectNotFound(_) => true,
+            PwError::Core { res, .. } => matches!(
+                io::Error::from_raw_os_error(-*res).kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted | io::ErrorKind::TimedOut
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for PwError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PwError::MainLoopNew(e) => write!(f, "failed to create main loop ({})", e),
+            PwError::ContextNew(e) => write!(f, "failed to create context ({})", e),
+            PwError::Connect(e) => write!(f, "failed to connect to pipewire ({})", e),
+            PwError::GetRegistry(e) => write!(f, "failed to get registry ({})", e),
+            PwError::Timer(e) => write!(f, "failed to setup timer ({})", e),
+            PwError::Sync(e) => write!(f, "failed to sync ({})", e),
+            PwError::FactoryNotFound(type_) => write!(f, "factory not found for {}", type_),
+            PwError::ObjectNotFound(what) => write!(f, "object not found ({})", what),
+            PwError::CreateObject(e) => write!(f, "failed to create object ({})", e),
+            PwError::AlreadyLinked(id) => write!(f, "already linked (link {})", id),
+            PwError::DestroyGlobal(id, e) => write!(f, "failed to destroy object {} ({})", id, e),
+            PwError::Bind(id, e) => write!(f, "failed to bind object {} ({})", id, e),
+            PwError::Jack(e) => write!(f, "JACK: {}", e),
+            PwError::Unsupported(what) => write!(f, "not supported ({})", what),
+            PwError::Core {
+                id, res, message, ..
+            } => write!(
+                f,
+                "core error on object {}: {} ({})",
+                id,
+                message,
+                io::Error::from_raw_os_error(-*res)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PwError {}
+
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub delay: Duration,
+}
+
+// e.g. app started together with the session before pipewire is up
+pub const CONNECT_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: 10,
+    delay: Duration::from_millis(500),
+};
+
+// e.g. linking ports of a node which just appeared
+pub const MESSAGE_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: 5,
+    delay: Duration::from_millis(200),
+};
+
+// blocking retry for the startup sequence
+pub fn retry<T>(
+    policy: &RetryPolicy,
+    mut f: impl FnMut() -> Result<T, PwError>,
+) -> Result<T, PwError> {
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(e) if e.is_transient() && attempt < policy.max_attempts => {
+                tracing::warn!("{} (retry {}/{})", e, attempt, policy.max_attempts);
+                std::thread::sleep(policy.delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use eframe::egui::{Pos2, Rect};
+use egui_node_graph::WireStyle;
+use pipewire::types::ObjectType;
+
+use crate::pipewire_wrapper::{PipewireObject, PipewireState};
+
+//
+// GraphViz DOT of the registry topology
+//
+
+pub fn to_dot(state: &PipewireState) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph pipewire {{").unwrap();
+    writeln!(out, "  rankdir=LR;").unwrap();
+    writeln!(out, "  node [shape=box];").unwrap();
+
+    // one cluster per node containing its ports
+    for object in state.global_objects.values() {
+        if object.type_ != ObjectType::Node {
+            continue;
+        }
+        writeln!(out, "  subgraph cluster_{} {{", object.id).unwrap();
+        writeln!(
+            out,
+            "    label=\"{}\\n{}\";",
+            escape_dot(PipewireObject::get_node_label(object).unwrap_or("--")),
+            escape_dot(PipewireObject::get_media_class(object).unwrap_or("")),
+        )
+        .unwrap();
+        for port in state.get_ports(object.id) {
+            writeln!(
+                out,
+                "    port_{} [label=\"{}\"];",
+                port.id,
+                escape_dot(PipewireObject::get_port_name(port).unwrap_or("--")),
+            )
+            .unwrap();
+        }
+        writeln!(out, "  }}").unwrap();
+    }
+
+    for object in state.global_objects.values() {
+        if let Some((output_port, input_port)) = PipewireObject::get_link_ports(object) {
+            writeln!(out, "  port_{} -> port_{};", output_port, input_port).unwrap();
+        }
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+//
+// SVG of the editor canvas
+//
+
+pub struct SvgNode {
+    pub rect: Rect,
+    pub label: String,
+    pub ports: Vec<(Pos2, String)>,
+}
+
+pub struct SvgWire {
+    pub from: Pos2,
+    pub to: Pos2,
+    pub style: WireStyle,
+}
+
+pub fn to_svg(nodes: &[SvgNode], wires: &[SvgWire]) -> String {
+    let bounds = nodes
+        .iter()
+        .map(|node| node.rect)
+        .reduce(|a, b| a.union(b))
+        .unwrap_or(Rect::from_min_size(Pos2::ZERO, [100.0, 100.0].into()))
+        .expand(20.0);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" font-family="sans-serif" font-size="12">"#,
+        bounds.min.x,
+        bounds.min.y,
+        bounds.width(),
+        bounds.height()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#1b1b1b"/>"##,
+        bounds.min.x,
+        bounds.min.y,
+        bounds.width(),
+        bounds.height()
+    )
+    .unwrap();
+
+    for node in nodes {
+        let rect = node.rect;
+        writeln!(
+            out,
+            r##"<rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="#3f3f3f"/>"##,
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height()
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r##"<text x="{}" y="{}" fill="#fefefe" font-weight="bold">{}</text>"##,
+            rect.min.x + 10.0,
+            rect.min.y + 18.0,
+            escape_xml(&node.label)
+        )
+        .unwrap();
+        for (pos, name) in &node.ports {
+            writeln!(
+                out,
+                r##"<circle cx="{}" cy="{}" r="5" fill="#aaaaaa"/><text x="{}" y="{}" fill="#fefefe">{}</text>"##,
+                pos.x,
+                pos.y,
+                rect.min.x + 15.0,
+                pos.y + 4.0,
+                escape_xml(name)
+            )
+            .unwrap();
+        }
+    }
+
+    for wire in wires {
+        let control = ((wire.to.x - wire.from.x) / 2.0).max(30.0);
+        let (color, width) = (wire.style.color, wire.style.width);
+        let dash = if wire.style.dashed {
+            format!(r#" stroke-dasharray="{}""#, width * 2.0)
+        } else {
+            String::new()
+        };
+        writeln!(
+            out,
+            r#"<path d="M {} {} C {} {} {} {} {} {}" stroke="rgb({},{},{})" stroke-width="{}"{} fill="none"/>"#,
+            wire.from.x,
+            wire.from.y,
+            wire.from.x + control,
+            wire.from.y,
+            wire.to.x - control,
+            wire.to.y,
+            wire.to.x,
+            wire.to.y,
+            color.r(),
+            color.g(),
+            color.b(),
+            width,
+            dash
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "</svg>").unwrap();
+    out
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
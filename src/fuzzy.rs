@@ -0,0 +1,90 @@
+//
+// fzf-like matching for the quick connect picker
+//
+
+// characters after these start a new word (e.g. "alsa_output.pci:playback_FL")
+const SEPARATORS: &[char] = &['_', '.', ':', '-', ' ', '/'];
+
+// score of `query` as a case-insensitive subsequence of `text` (`None` if it isn't one)
+// (consecutive characters and word starts score higher, gaps lower)
+pub fn score(query: &str, text: &str) -> Option<i32> {
+    let mut query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    for c in text.chars() {
+        let expected = match query.peek() {
+            Some(&expected) => expected,
+            None => break,
+        };
+        let word_start = previous.map_or(true, |previous| {
+            SEPARATORS.contains(&previous) || (previous.is_lowercase() && c.is_uppercase())
+        });
+        if c.to_lowercase().eq(std::iter::once(expected)) {
+            query.next();
+            score += 1;
+            if consecutive {
+                score += 4;
+            }
+            if word_start {
+                score += 3;
+            }
+            consecutive = true;
+        } else {
+            // leading characters before the first match don't count
+            if score > 0 {
+                score -= 1;
+            }
+            consecutive = false;
+        }
+        previous = Some(c);
+    }
+    query.peek().is_none().then_some(score)
+}
+
+// indices of `texts` matching `query` from the best match (ties keep their order)
+pub fn rank<'a>(query: &str, texts: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut scored: Vec<(i32, usize)> = texts
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, text)| Some((score(query, text)?, i)))
+        .collect();
+    scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsequence_only() {
+        assert!(score("fl", "playback_FL").is_some());
+        assert!(score("lp", "playback_FL").is_none());
+        assert_eq!(score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn consecutive_characters_first() {
+        assert!(score("play", "playback") > score("play", "pxlxaxy"));
+        assert_eq!(rank("play", ["pxlxaxy", "playback"]), [1, 0]);
+    }
+
+    #[test]
+    fn word_starts_first() {
+        assert!(score("ab", "x_a_b") > score("ab", "xxaxb"));
+        // after a separator or a lowercase to uppercase change
+        assert!(score("mic", "alsa_input.usb-mic") > score("mic", "alsa_input.usb-comic"));
+        assert!(score("cm", "CaptureMic") > score("cm", "Capturemic"));
+    }
+
+    #[test]
+    fn ranking() {
+        let texts = ["usb-comic", "speaker", "Built-in Mic", "mic 2"];
+        assert_eq!(rank("mic", texts), [2, 3, 0]);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{channel::ChannelMessage, pipewire_wrapper::LinkFlags};
+
+// user initiated link operation with ports identified by (key, value) prop pairs
+#[derive(Clone, Debug)]
+pub struct LinkAction {
+    pub create: bool,
+    pub from: (String, String),
+    pub to: (String, String),
+    // only for creation (kept so that redo recreates the same link)
+    pub flags: LinkFlags,
+    // create another link even if the ports are already linked
+    pub force: bool,
+}
+
+impl LinkAction {
+    pub fn inverse(&self) -> Self {
+        Self {
+            create: !self.create,
+            ..self.clone()
+        }
+    }
+
+    pub fn to_message(&self) -> ChannelMessage {
+        if self.create {
+            ChannelMessage::LinkCreate(self.from.clone(), self.to.clone(), self.flags, self.force)
+        } else {
+            ChannelMessage::LinkDestroy(self.from.clone(), self.to.clone())
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LinkHistory {
+    // actions undone together (e.g. all the links of a node disconnected at once)
+    undo_stack: Vec<Vec<LinkAction>>,
+    redo_stack: Vec<Vec<LinkAction>>,
+}
+
+const MAX_HISTORY: usize = 100;
+
+impl LinkHistory {
+    pub fn push(&mut self, action: LinkAction) {
+        self.push_all(vec![action]);
+    }
+
+    // a single undo step
+    pub fn push_all(&mut self, actions: Vec<LinkAction>) {
+        if actions.is_empty() {
+            return;
+        }
+        if self.undo_stack.len() >= MAX_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(actions);
+        self.redo_stack.clear();
+    }
+
+    // returns the operations to issue for reverting the last step
+    pub fn undo(&mut self) -> Vec<LinkAction> {
+        let actions = match self.undo_stack.pop() {
+            Some(actions) => actions,
+            None => return vec![],
+        };
+        let inverses = actions.iter().rev().map(LinkAction::inverse).collect();
+        self.redo_stack.push(actions);
+        inverses
+    }
+
+    pub fn redo(&mut self) -> Vec<LinkAction> {
+        let actions = match self.redo_stack.pop() {
+            Some(actions) => actions,
+            None => return vec![],
+        };
+        self.undo_stack.push(actions.clone());
+        actions
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::Shutdown,
+    os::unix::{
+        fs::{FileTypeExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use pipewire::spa::Direction;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    channel::ChannelSender, cli, pipewire_wrapper::PipewireState, props::PropKey,
+    topology::Topology,
+};
+
+//
+// automation over a unix socket (newline delimited json, one response per request), e.g.
+//   {"id": 1, "method": "topology"}
+//   {"id": 2, "method": "link_create", "params": {"output": "<node>:<port>", "input": "54"}}
+// answered with {"id": 1, "result": {...}} or {"id": 2, "error": "..."}
+//
+// requests are handled by the owner of the pipewire connection (see `cli::serve`), so they
+// drive the same backend as the gui
+//
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Request {
+    Topology,
+    // ports as for `pipewire_graph_egui link create` (id, port.alias, object.path or
+    // <node.name>:<port.name>)
+    LinkCreate { output: String, input: String },
+    LinkDestroy { output: String, input: String },
+}
+
+#[derive(Deserialize)]
+struct RequestLine {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Reply {
+    Topology(TopologyDump),
+    // the command was sent to the pipewire thread (`null`)
+    Done,
+}
+
+#[derive(Serialize)]
+struct ResponseLine {
+    id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Reply>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TopologyDump {
+    nodes: Vec<NodeDump>,
+    ports: Vec<PortDump>,
+    links: Vec<LinkDump>,
+}
+
+#[derive(Serialize)]
+struct NodeDump {
+    id: u32,
+    name: Option<String>,
+    media_class: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PortDump {
+    id: u32,
+    node_id: u32,
+    name: Option<String>,
+    // "input" or "output"
+    direction: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct LinkDump {
+    id: u32,
+    output_port: u32,
+    input_port: u32,
+}
+
+impl TopologyDump {
+    fn new(state: &PipewireState, topology: &Topology) -> Self {
+        let prop = |id: u32, key: PropKey| {
+            let object = state.global_objects.get(&id)?;
+            Some(object.props.as_ref()?.get_key(key)?.to_owned())
+        };
+        let nodes = topology.nodes().map(|node| NodeDump {
+            id: node.id,
+            name: prop(node.id, PropKey::NodeName),
+            media_class: prop(node.id, PropKey::MediaClass),
+        });
+        let ports = topology.ports().map(|port| PortDump {
+            id: port.id,
+            node_id: port.node_id,
+            name: prop(port.id, PropKey::PortName),
+            direction: port.direction.map(|direction| match direction {
+                Direction::Input => "input",
+                Direction::Output => "output",
+            }),
+        });
+        let links = topology.links().map(|link| LinkDump {
+            id: link.id,
+            output_port: link.output_port,
+            input_port: link.input_port,
+        });
+        Self {
+            nodes: nodes.collect(),
+            ports: ports.collect(),
+            links: links.collect(),
+        }
+    }
+}
+
+pub fn handle(
+    state: &PipewireState,
+    topology: &Topology,
+    sender: &ChannelSender,
+    request: &Request,
+) -> Result<Reply> {
+    let (create, output, input) = match request {
+        Request::Topology => return Ok(Reply::Topology(TopologyDump::new(state, topology))),
+        Request::LinkCreate { output, input } => (true, output, input),
+        Request::LinkDestroy { output, input } => (false, output, input),
+    };
+    let message = cli::link_message(state, create, output, input)?;
+    sender.send(message)?;
+    Ok(Reply::Done)
+}
+
+/// Request of a client with the channel for its response.
+pub struct Pending {
+    pub request: Request,
+    reply: Sender<Result<Reply>>,
+}
+
+impl Pending {
+    pub fn reply(self, reply: Result<Reply>) {
+        // the client is gone
+        self.reply.send(reply).ok();
+    }
+}
+
+pub struct IpcServer {
+    pub path: PathBuf,
+    pub requests: Receiver<Pending>,
+    stop: Arc<AtomicBool>,
+    // to shut down the clients waiting for requests (removed as they disconnect)
+    clients: Arc<Mutex<HashMap<usize, UnixStream>>>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl IpcServer {
+    pub fn new(path: PathBuf) -> Result<Self> {
+        // left over by a previous run which didn't exit cleanly (never some other file)
+        let is_socket = std::fs::symlink_metadata(&path)
+            .map_or(false, |metadata| metadata.file_type().is_socket());
+        if is_socket && UnixStream::connect(&path).is_err() {
+            std::fs::remove_file(&path).ok();
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("failed to bind {}", path.display()))?;
+        // anyone who can connect can rewire the graph
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        listener.set_nonblocking(true)?;
+
+        let (sender, requests) = mpsc::channel();
+        let stop: Arc<AtomicBool> = Default::default();
+        let clients: Arc<Mutex<HashMap<usize, UnixStream>>> = Default::default();
+        let stop_ = stop.clone();
+        let clients_ = clients.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let mut next_client = 0;
+            while !stop_.load(Ordering::Relaxed) {
+                while let Ok((stream, _)) = listener.accept() {
+                    tracing::info!("ipc client connected");
+                    let key = next_client;
+                    next_client += 1;
+                    match stream.try_clone() {
+                        Ok(clone) => {
+                            clients_.lock().unwrap().insert(key, clone);
+                        }
+                        Err(e) => tracing::error!("ipc client rejected ({})", e),
+                    }
+                    let (sender, clients) = (sender.clone(), clients_.clone());
+                    std::thread::spawn(move || {
+                        if let Err(e) = serve_client(stream, sender) {
+                            tracing::warn!("ipc client error ({:#})", e);
+                        }
+                        clients.lock().unwrap().remove(&key);
+                    });
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        Ok(Self {
+            path,
+            requests,
+            stop,
+            clients,
+            thread_handle: Some(thread_handle),
+        })
+    }
+
+    // $XDG_RUNTIME_DIR/pipewire_graph_egui.sock (next to the pipewire socket)
+    pub fn default_path() -> PathBuf {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR").unwrap_or_else(|| "/tmp".into());
+        PathBuf::from(dir).join(concat!(env!("CARGO_PKG_NAME"), ".sock"))
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread_handle) = self.thread_handle.take() {
+            thread_handle.join().ok();
+        }
+        for (_, client) in self.clients.lock().unwrap().drain() {
+            client.shutdown(Shutdown::Both).ok();
+        }
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+// ends when the client disconnects or the server shuts down
+fn serve_client(stream: UnixStream, sender: Sender<Pending>) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<RequestLine>(&line) {
+            Ok(RequestLine { id, request }) => {
+                let (reply, receiver) = mpsc::channel();
+                sender.send(Pending { request, reply })?;
+                match receiver.recv()? {
+                    Ok(reply) => ResponseLine {
+                        id,
+                        result: Some(reply),
+                        error: None,
+                    },
+                    Err(e) => ResponseLine {
+                        id,
+                        result: None,
+                        error: Some(format!("{:#}", e)),
+                    },
+                }
+            }
+            Err(e) => ResponseLine {
+                id: None,
+                result: None,
+                error: Some(format!("invalid request ({})", e)),
+            },
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    }
+    Ok(())
+}
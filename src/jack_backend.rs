@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use jack::{Client, ClientOptions, ClientStatus, Control, PortFlags, PortId};
+use pipewire::prelude::ReadableDict;
+
+use crate::{
+    backend::{self, Backend},
+    channel::{ChannelMessage, ChannelReceiver, ChannelSender, Priority},
+    error::PwError,
+    metrics,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+};
+
+//
+// degraded mode showing a plain JACK server (e.g. jackd without pipewire) with the `jack` feature
+//
+// the ports known to libjack are turned into the registry objects of the graph: a node per
+// JACK client ("system" of "system:playback_1"), its ports and their connections as links.
+// only linking and unlinking work, the other commands are answered with `PwError::Unsupported`
+//
+
+const CLIENT_NAME: &str = env!("CARGO_PKG_NAME");
+
+// the graph is listed again after a change notified by the server
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Backend showing the ports and connections of a JACK server instead of pipewire.
+pub struct JackBackend;
+
+impl Backend for JackBackend {
+    fn run(
+        self,
+        state: Arc<Mutex<PipewireState>>,
+        sender: ChannelSender,
+        receiver: ChannelReceiver,
+    ) -> Result<(), PwError> {
+        // an inactive client doesn't get notified, so it's activated without any port
+        let (client, _) = Client::new(CLIENT_NAME, ClientOptions::NO_START_SERVER)
+            .map_err(|e| PwError::Jack(format!("no JACK server to connect to ({})", e)))?;
+        let notifications = Notifications::default();
+        let changed = notifications.changed.clone();
+        let shutdown = notifications.shutdown.clone();
+        let active_client = client
+            .activate_async(notifications, ())
+            .map_err(|e| PwError::Jack(format!("failed to activate client ({})", e)))?;
+        let client = active_client.as_client();
+
+        let mut registry = Registry::default();
+        let mut initial_sync_done = false;
+        loop {
+            if let Some(reason) = shutdown.lock().unwrap().take() {
+                return Err(PwError::Jack(format!("server shut down ({})", reason)));
+            }
+            if changed.swap(false, Ordering::Relaxed) || !initial_sync_done {
+                let graph = Graph::list(client);
+                let batch = registry.update(&graph, &mut metrics::lock(&state));
+                if !batch.is_empty() {
+                    sender.send(ChannelMessage::Batch(batch)).ok();
+                }
+                if !initial_sync_done {
+                    initial_sync_done = true;
+                    sender.send(ChannelMessage::PipewireInitialSyncDone).ok();
+                }
+            }
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(ChannelMessage::PipewireMainLoopStopRequest) => return Ok(()),
+                Ok(message) => {
+                    let result = handle_message(client, &metrics::lock(&state), message);
+                    if let Err(e) = result {
+                        sender.send(ChannelMessage::PipewireError(e)).ok();
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+    }
+}
+
+// flags set from the notification thread of libjack
+#[derive(Default)]
+struct Notifications {
+    changed: Arc<AtomicBool>,
+    shutdown: Arc<Mutex<Option<String>>>,
+}
+
+impl jack::NotificationHandler for Notifications {
+    fn shutdown(&mut self, _status: ClientStatus, reason: &str) {
+        *self.shutdown.lock().unwrap() = Some(reason.to_owned());
+    }
+
+    fn client_registration(&mut self, _: &Client, _name: &str, _is_registered: bool) {
+        self.changed.store(true, Ordering::Relaxed);
+    }
+
+    fn port_registration(&mut self, _: &Client, _port_id: PortId, _is_registered: bool) {
+        self.changed.store(true, Ordering::Relaxed);
+    }
+
+    fn port_rename(&mut self, _: &Client, _: PortId, _old_name: &str, _new_name: &str) -> Control {
+        self.changed.store(true, Ordering::Relaxed);
+        Control::Continue
+    }
+
+    fn ports_connected(&mut self, _: &Client, _: PortId, _: PortId, _are_connected: bool) {
+        self.changed.store(true, Ordering::Relaxed);
+    }
+}
+
+struct JackPort {
+    // e.g. "system:playback_1"
+    name: String,
+    // e.g. "32 bit float mono audio" or "8 bit raw midi"
+    port_type: String,
+    flags: PortFlags,
+}
+
+// (client, short name) of a full port name
+fn split_port_name(name: &str) -> (&str, &str) {
+    name.split_once(':').unwrap_or(("", name))
+}
+
+struct Graph {
+    ports: Vec<JackPort>,
+    // (output port, input port)
+    connections: Vec<(String, String)>,
+}
+
+impl Graph {
+    fn list(client: &Client) -> Self {
+        let ports: Vec<JackPort> = client
+            .ports(None, None, PortFlags::empty())
+            .into_iter()
+            .filter_map(|name| {
+                let port = client.port_by_name(&name)?;
+                Some(JackPort {
+                    port_type: port.port_type().unwrap_or_default(),
+                    flags: port.flags(),
+                    name,
+                })
+            })
+            .collect();
+        let mut connections = vec![];
+        for output in ports
+            .iter()
+            .filter(|port| port.flags.contains(PortFlags::IS_OUTPUT))
+        {
+            let port = match client.port_by_name(&output.name) {
+                Some(port) if port.connected_count().unwrap_or(0) > 0 => port,
+                _ => continue,
+            };
+            for input in ports
+                .iter()
+                .filter(|port| port.flags.contains(PortFlags::IS_INPUT))
+            {
+                if port.is_connected_to(&input.name).unwrap_or(false) {
+                    connections.push((output.name.clone(), input.name.clone()));
+                }
+            }
+        }
+        Self { ports, connections }
+    }
+}
+
+// what a registry object stands for (ordered so that nodes come before their ports and links)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Key {
+    Client(String),
+    Port(String),
+    Link(String, String),
+}
+
+impl Key {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Key::Client(_) => "Node",
+            Key::Port(_) => "Port",
+            Key::Link(..) => "Link",
+        }
+    }
+}
+
+// registry objects sent to the ui, keeping their id while they exist
+#[derive(Default)]
+struct Registry {
+    ids: HashMap<Key, u32>,
+    next_id: u32,
+    objects: BTreeMap<Key, BTreeMap<String, String>>,
+}
+
+impl Registry {
+    fn id(&mut self, key: &Key) -> u32 {
+        if let Some(&id) = self.ids.get(key) {
+            return id;
+        }
+        self.next_id += 1;
+        self.ids.insert(key.clone(), self.next_id);
+        self.next_id
+    }
+
+    // registry messages turning `state` into the listed graph
+    fn update(&mut self, graph: &Graph, state: &mut PipewireState) -> Vec<ChannelMessage> {
+        let mut objects = BTreeMap::new();
+        // `port.id` is the index within the node
+        let mut port_counts: HashMap<&str, u32> = HashMap::new();
+        for port in &graph.ports {
+            let (client, short_name) = split_port_name(&port.name);
+            let node_id = self.id(&Key::Client(client.to_owned()));
+            objects.insert(
+                Key::Client(client.to_owned()),
+                props(&[("node.name", client), ("node.description", client)]),
+            );
+            let port_index = port_counts.entry(client).or_insert(0);
+            let is_output = port.flags.contains(PortFlags::IS_OUTPUT);
+            let mut port_props = props(&[
+                ("node.id", &node_id.to_string()),
+                ("port.id", &port_index.to_string()),
+                ("port.name", short_name),
+                ("port.alias", &port.name),
+                ("port.direction", if is_output { "out" } else { "in" }),
+                ("format.dsp", &port.port_type),
+            ]);
+            *port_index += 1;
+            for (flag, key) in [
+                (PortFlags::IS_PHYSICAL, "port.physical"),
+                (PortFlags::IS_TERMINAL, "port.terminal"),
+            ] {
+                if port.flags.contains(flag) {
+                    port_props.insert(key.to_owned(), "true".to_owned());
+                }
+            }
+            objects.insert(Key::Port(port.name.clone()), port_props);
+        }
+        for (output, input) in &graph.connections {
+            let mut ids = |port: &String| {
+                let client = split_port_name(port).0.to_owned();
+                let node_id = self.id(&Key::Client(client));
+                (
+                    node_id.to_string(),
+                    self.id(&Key::Port(port.clone())).to_string(),
+                )
+            };
+            let ((output_node, output_port), (input_node, input_port)) = (ids(output), ids(input));
+            objects.insert(
+                Key::Link(output.clone(), input.clone()),
+                props(&[
+                    ("link.output.node", &output_node),
+                    ("link.output.port", &output_port),
+                    ("link.input.node", &input_node),
+                    ("link.input.port", &input_port),
+                ]),
+            );
+        }
+
+        let mut batch = vec![];
+        // links before their ports and ports before their nodes
+        let removed: Vec<Key> = self
+            .objects
+            .keys()
+            .rev()
+            .filter(|key| !objects.contains_key(key))
+            .cloned()
+            .collect();
+        for key in removed {
+            if let Some(id) = self.ids.remove(&key) {
+                state.global_objects.remove(&id);
+                batch.push(ChannelMessage::PipewireRegistryGlobalRemove(id));
+            }
+        }
+        for (key, props) in &objects {
+            if self.objects.get(key) == Some(props) {
+                continue;
+            }
+            let id = self.id(key);
+            let object = backend::mock_global(id, key.type_name(), None, props);
+            state.global_objects.insert(id, object);
+            batch.push(ChannelMessage::PipewireRegistryGlobal(id));
+        }
+        self.objects = objects;
+        batch
+    }
+}
+
+fn props(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn handle_message(
+    client: &Client,
+    state: &PipewireState,
+    message: ChannelMessage,
+) -> Result<(), PwError> {
+    // full JACK port name kept in `port.alias`
+    let port_name = |(key, value): &(String, String)| {
+        state
+            .find_object_by_prop(key, value)
+            .and_then(|port| port.props.as_ref()?.get_key(PropKey::PortAlias))
+            .map(str::to_owned)
+            .ok_or_else(|| PwError::ObjectNotFound(format!("{}={}", key, value)))
+    };
+    let link_ports = |id: u32| {
+        let object = state
+            .global_objects
+            .get(&id)
+            .ok_or_else(|| PwError::ObjectNotFound(format!("object.id={}", id)))?;
+        let (output, input) = PipewireObject::get_link_ports(object)
+            .ok_or_else(|| PwError::Unsupported("removing nodes or ports with JACK".to_owned()))?;
+        let (from, to) = (
+            PipewireObject::id_key(output),
+            PipewireObject::id_key(input),
+        );
+        Ok::<_, PwError>((port_name(&from)?, port_name(&to)?))
+    };
+    let disconnect = |(output, input): (String, String)| {
+        client
+            .disconnect_ports_by_name(&output, &input)
+            .map_err(|e| PwError::Jack(format!("failed to disconnect {} ({})", output, e)))
+    };
+    match message {
+        ChannelMessage::LinkCreate(from, to, _, _) => {
+            let (output, input) = (port_name(&from)?, port_name(&to)?);
+            match client.connect_ports_by_name(&output, &input) {
+                Err(jack::Error::PortAlreadyConnected(..)) => {
+                    let id = state.find_link(&from, &to).unwrap_or_default();
+                    Err(PwError::AlreadyLinked(id))
+                }
+                result => result.map_err(|e| PwError::Jack(format!("failed to connect ({})", e))),
+            }
+        }
+        ChannelMessage::LinkDestroy(from, to) => disconnect((port_name(&from)?, port_name(&to)?)),
+        // only links can be removed from the outside
+        ChannelMessage::ObjectDestroy(id, _) => disconnect(link_ports(id)?),
+        ChannelMessage::ObjectsDestroy(objects) => {
+            for (id, _) in objects {
+                disconnect(link_ports(id)?)?;
+            }
+            Ok(())
+        }
+        ChannelMessage::Batch(messages) => {
+            for message in messages {
+                handle_message(client, state, message)?;
+            }
+            Ok(())
+        }
+        // background queries and settings which simply don't apply
+        message if message.priority() == Priority::Bulk => Ok(()),
+        ChannelMessage::PipewireTimerIntervalSet(_)
+        | ChannelMessage::NodeMetersSet(_)
+        | ChannelMessage::ScopeStop
+        | ChannelMessage::PathTestStop
+        | ChannelMessage::RecordStop
+        | ChannelMessage::CreatedObjectsDestroy => Ok(()),
+        message => {
+            // variant name e.g. "NodeSetVolume"
+            let debug = format!("{:?}", message);
+            let name = debug.split(['(', ' ']).next().unwrap_or("");
+            Err(PwError::Unsupported(format!("{} with JACK", name)))
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+
+use anyhow::{Context, Result};
+
+// follow daemon side warnings/errors without a separate terminal
+//   journalctl --user -u pipewire -u wireplumber -p warning -f
+pub struct JournalReader {
+    pub entries: Arc<Mutex<VecDeque<JournalEntry>>>,
+    child: Child,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+pub struct JournalEntry {
+    pub line: String,
+    // global ids mentioned in the message e.g. "node 42", "port:57", "id=3"
+    pub object_ids: Vec<u32>,
+}
+
+const UNITS: [&str; 3] = ["pipewire", "pipewire-pulse", "wireplumber"];
+
+const MAX_ENTRIES: usize = 1000;
+
+impl JournalReader {
+    pub fn new() -> Result<Self> {
+        let mut command = Command::new("journalctl");
+        command.args(["--user", "--follow", "--lines=200", "--priority=warning"]);
+        command.args(["--output=short-iso", "--no-pager"]);
+        for unit in UNITS {
+            command.args(["--unit", unit]);
+        }
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to spawn journalctl")?;
+        let stdout = child.stdout.take().context("journalctl stdout")?;
+
+        let entries: Arc<Mutex<VecDeque<JournalEntry>>> = Default::default();
+        let entries_ = entries.clone();
+        let thread_handle = std::thread::spawn(move || {
+            // ends when the process is killed
+            for line in BufReader::new(stdout).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                let object_ids = parse_object_ids(&line);
+                let mut entries = entries_.lock().unwrap();
+                if entries.len() >= MAX_ENTRIES {
+                    entries.pop_front();
+                }
+                entries.push_back(JournalEntry { line, object_ids });
+            }
+        });
+
+        Ok(Self {
+            entries,
+            child,
+            thread_handle: Some(thread_handle),
+        })
+    }
+}
+
+impl Drop for JournalReader {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+        if let Some(thread_handle) = self.thread_handle.take() {
+            thread_handle.join().ok();
+        }
+    }
+}
+
+// there's no structured field for object ids, so pick up numbers following typical words
+fn parse_object_ids(line: &str) -> Vec<u32> {
+    let mut result = vec![];
+    let mut previous = "";
+    for word in line.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')') {
+        let (key, value) = match word.split_once(|c| c == ':' || c == '=') {
+            Some((key, value)) if !key.is_empty() => (key, value),
+            _ => (previous, word),
+        };
+        if matches!(key, "id" | "node" | "port" | "link" | "client" | "device") {
+            if let Ok(id) = value.parse::<u32>() {
+                if !result.contains(&id) {
+                    result.push(id);
+                }
+            }
+        }
+        previous = word;
+    }
+    result
+}
@@ -0,0 +1,83 @@
+use pipewire::spa::Direction;
+
+use crate::pipewire_wrapper::{PipewireObject, PipewireState, PortLatency};
+
+// graph quantum assumed when no node on the path requests `node.latency`
+pub const DEFAULT_QUANTUM: (u32, u32) = (1024, 48000);
+
+// link between consecutive nodes of a path
+#[derive(Clone, Debug)]
+pub struct LatencyHop {
+    pub from_node: u32,
+    pub to_node: u32,
+    pub output_port: u32,
+    pub input_port: u32,
+}
+
+// follow links among the given nodes starting from the one without incoming links
+// (only the first link is used when nodes are connected by multiple channels)
+pub fn find_path(state: &PipewireState, node_ids: &[u32]) -> Vec<LatencyHop> {
+    let parent_node = |port_id: u32| {
+        state
+            .global_objects
+            .get(&port_id)
+            .and_then(PipewireObject::get_parent_node_id)
+    };
+    let hops: Vec<LatencyHop> = state
+        .global_objects
+        .values()
+        .filter_map(|object| {
+            let (output_port, input_port) = PipewireObject::get_link_ports(object)?;
+            let from_node = parent_node(output_port)?;
+            let to_node = parent_node(input_port)?;
+            let on_path = node_ids.contains(&from_node) && node_ids.contains(&to_node);
+            (on_path && from_node != to_node).then_some(LatencyHop {
+                from_node,
+                to_node,
+                output_port,
+                input_port,
+            })
+        })
+        .collect();
+
+    let mut current = match node_ids
+        .iter()
+        .find(|&&id| !hops.iter().any(|hop| hop.to_node == id))
+    {
+        Some(&id) => id,
+        None => return vec![],
+    };
+    let mut visited = vec![current];
+    let mut path = vec![];
+    while let Some(hop) = hops
+        .iter()
+        .find(|hop| hop.from_node == current && !visited.contains(&hop.to_node))
+    {
+        current = hop.to_node;
+        visited.push(current);
+        path.push(hop.clone());
+    }
+    path
+}
+
+// the driver runs with the lowest latency requested by its followers
+pub fn path_quantum(state: &PipewireState, path: &[LatencyHop]) -> Option<(u32, u32)> {
+    path.iter()
+        .flat_map(|hop| [hop.from_node, hop.to_node])
+        .filter_map(|id| PipewireObject::get_node_latency(state.global_objects.get(&id)?))
+        .filter(|&(_, rate)| rate > 0)
+        .min_by(|a, b| (a.0 as f32 / a.1 as f32).total_cmp(&(b.0 as f32 / b.1 as f32)))
+}
+
+// latency reported by a port is cumulative (capture latency for `Input`, playback latency for `Output`)
+pub fn port_latency(
+    state: &PipewireState,
+    port_id: u32,
+    direction: Direction,
+) -> Option<&PortLatency> {
+    state
+        .port_latencies
+        .get(&port_id)?
+        .iter()
+        .find(|latency| latency.direction == direction)
+}
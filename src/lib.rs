@@ -1,3 +1,41 @@
+mod align;
 pub mod app;
-mod channel;
-mod pipewire_wrapper;
+pub mod backend;
+pub mod channel;
+pub mod cli;
+mod confirm;
+pub mod error;
+mod export;
+mod fuzzy;
+mod history;
+pub mod ipc;
+#[cfg(feature = "jack")]
+pub mod jack_backend;
+mod journal;
+mod latency;
+pub mod log;
+pub mod metrics;
+mod mpris;
+mod network;
+mod path_test;
+pub mod pipewire_registry;
+pub mod pipewire_wrapper;
+mod preset;
+mod process;
+mod props;
+mod recording;
+mod scope;
+mod session;
+mod session_manager;
+mod share;
+mod snapshot;
+pub mod tabs;
+mod theme;
+mod timeline;
+mod toast;
+mod tone;
+mod topology;
+#[cfg(feature = "tray")]
+mod tray;
+mod troubleshoot;
+mod tutorial;
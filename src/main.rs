@@ -1,15 +1,104 @@
-use eframe::{egui::Visuals, run_native, NativeOptions};
-use pipewire_graph_egui::app::NodeGraphExample;
+use std::time::Duration;
+
+use eframe::{run_native, NativeOptions};
+use pipewire_graph_egui::{
+    app::{PipewireGraphApp, PopOut},
+    backend::MockBackend,
+    cli,
+    log::LogLayer,
+    pipewire_wrapper,
+    tabs::PipewireGraphTabs,
+};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*};
 
 fn main() {
-    tracing_subscriber::fmt::init();
+    // terminal output as before and everything but trace for the Log window
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_filter(LevelFilter::INFO))
+        .with(LogLayer::new().with_filter(LevelFilter::DEBUG))
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    run(&args);
+    // every connection has quit by now (`run_native` returns when the window closes)
+    pipewire_wrapper::deinit();
+}
+
+fn run(args: &[String]) {
+    // offline gui driven by a session recorded with `--record`
+    if let [flag, path] = args {
+        if flag == "--replay" {
+            let backend = std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(MockBackend::from_json(&json)?));
+            let backend = match backend {
+                Ok(backend) => backend.with_tick_interval(Duration::from_millis(20)),
+                Err(e) => {
+                    eprintln!("error: invalid session {} ({:#})", path, e);
+                    std::process::exit(1);
+                }
+            };
+            run_native(
+                env!("CARGO_PKG_NAME"),
+                NativeOptions::default(),
+                Box::new(|cc| Box::new(PipewireGraphApp::with_backend(&cc.egui_ctx, backend))),
+            );
+            return;
+        }
+    }
+    // plain JACK server instead of pipewire
+    #[cfg(feature = "jack")]
+    if let [flag] = args {
+        if flag == "--jack" {
+            run_native(
+                env!("CARGO_PKG_NAME"),
+                NativeOptions::default(),
+                Box::new(|cc| Box::new(PipewireGraphApp::with_jack(cc))),
+            );
+            return;
+        }
+    }
+    // one window on its own (e.g. the Mixer on another monitor)
+    if let [flag, name] = args {
+        if flag == "--pop-out" {
+            let pop_out = match PopOut::from_name(name) {
+                Some(pop_out) => pop_out,
+                None => {
+                    eprintln!("error: unknown window {} (object or mixer)", name);
+                    std::process::exit(1);
+                }
+            };
+            run_native(
+                env!("CARGO_PKG_NAME"),
+                NativeOptions::default(),
+                Box::new(move |cc| Box::new(PipewireGraphApp::popped_out(cc, pop_out))),
+            );
+            return;
+        }
+    }
+    let record = match args {
+        [flag, path] if flag == "--record" => Some(path.clone()),
+        _ => None,
+    };
+
+    // headless mode when subcommand is given
+    if !args.is_empty() && record.is_none() {
+        if let Err(e) = cli::run(args) {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     run_native(
         env!("CARGO_PKG_NAME"),
         NativeOptions::default(),
         Box::new(|cc| {
-            cc.egui_ctx.set_visuals(Visuals::dark());
-            Box::new(NodeGraphExample::new(cc))
+            let mut tabs = PipewireGraphTabs::new(cc);
+            if let Some(path) = record {
+                tabs.record_session(path);
+            }
+            Box::new(tabs)
         }),
     );
 }
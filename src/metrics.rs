@@ -0,0 +1,246 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        Mutex, MutexGuard, TryLockError,
+    },
+    time::{Duration, Instant},
+};
+
+//
+// self-metrics of the pipewire thread and the state lock for the Debug window
+// (process-wide since there is only one pipewire thread and one ui thread)
+//
+
+pub static METRICS: Metrics = Metrics {
+    ui_to_pw: ChannelStats::new(),
+    pw_to_ui: ChannelStats::new(),
+    lock_acquired: AtomicU64::new(0),
+    lock_contended: AtomicU64::new(0),
+    lock_wait_us: AtomicU64::new(0),
+    lock_max_wait_us: AtomicU64::new(0),
+    ui_lock_acquired: AtomicU64::new(0),
+    ui_lock_held_us: AtomicU64::new(0),
+    ui_lock_max_held_us: AtomicU64::new(0),
+    paused_events_dropped: AtomicU64::new(0),
+    timer_max_us: AtomicU64::new(0),
+    cpu_permille: AtomicU32::new(0),
+    rss_kb: AtomicU64::new(0),
+};
+
+pub struct Metrics {
+    pub ui_to_pw: ChannelStats,
+    pub pw_to_ui: ChannelStats,
+    // state locks taken by the pipewire thread (contended = had to wait for the ui thread)
+    pub lock_acquired: AtomicU64,
+    pub lock_contended: AtomicU64,
+    pub lock_wait_us: AtomicU64,
+    pub lock_max_wait_us: AtomicU64,
+    // state locks taken by the ui thread (see `lock_ui`)
+    pub ui_lock_acquired: AtomicU64,
+    pub ui_lock_held_us: AtomicU64,
+    pub ui_lock_max_held_us: AtomicU64,
+    // registry events thrown away by a paused graph (rebuilt instead on resume)
+    pub paused_events_dropped: AtomicU64,
+    // longest run of the channel message handler
+    pub timer_max_us: AtomicU64,
+    // sampled by `CpuSampler`
+    pub cpu_permille: AtomicU32,
+    pub rss_kb: AtomicU64,
+}
+
+pub struct ChannelStats {
+    pub queued: AtomicUsize,
+    pub max_queued: AtomicUsize,
+    pub total: AtomicU64,
+    // replaced while queued (see `ChannelMessage::supersede_key`)
+    pub superseded: AtomicU64,
+    // sent after the receiver was gone
+    pub dropped: AtomicU64,
+}
+
+impl ChannelStats {
+    const fn new() -> Self {
+        Self {
+            queued: AtomicUsize::new(0),
+            max_queued: AtomicUsize::new(0),
+            total: AtomicU64::new(0),
+            superseded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn on_send(&self) {
+        let queued = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_queued.fetch_max(queued, Ordering::Relaxed);
+        self.total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn on_supersede(&self) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        self.superseded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn on_receive(&self) {
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn on_drop(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Metrics {
+    pub fn record_timer(&self, elapsed: Duration) {
+        self.timer_max_us
+            .fetch_max(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    // reset the high-water marks
+    pub fn reset_max(&self) {
+        self.ui_to_pw.max_queued.store(0, Ordering::Relaxed);
+        self.pw_to_ui.max_queued.store(0, Ordering::Relaxed);
+        self.timer_max_us.store(0, Ordering::Relaxed);
+        self.lock_max_wait_us.store(0, Ordering::Relaxed);
+        self.ui_lock_max_held_us.store(0, Ordering::Relaxed);
+    }
+}
+
+// `mutex.lock().unwrap()` counting how often the pipewire thread waits for the ui thread
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    METRICS.lock_acquired.fetch_add(1, Ordering::Relaxed);
+    match mutex.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::WouldBlock) => {
+            let start = Instant::now();
+            let guard = mutex.lock().unwrap();
+            let waited = start.elapsed().as_micros() as u64;
+            METRICS.lock_contended.fetch_add(1, Ordering::Relaxed);
+            METRICS.lock_wait_us.fetch_add(waited, Ordering::Relaxed);
+            METRICS
+                .lock_max_wait_us
+                .fetch_max(waited, Ordering::Relaxed);
+            guard
+        }
+        Err(TryLockError::Poisoned(e)) => panic!("{}", e),
+    }
+}
+
+// `mutex.lock().unwrap()` on the ui thread recording how long the guard is held
+pub fn lock_ui<T>(mutex: &Mutex<T>) -> UiLockGuard<'_, T> {
+    let guard = mutex.lock().unwrap();
+    METRICS.ui_lock_acquired.fetch_add(1, Ordering::Relaxed);
+    UiLockGuard {
+        guard,
+        acquired: Instant::now(),
+    }
+}
+
+pub struct UiLockGuard<'a, T> {
+    guard: MutexGuard<'a, T>,
+    acquired: Instant,
+}
+
+impl<T> Deref for UiLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for UiLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for UiLockGuard<'_, T> {
+    fn drop(&mut self) {
+        let held = self.acquired.elapsed().as_micros() as u64;
+        METRICS.ui_lock_held_us.fetch_add(held, Ordering::Relaxed);
+        METRICS
+            .ui_lock_max_held_us
+            .fetch_max(held, Ordering::Relaxed);
+    }
+}
+
+// per second rate of an ever increasing counter (e.g. `ChannelStats::total`)
+#[derive(Default)]
+pub struct RateMeter {
+    last: Option<(Instant, u64)>,
+    rate: f32,
+}
+
+impl RateMeter {
+    pub fn sample(&mut self, total: u64) -> f32 {
+        let now = Instant::now();
+        match self.last {
+            Some((time, _)) if now.duration_since(time) < SAMPLE_INTERVAL => {}
+            Some((time, last_total)) => {
+                let elapsed = now.duration_since(time).as_secs_f32();
+                self.rate = total.saturating_sub(last_total) as f32 / elapsed;
+                self.last = Some((now, total));
+            }
+            None => self.last = Some((now, total)),
+        }
+        self.rate
+    }
+}
+
+//
+// cpu time of the calling thread and rss of the process via procfs (linux only, like pipewire)
+//
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+// USER_HZ is 100 on every architecture pipewire runs on
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+#[derive(Default)]
+pub struct CpuSampler {
+    last: Option<(Instant, u64)>,
+}
+
+impl CpuSampler {
+    // call from the thread to measure (cheap enough to call on every timer tick)
+    pub fn sample(&mut self) {
+        let now = Instant::now();
+        if let Some((time, _)) = self.last {
+            if now.duration_since(time) < SAMPLE_INTERVAL {
+                return;
+            }
+        }
+        let ticks = match read_thread_ticks() {
+            Some(ticks) => ticks,
+            None => return,
+        };
+        if let Some((time, last_ticks)) = self.last {
+            let busy = ticks.saturating_sub(last_ticks) as f64 / CLOCK_TICKS_PER_SEC;
+            let permille = busy / now.duration_since(time).as_secs_f64() * 1000.0;
+            METRICS
+                .cpu_permille
+                .store(permille as u32, Ordering::Relaxed);
+        }
+        self.last = Some((now, ticks));
+        if let Some(rss_kb) = read_rss_kb() {
+            METRICS.rss_kb.store(rss_kb, Ordering::Relaxed);
+        }
+    }
+}
+
+// utime + stime of /proc/thread-self/stat
+fn read_thread_ticks() -> Option<u64> {
+    let stat = std::fs::read_to_string("/proc/thread-self/stat").ok()?;
+    // the command name in parens may contain spaces
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    // fields 14 and 15 counting from the pid
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+fn read_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
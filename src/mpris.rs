@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+//
+// play/pause/skip of media players through their MPRIS service on the session bus
+//   dbus-send --session --dest=org.mpris.MediaPlayer2.<player> /org/mpris/MediaPlayer2 ...
+//
+// players are matched to stream nodes by the pid of their bus connection, which is the
+// `application.process.id` of the stream unless one of them runs in a pid namespace (e.g. flatpak)
+//
+
+const BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaCommand {
+    PlayPause,
+    Previous,
+    Next,
+}
+
+impl MediaCommand {
+    fn method(self) -> &'static str {
+        match self {
+            MediaCommand::PlayPause => "PlayPause",
+            MediaCommand::Previous => "Previous",
+            MediaCommand::Next => "Next",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MediaPlayer {
+    // e.g. "org.mpris.MediaPlayer2.firefox.instance_1_42"
+    pub bus_name: String,
+    // "Playing", "Paused" or "Stopped"
+    pub status: Option<String>,
+}
+
+impl MediaPlayer {
+    pub fn is_playing(&self) -> bool {
+        self.status.as_deref() == Some("Playing")
+    }
+
+    // fire and forget (the status follows on the next refresh)
+    pub fn send(&self, command: MediaCommand) {
+        let mut dbus_send = Command::new("dbus-send");
+        dbus_send.args(["--session", "--type=method_call"]);
+        dbus_send.arg(format!("--dest={}", self.bus_name));
+        dbus_send.arg(OBJECT_PATH);
+        dbus_send.arg(format!("{}.{}", PLAYER_INTERFACE, command.method()));
+        // wait in the background so that the process is reaped
+        std::thread::spawn(move || match dbus_send.stderr(Stdio::null()).status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::warn!("mpris {:?} failed ({})", command, status),
+            Err(e) => tracing::error!("failed to spawn dbus-send ({})", e),
+        });
+    }
+}
+
+// polls the players on the session bus while alive (none until the first listing is done)
+pub struct MprisWatcher {
+    // keyed by process id
+    pub players: Arc<Mutex<HashMap<u32, MediaPlayer>>>,
+    // the first listing failed (e.g. no dbus-send), which stops the watcher
+    error: Arc<Mutex<Option<String>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MprisWatcher {
+    pub fn spawn() -> Self {
+        let players: Arc<Mutex<HashMap<u32, MediaPlayer>>> = Default::default();
+        let error: Arc<Mutex<Option<String>>> = Default::default();
+        let stop: Arc<AtomicBool> = Default::default();
+        let players_ = players.clone();
+        let error_ = error.clone();
+        let stop_ = stop.clone();
+        std::thread::spawn(move || {
+            // fail early without dbus-send rather than logging on every refresh
+            match list_players() {
+                Ok(players) => *players_.lock().unwrap() = players,
+                Err(e) => {
+                    *error_.lock().unwrap() = Some(format!("{:#}", e));
+                    return;
+                }
+            }
+            let mut last_refresh = Instant::now();
+            while !stop_.load(Ordering::Relaxed) {
+                if last_refresh.elapsed() >= REFRESH_INTERVAL {
+                    last_refresh = Instant::now();
+                    match list_players() {
+                        Ok(players) => *players_.lock().unwrap() = players,
+                        Err(e) => tracing::warn!("mpris refresh failed ({:#})", e),
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Self {
+            players,
+            error,
+            stop,
+        }
+    }
+
+    pub fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap().take()
+    }
+}
+
+impl Drop for MprisWatcher {
+    // not joined since a listing in progress can take seconds
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn list_players() -> Result<HashMap<u32, MediaPlayer>> {
+    let names = dbus_call(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus.ListNames",
+        &[],
+    )?;
+    let mut players = HashMap::new();
+    for bus_name in parse_strings(&names) {
+        if !bus_name.starts_with(BUS_PREFIX) {
+            continue;
+        }
+        let pid = dbus_call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.GetConnectionUnixProcessID",
+            &[&format!("string:{}", bus_name)],
+        )
+        .ok()
+        .and_then(|reply| parse_uint32(&reply));
+        let pid = match pid {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let status = dbus_call(
+            bus_name,
+            OBJECT_PATH,
+            "org.freedesktop.DBus.Properties.Get",
+            &[
+                &format!("string:{}", PLAYER_INTERFACE),
+                "string:PlaybackStatus",
+            ],
+        )
+        .ok()
+        .and_then(|reply| parse_strings(&reply).next().map(str::to_owned));
+        let bus_name = bus_name.to_owned();
+        players.insert(pid, MediaPlayer { bus_name, status });
+    }
+    Ok(players)
+}
+
+fn dbus_call(dest: &str, path: &str, method: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("dbus-send")
+        .args(["--session", "--print-reply", "--reply-timeout=1000"])
+        .arg(format!("--dest={}", dest))
+        .arg(path)
+        .arg(method)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .context("failed to spawn dbus-send")?;
+    if !output.status.success() {
+        bail!("{} failed ({})", method, output.status);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// values of `string "..."` lines of a --print-reply output
+fn parse_strings(reply: &str) -> impl Iterator<Item = &str> {
+    reply.lines().filter_map(|line| {
+        let (_, value) = line.split_once("string \"")?;
+        value.strip_suffix('"')
+    })
+}
+
+fn parse_uint32(reply: &str) -> Option<u32> {
+    reply.lines().find_map(|line| {
+        let (_, value) = line.split_once("uint32 ")?;
+        value.trim().parse().ok()
+    })
+}
@@ -0,0 +1,207 @@
+use pipewire::{prelude::ReadableDict, registry::GlobalObject};
+use serde::{Deserialize, Serialize};
+
+use crate::props::{PropKey, PropStore};
+
+//
+// network audio set up with modules loaded into this app (see `ChannelMessage::ModuleLoad`)
+//   libpipewire-module-rtp-sink { destination.ip = "224.0.0.56" destination.port = 46000 ... }
+//
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkKind {
+    #[default]
+    RtpSink,
+    RtpSource,
+    TunnelSink,
+    TunnelSource,
+    Zeroconf,
+}
+
+impl NetworkKind {
+    pub const ALL: [NetworkKind; 5] = [
+        NetworkKind::RtpSink,
+        NetworkKind::RtpSource,
+        NetworkKind::TunnelSink,
+        NetworkKind::TunnelSource,
+        NetworkKind::Zeroconf,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NetworkKind::RtpSink => "Send RTP",
+            NetworkKind::RtpSource => "Receive RTP",
+            NetworkKind::TunnelSink => "Tunnel to a remote sink",
+            NetworkKind::TunnelSource => "Tunnel from a remote source",
+            NetworkKind::Zeroconf => "Discover servers (zeroconf)",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            NetworkKind::RtpSink => "a sink streaming to a (multicast) address",
+            NetworkKind::RtpSource => "a source playing what is streamed to the address",
+            NetworkKind::TunnelSink => "a sink playing on a PulseAudio/PipeWire server",
+            NetworkKind::TunnelSource => "a source recording from a PulseAudio/PipeWire server",
+            NetworkKind::Zeroconf => "tunnels to the servers announced on the local network",
+        }
+    }
+
+    fn module_name(self) -> &'static str {
+        match self {
+            NetworkKind::RtpSink => "libpipewire-module-rtp-sink",
+            NetworkKind::RtpSource => "libpipewire-module-rtp-source",
+            NetworkKind::TunnelSink | NetworkKind::TunnelSource => {
+                "libpipewire-module-pulse-tunnel"
+            }
+            NetworkKind::Zeroconf => "libpipewire-module-zeroconf-discover",
+        }
+    }
+
+    pub fn has_address(self) -> bool {
+        self != NetworkKind::Zeroconf
+    }
+
+    pub fn default_port(self) -> u16 {
+        match self {
+            NetworkKind::TunnelSink | NetworkKind::TunnelSource => 4713,
+            _ => 46000,
+        }
+    }
+}
+
+// fields of the Network audio window
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSetup {
+    pub kind: NetworkKind,
+    pub address: String,
+    pub port: u16,
+    // node.name suffix (e.g. "rtp-sink.living_room")
+    pub name: String,
+}
+
+impl Default for NetworkSetup {
+    fn default() -> Self {
+        Self {
+            kind: NetworkKind::RtpSink,
+            address: "224.0.0.56".to_owned(),
+            port: NetworkKind::RtpSink.default_port(),
+            name: "network".to_owned(),
+        }
+    }
+}
+
+impl NetworkSetup {
+    pub fn node_name(&self) -> String {
+        let prefix = match self.kind {
+            NetworkKind::RtpSink => "rtp-sink",
+            NetworkKind::RtpSource => "rtp-source",
+            NetworkKind::TunnelSink => "tunnel-sink",
+            NetworkKind::TunnelSource => "tunnel-source",
+            NetworkKind::Zeroconf => "",
+        };
+        format!("{}.{}", prefix, self.name.trim())
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !self.kind.has_address() {
+            return Ok(());
+        }
+        let address = self.address.trim();
+        if address.is_empty() {
+            return Err("address is empty");
+        }
+        if address.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+            return Err("address contains spaces or quotes");
+        }
+        if self.port == 0 {
+            return Err("port is 0");
+        }
+        let name = self.name.trim();
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '"') {
+            return Err("name is empty or contains spaces or quotes");
+        }
+        Ok(())
+    }
+
+    // (module name, SPA-JSON args) for `ChannelMessage::ModuleLoad`
+    pub fn module(&self) -> (String, String) {
+        let (address, port) = (self.address.trim(), self.port);
+        let stream_props = format!(
+            "stream.props = {{ node.name = \"{}\" node.description = \"{} ({}:{})\" }}",
+            self.node_name(),
+            self.kind.label(),
+            address,
+            port
+        );
+        let args = match self.kind {
+            NetworkKind::RtpSink => format!(
+                "{{ destination.ip = \"{}\" destination.port = {} sess.name = \"{}\" {} }}",
+                address,
+                port,
+                self.name.trim(),
+                stream_props
+            ),
+            NetworkKind::RtpSource => format!(
+                "{{ source.ip = \"{}\" source.port = {} sess.name = \"{}\" {} }}",
+                address,
+                port,
+                self.name.trim(),
+                stream_props
+            ),
+            NetworkKind::TunnelSink | NetworkKind::TunnelSource => format!(
+                "{{ tunnel.mode = {} pulse.server.address = \"tcp:{}:{}\" {} }}",
+                if self.kind == NetworkKind::TunnelSink {
+                    "sink"
+                } else {
+                    "source"
+                },
+                address,
+                port,
+                stream_props
+            ),
+            NetworkKind::Zeroconf => String::new(),
+        };
+        (self.kind.module_name().to_owned(), args)
+    }
+}
+
+/// Transport of a node sending or receiving audio over the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkProtocol {
+    Rtp,
+    PulseTunnel,
+    Raop,
+    Roc,
+}
+
+impl NetworkProtocol {
+    // from the props the network modules put on their nodes (or the names given above)
+    pub fn of(object: &GlobalObject<PropStore>) -> Option<Self> {
+        let props = object.props.as_ref()?;
+        let has_prefix = |prefix: &str| props.iter().any(|(key, _)| key.starts_with(prefix));
+        let name = props.get_key(PropKey::NodeName).unwrap_or("");
+        if has_prefix("rtp.") || has_prefix("sess.") || name.starts_with("rtp-") {
+            Some(NetworkProtocol::Rtp)
+        } else if has_prefix("pulse.server.") || has_prefix("tunnel.") || name.starts_with("tunnel")
+        {
+            Some(NetworkProtocol::PulseTunnel)
+        } else if has_prefix("raop.") {
+            Some(NetworkProtocol::Raop)
+        } else if has_prefix("roc.") {
+            Some(NetworkProtocol::Roc)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NetworkProtocol::Rtp => "RTP",
+            NetworkProtocol::PulseTunnel => "Pulse tunnel",
+            NetworkProtocol::Raop => "AirPlay",
+            NetworkProtocol::Roc => "ROC",
+        }
+    }
+}
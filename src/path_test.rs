@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+//
+// end-to-end check of a path: play a tone into the first node and tap every node after it
+//
+
+pub const TONE_FREQUENCY: f32 = 997.0; // not a divisor of common rates (no repeating sample pattern)
+pub const TONE_LEVEL_DB: f32 = -20.0;
+pub const TONE_RATE: u32 = 48000;
+// includes the time for the session manager to link the streams
+pub const DURATION: Duration = Duration::from_secs(3);
+
+// loss still regarded as a working hop (e.g. channel mixing or a moderate gain stage)
+const ATTENUATION_TOLERANCE_DB: f32 = 6.0;
+const SILENCE_DB: f32 = -60.0;
+
+pub fn to_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.max(1e-10).log10()
+}
+
+// mono f32 sine at `TONE_RATE` (user data of the playback stream)
+#[derive(Default)]
+pub struct SineGenerator {
+    phase: f32,
+}
+
+impl SineGenerator {
+    pub fn next_sample(&mut self) -> f32 {
+        let amplitude = 10f32.powf(TONE_LEVEL_DB / 20.0);
+        let sample = amplitude * self.phase.sin();
+        let step = TONE_FREQUENCY / TONE_RATE as f32 * std::f32::consts::TAU;
+        self.phase = (self.phase + step) % std::f32::consts::TAU;
+        sample
+    }
+}
+
+pub fn peak(samples: impl Iterator<Item = f32>) -> f32 {
+    samples.fold(0.0, |peak, sample| peak.max(sample.abs()))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PathTestReport {
+    // false once `DURATION` passed or the test was stopped
+    pub running: bool,
+    // the tone is played into the first hop
+    pub hops: Vec<PathTestHop>,
+    // nothing could be started (e.g. no node with input ports on the path)
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PathTestHop {
+    pub node_id: u32,
+    // highest amplitude captured at the node's output (monitor for sinks)
+    pub peak: f32,
+    // tap stream failed (e.g. the session manager refused to link it)
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Attenuated,
+    Silent,
+}
+
+impl Verdict {
+    pub fn of(peak: f32) -> Self {
+        let db = to_db(peak);
+        if db >= TONE_LEVEL_DB - ATTENUATION_TOLERANCE_DB {
+            Verdict::Pass
+        } else if db >= SILENCE_DB {
+            Verdict::Attenuated
+        } else {
+            Verdict::Silent
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Attenuated => "attenuated",
+            Verdict::Silent => "no signal",
+        }
+    }
+}
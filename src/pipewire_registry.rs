@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use pipewire::types::ObjectType;
+
+use crate::{
+    channel::ChannelMessage,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    topology::Topology,
+};
+
+//
+// typed registry events on top of `PipewireWrapper` (what the graph editor is built from)
+//
+
+/// Change of the registry relevant to a node graph.
+///
+/// Globals are announced in no particular order, e.g. a port can arrive before its node.
+/// `*Added` is emitted again for a known global when the permissions on it change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryEvent {
+    NodeAdded(u32),
+    PortAdded {
+        id: u32,
+        node_id: u32,
+    },
+    LinkAdded {
+        id: u32,
+        output_port: u32,
+        input_port: u32,
+    },
+    /// Any other object type (e.g. `Device`, `Client`) and malformed ports/links.
+    GlobalAdded(u32, ObjectType),
+    NodeRemoved(u32),
+    PortRemoved(u32),
+    LinkRemoved(u32),
+    GlobalRemoved(u32),
+}
+
+impl RegistryEvent {
+    pub fn id(&self) -> u32 {
+        match *self {
+            RegistryEvent::NodeAdded(id)
+            | RegistryEvent::PortAdded { id, .. }
+            | RegistryEvent::LinkAdded { id, .. }
+            | RegistryEvent::GlobalAdded(id, _)
+            | RegistryEvent::NodeRemoved(id)
+            | RegistryEvent::PortRemoved(id)
+            | RegistryEvent::LinkRemoved(id)
+            | RegistryEvent::GlobalRemoved(id) => id,
+        }
+    }
+}
+
+/// Translates the raw registry messages of `PipewireWrapper::channel_receiver`.
+#[derive(Default)]
+pub struct PipewireRegistry {
+    // types of announced globals (the object is already gone from `PipewireState` on removal)
+    types: HashMap<u32, ObjectType>,
+    topology: Topology,
+}
+
+impl PipewireRegistry {
+    /// Event for `PipewireRegistryGlobal` / `PipewireRegistryGlobalRemove` (`None` for other messages).
+    ///
+    /// Call it for every such message in order with the current `PipewireWrapper::state`.
+    pub fn event(
+        &mut self,
+        state: &PipewireState,
+        message: &ChannelMessage,
+    ) -> Option<RegistryEvent> {
+        let event = self.translate(state, message)?;
+        self.topology.apply(state, &event);
+        Some(event)
+    }
+
+    fn translate(
+        &mut self,
+        state: &PipewireState,
+        message: &ChannelMessage,
+    ) -> Option<RegistryEvent> {
+        match *message {
+            ChannelMessage::PipewireRegistryGlobal(id) => {
+                // already removed again (its removal follows)
+                let object = state.global_objects.get(&id)?;
+                self.types.insert(id, object.type_.clone());
+                let event = match object.type_ {
+                    ObjectType::Node => RegistryEvent::NodeAdded(id),
+                    ObjectType::Port => match PipewireObject::get_parent_node_id(object) {
+                        Some(node_id) => RegistryEvent::PortAdded { id, node_id },
+                        None => {
+                            tracing::error!("invalid port (node.id not found) {}", id);
+                            RegistryEvent::GlobalAdded(id, ObjectType::Port)
+                        }
+                    },
+                    ObjectType::Link => match PipewireObject::get_link_ports(object) {
+                        Some((output_port, input_port)) => RegistryEvent::LinkAdded {
+                            id,
+                            output_port,
+                            input_port,
+                        },
+                        None => RegistryEvent::GlobalAdded(id, ObjectType::Link),
+                    },
+                    ref type_ => RegistryEvent::GlobalAdded(id, type_.clone()),
+                };
+                Some(event)
+            }
+            ChannelMessage::PipewireRegistryGlobalRemove(id) => {
+                let event = match self.types.remove(&id) {
+                    Some(ObjectType::Node) => RegistryEvent::NodeRemoved(id),
+                    Some(ObjectType::Port) => RegistryEvent::PortRemoved(id),
+                    Some(ObjectType::Link) => RegistryEvent::LinkRemoved(id),
+                    _ => RegistryEvent::GlobalRemoved(id),
+                };
+                Some(event)
+            }
+            _ => None,
+        }
+    }
+
+    /// Call it for `PipewireNodePropsChanged` (props of a node are not announced again).
+    pub fn node_props_changed(&mut self, state: &PipewireState, id: u32) {
+        self.topology.update_node(state, id);
+    }
+
+    /// Nodes, ports and links announced so far.
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    /// Forget every global (e.g. after reconnecting with a new `PipewireWrapper`).
+    pub fn clear(&mut self) {
+        self.types.clear();
+        self.topology.clear();
+    }
+}
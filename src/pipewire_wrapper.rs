@@ -1,32 +1,397 @@
 use std::{
-    cell::RefCell,
-    collections::BTreeMap,
+    cell::{Cell, RefCell},
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    io::Cursor,
+    path::Path,
     rc::Rc,
     sync::{
-        mpsc::{self, Receiver, Sender},
+        atomic::{self, AtomicBool, AtomicUsize},
         Arc, Mutex,
     },
     thread::JoinHandle,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
-use crate::channel::ChannelMessage;
-use pipewire::{prelude::ReadableDict, registry::GlobalObject, types::ObjectType, Properties};
+use crate::{
+    backend::Backend,
+    channel::{self, ChannelMessage, ChannelReceiver, ChannelSender},
+    error::{retry, PwError, CONNECT_RETRY, MESSAGE_RETRY},
+    metrics::{self, CpuSampler, METRICS},
+    path_test::{self, PathTestHop, PathTestReport, SineGenerator},
+    props::{self, PropKey, PropStore},
+    recording::{self, RecordingReport, WavWriter},
+    scope::{self, SampleRing},
+    tone::{self, ToneGenerator, ToneSettings},
+};
+use pipewire::{
+    client::{Client, ClientListener},
+    device::{Device, DeviceChangeMask, DeviceListener},
+    link::{Link, LinkListener},
+    metadata::{Metadata, MetadataListener},
+    module::{ImplModule, Module, ModuleListener},
+    node::{NodeChangeMask, NodeListener},
+    port::{Port, PortChangeMask, PortListener},
+    prelude::{ListenerBuilderT, ReadableDict, WritableDict},
+    proxy::{Proxy, ProxyListener, ProxyT},
+    registry::{GlobalObject, Permission},
+    spa::{
+        pod::{
+            deserialize::PodDeserializer, serialize::PodSerializer, CanonicalFixedSizedPod,
+            ChoiceValue, Object, Property, PropertyFlags, Value, ValueArray,
+        },
+        sys::spa_pod,
+        sys::{
+            SPA_FORMAT_AUDIO_channels, SPA_FORMAT_AUDIO_format, SPA_FORMAT_AUDIO_rate,
+            SPA_FORMAT_VIDEO_framerate, SPA_FORMAT_VIDEO_size, SPA_FORMAT_mediaSubtype,
+            SPA_FORMAT_mediaType, SPA_MEDIA_SUBTYPE_raw, SPA_MEDIA_TYPE_audio,
+            SPA_MEDIA_TYPE_video, SPA_PARAM_AVAILABILITY_no, SPA_PARAM_EnumFormat,
+            SPA_PARAM_EnumProfile, SPA_PARAM_EnumRoute, SPA_PARAM_Format,
+            SPA_PARAM_LATENCY_direction, SPA_PARAM_LATENCY_maxNs, SPA_PARAM_LATENCY_maxQuantum,
+            SPA_PARAM_LATENCY_maxRate, SPA_PARAM_LATENCY_minNs, SPA_PARAM_LATENCY_minQuantum,
+            SPA_PARAM_LATENCY_minRate, SPA_PARAM_Latency, SPA_PARAM_PORT_CONFIG_MODE_convert,
+            SPA_PARAM_PORT_CONFIG_direction, SPA_PARAM_PORT_CONFIG_format,
+            SPA_PARAM_PORT_CONFIG_mode, SPA_PARAM_PROFILE_available, SPA_PARAM_PROFILE_description,
+            SPA_PARAM_PROFILE_index, SPA_PARAM_PROFILE_name, SPA_PARAM_PortConfig,
+            SPA_PARAM_Profile, SPA_PARAM_Props, SPA_PARAM_ROUTE_available,
+            SPA_PARAM_ROUTE_description, SPA_PARAM_ROUTE_device, SPA_PARAM_ROUTE_devices,
+            SPA_PARAM_ROUTE_direction, SPA_PARAM_ROUTE_index, SPA_PARAM_ROUTE_name,
+            SPA_PARAM_ROUTE_props, SPA_PARAM_ROUTE_save, SPA_PARAM_Route, SPA_PROP_channelVolumes,
+            SPA_PROP_mute, SPA_PROP_params, SPA_TYPE_OBJECT_Format,
+            SPA_TYPE_OBJECT_ParamPortConfig, SPA_TYPE_OBJECT_ParamProfile,
+            SPA_TYPE_OBJECT_ParamRoute, SPA_TYPE_OBJECT_Props, SPA_AUDIO_FORMAT_F32P,
+            SPA_AUDIO_FORMAT_F32_LE, SPA_AUDIO_FORMAT_S16_LE, SPA_AUDIO_FORMAT_S24_LE,
+            SPA_AUDIO_FORMAT_S32_LE,
+        },
+        utils::{Choice, ChoiceEnum, Id},
+        AsyncSeq, Direction,
+    },
+    stream::{Stream, StreamFlags, StreamListener, StreamState},
+    types::ObjectType,
+    Properties,
+};
+use serde::{Deserialize, Serialize};
+
+// connections whose thread is still running (see `deinit`)
+static LIVE_WRAPPERS: AtomicUsize = AtomicUsize::new(0);
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+// how long `PipewireWrapper::quit` waits for the main loop to stop before giving up on it
+const QUIT_TIMEOUT: Duration = Duration::from_secs(3);
+const QUIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 pub struct PipewireWrapper {
-    pub channel_sender: Sender<ChannelMessage>,
-    pub channel_receiver: Receiver<ChannelMessage>,
+    pub channel_sender: ChannelSender,
+    pub channel_receiver: ChannelReceiver,
     pub state: Arc<Mutex<PipewireState>>, // TODO: ui thread locks too much?
     thread_handle: Option<JoinHandle<()>>,
 }
 
 #[derive(Default)]
 pub struct PipewireState {
-    pub error: bool, // TODO feedback error to UI e.g. via https://github.com/ItsEthra/egui-notify https://github.com/RegenJacob/egui_logger
+    // fatal error which stopped the pipewire thread
+    pub error: Option<PwError>,
     pub core_info: Option<String>,
-    pub global_objects: BTreeMap<u32, GlobalObject<Properties>>,
+    pub global_objects: BTreeMap<u32, GlobalObject<PropStore>>,
+    pub device_profiles: BTreeMap<u32, DeviceProfiles>,
+    pub device_routes: BTreeMap<u32, DeviceRoutes>,
+    // only for ports requested via `ChannelMessage::PortParamsQuery`
+    pub port_latencies: BTreeMap<u32, Vec<PortLatency>>,
+    pub port_formats: BTreeMap<u32, AudioFormat>,
+    pub port_video_formats: BTreeMap<u32, VideoFormats>,
+    // created via `ChannelMessage::VirtualDeviceCreate`
+    pub virtual_devices: Vec<VirtualDevice>,
+    // globals created by this app (links, virtual devices) which still exist
+    pub created_objects: BTreeMap<u32, CreatedObject>,
+    // only for clients requested via `ChannelMessage::ClientPermissionsQuery`
+    pub client_permissions: BTreeMap<u32, Vec<(u32, Permission)>>,
+    // last run of `ChannelMessage::PathTestStart`
+    pub path_test: Option<PathTestReport>,
+    // last run of `ChannelMessage::RecordStart`
+    pub recording: Option<RecordingReport>,
+    // properties of the "default" metadata keyed by subject (e.g. `target.object` of streams)
+    pub metadata: BTreeMap<u32, BTreeMap<String, String>>,
+    // running `ChannelMessage::ToneGeneratorCreate` keyed by node.name
+    pub tone_generators: BTreeMap<String, ToneSettings>,
+    // arguments of the server's Module globals (from their info)
+    pub module_args: BTreeMap<u32, String>,
+    // loaded into this app's context via `ChannelMessage::ModuleLoad`
+    pub loaded_modules: Vec<LoadedModule>,
+    // from the info of every link (not part of the global props)
+    pub link_flags: BTreeMap<u32, LinkFlags>,
+    // SPA_PARAM_Props of the nodes requested via `ChannelMessage::NodeParamsQuery`
+    pub node_volumes: BTreeMap<u32, NodeVolume>,
+    // peak since the previous tick of the nodes metered via `ChannelMessage::NodeMetersSet`
+    pub node_peaks: BTreeMap<u32, f32>,
+    // failure of the stream of `ChannelMessage::ScopeStart`
+    pub scope_error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoadedModule {
+    // for `ChannelMessage::ModuleUnload` (not a global id since the module runs in this process)
+    pub key: u32,
+    pub name: String,
+    pub args: String,
+}
+
+// props of a Factory global
+#[derive(Clone, Copy, Debug)]
+pub struct Factory<'a> {
+    pub id: u32,
+    pub name: &'a str,
+    // e.g. "PipeWire:Interface:Node"
+    pub type_name: &'a str,
+    pub type_version: Option<&'a str>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CreatedObject {
+    pub proxy_id: u32,
+    // to avoid destroying an unrelated object which reused the global id
+    pub serial: Option<String>,
+}
+
+// SPA_PARAM_EnumProfile / SPA_PARAM_Profile of a device
+#[derive(Default, Clone, Debug)]
+pub struct DeviceProfiles {
+    pub profiles: Vec<DeviceProfile>,
+    pub active: Option<i32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeviceProfile {
+    pub index: i32,
+    pub name: String,
+    pub description: String,
+    pub available: bool,
+}
+
+// SPA_PARAM_EnumRoute / SPA_PARAM_Route of a device
+#[derive(Default, Clone, Debug)]
+pub struct DeviceRoutes {
+    pub routes: Vec<DeviceRoute>,
+    // one per card profile device
+    pub active: Vec<DeviceRoute>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeviceRoute {
+    pub index: i32,
+    pub direction: Direction,
+    pub name: String,
+    pub description: String,
+    pub available: bool,
+    // card profile devices (`card.profile.device` of nodes) this route applies to (EnumRoute only)
+    pub devices: Vec<i32>,
+    // card profile device (Route only)
+    pub device: i32,
+    pub channel_volumes: Vec<f32>,
+    pub mute: bool,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct NodeVolume {
+    pub channel_volumes: Vec<f32>,
+    pub mute: bool,
+}
+
+impl DeviceRoutes {
+    pub fn get_routes(&self, device: i32) -> impl Iterator<Item = &DeviceRoute> {
+        self.routes
+            .iter()
+            .filter(move |route| route.devices.contains(&device))
+    }
+
+    pub fn get_active(&self, device: i32) -> Option<&DeviceRoute> {
+        self.active.iter().find(|route| route.device == device)
+    }
+}
+
+// null-audio-sink node created by this app
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VirtualDevice {
+    pub name: String,
+    pub description: String,
+    pub source: bool,
+    // forwards its inputs to the outputs like a loopback (overrides `source`)
+    pub duplex: bool,
+    // `audio.position` e.g. "FL,FR"
+    pub channels: String,
+    // keep the node after this app exits
+    pub linger: bool,
+}
+
+impl VirtualDevice {
+    pub fn kind(&self) -> &'static str {
+        if self.duplex {
+            "loopback"
+        } else if self.source {
+            "source"
+        } else {
+            "sink"
+        }
+    }
+
+    fn properties(&self) -> Properties {
+        use pipewire::keys::*;
+        let media_class = if self.duplex {
+            "Audio/Duplex"
+        } else if self.source {
+            "Audio/Source/Virtual"
+        } else {
+            "Audio/Sink"
+        };
+        let description = if self.description.is_empty() {
+            &self.name
+        } else {
+            &self.description
+        };
+        pipewire::properties! {
+            *FACTORY_NAME => "support.null-audio-sink",
+            *NODE_NAME => self.name.as_str(),
+            *NODE_DESCRIPTION => description.as_str(),
+            *MEDIA_CLASS => media_class,
+            *AUDIO_CHANNELS => self.channels.split(',').count().to_string(),
+            "audio.position" => self.channels.as_str(),
+            "monitor.channel-volumes" => "true",
+            *OBJECT_LINGER => if self.linger { "true" } else { "false" }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkFlags {
+    // outlive this app (otherwise the link goes away with its client)
+    pub linger: bool,
+    // doesn't keep the nodes running and session managers don't treat it as a routing choice
+    pub passive: bool,
+}
+
+impl Default for LinkFlags {
+    fn default() -> Self {
+        Self {
+            linger: true,
+            passive: false,
+        }
+    }
+}
+
+impl LinkFlags {
+    fn from_props<D: ReadableDict>(props: &D) -> Self {
+        let is_true = |key: &str| matches!(props.get(key), Some("true" | "1"));
+        Self {
+            linger: is_true(*pipewire::keys::OBJECT_LINGER),
+            passive: is_true(*pipewire::keys::LINK_PASSIVE),
+        }
+    }
+}
+
+// raw audio format (SPA_PARAM_Format of a port or requested via SPA_PARAM_PortConfig)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioFormat {
+    // SPA_AUDIO_FORMAT_xxx
+    pub format: u32,
+    pub rate: i32,
+    pub channels: i32,
+}
+
+impl Default for AudioFormat {
+    fn default() -> Self {
+        Self {
+            format: SPA_AUDIO_FORMAT_F32_LE,
+            rate: 48000,
+            channels: 2,
+        }
+    }
+}
+
+// sample formats offered for stream format requests
+pub const AUDIO_FORMATS: [(&str, u32); 5] = [
+    ("S16LE", SPA_AUDIO_FORMAT_S16_LE),
+    ("S24LE", SPA_AUDIO_FORMAT_S24_LE),
+    ("S32LE", SPA_AUDIO_FORMAT_S32_LE),
+    ("F32LE", SPA_AUDIO_FORMAT_F32_LE),
+    ("F32P", SPA_AUDIO_FORMAT_F32P),
+];
+
+impl std::fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = AUDIO_FORMATS
+            .iter()
+            .find(|(_, format)| *format == self.format)
+            .map_or_else(
+                || format!("format {}", self.format),
+                |(name, _)| name.to_string(),
+            );
+        write!(f, "{} {}Hz {}ch", format, self.rate, self.channels)
+    }
+}
+
+// SPA_PARAM_EnumFormat / SPA_PARAM_Format of a video port
+#[derive(Default, Clone, Debug)]
+pub struct VideoFormats {
+    // e.g. camera modes (ranges are reduced to their default)
+    pub offered: Vec<VideoFormat>,
+    pub negotiated: Option<VideoFormat>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VideoFormat {
+    pub width: u32,
+    pub height: u32,
+    // (num, denom) where 0/1 means variable (e.g. screencast damage driven)
+    pub framerate: (u32, u32),
+}
+
+impl std::fmt::Display for VideoFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        match self.framerate {
+            (0, _) | (_, 0) => write!(f, " variable fps"),
+            (num, 1) => write!(f, " @ {} fps", num),
+            (num, denom) => write!(f, " @ {:.2} fps", num as f32 / denom as f32),
+        }
+    }
+}
+
+// SPA_PARAM_Latency of a port (one per direction)
+#[derive(Clone, Debug)]
+pub struct PortLatency {
+    pub direction: Direction,
+    pub min_quantum: f32,
+    pub max_quantum: f32,
+    pub min_rate: i32,
+    pub max_rate: i32,
+    pub min_ns: i64,
+    pub max_ns: i64,
+}
+
+impl PortLatency {
+    // (min, max) milliseconds for the given graph quantum and rate
+    pub fn to_ms(&self, quantum: u32, rate: u32) -> (f32, f32) {
+        let ms = |q: f32, r: i32, ns: i64| {
+            (q * quantum as f32 + r as f32) / rate as f32 * 1000.0 + ns as f32 / 1_000_000.0
+        };
+        (
+            ms(self.min_quantum, self.min_rate, self.min_ns),
+            ms(self.max_quantum, self.max_rate, self.max_ns),
+        )
+    }
+}
+
+// serializable copy of a global object (e.g. for `dump --json`)
+#[derive(Serialize, Deserialize)]
+pub struct GlobalObjectDump {
+    pub id: u32,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub version: u32,
+    pub permissions: String,
+    pub props: BTreeMap<String, String>,
 }
 
 // TODO: it's still non `Send` after `GlobalObject::to_owned` ??
@@ -38,198 +403,234 @@ impl PipewireState {
     //   "PipeWire:Interface:Link" => "link-factory"
     //    https://gitlab.freedesktop.org/pipewire/pipewire/-/blob/792defde27e22673bd42b0584e875c78311e900b/src/tools/pw-cli.c#L1530
     fn get_factory_name(&self, object_type: pipewire::types::ObjectType) -> Option<&str> {
+        self.factories()
+            .find(|factory| factory.type_name == object_type.to_str())
+            .map(|factory| factory.name)
+    }
+
+    pub fn factories(&self) -> impl Iterator<Item = Factory<'_>> {
         self.global_objects
             .values()
             .filter(|object| object.type_ == pipewire::types::ObjectType::Factory)
-            .flat_map(|object| {
+            .filter_map(|object| {
                 let props = object.props.as_ref()?;
-                let factory_type_name = props.get(*pipewire::keys::FACTORY_TYPE_NAME)?;
-                let factory_name = props.get(*pipewire::keys::FACTORY_NAME)?;
-                if factory_type_name == object_type.to_str() {
-                    Some(factory_name)
-                } else {
-                    None
-                }
+                Some(Factory {
+                    id: object.id,
+                    name: props.get(*pipewire::keys::FACTORY_NAME)?,
+                    type_name: props.get(*pipewire::keys::FACTORY_TYPE_NAME)?,
+                    type_version: props.get(*pipewire::keys::FACTORY_TYPE_VERSION),
+                })
             })
-            .next()
     }
 
-    fn find_object_by_props<F: Fn(&Properties) -> bool>(
+    fn find_object_by_props<F: Fn(&PropStore) -> bool>(
         &self,
         f: F,
-    ) -> Option<&GlobalObject<Properties>> {
+    ) -> Option<&GlobalObject<PropStore>> {
         self.global_objects
             .values()
             .filter(|object| object.props.as_ref().map_or(false, |props| f(props)))
             .next()
     }
 
-    fn find_object_by_prop(&self, k: &str, v: &str) -> Option<&GlobalObject<Properties>> {
+    pub fn find_object_by_prop(&self, k: &str, v: &str) -> Option<&GlobalObject<PropStore>> {
         self.find_object_by_props(|props| props.iter().find(|&kv| kv == (k, v)).is_some())
     }
+
+    // node of `default.audio.sink` in the "default" metadata (e.g. `{"name":"alsa_output..."}`)
+    pub fn default_sink(&self) -> Option<u32> {
+        let value = self.metadata.get(&0)?.get("default.audio.sink")?;
+        let value: serde_json::Value = serde_json::from_str(value).ok()?;
+        let name = value.get("name")?.as_str()?;
+        self.find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+            .map(|node| node.id)
+    }
+
+    // link between two ports given as (key, value) prop pairs
+    #[rustfmt::skip]
+    pub fn find_link(&self, from: &(String, String), to: &(String, String)) -> Option<u32> {
+        use pipewire::keys::*;
+        let object_from = self.find_object_by_prop(from.0.as_str(), from.1.as_str())?;
+        let object_to = self.find_object_by_prop(to.0.as_str(), to.1.as_str())?;
+        let object_from_props = object_from.props.as_ref()?;
+        let object_to_props = object_to.props.as_ref()?;
+        let output_node = object_from_props.get(*NODE_ID)?;
+        let output_port = object_from.id.to_string();
+        let input_node = object_to_props.get(*NODE_ID)?;
+        let input_port = object_to.id.to_string();
+        let object = self.find_object_by_props(|props| {
+            props.get(*LINK_OUTPUT_NODE) == Some(output_node) &&
+            props.get(*LINK_OUTPUT_PORT) == Some(&output_port) &&
+            props.get(*LINK_INPUT_NODE) == Some(input_node) &&
+            props.get(*LINK_INPUT_PORT) == Some(&input_port)
+        })?;
+        Some(object.id)
+    }
+
+    pub fn dump(&self) -> Vec<GlobalObjectDump> {
+        self.global_objects
+            .values()
+            .map(|object| GlobalObjectDump {
+                id: object.id,
+                type_: object.type_.to_str().to_owned(),
+                version: object.version,
+                permissions: format!("{:?}", object.permissions),
+                props: object.props.as_ref().map_or_else(BTreeMap::new, |props| {
+                    props
+                        .iter()
+                        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                        .collect()
+                }),
+            })
+            .collect()
+    }
+
+    // resolve a port given as global id, `port.alias`, `object.path` or "<node.name>:<port.name>" (like pw-link)
+    pub fn find_port(&self, spec: &str, is_output: bool) -> Option<&GlobalObject<PropStore>> {
+        use pipewire::keys::*;
+        let direction_ok = |object: &GlobalObject<PropStore>| {
+            if is_output {
+                PipewireObject::is_output(object)
+            } else {
+                PipewireObject::is_input(object)
+            }
+        };
+        // e.g. a node id is not a port
+        if let Ok(id) = spec.parse::<u32>() {
+            return self
+                .global_objects
+                .get(&id)
+                .filter(|object| direction_ok(object));
+        }
+        self.global_objects.values().find(|object| {
+            let direction_ok = direction_ok(object);
+            let props = match &object.props {
+                Some(props) if direction_ok => props,
+                _ => return false,
+            };
+            if props.get(*PORT_ALIAS) == Some(spec) || props.get(*OBJECT_PATH) == Some(spec) {
+                return true;
+            }
+            let node_name = PipewireObject::get_parent_node_id(object)
+                .and_then(|node_id| self.global_objects.get(&node_id))
+                .and_then(|node| node.props.as_ref()?.get_key(PropKey::NodeName));
+            match (node_name, props.get(*PORT_NAME)) {
+                (Some(node_name), Some(port_name)) => {
+                    spec.strip_prefix(node_name)
+                        .and_then(|s| s.strip_prefix(':'))
+                        == Some(port_name)
+                }
+                _ => false,
+            }
+        })
+    }
+
+    // the link factory is not visible e.g. inside a sandbox which only got the camera portal
+    pub fn can_create_links(&self) -> bool {
+        self.get_factory_name(ObjectType::Link).is_some()
+    }
+
+    // number of links per port (ports without links are absent)
+    pub fn link_counts(&self) -> HashMap<u32, usize> {
+        let mut counts = HashMap::new();
+        for (output_port, input_port) in self
+            .global_objects
+            .values()
+            .filter_map(PipewireObject::get_link_ports)
+        {
+            *counts.entry(output_port).or_insert(0) += 1;
+            *counts.entry(input_port).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn get_ports(&self, node_id: u32) -> impl Iterator<Item = &GlobalObject<PropStore>> {
+        self.global_objects
+            .values()
+            .filter(move |object| PipewireObject::get_parent_node_id(object) == Some(node_id))
+    }
+
+    // pair up output ports of one node with input ports of another by `audio.channel`
+    // (like qpwgraph's node-to-node connect), the remaining ports in port order (e.g. MIDI, AUX0)
+    pub fn match_ports_by_channel(&self, output_node: u32, input_node: u32) -> Vec<(u32, u32)> {
+        // `port.id` is the index within the node
+        let sorted_ports = |node_id: u32, is_output: bool| {
+            let mut ports: Vec<_> = self
+                .get_ports(node_id)
+                .filter(|object| PipewireObject::is_output(object) == is_output)
+                .collect();
+            ports.sort_by_key(|port| {
+                let index = port
+                    .props
+                    .as_ref()
+                    .and_then(|props| props.get_key(PropKey::PortId)?.parse::<u32>().ok());
+                (index, port.id)
+            });
+            ports
+        };
+        // control ports are never auto-connected and dsp ports only pair with the same format
+        // (a passthrough port only with another passthrough port)
+        let mut outputs = sorted_ports(output_node, true);
+        outputs.retain(|port| !PipewireObject::is_control(port));
+        let mut inputs = sorted_ports(input_node, false);
+        inputs.retain(|port| !PipewireObject::is_control(port));
+        let compatible = |output: &GlobalObject<PropStore>, input: &GlobalObject<PropStore>| {
+            PipewireObject::get_format_dsp(output) == PipewireObject::get_format_dsp(input)
+        };
+        let mut pairs = vec![];
+        outputs.retain(|output| {
+            let channel = match PipewireObject::get_channel(output) {
+                Some(channel) => channel,
+                None => return true,
+            };
+            let position = inputs.iter().position(|input| {
+                PipewireObject::get_channel(input) == Some(channel) && compatible(output, input)
+            });
+            match position {
+                Some(position) => {
+                    pairs.push((output.id, inputs.remove(position).id));
+                    false
+                }
+                None => true,
+            }
+        });
+        for output in outputs {
+            if let Some(position) = inputs.iter().position(|input| compatible(output, input)) {
+                pairs.push((output.id, inputs.remove(position).id));
+            }
+        }
+        pairs
+    }
 }
 
 impl PipewireWrapper {
     pub fn new() -> Self {
-        pipewire::init();
+        Self::with_remote(None)
+    }
+
+    // e.g. "tcp:192.168.1.2:4656" or another local socket name (`None` for the default)
+    pub fn with_remote(remote: Option<String>) -> Self {
+        Self::with_backend(PipewireBackend { remote })
+    }
 
-        // TODO: macro trick to reduce `xxx.clone()` patterns?
+    // e.g. `MockBackend` replaying a fixture in tests
+    pub fn with_backend(backend: impl Backend) -> Self {
+        pipewire::init();
+        INITIALIZED.store(true, atomic::Ordering::SeqCst);
+        LIVE_WRAPPERS.fetch_add(1, atomic::Ordering::SeqCst);
 
-        let (ui_sender, pw_receiver) = mpsc::channel::<ChannelMessage>();
-        let (pw_sender, ui_receiver) = mpsc::channel::<ChannelMessage>();
+        let (ui_sender, pw_receiver) = channel::channel(&METRICS.ui_to_pw);
+        let (pw_sender, ui_receiver) = channel::channel(&METRICS.pw_to_ui);
 
         let state = Arc::new(Mutex::new(PipewireState::default()));
         let state_clone = state.clone();
 
         let thread_handle = std::thread::spawn(move || {
-            // TODO: error handling
-            let main_loop = pipewire::MainLoop::new().unwrap();
-            let context = pipewire::Context::new(&main_loop).unwrap();
-            let core = context.connect(None).unwrap();
-            let registry = Rc::new(RefCell::new(core.get_registry().unwrap()));
-
-            // channel message handler via `add_timer`
-            // (`add_idle` looks too expensive)
-            // (it would be probablly more efficient with `add_event` if we have one more thread to proxy message)
-            let main_loop_weak = main_loop.downgrade();
-            let core_ = core.clone();
-            let state_ = state.clone();
-            let registry_ = registry.clone();
-            let timer_source = main_loop.add_timer(move |_| {
-                let state = state_.lock().unwrap();
-                while let Ok(message) = pw_receiver.try_recv() {
-                    match message {
-                        ChannelMessage::PipewireMainLoopStopRequest => {
-                            main_loop_weak.upgrade().unwrap().quit();
-                        }
-                        ChannelMessage::LinkCreate(from, to) => {
-                            #[rustfmt::skip]
-                            let properties = || -> Option<Properties> {
-                                use pipewire::keys::*;
-                                let object_from = state.find_object_by_prop(from.0.as_str(), from.1.as_str())?;
-                                let object_to = state.find_object_by_prop(to.0.as_str(), to.1.as_str())?;
-                                let object_from_props = object_from.props.as_ref()?;
-                                let object_to_props = object_to.props.as_ref()?;
-                                let output_node = object_from_props.get(*NODE_ID)?;
-                                let output_port = object_from.id.to_string();
-                                let input_node = object_to_props.get(*NODE_ID)?;
-                                let input_port = object_to.id.to_string();
-                                Some(pipewire::properties! {
-                                    *LINK_OUTPUT_NODE => output_node,
-                                    *LINK_OUTPUT_PORT => output_port,
-                                    *LINK_INPUT_NODE => input_node,
-                                    *LINK_INPUT_PORT => input_port,
-                                    *OBJECT_LINGER => "1" // otherwise the new object will removed immediately?
-                                })
-                            }();
-                            dbg!(&properties);
-                            if let Some(properties) = &properties {
-                                // TODO: does Proxy need to be dropped manually?
-                                let _link = core_
-                                    .create_object::<pipewire::link::Link, _>(
-                                        state
-                                            .get_factory_name(pipewire::types::ObjectType::Link)
-                                            .unwrap(),
-                                        properties,
-                                    )
-                                    .unwrap();
-                            } else {
-                                tracing::error!("LinkCreate not found");
-                            }
-                        }
-                        ChannelMessage::LinkDestroy(from, to) => {
-                            #[rustfmt::skip]
-                            let object_id = || -> Option<u32> {
-                                use pipewire::keys::*;
-                                let object_from = state.find_object_by_prop(from.0.as_str(), from.1.as_str())?;
-                                let object_to =state.find_object_by_prop(to.0.as_str(), to.1.as_str())?;
-                                let object_from_props = object_from.props.as_ref()?;
-                                let object_to_props = object_to.props.as_ref()?;
-                                let output_node = object_from_props.get(*NODE_ID)?;
-                                let output_port = object_from.id.to_string();
-                                let input_node = object_to_props.get(*NODE_ID)?;
-                                let input_port = object_to.id.to_string();
-                                let object = state.find_object_by_props(|props| {
-                                    props.get(*LINK_OUTPUT_NODE) == Some(output_node) &&
-                                    props.get(*LINK_OUTPUT_PORT) == Some(&output_port) &&
-                                    props.get(*LINK_INPUT_NODE) == Some(input_node) &&
-                                    props.get(*LINK_INPUT_PORT) == Some(&input_port)
-                                })?;
-                                Some(object.id)
-                            }();
-                            dbg!(&object_id);
-                            if let Some(object_id) = object_id {
-                                registry_.borrow().destroy_global(object_id).into_result().unwrap();
-                            } else {
-                                tracing::error!("LinkDestroy not found");
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            });
-            timer_source
-                .update_timer(
-                    Some(Duration::from_millis(1)),
-                    Some(Duration::from_millis(100)),
-                )
-                .into_result()
-                .unwrap();
-
-            // core event handler
-            let state_ = state.clone();
-            let pw_sender_1 = pw_sender.clone();
-            let _must_use = core
-                .add_listener_local()
-                .info(move |core_info| {
-                    state_.lock().unwrap().core_info = Some(format!("{:#?}", core_info));
-                    pw_sender_1
-                        .send(ChannelMessage::PipewireMainLoopReady)
-                        .unwrap();
-                })
-                .done(|done_id, seq| {
-                    tracing::info!("core done");
-                    dbg!((done_id, seq));
-                })
-                .error(|error_id, seq, res, message| {
-                    tracing::error!("core error");
-                    dbg!((error_id, seq, res, message));
-                })
-                .register();
-
-            // registry event handler
-            let pw_sender_1 = pw_sender.clone();
-            let pw_sender_2 = pw_sender.clone();
-            let state_1 = state.clone();
-            let state_2 = state.clone();
-            let _must_use = registry
-                .borrow()
-                .add_listener_local()
-                .global(move |global_object| {
-                    dbg!(global_object);
-                    state_1
-                        .lock()
-                        .unwrap()
-                        .global_objects
-                        .insert(global_object.id, global_object.to_owned());
-                    pw_sender_1
-                        .send(ChannelMessage::PipewireRegistryGlobal)
-                        .unwrap();
-                })
-                .global_remove(move |global_remove_id| {
-                    dbg!(global_remove_id);
-                    state_2
-                        .lock()
-                        .unwrap()
-                        .global_objects
-                        .remove(&global_remove_id);
-                    pw_sender_2
-                        .send(ChannelMessage::PipewireRegistryGlobalRemove)
-                        .unwrap();
-                })
-                .register();
-
-            main_loop.run(); // blocking
+            if let Err(e) = backend.run(state.clone(), pw_sender.clone(), pw_receiver) {
+                tracing::error!("{}", e);
+                metrics::lock(&state).error = Some(e.clone());
+                pw_sender.send(ChannelMessage::PipewireError(e)).ok();
+            }
         });
 
         Self {
@@ -241,78 +642,2417 @@ impl PipewireWrapper {
     }
 
     pub fn quit(&mut self) -> Result<()> {
+        // the thread might be already gone after a fatal error
         self.channel_sender
-            .send(ChannelMessage::PipewireMainLoopStopRequest)?;
+            .send(ChannelMessage::PipewireMainLoopStopRequest)
+            .ok();
 
-        // TODO: handle with timeout in case of pipewire thread halting?
-        self.thread_handle
-            .take()
-            .context("invalid thread_handle")?
-            .join()
-            .ok()
-            .context("join error")?;
+        let thread_handle = self.thread_handle.take().context("invalid thread_handle")?;
+        let start = Instant::now();
+        while !thread_handle.is_finished() {
+            if start.elapsed() >= QUIT_TIMEOUT {
+                // detached (the process exit takes it down) and still counted as live,
+                // so that `deinit` leaves the library alone
+                bail!(
+                    "pipewire thread didn't stop within {:?} (main loop hung?), leaving it behind",
+                    QUIT_TIMEOUT
+                );
+            }
+            std::thread::sleep(QUIT_POLL_INTERVAL);
+        }
+        // finished even if it panicked
+        LIVE_WRAPPERS.fetch_sub(1, atomic::Ordering::SeqCst);
+        if let Err(panic) = thread_handle.join() {
+            let message = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown");
+            bail!("pipewire thread panicked ({})", message);
+        }
+        Ok(())
+    }
+}
 
+// once at the end of the process since `pipewire::init` can't run again after it
+// (skipped while a thread might still be inside of the library)
+pub fn deinit() {
+    if LIVE_WRAPPERS.load(atomic::Ordering::SeqCst) == 0
+        && INITIALIZED.swap(false, atomic::Ordering::SeqCst)
+    {
         unsafe {
             pipewire::deinit();
         }
-        Ok(())
     }
 }
 
-//
-// object utilities
-//
+// the pipewire daemon (or a remote one)
+pub struct PipewireBackend {
+    pub remote: Option<String>,
+}
 
-pub struct PipewireObject {}
+impl Backend for PipewireBackend {
+    fn run(
+        self,
+        state: Arc<Mutex<PipewireState>>,
+        sender: ChannelSender,
+        receiver: ChannelReceiver,
+    ) -> Result<(), PwError> {
+        run_main_loop(state, sender, receiver, self.remote)
+    }
+}
 
-impl PipewireObject {
-    pub fn get_name(object: &GlobalObject<Properties>) -> Option<(&str, &str)> {
-        use pipewire::keys::*;
-        [
-            *CLIENT_NAME,
-            *CORE_NAME,
-            *DEVICE_NAME,
-            *FACTORY_NAME,
-            *NODE_NAME,
-            *MODULE_NAME,
-            *APP_NAME,
-            unsafe {
-                std::ffi::CStr::from_bytes_with_nul_unchecked(pipewire::sys::PW_KEY_METADATA_NAME)
-                    .to_str()
-                    .unwrap()
-            },
-            *OBJECT_PATH,
-            *PORT_ALIAS,
-        ]
-        .iter()
-        .flat_map(|&k| {
-            object
+// proxies bound on demand with their listeners, keyed by global id
+type BoundProxies<P, L> = Rc<RefCell<HashMap<u32, (P, L)>>>;
+
+// TODO: macro trick to reduce `xxx.clone()` patterns?
+fn run_main_loop(
+    state: Arc<Mutex<PipewireState>>,
+    pw_sender: ChannelSender,
+    pw_receiver: ChannelReceiver,
+    remote: Option<String>,
+) -> Result<(), PwError> {
+    let main_loop = pipewire::MainLoop::new().map_err(|e| PwError::MainLoopNew(e.to_string()))?;
+    // shared with the message handler for `ChannelMessage::ModuleLoad`
+    let context = Rc::new(
+        pipewire::Context::new(&main_loop).map_err(|e| PwError::ContextNew(e.to_string()))?,
+    );
+    let context_ = context.clone();
+    let core = retry(&CONNECT_RETRY, || {
+        let properties = remote.as_ref().map(|remote| {
+            pipewire::properties! {
+                *pipewire::keys::REMOTE_NAME => remote.as_str()
+            }
+        });
+        context
+            .connect(properties)
+            .map_err(|e| PwError::Connect(e.to_string()))
+    })?;
+    let registry = Rc::new(RefCell::new(
+        core.get_registry()
+            .map_err(|e| PwError::GetRegistry(e.to_string()))?,
+    ));
+
+    // channel message handler via `add_timer`
+    // (`add_idle` looks too expensive)
+    // (it would be probablly more efficient with `add_event` if we have one more thread to proxy message)
+    let main_loop_weak = main_loop.downgrade();
+    let core_ = core.clone();
+    let state_ = state.clone();
+    let registry_ = registry.clone();
+    let pw_sender_ = pw_sender.clone();
+    let initial_sync_seq: Rc<Cell<Option<AsyncSeq>>> = Rc::new(Cell::new(None));
+    let roundtrip_seq: Rc<Cell<Option<AsyncSeq>>> = Rc::new(Cell::new(None));
+    let roundtrip_seq_ = roundtrip_seq.clone();
+    // bound device proxies (for profile params)
+    let devices: BoundProxies<Rc<Device>, DeviceListener> = Default::default();
+    // bound module proxies (for their arguments)
+    let modules: Rc<RefCell<HashMap<u32, (Module, ModuleListener)>>> = Default::default();
+    // bound link proxies (for their flags)
+    let links: Rc<RefCell<HashMap<u32, (Link, LinkListener)>>> = Default::default();
+    let devices_ = devices.clone();
+    // bound client proxies (for permissions)
+    let clients: Rc<RefCell<HashMap<u32, (Client, ClientListener)>>> = Default::default();
+    let clients_ = clients.clone();
+    // bound port proxies (for latency params)
+    let ports: BoundProxies<Rc<Port>, PortListener> = Default::default();
+    let ports_ = ports.clone();
+    // bound node proxies (for stream format requests, property updates and volumes)
+    let nodes: BoundProxies<Rc<pipewire::node::Node>, NodeListener> = Default::default();
+    let nodes_ = nodes.clone();
+    // bound "default" metadata (for stream targets)
+    let default_metadata: Rc<RefCell<Option<(u32, Metadata, MetadataListener)>>> =
+        Default::default();
+    let default_metadata_ = default_metadata.clone();
+    // proxies of objects created by this app (kept until the globals are removed)
+    let created: Rc<RefCell<HashMap<u32, (Proxy, ProxyListener)>>> = Default::default();
+    let created_ = created.clone();
+    // proxy ids of non-lingering virtual devices (the server removes the nodes once dropped)
+    let virtual_devices: RefCell<HashMap<String, u32>> = Default::default();
+    // messages failed with transient errors (message, attempts, next attempt time)
+    let retry_queue: RefCell<Vec<(ChannelMessage, u32, Instant)>> = Default::default();
+    let cpu_sampler: RefCell<CpuSampler> = Default::default();
+    // streams of the running path test
+    let path_test: RefCell<Option<PathTest>> = Default::default();
+    let recording: RefCell<Option<Recording>> = Default::default();
+    let tone_generators: RefCell<HashMap<String, ToneStream>> = Default::default();
+    // captures of the nodes shown in the Mixer window
+    let meters: Rc<RefCell<HashMap<u32, PeakTap>>> = Default::default();
+    let meters_ = meters.clone();
+    // capture of the port shown in the Scope window
+    let scope: Rc<RefCell<Option<ScopeTap>>> = Default::default();
+    let scope_ = scope.clone();
+    // modules loaded into `context` keyed by `LoadedModule::key`
+    let loaded_modules: RefCell<Vec<(u32, ImplModule)>> = Default::default();
+    let next_module_key = Cell::new(0);
+    // registry messages of the current tick (sent to the ui as one `ChannelMessage::Batch`)
+    let registry_batch: Rc<RefCell<Vec<ChannelMessage>>> = Default::default();
+    let registry_batch_ = registry_batch.clone();
+    let pw_sender_batch = pw_sender.clone();
+    // the main loop quits to apply a new interval to the timer (`None` to stop for good)
+    let timer_interval: Rc<Cell<Option<Duration>>> = Default::default();
+    let timer_interval_ = timer_interval.clone();
+    let stopping = Cell::new(false);
+    let timer_source = main_loop.add_timer(move |_| {
+        cpu_sampler.borrow_mut().sample();
+        let mut state = metrics::lock(&state_);
+        let now = Instant::now();
+        let (due, pending) = retry_queue
+            .take()
+            .into_iter()
+            .partition(|(_, _, time)| *time <= now);
+        *retry_queue.borrow_mut() = pending;
+        let messages = due
+            .into_iter()
+            .map(|(message, attempts, _)| (message, attempts))
+            .chain(pw_receiver.try_iter().map(|message| (message, 0)));
+        for (message, attempts) in messages {
+            let result = match message.clone() {
+                ChannelMessage::PipewireMainLoopStopRequest => {
+                    timer_interval_.set(None);
+                    stopping.set(true);
+                    if let Some(main_loop) = main_loop_weak.upgrade() {
+                        main_loop.quit();
+                    }
+                    Ok(())
+                }
+                ChannelMessage::PipewireTimerIntervalSet(ms) => {
+                    if !stopping.get() {
+                        timer_interval_.set(Some(Duration::from_millis(ms.max(1).into())));
+                        if let Some(main_loop) = main_loop_weak.upgrade() {
+                            main_loop.quit();
+                        }
+                    }
+                    Ok(())
+                }
+                ChannelMessage::PipewireRoundtrip => core_
+                    .sync(0)
+                    .map(|seq| roundtrip_seq_.set(Some(seq)))
+                    .map_err(|e| PwError::Sync(e.to_string())),
+                ChannelMessage::LinkCreate(from, to, flags, force) => {
+                    create_link(&core_, &state, &from, &to, flags, force).map(|link| {
+                        track_created(link.upcast(), &created_, state_.clone());
+                    })
+                }
+                ChannelMessage::LinkDestroy(from, to) => match state.find_link(&from, &to) {
+                    Some(object_id) => destroy_global(&registry_.borrow(), object_id),
+                    None => Err(PwError::ObjectNotFound(format!(
+                        "LinkDestroy {}={} -> {}={}",
+                        from.0, from.1, to.0, to.1
+                    ))),
+                },
+                ChannelMessage::ObjectDestroy(object_id, serial) => {
+                    destroy_global_serial(&registry_.borrow(), &state, object_id, serial)
+                }
+                ChannelMessage::ObjectsDestroy(objects) => objects
+                    .into_iter()
+                    .map(|(object_id, serial)| {
+                        destroy_global_serial(&registry_.borrow(), &state, object_id, serial)
+                    })
+                    .fold(Ok(()), Result::and),
+                ChannelMessage::DeviceSetProfile(device_id, index) => {
+                    match devices_.borrow().get(&device_id) {
+                        Some((device, _)) => {
+                            device.set_param(SPA_PARAM_Profile, 0, &profile_pod(index));
+                            Ok(())
+                        }
+                        None => Err(PwError::ObjectNotFound(format!(
+                            "DeviceSetProfile {}",
+                            device_id
+                        ))),
+                    }
+                }
+                ChannelMessage::DeviceSetRoute(device_id, index, route_device, volumes) => {
+                    match devices_.borrow().get(&device_id) {
+                        Some((device, _)) => {
+                            let pod = route_pod(index, route_device, volumes);
+                            device.set_param(SPA_PARAM_Route, 0, &pod);
+                            Ok(())
+                        }
+                        None => Err(PwError::ObjectNotFound(format!(
+                            "DeviceSetRoute {}",
+                            device_id
+                        ))),
+                    }
+                }
+                ChannelMessage::PortParamsQuery(port_ids) => {
+                    port_ids.into_iter().try_for_each(|port_id| {
+                        if ports_.borrow().contains_key(&port_id) {
+                            return Ok(());
+                        }
+                        let object = state.global_objects.get(&port_id).ok_or_else(|| {
+                            PwError::ObjectNotFound(format!("PortParamsQuery {}", port_id))
+                        })?;
+                        let port = registry_
+                            .borrow()
+                            .bind::<Port, _>(object)
+                            .map_err(|e| PwError::Bind(port_id, e.to_string()))?;
+                        let port = Rc::new(port);
+                        let listener = add_port_listener(port_id, &port, state_.clone());
+                        ports_.borrow_mut().insert(port_id, (port, listener));
+                        Ok(())
+                    })
+                }
+                ChannelMessage::StreamFormatRequest(node_id, format) => bind_node(
+                    &registry_.borrow(),
+                    &mut nodes_.borrow_mut(),
+                    &state,
+                    (&state_, &pw_sender_),
+                    node_id,
+                )
+                .map(|node| request_stream_format(node, &state, node_id, format)),
+                ChannelMessage::NodeUpdateProps(node_id, props) => bind_node(
+                    &registry_.borrow(),
+                    &mut nodes_.borrow_mut(),
+                    &state,
+                    (&state_, &pw_sender_),
+                    node_id,
+                )
+                .map(|node| node.set_param(SPA_PARAM_Props, 0, &props_params_pod(&props))),
+                ChannelMessage::NodeParamsQuery(node_ids) => {
+                    node_ids.into_iter().try_for_each(|node_id| {
+                        bind_node(
+                            &registry_.borrow(),
+                            &mut nodes_.borrow_mut(),
+                            &state,
+                            (&state_, &pw_sender_),
+                            node_id,
+                        )
+                        .map(|_| ())
+                    })
+                }
+                ChannelMessage::NodeSetVolume(node_id, channel_volumes) => bind_node(
+                    &registry_.borrow(),
+                    &mut nodes_.borrow_mut(),
+                    &state,
+                    (&state_, &pw_sender_),
+                    node_id,
+                )
+                .map(|node| {
+                    let pod = volume_props_pod(Some(channel_volumes), None);
+                    node.set_param(SPA_PARAM_Props, 0, &pod)
+                }),
+                ChannelMessage::NodeSetMute(node_id, mute) => bind_node(
+                    &registry_.borrow(),
+                    &mut nodes_.borrow_mut(),
+                    &state,
+                    (&state_, &pw_sender_),
+                    node_id,
+                )
+                .map(|node| {
+                    node.set_param(SPA_PARAM_Props, 0, &volume_props_pod(None, Some(mute)))
+                }),
+                ChannelMessage::NodeMetersSet(node_ids) => {
+                    let mut meters = meters_.borrow_mut();
+                    meters.retain(|node_id, _| node_ids.contains(node_id));
+                    state
+                        .node_peaks
+                        .retain(|node_id, _| node_ids.contains(node_id));
+                    node_ids.into_iter().try_for_each(|node_id| {
+                        if meters.contains_key(&node_id) {
+                            return Ok(());
+                        }
+                        let target = state.global_objects.get(&node_id).ok_or_else(|| {
+                            PwError::ObjectNotFound(format!("NodeMetersSet {}", node_id))
+                        })?;
+                        let tap = create_peak_tap(&core_, target, "mixer-meter", "Mixer meter")?;
+                        meters.insert(node_id, tap);
+                        Ok(())
+                    })
+                }
+                ChannelMessage::ScopeStart(port_id, ring) => {
+                    // replaces a running one
+                    scope_.take();
+                    state.scope_error = None;
+                    create_scope_tap(&core_, &state, port_id, ring)
+                        .map(|tap| *scope_.borrow_mut() = Some(tap))
+                }
+                ChannelMessage::ScopeStop => {
+                    scope_.take();
+                    state.scope_error = None;
+                    Ok(())
+                }
+                ChannelMessage::VirtualDeviceCreate(device) => {
+                    create_virtual_device(&core_, &state, &device).map(|node| {
+                        let proxy_id = track_created(node.upcast(), &created_, state_.clone());
+                        if !device.linger {
+                            virtual_devices
+                                .borrow_mut()
+                                .insert(device.name.clone(), proxy_id);
+                        }
+                        state
+                            .virtual_devices
+                            .retain(|other| other.name != device.name);
+                        state.virtual_devices.push(device);
+                    })
+                }
+                ChannelMessage::VirtualDeviceDestroy(name) => {
+                    state.virtual_devices.retain(|other| other.name != name);
+                    if let Some(proxy_id) = virtual_devices.borrow_mut().remove(&name) {
+                        created_.borrow_mut().remove(&proxy_id);
+                        Ok(())
+                    } else {
+                        match state.find_object_by_prop(*pipewire::keys::NODE_NAME, &name) {
+                            Some(object) => destroy_global(&registry_.borrow(), object.id),
+                            None => Err(PwError::ObjectNotFound(format!(
+                                "VirtualDeviceDestroy {}",
+                                name
+                            ))),
+                        }
+                    }
+                }
+                ChannelMessage::ClientPermissionsQuery(client_id) => bind_client(
+                    &registry_.borrow(),
+                    &mut clients_.borrow_mut(),
+                    &state,
+                    &state_,
+                    client_id,
+                )
+                .map(|_| ()),
+                ChannelMessage::ClientUpdatePermissions(client_id, permissions) => {
+                    let permissions: Vec<(u32, Permission)> = permissions
+                        .into_iter()
+                        .map(|(id, bits)| (id, Permission::from_bits_truncate(bits)))
+                        .collect();
+                    bind_client(
+                        &registry_.borrow(),
+                        &mut clients_.borrow_mut(),
+                        &state,
+                        &state_,
+                        client_id,
+                    )
+                    .map(|client| {
+                        client.update_permissions(&permissions);
+                        // the server doesn't notify the change
+                        client.get_permissions(0, u32::MAX);
+                    })
+                }
+                ChannelMessage::PathTestStart(node_ids) => {
+                    // replaces a running one
+                    path_test.take();
+                    let result = start_path_test(&core_, &state, &node_ids);
+                    state.path_test = Some(PathTestReport {
+                        running: result.is_ok(),
+                        hops: result.as_ref().map_or_else(|_| vec![], PathTest::hops),
+                        error: result.as_ref().err().map(ToString::to_string),
+                    });
+                    result.map(|test| *path_test.borrow_mut() = Some(test))
+                }
+                ChannelMessage::RecordStart(node_id, path) => {
+                    // replaces a running one
+                    stop_recording(&recording, &mut state);
+                    let result = start_recording(&core_, &state, node_id, Path::new(&path));
+                    state.recording = Some(RecordingReport {
+                        node_id,
+                        path: path.into(),
+                        running: result.is_ok(),
+                        frames: 0,
+                        level: 0.0,
+                        error: result.as_ref().err().map(ToString::to_string),
+                    });
+                    result.map(|started| {
+                        *recording.borrow_mut() = Some(started);
+                    })
+                }
+                ChannelMessage::RecordStop => {
+                    stop_recording(&recording, &mut state);
+                    Ok(())
+                }
+                ChannelMessage::ToneGeneratorCreate(name, settings) => {
+                    create_tone_generator(&core_, &name, settings).map(|generator| {
+                        tone_generators.borrow_mut().insert(name.clone(), generator);
+                        state.tone_generators.insert(name, settings);
+                    })
+                }
+                ChannelMessage::ToneGeneratorUpdate(name, settings) => {
+                    match tone_generators.borrow().get(&name) {
+                        Some(generator) => {
+                            generator.settings.set(settings);
+                            state.tone_generators.insert(name, settings);
+                            Ok(())
+                        }
+                        None => Err(PwError::ObjectNotFound(format!(
+                            "ToneGeneratorUpdate {}",
+                            name
+                        ))),
+                    }
+                }
+                ChannelMessage::ObjectCreate(factory_name, props) => {
+                    create_factory_object(&core_, &state, &factory_name, &props).map(|proxy| {
+                        track_created(proxy, &created_, state_.clone());
+                    })
+                }
+                ChannelMessage::ModuleLoad(name, args) => {
+                    let args = args.trim().to_owned();
+                    context_
+                        .load_module(
+                            &name,
+                            Some(args.as_str()).filter(|args| !args.is_empty()),
+                            None,
+                        )
+                        .map_err(|e| PwError::CreateObject(format!("module {} ({})", name, e)))
+                        .map(|module| {
+                            let key = next_module_key.get();
+                            next_module_key.set(key + 1);
+                            loaded_modules.borrow_mut().push((key, module));
+                            state.loaded_modules.push(LoadedModule { key, name, args });
+                        })
+                }
+                ChannelMessage::ModuleUnload(key) => {
+                    loaded_modules
+                        .borrow_mut()
+                        .retain(|(other, _)| *other != key);
+                    state.loaded_modules.retain(|module| module.key != key);
+                    Ok(())
+                }
+                ChannelMessage::ToneGeneratorDestroy(name) => {
+                    state.tone_generators.remove(&name);
+                    tone_generators.borrow_mut().remove(&name);
+                    Ok(())
+                }
+                ChannelMessage::StreamSetTarget(stream_id, target_id) => {
+                    match &*default_metadata_.borrow() {
+                        Some((_, metadata, _)) => {
+                            set_stream_target(metadata, &state, stream_id, target_id)
+                        }
+                        None => Err(PwError::ObjectNotFound("default metadata".to_owned())),
+                    }
+                }
+                ChannelMessage::PathTestStop => {
+                    path_test.take();
+                    if let Some(report) = &mut state.path_test {
+                        report.running = false;
+                    }
+                    Ok(())
+                }
+                ChannelMessage::CreatedObjectsDestroy => {
+                    let object_ids: Vec<u32> = state
+                        .created_objects
+                        .iter()
+                        .filter(|(id, created)| {
+                            state.global_objects.get(id).map_or(false, |object| {
+                                PipewireObject::get_serial(object) == created.serial.as_deref()
+                            })
+                        })
+                        .map(|(&id, _)| id)
+                        .collect();
+                    // dropping a module unloads it
+                    loaded_modules.borrow_mut().clear();
+                    state.loaded_modules.clear();
+                    object_ids
+                        .into_iter()
+                        .map(|object_id| destroy_global(&registry_.borrow(), object_id))
+                        .fold(Ok(()), Result::and)
+                }
+                _ => Ok(()),
+            };
+            match result {
+                Ok(()) => {}
+                Err(e) if e.is_transient() && attempts + 1 < MESSAGE_RETRY.max_attempts => {
+                    tracing::warn!(
+                        "{} (retry {}/{})",
+                        e,
+                        attempts + 1,
+                        MESSAGE_RETRY.max_attempts
+                    );
+                    retry_queue.borrow_mut().push((
+                        message,
+                        attempts + 1,
+                        now + MESSAGE_RETRY.delay,
+                    ));
+                }
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    pw_sender_.send(ChannelMessage::PipewireError(e)).ok();
+                }
+            }
+        }
+        let finished = match &*path_test.borrow() {
+            Some(test) => {
+                if let Some(report) = &mut state.path_test {
+                    test.update(report);
+                }
+                test.deadline <= now
+            }
+            None => false,
+        };
+        if finished {
+            path_test.take();
+            if let Some(report) = &mut state.path_test {
+                report.running = false;
+            }
+        }
+        for (&node_id, tap) in meters_.borrow().iter() {
+            state.node_peaks.insert(node_id, tap.peak.replace(0.0));
+        }
+        if let Some(tap) = &*scope_.borrow() {
+            state.scope_error = tap.error.borrow().clone();
+        }
+        let failed = match (&*recording.borrow(), &mut state.recording) {
+            (Some(recording), Some(report)) => {
+                recording.update(report);
+                report.error.is_some()
+            }
+            _ => false,
+        };
+        if failed {
+            stop_recording(&recording, &mut state);
+        }
+        // e.g. a tunnel which lost its server
+        loaded_modules.borrow_mut().retain(|(key, module)| {
+            let destroyed = module.is_destroyed();
+            if destroyed {
+                state.loaded_modules.retain(|module| module.key != *key);
+            }
+            !destroyed
+        });
+        // e.g. the node was destroyed from elsewhere
+        tone_generators.borrow_mut().retain(|name, generator| {
+            let stopped = generator.stopped.get();
+            if stopped {
+                state.tone_generators.remove(name);
+            }
+            !stopped
+        });
+        flush_registry_batch(&pw_sender_batch, &registry_batch_);
+        METRICS.record_timer(now.elapsed());
+    });
+    timer_source
+        .update_timer(
+            Some(Duration::from_millis(1)),
+            Some(Duration::from_millis(100)),
+        )
+        .into_result()
+        .map_err(|e| PwError::Timer(e.to_string()))?;
+
+    // core event handler
+    let state_ = state.clone();
+    let pw_sender_1 = pw_sender.clone();
+    let pw_sender_2 = pw_sender.clone();
+    let pw_sender_3 = pw_sender.clone();
+    let initial_sync_seq_ = initial_sync_seq.clone();
+    let registry_batch_ = registry_batch.clone();
+    let _must_use = core
+        .add_listener_local()
+        .info(move |core_info| {
+            metrics::lock(&state_).core_info = Some(format!("{:#?}", core_info));
+            pw_sender_1.send(ChannelMessage::PipewireMainLoopReady).ok();
+        })
+        .done(move |done_id, seq| {
+            tracing::debug!(done_id, ?seq, "core done");
+            // the globals announced before the sync go first
+            flush_registry_batch(&pw_sender_2, &registry_batch_);
+            if initial_sync_seq_.get() == Some(seq) {
+                initial_sync_seq_.set(None);
+                pw_sender_2
+                    .send(ChannelMessage::PipewireInitialSyncDone)
+                    .ok();
+            }
+            if roundtrip_seq.get() == Some(seq) {
+                roundtrip_seq.set(None);
+                pw_sender_2.send(ChannelMessage::PipewireRoundtripDone).ok();
+            }
+        })
+        .error(move |id, seq, res, message| {
+            let e = PwError::Core {
+                id,
+                seq,
+                res,
+                message: message.to_owned(),
+            };
+            tracing::error!("{}", e);
+            pw_sender_3.send(ChannelMessage::PipewireError(e)).ok();
+        })
+        .register();
+
+    // registry event handler
+    let pw_sender_1 = pw_sender;
+    let registry_batch_1 = registry_batch.clone();
+    let registry_batch_2 = registry_batch;
+    let state_1 = state.clone();
+    let state_2 = state;
+    let registry_ = registry.clone();
+    let devices_1 = devices.clone();
+    let devices_2 = devices;
+    let modules_1 = modules.clone();
+    let modules_2 = modules;
+    let links_1 = links.clone();
+    let links_2 = links;
+    let default_metadata_1 = default_metadata.clone();
+    let default_metadata_2 = default_metadata;
+    let _must_use = registry
+        .borrow()
+        .add_listener_local()
+        .global(move |global_object| {
+            tracing::debug!(?global_object, "registry global");
+            if global_object.type_ == ObjectType::Device {
+                match registry_.borrow().bind::<Device, _>(global_object) {
+                    Ok(device) => {
+                        let device = Rc::new(device);
+                        let listener =
+                            add_device_listener(global_object.id, &device, state_1.clone());
+                        devices_1
+                            .borrow_mut()
+                            .insert(global_object.id, (device, listener));
+                    }
+                    Err(e) => {
+                        let e = PwError::Bind(global_object.id, e.to_string());
+                        tracing::error!("{}", e);
+                        pw_sender_1.send(ChannelMessage::PipewireError(e)).ok();
+                    }
+                }
+            }
+            if global_object.type_ == ObjectType::Module {
+                match registry_.borrow().bind::<Module, _>(global_object) {
+                    Ok(module) => {
+                        let listener =
+                            add_module_listener(global_object.id, &module, state_1.clone());
+                        modules_1
+                            .borrow_mut()
+                            .insert(global_object.id, (module, listener));
+                    }
+                    Err(e) => {
+                        let e = PwError::Bind(global_object.id, e.to_string());
+                        tracing::error!("{}", e);
+                        pw_sender_1.send(ChannelMessage::PipewireError(e)).ok();
+                    }
+                }
+            }
+            if global_object.type_ == ObjectType::Link {
+                match registry_.borrow().bind::<Link, _>(global_object) {
+                    Ok(link) => {
+                        let listener = add_link_listener(global_object.id, &link, state_1.clone());
+                        links_1
+                            .borrow_mut()
+                            .insert(global_object.id, (link, listener));
+                    }
+                    Err(e) => {
+                        let e = PwError::Bind(global_object.id, e.to_string());
+                        tracing::error!("{}", e);
+                        pw_sender_1.send(ChannelMessage::PipewireError(e)).ok();
+                    }
+                }
+            }
+            let metadata_name = global_object
                 .props
                 .as_ref()
-                .map(|prop| prop.get(k).map(|v| (k, v)))
-                .flatten()
+                .and_then(|props| props.get(PropKey::MetadataName.as_str()));
+            if global_object.type_ == ObjectType::Metadata && metadata_name == Some("default") {
+                match registry_.borrow().bind::<Metadata, _>(global_object) {
+                    Ok(metadata) => {
+                        let listener = add_metadata_listener(&metadata, state_1.clone());
+                        *default_metadata_1.borrow_mut() =
+                            Some((global_object.id, metadata, listener));
+                    }
+                    Err(e) => {
+                        let e = PwError::Bind(global_object.id, e.to_string());
+                        tracing::error!("{}", e);
+                        pw_sender_1.send(ChannelMessage::PipewireError(e)).ok();
+                    }
+                }
+            }
+            let old_object = {
+                let mut state = metrics::lock(&state_1);
+                let old_object = state
+                    .global_objects
+                    .insert(global_object.id, props::intern(global_object));
+                // `bound` of our own proxy can arrive before the global
+                let serial = PipewireObject::get_serial(&state.global_objects[&global_object.id])
+                    .map(str::to_owned);
+                if let Some(created) = state.created_objects.get_mut(&global_object.id) {
+                    created.serial = created.serial.take().or(serial);
+                }
+                old_object
+            };
+            let mut batch = registry_batch_1.borrow_mut();
+            batch.push(ChannelMessage::PipewireRegistryGlobal(global_object.id));
+            // server re-announces a known global when our permissions on it change
+            if let Some(old_object) = old_object {
+                if old_object.permissions != global_object.permissions {
+                    batch.push(ChannelMessage::PipewirePermissionChanged(
+                        global_object.id,
+                        old_object.permissions.bits(),
+                    ));
+                }
+            }
         })
-        .next()
+        .global_remove(move |global_remove_id| {
+            tracing::debug!(global_remove_id, "registry global_remove");
+            devices_2.borrow_mut().remove(&global_remove_id);
+            modules_2.borrow_mut().remove(&global_remove_id);
+            links_2.borrow_mut().remove(&global_remove_id);
+            let is_default_metadata = matches!(
+                &*default_metadata_2.borrow(),
+                Some((id, ..)) if *id == global_remove_id
+            );
+            ports.borrow_mut().remove(&global_remove_id);
+            clients.borrow_mut().remove(&global_remove_id);
+            nodes.borrow_mut().remove(&global_remove_id);
+            // otherwise the session manager moves the capture to another node
+            meters.borrow_mut().remove(&global_remove_id);
+            let scope_removed = matches!(
+                &*scope.borrow(),
+                Some(tap) if tap.port_id == global_remove_id || tap.node_id == global_remove_id
+            );
+            if scope_removed {
+                scope.take();
+            }
+            let mut state = metrics::lock(&state_2);
+            let object = state.global_objects.remove(&global_remove_id);
+            if let Some(created_object) = state.created_objects.remove(&global_remove_id) {
+                created.borrow_mut().remove(&created_object.proxy_id);
+            }
+            // e.g. virtual device removed by another client
+            if let Some(name) = object
+                .as_ref()
+                .and_then(|object| object.props.as_ref()?.get_key(PropKey::NodeName))
+            {
+                state.virtual_devices.retain(|device| device.name != name);
+            }
+            state.device_profiles.remove(&global_remove_id);
+            state.device_routes.remove(&global_remove_id);
+            state.port_latencies.remove(&global_remove_id);
+            state.port_formats.remove(&global_remove_id);
+            state.port_video_formats.remove(&global_remove_id);
+            state.client_permissions.remove(&global_remove_id);
+            state.metadata.remove(&global_remove_id);
+            state.module_args.remove(&global_remove_id);
+            state.link_flags.remove(&global_remove_id);
+            state.node_volumes.remove(&global_remove_id);
+            state.node_peaks.remove(&global_remove_id);
+            if is_default_metadata {
+                default_metadata_2.borrow_mut().take();
+                state.metadata.clear();
+            }
+            drop(state);
+            registry_batch_2
+                .borrow_mut()
+                .push(ChannelMessage::PipewireRegistryGlobalRemove(
+                    global_remove_id,
+                ));
+        })
+        .register();
+
+    // "done" for this sync arrives after the initial registry dump
+    initial_sync_seq.set(Some(
+        core.sync(0).map_err(|e| PwError::Sync(e.to_string()))?,
+    ));
+
+    loop {
+        main_loop.run(); // blocking
+        match timer_interval.take() {
+            Some(interval) => {
+                timer_source
+                    .update_timer(Some(interval), Some(interval))
+                    .into_result()
+                    .map_err(|e| PwError::Timer(e.to_string()))?;
+            }
+            None => break,
+        }
     }
+    Ok(())
+}
 
-    pub fn is_input(object: &GlobalObject<Properties>) -> bool {
-        use pipewire::keys::*;
-        object.type_ == ObjectType::Port
-            && object
-                .props
-                .as_ref()
-                .map(|prop| prop.get(*PORT_DIRECTION))
-                .flatten()
-                == Some("in")
+fn flush_registry_batch(pw_sender: &ChannelSender, batch: &RefCell<Vec<ChannelMessage>>) {
+    let messages = batch.take();
+    if !messages.is_empty() {
+        pw_sender.send(ChannelMessage::Batch(messages)).ok();
     }
+}
 
-    pub fn is_output(object: &GlobalObject<Properties>) -> bool {
+fn create_link(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    from: &(String, String),
+    to: &(String, String),
+    flags: LinkFlags,
+    force: bool,
+) -> Result<Link, PwError> {
+    // the server happily creates another link between the same ports
+    if let (false, Some(link_id)) = (force, state.find_link(from, to)) {
+        return Err(PwError::AlreadyLinked(link_id));
+    }
+    #[rustfmt::skip]
+    let properties = || -> Option<Properties> {
         use pipewire::keys::*;
+        let object_from = state.find_object_by_prop(from.0.as_str(), from.1.as_str())?;
+        let object_to = state.find_object_by_prop(to.0.as_str(), to.1.as_str())?;
+        let object_from_props = object_from.props.as_ref()?;
+        let object_to_props = object_to.props.as_ref()?;
+        let output_node = object_from_props.get(*NODE_ID)?;
+        let output_port = object_from.id.to_string();
+        let input_node = object_to_props.get(*NODE_ID)?;
+        let input_port = object_to.id.to_string();
+        Some(pipewire::properties! {
+            *LINK_OUTPUT_NODE => output_node,
+            *LINK_OUTPUT_PORT => output_port,
+            *LINK_INPUT_NODE => input_node,
+            *LINK_INPUT_PORT => input_port,
+            // otherwise the link goes away with this app
+            *OBJECT_LINGER => if flags.linger { "true" } else { "false" },
+            *LINK_PASSIVE => if flags.passive { "true" } else { "false" }
+        })
+    }();
+    tracing::debug!(?properties, "LinkCreate");
+    let properties = properties.ok_or_else(|| {
+        PwError::ObjectNotFound(format!(
+            "LinkCreate {}={} -> {}={}",
+            from.0, from.1, to.0, to.1
+        ))
+    })?;
+    let factory_name = state
+        .get_factory_name(ObjectType::Link)
+        .ok_or_else(|| PwError::FactoryNotFound(ObjectType::Link.to_str().to_owned()))?;
+    core.create_object::<Link, _>(factory_name, &properties)
+        .map_err(|e| PwError::CreateObject(e.to_string()))
+}
+
+// node proxies are bound on demand and keep the props of the node's global up to date
+fn bind_node<'a>(
+    registry: &pipewire::registry::Registry,
+    nodes: &'a mut HashMap<u32, (Rc<pipewire::node::Node>, NodeListener)>,
+    state: &PipewireState,
+    (shared_state, pw_sender): (&Arc<Mutex<PipewireState>>, &ChannelSender),
+    node_id: u32,
+) -> Result<&'a pipewire::node::Node, PwError> {
+    let entry = match nodes.entry(node_id) {
+        Entry::Occupied(entry) => return Ok(&entry.into_mut().0),
+        Entry::Vacant(entry) => entry,
+    };
+    let object = state
+        .global_objects
+        .get(&node_id)
+        .filter(|object| object.type_ == ObjectType::Node)
+        .ok_or_else(|| PwError::ObjectNotFound(format!("node {}", node_id)))?;
+    let node = registry
+        .bind::<pipewire::node::Node, _>(object)
+        .map_err(|e| PwError::Bind(node_id, e.to_string()))?;
+    let node = Rc::new(node);
+    let listener = add_node_listener(node_id, &node, shared_state.clone(), pw_sender.clone());
+    Ok(&entry.insert((node, listener)).0)
+}
+
+// e.g. node.description updated via `SPA_PROP_params`
+// (the registry only announces the props the node had when it was registered)
+#[allow(non_upper_case_globals)]
+fn add_node_listener(
+    node_id: u32,
+    node: &Rc<pipewire::node::Node>,
+    state: Arc<Mutex<PipewireState>>,
+    pw_sender: ChannelSender,
+) -> NodeListener {
+    let node_weak = Rc::downgrade(node);
+    let state_ = state.clone();
+    node.add_listener_local()
+        .info(move |info| {
+            // (re-)enumerate when params are changed (e.g. volume changed by another client)
+            if info.change_mask().contains(NodeChangeMask::PARAMS) {
+                if let Some(node) = node_weak.upgrade() {
+                    node.enum_params(0, SPA_PARAM_Props, 0, u32::MAX);
+                }
+            }
+            if !info.change_mask().contains(NodeChangeMask::PROPS) {
+                return;
+            }
+            if let Some(props) = info.props() {
+                let mut state = metrics::lock(&state);
+                if let Some(object) = state.global_objects.get_mut(&node_id) {
+                    object.props = Some(PropStore::new(props));
+                }
+                drop(state);
+                pw_sender
+                    .send(ChannelMessage::PipewireNodePropsChanged(node_id))
+                    .ok();
+            }
+        })
+        .param(move |_seq, id, _index, _next, pod| {
+            if id != SPA_PARAM_Props {
+                return;
+            }
+            let properties = match PodDeserializer::deserialize_any_from(pod) {
+                Ok((_, Value::Object(Object { properties, .. }))) => properties,
+                _ => return,
+            };
+            let mut state = metrics::lock(&state_);
+            let volume = state.node_volumes.entry(node_id).or_default();
+            for property in properties {
+                match (property.key, property.value) {
+                    (SPA_PROP_channelVolumes, Value::ValueArray(ValueArray::Float(v))) => {
+                        volume.channel_volumes = v
+                    }
+                    (SPA_PROP_mute, Value::Bool(mute)) => volume.mute = mute,
+                    _ => {}
+                }
+            }
+        })
+        .register()
+}
+
+// `SPA_PROP_channelVolumes` and/or `SPA_PROP_mute` of a node
+fn volume_props_pod(channel_volumes: Option<Vec<f32>>, mute: Option<bool>) -> Vec<u8> {
+    let mut properties = vec![];
+    if let Some(channel_volumes) = channel_volumes {
+        properties.push(property(
+            SPA_PROP_channelVolumes,
+            Value::ValueArray(ValueArray::Float(channel_volumes)),
+        ));
+    }
+    if let Some(mute) = mute {
+        properties.push(property(SPA_PROP_mute, Value::Bool(mute)));
+    }
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_Props,
+        id: SPA_PARAM_Props,
+        properties,
+    }))
+}
+
+fn request_stream_format(
+    node: &pipewire::node::Node,
+    state: &PipewireState,
+    node_id: u32,
+    format: AudioFormat,
+) {
+    // ports facing the graph e.g. output ports of "Stream/Output/Audio"
+    let direction = if state
+        .global_objects
+        .get(&node_id)
+        .and_then(PipewireObject::get_media_class)
+        .map_or(false, |class| class.contains("Output"))
+    {
+        Direction::Output
+    } else {
+        Direction::Input
+    };
+    node.set_param(SPA_PARAM_PortConfig, 0, &port_config_pod(direction, format));
+}
+
+fn create_virtual_device(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    device: &VirtualDevice,
+) -> Result<pipewire::node::Node, PwError> {
+    const FACTORY_NAME: &str = "adapter";
+    state
+        .find_object_by_prop(*pipewire::keys::FACTORY_NAME, FACTORY_NAME)
+        .ok_or_else(|| PwError::FactoryNotFound(FACTORY_NAME.to_owned()))?;
+    core.create_object::<pipewire::node::Node, _>(FACTORY_NAME, &device.properties())
+        .map_err(|e| PwError::CreateObject(e.to_string()))
+}
+
+// object of any factory whose type has a proxy here (e.g. "adapter", "link-factory", "metadata")
+fn create_factory_object(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    factory_name: &str,
+    props: &[(String, String)],
+) -> Result<Proxy, PwError> {
+    let factory = state
+        .factories()
+        .find(|factory| factory.name == factory_name)
+        .ok_or_else(|| PwError::FactoryNotFound(factory_name.to_owned()))?;
+    let mut properties = pipewire::Properties::new();
+    for (key, value) in props {
+        properties.insert(key.as_str(), value.as_str());
+    }
+    fn create<P: ProxyT>(
+        core: &pipewire::Core,
+        factory_name: &str,
+        properties: &pipewire::Properties,
+    ) -> Result<Proxy, PwError> {
+        core.create_object::<P, _>(factory_name, properties)
+            .map(ProxyT::upcast)
+            .map_err(|e| PwError::CreateObject(e.to_string()))
+    }
+    let type_name = factory.type_name;
+    if type_name == ObjectType::Node.to_str() {
+        create::<pipewire::node::Node>(core, factory_name, &properties)
+    } else if type_name == ObjectType::Link.to_str() {
+        create::<Link>(core, factory_name, &properties)
+    } else if type_name == ObjectType::Device.to_str() {
+        create::<Device>(core, factory_name, &properties)
+    } else if type_name == ObjectType::Metadata.to_str() {
+        create::<Metadata>(core, factory_name, &properties)
+    } else if type_name == ObjectType::Port.to_str() {
+        create::<pipewire::port::Port>(core, factory_name, &properties)
+    } else {
+        Err(PwError::CreateObject(format!(
+            "{} ({}) is not supported",
+            factory_name, type_name
+        )))
+    }
+}
+
+//
+// path test (tone played into the first node of a path and captured after every node)
+//
+
+// stream of this app (path test, recording, tone generator)
+// (listener declared first to be removed before the stream is destroyed)
+struct OwnedStream<D> {
+    _listener: StreamListener<D>,
+    _stream: Stream<D>,
+}
+
+// capture of a node keeping its peak level (path test and mixer meters)
+struct PeakTap {
+    node_id: u32,
+    _stream: OwnedStream<()>,
+    peak: Rc<Cell<f32>>,
+    error: Rc<RefCell<Option<String>>>,
+}
+
+struct PathTest {
+    deadline: Instant,
+    _playback: OwnedStream<SineGenerator>,
+    taps: Vec<PeakTap>,
+}
+
+impl PathTest {
+    fn hops(&self) -> Vec<PathTestHop> {
+        self.taps
+            .iter()
+            .map(|tap| PathTestHop {
+                node_id: tap.node_id,
+                peak: 0.0,
+                error: None,
+            })
+            .collect()
+    }
+
+    // copy what the taps captured so far
+    fn update(&self, report: &mut PathTestReport) {
+        for (hop, tap) in report.hops.iter_mut().zip(&self.taps) {
+            hop.peak = tap.peak.get();
+            hop.error = tap.error.borrow().clone();
+        }
+    }
+}
+
+// `node_ids` in the order of the path (sources before the first node with input ports are skipped)
+fn start_path_test(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    node_ids: &[u32],
+) -> Result<PathTest, PwError> {
+    let start = node_ids
+        .iter()
+        .position(|&node_id| state.get_ports(node_id).any(PipewireObject::is_input))
+        .ok_or_else(|| {
+            PwError::ObjectNotFound("PathTestStart (no node with input ports)".to_owned())
+        })?;
+    let node = |node_id: u32| {
+        state
+            .global_objects
+            .get(&node_id)
+            .ok_or_else(|| PwError::ObjectNotFound(format!("PathTestStart {}", node_id)))
+    };
+    let playback = create_path_test_playback(core, node(node_ids[start])?)?;
+    let taps = node_ids[start..]
+        .iter()
+        .map(|&node_id| create_peak_tap(core, node(node_id)?, "path-test-tap", "Path test tap"))
+        .collect::<Result<_, _>>()?;
+    Ok(PathTest {
+        deadline: Instant::now() + path_test::DURATION,
+        _playback: playback,
+        taps,
+    })
+}
+
+fn target_stream_properties(
+    category: &str,
+    description: &str,
+    target: &GlobalObject<PropStore>,
+) -> Properties {
+    use pipewire::keys::*;
+    let mut properties = pipewire::properties! {
+        *MEDIA_TYPE => "Audio",
+        *MEDIA_CATEGORY => category,
+        *MEDIA_ROLE => "Test",
+        *NODE_DESCRIPTION => description,
+        // superseded by "target.object" (serial) but the only one older session managers follow
+        *NODE_TARGET => target.id.to_string()
+    };
+    if let Some(serial) = PipewireObject::get_serial(target) {
+        properties.insert("target.object", serial);
+    }
+    properties
+}
+
+// records the monitor of a sink instead of its input
+fn capture_stream_properties(description: &str, target: &GlobalObject<PropStore>) -> Properties {
+    let mut properties = target_stream_properties("Capture", description, target);
+    if is_sink(target) {
+        properties.insert(*pipewire::keys::STREAM_CAPTURE_SINK, "true");
+    }
+    properties
+}
+
+fn is_sink(node: &GlobalObject<PropStore>) -> bool {
+    PipewireObject::get_media_class(node).map_or(false, |class| class.starts_with("Audio/Sink"))
+}
+
+fn connect_own_stream<D>(
+    stream: &Stream<D>,
+    direction: Direction,
+    format: &[u8],
+) -> Result<(), PwError> {
+    stream
+        .connect(
+            direction,
+            None,
+            // fail instead of falling back to the default device
+            StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS | StreamFlags::DONT_RECONNECT,
+            &mut [format.as_ptr() as *const spa_pod],
+        )
+        .map_err(|e| PwError::CreateObject(format!("stream ({})", e)))
+}
+
+fn create_path_test_playback(
+    core: &pipewire::Core,
+    target: &GlobalObject<PropStore>,
+) -> Result<OwnedStream<SineGenerator>, PwError> {
+    let properties = target_stream_properties("Playback", "Path test tone", target);
+    let mut stream = Stream::<SineGenerator>::new(core, "path-test-tone", properties)
+        .map_err(|e| PwError::CreateObject(format!("path test stream ({})", e)))?;
+    let listener = stream
+        .add_local_listener()
+        .process(|stream, generator| {
+            let mut buffer = match stream.dequeue_buffer() {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let data = &mut buffer.datas_mut()[0];
+            let size = match data.data() {
+                Some(bytes) => {
+                    for frame in bytes.chunks_exact_mut(4) {
+                        frame.copy_from_slice(&generator.next_sample().to_le_bytes());
+                    }
+                    bytes.len() / 4 * 4
+                }
+                None => return,
+            };
+            let chunk = data.chunk_mut();
+            *chunk.offset_mut() = 0;
+            *chunk.stride_mut() = 4;
+            *chunk.size_mut() = size as u32;
+        })
+        .register()
+        .map_err(|e| PwError::CreateObject(format!("path test stream ({})", e)))?;
+    let format = raw_f32_format_pod(Some((path_test::TONE_RATE as i32, 1)));
+    connect_own_stream(&stream, Direction::Output, &format)?;
+    Ok(OwnedStream {
+        _listener: listener,
+        _stream: stream,
+    })
+}
+
+// captures the output ports of the node (or the monitor ports of a sink)
+fn create_peak_tap(
+    core: &pipewire::Core,
+    target: &GlobalObject<PropStore>,
+    name: &str,
+    description: &str,
+) -> Result<PeakTap, PwError> {
+    let properties = capture_stream_properties(description, target);
+    let mut stream = Stream::<()>::new(core, name, properties)
+        .map_err(|e| PwError::CreateObject(format!("{} ({})", name, e)))?;
+    let peak: Rc<Cell<f32>> = Default::default();
+    let error: Rc<RefCell<Option<String>>> = Default::default();
+    let peak_ = peak.clone();
+    let error_ = error.clone();
+    let listener = stream
+        .add_local_listener()
+        .state_changed(move |_old, new| {
+            if let StreamState::Error(e) = new {
+                *error_.borrow_mut() = Some(e);
+            }
+        })
+        .process(move |stream, _| {
+            let mut buffer = match stream.dequeue_buffer() {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let data = &mut buffer.datas_mut()[0];
+            let offset = data.chunk().offset() as usize;
+            let size = data.chunk().size() as usize;
+            if let Some(bytes) = data
+                .data()
+                .and_then(|bytes| bytes.get(offset..offset + size))
+            {
+                let samples = bytes
+                    .chunks_exact(4)
+                    .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]));
+                peak_.set(peak_.get().max(path_test::peak(samples)));
+            }
+        })
+        .register()
+        .map_err(|e| PwError::CreateObject(format!("{} ({})", name, e)))?;
+    // any rate and channels (converted to f32 so that the level is comparable)
+    let format = raw_f32_format_pod(None);
+    connect_own_stream(&stream, Direction::Input, &format)?;
+    Ok(PeakTap {
+        node_id: target.id,
+        _stream: OwnedStream {
+            _listener: listener,
+            _stream: stream,
+        },
+        peak,
+        error,
+    })
+}
+
+// capture of one output port into the ring buffer of the Scope window
+struct ScopeTap {
+    port_id: u32,
+    node_id: u32,
+    _stream: OwnedStream<()>,
+    error: Rc<RefCell<Option<String>>>,
+}
+
+// the node is captured as a whole, keeping the channel of the port
+// (its position among the node's output ports, which follow the channel order)
+fn create_scope_tap(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    port_id: u32,
+    ring: Arc<SampleRing>,
+) -> Result<ScopeTap, PwError> {
+    let not_found = || PwError::ObjectNotFound(format!("ScopeStart {}", port_id));
+    let port = state.global_objects.get(&port_id).ok_or_else(not_found)?;
+    let node_id = PipewireObject::get_parent_node_id(port).ok_or_else(not_found)?;
+    let target = state.global_objects.get(&node_id).ok_or_else(not_found)?;
+    let mut port_ids: Vec<u32> = state
+        .get_ports(node_id)
+        .filter(|port| PipewireObject::is_output(port))
+        .map(|port| port.id)
+        .collect();
+    port_ids.sort_unstable();
+    let channel = port_ids
+        .iter()
+        .position(|&id| id == port_id)
+        .ok_or_else(not_found)?;
+    let channels = port_ids.len();
+    let mut stream = Stream::<()>::new(core, "scope", capture_stream_properties("Scope", target))
+        .map_err(|e| PwError::CreateObject(format!("scope stream ({})", e)))?;
+    let error: Rc<RefCell<Option<String>>> = Default::default();
+    let error_ = error.clone();
+    let listener = stream
+        .add_local_listener()
+        .state_changed(move |_old, new| {
+            if let StreamState::Error(e) = new {
+                *error_.borrow_mut() = Some(e);
+            }
+        })
+        .process(move |stream, _| {
+            let mut buffer = match stream.dequeue_buffer() {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let data = &mut buffer.datas_mut()[0];
+            let offset = data.chunk().offset() as usize;
+            let size = data.chunk().size() as usize;
+            if let Some(bytes) = data
+                .data()
+                .and_then(|bytes| bytes.get(offset..offset + size))
+            {
+                let samples = bytes.chunks_exact(4 * channels).map(|frame| {
+                    let sample = &frame[4 * channel..4 * channel + 4];
+                    f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])
+                });
+                ring.push(samples);
+            }
+        })
+        .register()
+        .map_err(|e| PwError::CreateObject(format!("scope stream ({})", e)))?;
+    let format = raw_f32_format_pod(Some((scope::RATE as i32, channels as i32)));
+    connect_own_stream(&stream, Direction::Input, &format)?;
+    Ok(ScopeTap {
+        port_id,
+        node_id,
+        _stream: OwnedStream {
+            _listener: listener,
+            _stream: stream,
+        },
+        error,
+    })
+}
+
+//
+// recording (a node captured into a WAV file)
+//
+
+struct Recording {
+    _stream: OwnedStream<()>,
+    // taken on stop (or after a write error) to finish the file
+    writer: Rc<RefCell<Option<WavWriter>>>,
+    level: Rc<Cell<f32>>,
+    error: Rc<RefCell<Option<String>>>,
+}
+
+impl Recording {
+    fn update(&self, report: &mut RecordingReport) {
+        if let Some(writer) = &*self.writer.borrow() {
+            report.frames = writer.frames();
+        }
+        report.level = self.level.replace(0.0);
+        report.error = self.error.borrow().clone();
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self.writer.take() {
+            Some(writer) => writer.finish().map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn start_recording(
+    core: &pipewire::Core,
+    state: &PipewireState,
+    node_id: u32,
+    path: &Path,
+) -> Result<Recording, PwError> {
+    let target = state
+        .global_objects
+        .get(&node_id)
+        .ok_or_else(|| PwError::ObjectNotFound(format!("RecordStart {}", node_id)))?;
+    let num_ports = state
+        .get_ports(node_id)
+        .filter(|port| {
+            if is_sink(target) {
+                PipewireObject::is_input(port)
+            } else {
+                PipewireObject::is_output(port)
+            }
+        })
+        .count();
+    let channels = recording::channels(num_ports);
+    let mut properties = capture_stream_properties("Recording", target);
+    properties.insert(*pipewire::keys::MEDIA_ROLE, "Production");
+    let mut stream = Stream::<()>::new(core, "recording", properties)
+        .map_err(|e| PwError::CreateObject(format!("recording stream ({})", e)))?;
+    let writer: Rc<RefCell<Option<WavWriter>>> = Default::default();
+    let level: Rc<Cell<f32>> = Default::default();
+    let error: Rc<RefCell<Option<String>>> = Default::default();
+    let (writer_, level_, error_1, error_2) =
+        (writer.clone(), level.clone(), error.clone(), error.clone());
+    let listener = stream
+        .add_local_listener()
+        .state_changed(move |_old, new| {
+            if let StreamState::Error(e) = new {
+                *error_1.borrow_mut() = Some(e);
+            }
+        })
+        .process(move |stream, _| {
+            let mut buffer = match stream.dequeue_buffer() {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let data = &mut buffer.datas_mut()[0];
+            let offset = data.chunk().offset() as usize;
+            let size = data.chunk().size() as usize;
+            let bytes = match data
+                .data()
+                .and_then(|bytes| bytes.get(offset..offset + size))
+            {
+                Some(bytes) => bytes,
+                None => return,
+            };
+            let samples = bytes
+                .chunks_exact(4)
+                .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]));
+            level_.set(level_.get().max(path_test::peak(samples)));
+            let mut writer = writer_.borrow_mut();
+            if let Some(Err(e)) = writer.as_mut().map(|writer| writer.write(bytes)) {
+                // stop writing but keep what was written so far
+                *error_2.borrow_mut() = Some(e.to_string());
+                if let Some(writer) = writer.take() {
+                    writer.finish().ok();
+                }
+            }
+        })
+        .register()
+        .map_err(|e| PwError::CreateObject(format!("recording stream ({})", e)))?;
+    let format = raw_f32_format_pod(Some((recording::RATE as i32, channels as i32)));
+    connect_own_stream(&stream, Direction::Input, &format)?;
+    // only once the stream exists so that a failure doesn't leave an empty file behind
+    let file = WavWriter::create(path, channels)
+        .map_err(|e| PwError::CreateObject(format!("{} ({})", path.display(), e)))?;
+    *writer.borrow_mut() = Some(file);
+    Ok(Recording {
+        _stream: OwnedStream {
+            _listener: listener,
+            _stream: stream,
+        },
+        writer,
+        level,
+        error,
+    })
+}
+
+fn stop_recording(recording: &RefCell<Option<Recording>>, state: &mut PipewireState) {
+    let recording = match recording.take() {
+        Some(recording) => recording,
+        None => return,
+    };
+    if let Some(report) = &mut state.recording {
+        recording.update(report);
+        report.running = false;
+        if let Err(e) = recording.finish() {
+            report.error = Some(e);
+        }
+    }
+}
+
+//
+// tone generator (a playback stream left unlinked so that it can be linked anywhere in the graph)
+//
+
+struct ToneStream {
+    _stream: OwnedStream<ToneGenerator>,
+    settings: Rc<Cell<ToneSettings>>,
+    stopped: Rc<Cell<bool>>,
+}
+
+fn create_tone_generator(
+    core: &pipewire::Core,
+    name: &str,
+    settings: ToneSettings,
+) -> Result<ToneStream, PwError> {
+    let properties = {
+        use pipewire::keys::*;
+        pipewire::properties! {
+            *MEDIA_TYPE => "Audio",
+            *MEDIA_CATEGORY => "Playback",
+            *MEDIA_ROLE => "Test",
+            *NODE_NAME => name,
+            *NODE_DESCRIPTION => "Tone generator"
+        }
+    };
+    let mut stream = Stream::<ToneGenerator>::new(core, name, properties)
+        .map_err(|e| PwError::CreateObject(format!("tone generator stream ({})", e)))?;
+    let settings = Rc::new(Cell::new(settings));
+    let stopped: Rc<Cell<bool>> = Default::default();
+    let settings_ = settings.clone();
+    let stopped_ = stopped.clone();
+    let listener = stream
+        .add_local_listener()
+        .state_changed(move |_old, new| {
+            if matches!(new, StreamState::Error(_) | StreamState::Unconnected) {
+                stopped_.set(true);
+            }
+        })
+        .process(move |stream, generator| {
+            let mut buffer = match stream.dequeue_buffer() {
+                Some(buffer) => buffer,
+                None => return,
+            };
+            let settings = settings_.get();
+            let data = &mut buffer.datas_mut()[0];
+            let size = match data.data() {
+                Some(bytes) => {
+                    for frame in bytes.chunks_exact_mut(4) {
+                        frame.copy_from_slice(&generator.next_sample(&settings).to_le_bytes());
+                    }
+                    bytes.len() / 4 * 4
+                }
+                None => return,
+            };
+            let chunk = data.chunk_mut();
+            *chunk.offset_mut() = 0;
+            *chunk.stride_mut() = 4;
+            *chunk.size_mut() = size as u32;
+        })
+        .register()
+        .map_err(|e| PwError::CreateObject(format!("tone generator stream ({})", e)))?;
+    let format = raw_f32_format_pod(Some((tone::RATE as i32, 1)));
+    stream
+        .connect(
+            Direction::Output,
+            None,
+            // no AUTOCONNECT (linked by the user)
+            StreamFlags::MAP_BUFFERS,
+            &mut [format.as_ptr() as *const spa_pod],
+        )
+        .map_err(|e| PwError::CreateObject(format!("tone generator stream ({})", e)))?;
+    Ok(ToneStream {
+        _stream: OwnedStream {
+            _listener: listener,
+            _stream: stream,
+        },
+        settings,
+        stopped,
+    })
+}
+
+// keep the proxy of an object created by this app and record its global once bound
+fn track_created(
+    proxy: Proxy,
+    created: &RefCell<HashMap<u32, (Proxy, ProxyListener)>>,
+    state: Arc<Mutex<PipewireState>>,
+) -> u32 {
+    let proxy_id = proxy.id();
+    let listener = proxy
+        .add_listener_local()
+        .bound(move |global_id| {
+            let mut state = metrics::lock(&state);
+            let serial = state
+                .global_objects
+                .get(&global_id)
+                .and_then(PipewireObject::get_serial)
+                .map(str::to_owned);
+            state
+                .created_objects
+                .insert(global_id, CreatedObject { proxy_id, serial });
+        })
+        .register();
+    created.borrow_mut().insert(proxy_id, (proxy, listener));
+    proxy_id
+}
+
+// bind a client on first use and keep its permissions in `PipewireState::client_permissions`
+fn bind_client<'a>(
+    registry: &pipewire::registry::Registry,
+    clients: &'a mut HashMap<u32, (Client, ClientListener)>,
+    state: &PipewireState,
+    state_arc: &Arc<Mutex<PipewireState>>,
+    client_id: u32,
+) -> Result<&'a Client, PwError> {
+    let entry = match clients.entry(client_id) {
+        Entry::Occupied(entry) => return Ok(&entry.into_mut().0),
+        Entry::Vacant(entry) => entry,
+    };
+    let object = state
+        .global_objects
+        .get(&client_id)
+        .ok_or_else(|| PwError::ObjectNotFound(format!("Client {}", client_id)))?;
+    let client = registry
+        .bind::<Client, _>(object)
+        .map_err(|e| PwError::Bind(client_id, e.to_string()))?;
+    let state_arc = state_arc.clone();
+    let listener = client
+        .add_listener_local()
+        .permissions(move |index, permissions| {
+            let mut state = metrics::lock(&state_arc);
+            let entries = state.client_permissions.entry(client_id).or_default();
+            entries.truncate(index as usize);
+            entries.extend_from_slice(permissions);
+        })
+        .register();
+    client.get_permissions(0, u32::MAX);
+    Ok(&entry.insert((client, listener)).0)
+}
+
+fn add_module_listener(
+    module_id: u32,
+    module: &Module,
+    state: Arc<Mutex<PipewireState>>,
+) -> ModuleListener {
+    module
+        .add_listener_local()
+        .info(move |info| {
+            let args = info.args().unwrap_or("").to_owned();
+            metrics::lock(&state).module_args.insert(module_id, args);
+        })
+        .register()
+}
+
+fn add_link_listener(link_id: u32, link: &Link, state: Arc<Mutex<PipewireState>>) -> LinkListener {
+    link.add_listener_local()
+        .info(move |info| {
+            if let Some(props) = info.props() {
+                let flags = LinkFlags::from_props(props);
+                metrics::lock(&state).link_flags.insert(link_id, flags);
+            }
+        })
+        .register()
+}
+
+fn destroy_global(registry: &pipewire::registry::Registry, object_id: u32) -> Result<(), PwError> {
+    registry
+        .destroy_global(object_id)
+        .into_result()
+        .map(|_| ())
+        .map_err(|e| PwError::DestroyGlobal(object_id, e.to_string()))
+}
+
+// the id might belong to another global by now (ids are reused once a global is removed)
+fn destroy_global_serial(
+    registry: &pipewire::registry::Registry,
+    state: &PipewireState,
+    object_id: u32,
+    serial: Option<String>,
+) -> Result<(), PwError> {
+    let serial = serial.as_deref();
+    let current = state
+        .global_objects
+        .get(&object_id)
+        .map(PipewireObject::get_serial);
+    match current {
+        Some(current) if current == serial => destroy_global(registry, object_id),
+        Some(current) => Err(PwError::DestroyGlobal(
+            object_id,
+            format!(
+                "id reused (object.serial {} instead of {})",
+                current.unwrap_or("--"),
+                serial.unwrap_or("--")
+            ),
+        )),
+        None => Err(PwError::DestroyGlobal(
+            object_id,
+            "already removed".to_owned(),
+        )),
+    }
+}
+
+//
+// "default" metadata
+//
+
+fn add_metadata_listener(
+    metadata: &Metadata,
+    state: Arc<Mutex<PipewireState>>,
+) -> MetadataListener {
+    metadata
+        .add_listener_local()
+        .property(move |subject, key, _type, value| {
+            let mut state = metrics::lock(&state);
+            match (key, value) {
+                // cleared entirely (`PW_ID_ANY`) or per subject
+                (None, _) if subject == u32::MAX => state.metadata.clear(),
+                (None, _) => {
+                    state.metadata.remove(&subject);
+                }
+                (Some(key), None) => {
+                    if let Some(properties) = state.metadata.get_mut(&subject) {
+                        properties.remove(key);
+                    }
+                }
+                (Some(key), Some(value)) => {
+                    state
+                        .metadata
+                        .entry(subject)
+                        .or_default()
+                        .insert(key.to_owned(), value.to_owned());
+                }
+            }
+            0
+        })
+        .register()
+}
+
+// same as `pactl move-sink-input` (the session manager moves the stream and remembers it)
+fn set_stream_target(
+    metadata: &Metadata,
+    state: &PipewireState,
+    stream_id: u32,
+    target_id: Option<u32>,
+) -> Result<(), PwError> {
+    let target_serial = match target_id {
+        Some(target_id) => {
+            let target = state.global_objects.get(&target_id).ok_or_else(|| {
+                PwError::ObjectNotFound(format!("StreamSetTarget {} -> {}", stream_id, target_id))
+            })?;
+            Some(
+                PipewireObject::get_serial(target)
+                    .map_or_else(|| target_id.to_string(), str::to_owned),
+            )
+        }
+        None => None,
+    };
+    metadata.set_property(
+        stream_id,
+        "target.object",
+        Some("Spa:Id"),
+        target_serial.as_deref(),
+    );
+    // deprecated key would take precedence on some session managers
+    metadata.set_property(stream_id, "target.node", None, None);
+    Ok(())
+}
+
+//
+// device profiles and routes
+//
+
+#[allow(non_upper_case_globals)] // bindgen constants in patterns
+fn add_device_listener(
+    device_id: u32,
+    device: &Rc<Device>,
+    state: Arc<Mutex<PipewireState>>,
+) -> DeviceListener {
+    let device_weak = Rc::downgrade(device);
+    device
+        .add_listener_local()
+        .info(move |info| {
+            // (re-)enumerate when params are changed (e.g. profile switched or cable plugged)
+            if info.change_mask().contains(DeviceChangeMask::PARAMS) {
+                if let Some(device) = device_weak.upgrade() {
+                    for id in [
+                        SPA_PARAM_EnumProfile,
+                        SPA_PARAM_Profile,
+                        SPA_PARAM_EnumRoute,
+                        SPA_PARAM_Route,
+                    ] {
+                        device.enum_params(0, id, 0, u32::MAX);
+                    }
+                }
+            }
+        })
+        .param(move |_seq, id, index, _next, pod| {
+            let properties = match PodDeserializer::deserialize_any_from(pod) {
+                Ok((_, Value::Object(Object { properties, .. }))) => properties,
+                _ => return,
+            };
+            // enumeration restarts from index 0
+            let index = index as usize;
+            let mut state = metrics::lock(&state);
+            match id {
+                SPA_PARAM_EnumProfile | SPA_PARAM_Profile => {
+                    let profile = parse_profile(properties);
+                    let profiles = state.device_profiles.entry(device_id).or_default();
+                    if id == SPA_PARAM_EnumProfile {
+                        profiles.profiles.truncate(index);
+                        profiles.profiles.push(profile);
+                    } else {
+                        profiles.active = Some(profile.index);
+                    }
+                }
+                SPA_PARAM_EnumRoute | SPA_PARAM_Route => {
+                    let route = parse_route(properties);
+                    let routes = state.device_routes.entry(device_id).or_default();
+                    let list = if id == SPA_PARAM_EnumRoute {
+                        &mut routes.routes
+                    } else {
+                        &mut routes.active
+                    };
+                    list.truncate(index);
+                    list.push(route);
+                }
+                _ => {}
+            }
+        })
+        .register()
+}
+
+#[allow(non_upper_case_globals)]
+fn parse_profile(properties: Vec<Property>) -> DeviceProfile {
+    let mut profile = DeviceProfile {
+        index: -1,
+        name: String::new(),
+        description: String::new(),
+        available: true,
+    };
+    for property in properties {
+        match (property.key, property.value) {
+            (SPA_PARAM_PROFILE_index, Value::Int(index)) => profile.index = index,
+            (SPA_PARAM_PROFILE_name, Value::String(name)) => profile.name = name,
+            (SPA_PARAM_PROFILE_description, Value::String(description)) => {
+                profile.description = description
+            }
+            (SPA_PARAM_PROFILE_available, Value::Id(id)) => {
+                profile.available = id.0 != SPA_PARAM_AVAILABILITY_no
+            }
+            _ => {}
+        }
+    }
+    profile
+}
+
+#[allow(non_upper_case_globals)]
+fn parse_route(properties: Vec<Property>) -> DeviceRoute {
+    let mut route = DeviceRoute {
+        index: -1,
+        direction: Direction::Output,
+        name: String::new(),
+        description: String::new(),
+        available: true,
+        devices: vec![],
+        device: -1,
+        channel_volumes: vec![],
+        mute: false,
+    };
+    for property in properties {
+        match (property.key, property.value) {
+            (SPA_PARAM_ROUTE_index, Value::Int(index)) => route.index = index,
+            (SPA_PARAM_ROUTE_direction, Value::Id(id)) => {
+                route.direction = if id.0 == 0 {
+                    Direction::Input
+                } else {
+                    Direction::Output
+                }
+            }
+            (SPA_PARAM_ROUTE_name, Value::String(name)) => route.name = name,
+            (SPA_PARAM_ROUTE_description, Value::String(description)) => {
+                route.description = description
+            }
+            (SPA_PARAM_ROUTE_available, Value::Id(id)) => {
+                route.available = id.0 != SPA_PARAM_AVAILABILITY_no
+            }
+            (SPA_PARAM_ROUTE_devices, Value::ValueArray(ValueArray::Int(devices))) => {
+                route.devices = devices
+            }
+            (SPA_PARAM_ROUTE_device, Value::Int(device)) => route.device = device,
+            (SPA_PARAM_ROUTE_props, Value::Object(Object { properties, .. })) => {
+                for property in properties {
+                    match (property.key, property.value) {
+                        (SPA_PROP_channelVolumes, Value::ValueArray(ValueArray::Float(v))) => {
+                            route.channel_volumes = v
+                        }
+                        (SPA_PROP_mute, Value::Bool(mute)) => route.mute = mute,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    route
+}
+
+fn property(key: u32, value: Value) -> Property {
+    Property {
+        key,
+        flags: PropertyFlags::empty(),
+        value,
+    }
+}
+
+fn serialize_pod(value: &Value) -> Vec<u8> {
+    PodSerializer::serialize(Cursor::new(Vec::new()), value)
+        .unwrap()
+        .0
+        .into_inner()
+}
+
+// key/value pairs of `Struct( (String : key, Pod : value)* )`
+fn props_params_pod(props: &[(String, String)]) -> Vec<u8> {
+    let params = props
+        .iter()
+        .flat_map(|(key, value)| [Value::String(key.clone()), Value::String(value.clone())])
+        .collect();
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_Props,
+        id: SPA_PARAM_Props,
+        properties: vec![property(SPA_PROP_params, Value::Struct(params))],
+    }))
+}
+
+fn profile_pod(index: i32) -> Vec<u8> {
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_ParamProfile,
+        id: SPA_PARAM_Profile,
+        properties: vec![property(SPA_PARAM_PROFILE_index, Value::Int(index))],
+    }))
+}
+
+fn route_pod(index: i32, device: i32, channel_volumes: Option<Vec<f32>>) -> Vec<u8> {
+    let mut properties = vec![
+        property(SPA_PARAM_ROUTE_index, Value::Int(index)),
+        property(SPA_PARAM_ROUTE_device, Value::Int(device)),
+    ];
+    if let Some(channel_volumes) = channel_volumes {
+        properties.push(property(
+            SPA_PARAM_ROUTE_props,
+            Value::Object(Object {
+                type_: SPA_TYPE_OBJECT_Props,
+                id: SPA_PARAM_Route,
+                properties: vec![property(
+                    SPA_PROP_channelVolumes,
+                    Value::ValueArray(ValueArray::Float(channel_volumes)),
+                )],
+            }),
+        ));
+    }
+    // let session manager remember the choice
+    properties.push(property(SPA_PARAM_ROUTE_save, Value::Bool(true)));
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_ParamRoute,
+        id: SPA_PARAM_Route,
+        properties,
+    }))
+}
+
+//
+// port latencies and formats
+//
+
+#[allow(non_upper_case_globals)]
+fn add_port_listener(
+    port_id: u32,
+    port: &Rc<Port>,
+    state: Arc<Mutex<PipewireState>>,
+) -> PortListener {
+    let port_weak = Rc::downgrade(port);
+    port.add_listener_local()
+        .info(move |info| {
+            // (re-)enumerate when latency is updated e.g. after relinking
+            if info.change_mask().contains(PortChangeMask::PARAMS) {
+                if let Some(port) = port_weak.upgrade() {
+                    port.enum_params(0, SPA_PARAM_Latency, 0, u32::MAX);
+                    port.enum_params(0, SPA_PARAM_Format, 0, u32::MAX);
+                    port.enum_params(0, SPA_PARAM_EnumFormat, 0, u32::MAX);
+                }
+            }
+        })
+        .param(move |_seq, id, index, _next, pod| {
+            let properties = match PodDeserializer::deserialize_any_from(pod) {
+                Ok((_, Value::Object(Object { properties, .. }))) => properties,
+                _ => return,
+            };
+            let mut state = metrics::lock(&state);
+            match id {
+                SPA_PARAM_Latency => {
+                    let latencies = state.port_latencies.entry(port_id).or_default();
+                    latencies.truncate(index as usize);
+                    latencies.push(parse_latency(properties));
+                }
+                SPA_PARAM_Format | SPA_PARAM_EnumFormat if is_video_format(&properties) => {
+                    let format = parse_video_format(properties);
+                    let formats = state.port_video_formats.entry(port_id).or_default();
+                    if id == SPA_PARAM_EnumFormat {
+                        formats.offered.truncate(index as usize);
+                        formats.offered.push(format);
+                    } else {
+                        formats.negotiated = Some(format);
+                    }
+                }
+                SPA_PARAM_Format => {
+                    state.port_formats.insert(port_id, parse_format(properties));
+                }
+                _ => {}
+            }
+        })
+        .register()
+}
+
+#[allow(non_upper_case_globals)]
+fn parse_format(properties: Vec<Property>) -> AudioFormat {
+    let mut format = AudioFormat {
+        format: 0,
+        rate: 0,
+        channels: 0,
+    };
+    for property in properties {
+        match (property.key, property.value) {
+            (SPA_FORMAT_AUDIO_format, Value::Id(id)) => format.format = id.0,
+            (SPA_FORMAT_AUDIO_rate, Value::Int(rate)) => format.rate = rate,
+            (SPA_FORMAT_AUDIO_channels, Value::Int(channels)) => format.channels = channels,
+            _ => {}
+        }
+    }
+    format
+}
+
+#[allow(non_upper_case_globals)]
+fn is_video_format(properties: &[Property]) -> bool {
+    properties.iter().any(|property| {
+        property.key == SPA_FORMAT_mediaType
+            && property.value == Value::Id(Id(SPA_MEDIA_TYPE_video))
+    })
+}
+
+#[allow(non_upper_case_globals)]
+fn parse_video_format(properties: Vec<Property>) -> VideoFormat {
+    let mut format = VideoFormat::default();
+    for property in properties {
+        let (size, rate) = match (property.key, property.value) {
+            (SPA_FORMAT_VIDEO_size, Value::Rectangle(size)) => (Some(size), None),
+            (SPA_FORMAT_VIDEO_size, Value::Choice(ChoiceValue::Rectangle(Choice(_, size)))) => {
+                (Some(choice_default(size)), None)
+            }
+            (SPA_FORMAT_VIDEO_framerate, Value::Fraction(rate)) => (None, Some(rate)),
+            (SPA_FORMAT_VIDEO_framerate, Value::Choice(ChoiceValue::Fraction(Choice(_, rate)))) => {
+                (None, Some(choice_default(rate)))
+            }
+            _ => continue,
+        };
+        if let Some(size) = size {
+            format.width = size.width;
+            format.height = size.height;
+        }
+        if let Some(rate) = rate {
+            format.framerate = (rate.num, rate.denom);
+        }
+    }
+    format
+}
+
+// preferred value of a choice (e.g. the default camera mode)
+fn choice_default<T: CanonicalFixedSizedPod>(choice: ChoiceEnum<T>) -> T {
+    match choice {
+        ChoiceEnum::None(value)
+        | ChoiceEnum::Range { default: value, .. }
+        | ChoiceEnum::Step { default: value, .. }
+        | ChoiceEnum::Enum { default: value, .. }
+        | ChoiceEnum::Flags { default: value, .. } => value,
+    }
+}
+
+// switch the adapter to "convert" mode so that its ports use the given format instead of dsp
+// SPA_PARAM_EnumFormat of a stream with optional (rate, channels)
+fn raw_f32_format_pod(rate_channels: Option<(i32, i32)>) -> Vec<u8> {
+    let mut properties = vec![
+        property(SPA_FORMAT_mediaType, Value::Id(Id(SPA_MEDIA_TYPE_audio))),
+        property(
+            SPA_FORMAT_mediaSubtype,
+            Value::Id(Id(SPA_MEDIA_SUBTYPE_raw)),
+        ),
+        property(
+            SPA_FORMAT_AUDIO_format,
+            Value::Id(Id(SPA_AUDIO_FORMAT_F32_LE)),
+        ),
+    ];
+    if let Some((rate, channels)) = rate_channels {
+        properties.push(property(SPA_FORMAT_AUDIO_rate, Value::Int(rate)));
+        properties.push(property(SPA_FORMAT_AUDIO_channels, Value::Int(channels)));
+    }
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_Format,
+        id: SPA_PARAM_EnumFormat,
+        properties,
+    }))
+}
+
+fn port_config_pod(direction: Direction, format: AudioFormat) -> Vec<u8> {
+    let format = Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_Format,
+        id: SPA_PARAM_Format,
+        properties: vec![
+            property(SPA_FORMAT_mediaType, Value::Id(Id(SPA_MEDIA_TYPE_audio))),
+            property(
+                SPA_FORMAT_mediaSubtype,
+                Value::Id(Id(SPA_MEDIA_SUBTYPE_raw)),
+            ),
+            property(SPA_FORMAT_AUDIO_format, Value::Id(Id(format.format))),
+            property(SPA_FORMAT_AUDIO_rate, Value::Int(format.rate)),
+            property(SPA_FORMAT_AUDIO_channels, Value::Int(format.channels)),
+        ],
+    });
+    let direction = match direction {
+        Direction::Input => 0,
+        Direction::Output => 1,
+    };
+    serialize_pod(&Value::Object(Object {
+        type_: SPA_TYPE_OBJECT_ParamPortConfig,
+        id: SPA_PARAM_PortConfig,
+        properties: vec![
+            property(SPA_PARAM_PORT_CONFIG_direction, Value::Id(Id(direction))),
+            property(
+                SPA_PARAM_PORT_CONFIG_mode,
+                Value::Id(Id(SPA_PARAM_PORT_CONFIG_MODE_convert)),
+            ),
+            property(SPA_PARAM_PORT_CONFIG_format, format),
+        ],
+    }))
+}
+
+#[allow(non_upper_case_globals)]
+fn parse_latency(properties: Vec<Property>) -> PortLatency {
+    let mut latency = PortLatency {
+        direction: Direction::Input,
+        min_quantum: 0.0,
+        max_quantum: 0.0,
+        min_rate: 0,
+        max_rate: 0,
+        min_ns: 0,
+        max_ns: 0,
+    };
+    for property in properties {
+        match (property.key, property.value) {
+            (SPA_PARAM_LATENCY_direction, Value::Id(id)) => {
+                latency.direction = if id.0 == 0 {
+                    Direction::Input
+                } else {
+                    Direction::Output
+                }
+            }
+            (SPA_PARAM_LATENCY_minQuantum, Value::Float(v)) => latency.min_quantum = v,
+            (SPA_PARAM_LATENCY_maxQuantum, Value::Float(v)) => latency.max_quantum = v,
+            (SPA_PARAM_LATENCY_minRate, Value::Int(v)) => latency.min_rate = v,
+            (SPA_PARAM_LATENCY_maxRate, Value::Int(v)) => latency.max_rate = v,
+            (SPA_PARAM_LATENCY_minNs, Value::Long(v)) => latency.min_ns = v,
+            (SPA_PARAM_LATENCY_maxNs, Value::Long(v)) => latency.max_ns = v,
+            _ => {}
+        }
+    }
+    latency
+}
+
+//
+// object utilities
+//
+
+pub struct PipewireObject {}
+
+impl PipewireObject {
+    pub fn get_name(object: &GlobalObject<PropStore>) -> Option<(&str, &str)> {
+        [
+            PropKey::ClientName,
+            PropKey::CoreName,
+            PropKey::DeviceName,
+            PropKey::FactoryName,
+            PropKey::NodeName,
+            PropKey::ModuleName,
+            PropKey::AppName,
+            PropKey::MetadataName,
+            PropKey::ObjectPath,
+            PropKey::PortAlias,
+        ]
+        .iter()
+        .flat_map(|&k| {
+            object
+                .props
+                .as_ref()
+                .map(|prop| prop.get_key(k).map(|v| (k.as_str(), v)))
+                .flatten()
+        })
+        .next()
+        // e.g. interface types added after this list was written
+        .or_else(|| {
+            object
+                .props
+                .as_ref()?
+                .iter()
+                .find(|(k, _v)| k.ends_with(".name"))
+        })
+    }
+
+    pub fn is_input(object: &GlobalObject<PropStore>) -> bool {
+        object.type_ == ObjectType::Port
+            && object
+                .props
+                .as_ref()
+                .map(|prop| prop.get_key(PropKey::PortDirection))
+                .flatten()
+                == Some("in")
+    }
+
+    // (key, value) pair to look up an object by its global id
+    pub fn id_key(id: u32) -> (String, String) {
+        (pipewire::keys::OBJECT_ID.to_string(), id.to_string())
+    }
+
+    // write permission is required for modifying an object (e.g. params, destroy)
+    pub fn is_writable(object: &GlobalObject<PropStore>) -> bool {
+        object.permissions.contains(Permission::W)
+    }
+
+    // registry destroy is checked against W and X on the server
+    pub fn is_destroyable(object: &GlobalObject<PropStore>) -> bool {
+        object.permissions.contains(Permission::W | Permission::X)
+    }
+
+    // short type name for display (e.g. "Node" for "PipeWire:Interface:Node").
+    // interface types unknown to pipewire-rs (`ObjectType::Other`) keep their raw type string
+    // so that they are still listed instead of being dropped.
+    pub fn get_type_name(object: &GlobalObject<PropStore>) -> &str {
+        let type_str = object.type_.to_str();
+        match &object.type_ {
+            ObjectType::Other(_) => type_str,
+            _ => type_str
+                .strip_prefix("PipeWire:Interface:")
+                .unwrap_or(type_str),
+        }
+    }
+
+    // objects created by this process (e.g. our own playback streams)
+    pub fn is_own(object: &GlobalObject<PropStore>) -> bool {
+        object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::AppProcessId))
+            .and_then(|pid| pid.parse::<u32>().ok())
+            == Some(std::process::id())
+    }
+
+    // pid (set by the server on the client) and binary name of the client owning a node
+    pub fn get_process(
+        state: &PipewireState,
+        object: &GlobalObject<PropStore>,
+    ) -> Option<(u32, Option<String>)> {
+        let props = object.props.as_ref()?;
+        let client_id: u32 = props.get_key(PropKey::ClientId)?.parse().ok()?;
+        let client = state.global_objects.get(&client_id)?;
+        let pid: u32 = client
+            .props
+            .as_ref()?
+            .get_key(PropKey::SecPid)?
+            .parse()
+            .ok()?;
+        if pid <= 1 {
+            return None;
+        }
+        let binary = props.get_key(PropKey::AppProcessBinary).map(str::to_owned);
+        Some((pid, binary))
+    }
+
+    // application owning a node (`client.id` as fallback e.g. for session manager nodes)
+    pub fn get_client_label(object: &GlobalObject<PropStore>) -> Option<&str> {
+        let props = object.props.as_ref()?;
+        props
+            .get_key(PropKey::AppName)
+            .or_else(|| props.get_key(PropKey::ClientId))
+    }
+
+    pub fn get_node_label(object: &GlobalObject<PropStore>) -> Option<&str> {
+        let props = object.props.as_ref()?;
+        [
+            PropKey::NodeDescription,
+            PropKey::NodeNick,
+            PropKey::NodeName,
+        ]
+        .iter()
+        .flat_map(|&k| props.get_key(k))
+        .next()
+    }
+
+    pub fn get_port_name(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::PortName)
+    }
+
+    pub fn get_channel(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::AudioChannel)
+    }
+
+    pub fn get_format_dsp(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::FormatDsp)
+    }
+
+    // unlike the global id, never reused by the server
+    pub fn get_serial(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::ObjectSerial)
+    }
+
+    pub fn get_media_class(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::MediaClass)
+    }
+
+    // device which a node belongs to (e.g. alsa card)
+    pub fn get_device_id(object: &GlobalObject<PropStore>) -> Option<u32> {
+        object
+            .props
+            .as_ref()?
+            .get_key(PropKey::DeviceId)?
+            .parse()
+            .ok()
+    }
+
+    // requested `node.latency` e.g. "256/48000" as (quantum, rate)
+    pub fn get_node_latency(object: &GlobalObject<PropStore>) -> Option<(u32, u32)> {
+        let latency = object.props.as_ref()?.get_key(PropKey::NodeLatency)?;
+        let (quantum, rate) = latency.split_once('/')?;
+        Some((quantum.parse().ok()?, rate.parse().ok()?))
+    }
+
+    // e.g. a sound card jack (rather than a port of a stream or a filter)
+    pub fn is_physical(object: &GlobalObject<PropStore>) -> bool {
+        object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::PortPhysical))
+            == Some("true")
+    }
+
+    // data enters or leaves the graph here (sources and sinks)
+    pub fn is_terminal(object: &GlobalObject<PropStore>) -> bool {
+        object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::PortTerminal))
+            == Some("true")
+    }
+
+    // parameter automation (e.g. "8 bit raw control" of filter-chain) rather than media
+    pub fn is_control(object: &GlobalObject<PropStore>) -> bool {
+        let props = match object.props.as_ref() {
+            Some(props) => props,
+            None => return false,
+        };
+        props.get_key(PropKey::PortControl) == Some("true")
+            || props
+                .get_key(PropKey::FormatDsp)
+                .map_or(false, |format| format.ends_with("control"))
+    }
+
+    // no dsp format, so the format negotiated by the nodes is passed through as is
+    // (e.g. IEC958 or video) and it doesn't mix with dsp ports
+    pub fn is_passthrough(object: &GlobalObject<PropStore>) -> bool {
+        object.type_ == ObjectType::Port && PipewireObject::get_format_dsp(object).is_none()
+    }
+
+    pub fn is_monitor(object: &GlobalObject<PropStore>) -> bool {
+        object
+            .props
+            .as_ref()
+            .and_then(|props| props.get_key(PropKey::PortMonitor))
+            == Some("true")
+    }
+
+    // device index within the current card profile (used to match device routes)
+    pub fn get_card_profile_device(object: &GlobalObject<PropStore>) -> Option<i32> {
+        object
+            .props
+            .as_ref()?
+            .get_key(PropKey::CardProfileDevice)?
+            .parse()
+            .ok()
+    }
+
+    // codec of a bluetooth node (e.g. "aac", or "msbc" once a headset fell back to HSP/HFP)
+    pub fn get_bluez5_codec(object: &GlobalObject<PropStore>) -> Option<&str> {
+        object.props.as_ref()?.get_key(PropKey::Bluez5Codec)
+    }
+
+    pub fn get_parent_node_id(object: &GlobalObject<PropStore>) -> Option<u32> {
+        if object.type_ != ObjectType::Port {
+            return None;
+        }
+        object
+            .props
+            .as_ref()?
+            .get_key(PropKey::NodeId)?
+            .parse()
+            .ok()
+    }
+
+    // (output port, input port)
+    pub fn get_link_ports(object: &GlobalObject<PropStore>) -> Option<(u32, u32)> {
+        if object.type_ != ObjectType::Link {
+            return None;
+        }
+        let props = object.props.as_ref()?;
+        let output_port = props.get_key(PropKey::LinkOutputPort)?.parse().ok()?;
+        let input_port = props.get_key(PropKey::LinkInputPort)?.parse().ok()?;
+        Some((output_port, input_port))
+    }
+
+    // sort key to keep ports of the same channel next to each other in the usual speaker order
+    // (unknown channels e.g. "AUX0" come after the well-known ones)
+    pub fn channel_sort_key(channel: Option<&str>) -> (usize, String) {
+        const CHANNELS: [&str; 20] = [
+            "MONO", "FL", "FR", "FC", "LFE", "SL", "SR", "FLC", "FRC", "RC", "RL", "RR", "TC",
+            "TFL", "TFC", "TFR", "TRL", "TRC", "TRR", "UNK",
+        ];
+        match channel {
+            Some(channel) => (
+                CHANNELS
+                    .iter()
+                    .position(|&c| c == channel)
+                    .unwrap_or(CHANNELS.len()),
+                channel.to_owned(),
+            ),
+            None => (CHANNELS.len() + 1, String::new()),
+        }
+    }
+
+    pub fn is_output(object: &GlobalObject<PropStore>) -> bool {
         object.type_ == ObjectType::Port
             && object
                 .props
                 .as_ref()
-                .map(|prop| prop.get(*PORT_DIRECTION))
+                .map(|prop| prop.get_key(PropKey::PortDirection))
                 .flatten()
                 == Some("out")
     }
@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    pipewire_wrapper::{PipewireObject, PipewireState, VirtualDevice},
+    props::PropKey,
+};
+
+//
+// one-click setups of virtual devices linked together (see the Presets window)
+//
+// the ones in ./presets are built in, and users can add theirs as
+//   ~/.config/pipewire_graph_egui/presets/*.json
+//
+
+// stands for the current default sink in `PresetLink`
+pub const DEFAULT_SINK: &str = "@DEFAULT_SINK@";
+
+const BUILTIN: [&str; 2] = [
+    include_str!("../presets/streaming_mix_bus.json"),
+    include_str!("../presets/virtual_microphone.json"),
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // skipped when a node of the same node.name exists
+    #[serde(default)]
+    pub devices: Vec<VirtualDevice>,
+    #[serde(default)]
+    pub links: Vec<PresetLink>,
+}
+
+// outputs of a node linked to the inputs of another by channel (both by node.name)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PresetLink {
+    pub from: String,
+    pub to: String,
+}
+
+impl Preset {
+    // e.g. "streaming_mix_bus (sink)\nstreaming_mix_bus → @DEFAULT_SINK@"
+    pub fn summary(&self) -> String {
+        let devices = self
+            .devices
+            .iter()
+            .map(|device| format!("{} ({})", device.name, device.kind()));
+        let links = self
+            .links
+            .iter()
+            .map(|link| format!("{} → {}", link.from, link.to));
+        devices.chain(links).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl PresetLink {
+    // (output node, input node) once both are in the registry
+    pub fn resolve(&self, state: &PipewireState) -> Option<(u32, u32)> {
+        let node = |name: &str| {
+            if name == DEFAULT_SINK {
+                return state.default_sink();
+            }
+            state
+                .find_object_by_prop(*pipewire::keys::NODE_NAME, name)
+                .map(|node| node.id)
+        };
+        Some((node(&self.from)?, node(&self.to)?))
+    }
+}
+
+// whether the node has a port per channel on that side (the ports of a new node can show up
+// over several registry updates)
+pub fn has_all_ports(state: &PipewireState, node_id: u32, is_output: bool) -> bool {
+    let channels = state
+        .global_objects
+        .get(&node_id)
+        .and_then(|node| {
+            let props = node.props.as_ref()?;
+            match props.get_key(PropKey::AudioChannels) {
+                Some(channels) => channels.parse().ok(),
+                None => Some(props.get_key(PropKey::AudioPosition)?.split(',').count()),
+            }
+        })
+        // nothing to wait for
+        .unwrap_or(0);
+    let ports = state
+        .get_ports(node_id)
+        .filter(|port| PipewireObject::is_output(port) == is_output)
+        .count();
+    ports >= channels
+}
+
+// $XDG_CONFIG_HOME/pipewire_graph_egui/presets
+pub fn user_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+    Some(config.join(env!("CARGO_PKG_NAME")).join("presets"))
+}
+
+// built-in presets followed by the user's ones, and the errors of invalid files
+pub fn load() -> (Vec<Preset>, Vec<String>) {
+    let mut presets: Vec<Preset> = BUILTIN
+        .iter()
+        .map(|json| serde_json::from_str(json).expect("invalid built-in preset"))
+        .collect();
+    let mut errors = vec![];
+    let dir = match user_dir() {
+        Some(dir) if dir.is_dir() => dir,
+        _ => return (presets, errors),
+    };
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .collect(),
+        Err(e) => {
+            errors.push(format!("{} ({})", dir.display(), e));
+            return (presets, errors);
+        }
+    };
+    paths.sort();
+    for path in paths {
+        match load_file(&path) {
+            Ok(preset) => presets.push(preset),
+            Err(e) => errors.push(format!("{:#}", e)),
+        }
+    }
+    (presets, errors)
+}
+
+fn load_file(path: &Path) -> Result<Preset> {
+    let json =
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("invalid preset {}", path.display()))
+}
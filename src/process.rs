@@ -0,0 +1,52 @@
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+//
+// stopping a runaway client from its stream node
+//   kill -TERM <pipewire.sec.pid>
+//
+// the pid is the one the server got from the socket (unlike `application.process.id` which
+// the client sets itself), but it's still meaningless (or someone else) when the client runs
+// in another pid namespace e.g. a flatpak app
+//
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Signal {
+    Term,
+    Kill,
+}
+
+impl Signal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Term => "SIGTERM",
+            Signal::Kill => "SIGKILL",
+        }
+    }
+}
+
+pub fn send_signal(pid: u32, signal: Signal) -> Result<()> {
+    // 0 is our own process group and 1 is init
+    if pid <= 1 {
+        bail!("refusing to signal pid {}", pid);
+    }
+    if pid == std::process::id() {
+        bail!("refusing to signal this app itself");
+    }
+    let flag = match signal {
+        Signal::Term => "-TERM",
+        Signal::Kill => "-KILL",
+    };
+    let output = Command::new("kill")
+        .arg(flag)
+        .arg(pid.to_string())
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to spawn kill")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, fmt};
+
+use pipewire::{
+    registry::GlobalObject,
+    spa::{prelude::ReadableDict, ForeignDict},
+    Properties,
+};
+
+//
+// props of registry objects with well-known keys interned at ingestion
+// (`Properties::get` allocates a CString and scans the whole dict on every lookup,
+//  which adds up when the ui queries every object each frame)
+//
+
+macro_rules! prop_keys {
+    ($($name:ident => $key:literal,)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum PropKey {
+            $($name,)*
+        }
+
+        impl PropKey {
+            pub const ALL: &'static [PropKey] = &[$(PropKey::$name,)*];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(PropKey::$name => $key,)*
+                }
+            }
+
+            pub fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $($key => Some(PropKey::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+prop_keys! {
+    ObjectId => "object.id",
+    ObjectSerial => "object.serial",
+    ObjectPath => "object.path",
+    ObjectLinger => "object.linger",
+    ClientId => "client.id",
+    ClientName => "client.name",
+    ClientApi => "client.api",
+    CoreName => "core.name",
+    ModuleName => "module.name",
+    MetadataName => "metadata.name",
+    FactoryName => "factory.name",
+    FactoryTypeName => "factory.type.name",
+    AppName => "application.name",
+    AppVersion => "application.version",
+    AppProcessId => "application.process.id",
+    AppProcessBinary => "application.process.binary",
+    SecPid => "pipewire.sec.pid",
+    DeviceId => "device.id",
+    DeviceName => "device.name",
+    DeviceDescription => "device.description",
+    NodeId => "node.id",
+    NodeName => "node.name",
+    NodeNick => "node.nick",
+    NodeDescription => "node.description",
+    NodeLatency => "node.latency",
+    NodeAutoconnect => "node.autoconnect",
+    MediaClass => "media.class",
+    MediaName => "media.name",
+    PortId => "port.id",
+    PortName => "port.name",
+    PortAlias => "port.alias",
+    PortDirection => "port.direction",
+    PortMonitor => "port.monitor",
+    PortPhysical => "port.physical",
+    PortTerminal => "port.terminal",
+    PortControl => "port.control",
+    AudioChannel => "audio.channel",
+    AudioChannels => "audio.channels",
+    AudioPosition => "audio.position",
+    FormatDsp => "format.dsp",
+    CardProfileDevice => "card.profile.device",
+    Bluez5Codec => "api.bluez5.codec",
+    LinkOutputNode => "link.output.node",
+    LinkOutputPort => "link.output.port",
+    LinkInputNode => "link.input.node",
+    LinkInputPort => "link.input.port",
+}
+
+pub struct PropStore {
+    // original dict for iteration and binding
+    dict: Properties,
+    // indexed by `PropKey as usize`
+    known: Vec<Option<String>>,
+    other: HashMap<String, String>,
+}
+
+impl PropStore {
+    pub fn new<D: ReadableDict>(dict: &D) -> Self {
+        let mut known = vec![None; PropKey::ALL.len()];
+        let mut other = HashMap::new();
+        for (key, value) in dict.iter() {
+            match PropKey::from_key(key) {
+                Some(key) => known[key as usize] = Some(value.to_owned()),
+                None => {
+                    other.insert(key.to_owned(), value.to_owned());
+                }
+            }
+        }
+        Self {
+            dict: Properties::from_dict(dict),
+            known,
+            other,
+        }
+    }
+
+    pub fn get_key(&self, key: PropKey) -> Option<&str> {
+        self.known[key as usize].as_deref()
+    }
+}
+
+impl ReadableDict for PropStore {
+    fn get_dict_ptr(&self) -> *const pipewire::spa::sys::spa_dict {
+        self.dict.get_dict_ptr()
+    }
+
+    // same result as the default implementation without touching the ffi dict
+    fn get(&self, key: &str) -> Option<&str> {
+        match PropKey::from_key(key) {
+            Some(key) => self.get_key(key),
+            None => self.other.get(key).map(String::as_str),
+        }
+    }
+}
+
+impl fmt::Debug for PropStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.dict.fmt(f)
+    }
+}
+
+// owned copy of an object announced by the registry
+pub fn intern(object: &GlobalObject<ForeignDict>) -> GlobalObject<PropStore> {
+    GlobalObject {
+        id: object.id,
+        permissions: object.permissions,
+        type_: object.type_.clone(),
+        version: object.version,
+        props: object.props.as_ref().map(PropStore::new),
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+//
+// capture of a node into a WAV file (see `ChannelMessage::RecordStart`)
+//
+
+// the capture stream converts whatever the node produces
+pub const RATE: u32 = 48000;
+const MAX_CHANNELS: usize = 32;
+// the RIFF size (data + 36 bytes of header) is a u32, e.g. about 3 hours of 48kHz stereo
+const MAX_DATA_SIZE: u32 = u32::MAX - 36;
+
+// 32 bit float WAV whose sizes are patched on `finish`
+pub struct WavWriter {
+    file: BufWriter<File>,
+    channels: u16,
+    data_size: u32,
+}
+
+impl WavWriter {
+    pub fn create(path: &Path, channels: u16) -> io::Result<Self> {
+        let mut writer = Self {
+            file: BufWriter::new(File::create(path)?),
+            channels,
+            data_size: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let block_align = self.channels as u32 * 4;
+        let file = &mut self.file;
+        file.write_all(b"RIFF")?;
+        let riff_size = self
+            .data_size
+            .checked_add(36)
+            .ok_or_else(size_limit_error)?;
+        file.write_all(&riff_size.to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&3u16.to_le_bytes())?; // WAVE_FORMAT_IEEE_FLOAT
+        file.write_all(&self.channels.to_le_bytes())?;
+        file.write_all(&RATE.to_le_bytes())?;
+        file.write_all(&(RATE * block_align).to_le_bytes())?;
+        file.write_all(&(block_align as u16).to_le_bytes())?;
+        file.write_all(&32u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&self.data_size.to_le_bytes())
+    }
+
+    // interleaved little endian f32 as delivered by the stream
+    // (fails once the file would be too big, which stops the recording)
+    pub fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let data_size = u32::try_from(bytes.len())
+            .ok()
+            .and_then(|len| self.data_size.checked_add(len))
+            .filter(|&size| size <= MAX_DATA_SIZE)
+            .ok_or_else(size_limit_error)?;
+        self.file.write_all(bytes)?;
+        self.data_size = data_size;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.data_size as u64 / (self.channels as u64 * 4)
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.file.flush()
+    }
+}
+
+fn size_limit_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "reached the 4 GiB size limit of WAV files",
+    )
+}
+
+// one per port of the node (monitor ports for sinks)
+pub fn channels(num_ports: usize) -> u16 {
+    num_ports.clamp(1, MAX_CHANNELS) as u16
+}
+
+// e.g. "<dir>/alsa_output.pci-0000_00_1f.3.analog-stereo_1700000000.wav"
+pub fn file_path(dir: &str, node_name: &str) -> PathBuf {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let name: String = node_name
+        .chars()
+        .map(|c| if c == '/' { '_' } else { c })
+        .collect();
+    Path::new(dir).join(format!("{}_{}.wav", name, time))
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordingReport {
+    pub node_id: u32,
+    pub path: PathBuf,
+    // false once stopped or failed
+    pub running: bool,
+    pub frames: u64,
+    // peak amplitude since the previous report (for a live meter)
+    pub level: f32,
+    pub error: Option<String>,
+}
+
+impl RecordingReport {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames as f64 / RATE as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "{}-{}-{}",
+            env!("CARGO_PKG_NAME"),
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn header_sizes() {
+        let path = temp_path("header.wav");
+        let mut writer = WavWriter::create(&path, 2).unwrap();
+        // 3 stereo frames
+        writer.write(&[0; 16]).unwrap();
+        writer.write(&[0; 8]).unwrap();
+        assert_eq!(writer.frames(), 3);
+        writer.finish().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bytes.len(), 44 + 24);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32_at(&bytes, 4), 36 + 24);
+        assert_eq!(u16::from_le_bytes([bytes[22], bytes[23]]), 2);
+        assert_eq!(u32_at(&bytes, 28), RATE * 8);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u32_at(&bytes, 40), 24);
+    }
+
+    #[test]
+    fn stops_at_size_limit() {
+        let path = temp_path("limit.wav");
+        let mut writer = WavWriter::create(&path, 1).unwrap();
+        // as if almost 4 GiB were already written
+        writer.data_size = MAX_DATA_SIZE - 8;
+        writer.write(&[0; 8]).unwrap();
+        assert_eq!(writer.data_size, MAX_DATA_SIZE);
+        assert!(writer.write(&[0; 4]).is_err());
+        assert_eq!(writer.data_size, MAX_DATA_SIZE);
+        drop(writer);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
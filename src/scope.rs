@@ -0,0 +1,136 @@
+use std::{
+    f32::consts::PI,
+    fmt,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+
+//
+// live waveform/spectrum of a port (see `ChannelMessage::ScopeStart`)
+//
+// the capture stream on the pipewire thread pushes samples into a `SampleRing` shared with
+// the ui, which reads the latest ones every frame without taking a lock
+//
+
+pub const RATE: u32 = 48000;
+
+// samples of the waveform (about 21ms)
+pub const WAVEFORM_SIZE: usize = 1024;
+
+pub const FFT_SIZE: usize = 4096;
+
+// floor of the spectrum
+pub const MIN_DB: f32 = -120.0;
+
+/// Single producer ring buffer of f32 samples.
+///
+/// A reader racing the writer may see a few samples of the next lap, which is fine to look at.
+pub struct SampleRing {
+    // f32 bits
+    samples: Box<[AtomicU32]>,
+    // total number of samples pushed
+    written: AtomicUsize,
+}
+
+impl SampleRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: (0..capacity.next_power_of_two())
+                .map(|_| AtomicU32::new(0))
+                .collect(),
+            written: AtomicUsize::new(0),
+        }
+    }
+
+    // only from one thread at a time
+    pub fn push(&self, samples: impl Iterator<Item = f32>) {
+        let mask = self.samples.len() - 1;
+        let mut written = self.written.load(Ordering::Relaxed);
+        for sample in samples {
+            self.samples[written & mask].store(sample.to_bits(), Ordering::Relaxed);
+            written = written.wrapping_add(1);
+        }
+        self.written.store(written, Ordering::Release);
+    }
+
+    // the last `n` samples, oldest first (zeros before anything was captured)
+    pub fn latest(&self, n: usize) -> Vec<f32> {
+        let mask = self.samples.len() - 1;
+        let n = n.min(self.samples.len());
+        let written = self.written.load(Ordering::Acquire);
+        (0..n)
+            .map(|i| {
+                let index = written.wrapping_sub(n - i) & mask;
+                f32::from_bits(self.samples[index].load(Ordering::Relaxed))
+            })
+            .collect()
+    }
+
+    pub fn written(&self) -> usize {
+        self.written.load(Ordering::Acquire)
+    }
+}
+
+// the samples would flood the logs of `ChannelMessage`
+impl fmt::Debug for SampleRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleRing")
+            .field("capacity", &self.samples.len())
+            .field("written", &self.written())
+            .finish()
+    }
+}
+
+// magnitude in dBFS of the bins up to nyquist (`samples.len()` must be a power of two)
+pub fn spectrum(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+    debug_assert!(n.is_power_of_two());
+    // hann window (its coherent gain is 0.5)
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| sample * 0.5 * (1.0 - (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+    (0..n / 2)
+        .map(|i| {
+            let magnitude = (re[i] * re[i] + im[i] * im[i]).sqrt() * 4.0 / n as f32;
+            (20.0 * magnitude.log10()).max(MIN_DB)
+        })
+        .collect()
+}
+
+// in-place iterative radix-2 fft
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
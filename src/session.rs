@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::{backend::MockEvent, channel::ChannelMessage, pipewire_wrapper::PipewireState};
+
+//
+// registry events of a live session saved in the `MockBackend` fixture format,
+// replayed offline with `--replay <path>` (e.g. to reproduce layout or ordering bugs)
+//
+
+pub struct SessionRecorder {
+    pub path: String,
+    ticks: Vec<Vec<MockEvent>>,
+}
+
+impl SessionRecorder {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            ticks: vec![],
+        }
+    }
+
+    // globals which already exist when recording starts after connecting
+    pub fn snapshot(&mut self, state: &PipewireState) {
+        let tick: Vec<_> = state
+            .global_objects
+            .keys()
+            .filter_map(|&id| MockEvent::global(state, id))
+            .collect();
+        if !tick.is_empty() {
+            self.ticks.push(tick);
+        }
+    }
+
+    // call with the messages of one pipewire tick (a `ChannelMessage::Batch` or a single one)
+    pub fn record(&mut self, state: &PipewireState, messages: &[ChannelMessage]) {
+        let tick: Vec<_> = messages
+            .iter()
+            .filter_map(|message| match *message {
+                // the global might be gone from the state already (its removal follows)
+                ChannelMessage::PipewireRegistryGlobal(id) => MockEvent::global(state, id),
+                ChannelMessage::PipewireRegistryGlobalRemove(id) => {
+                    Some(MockEvent::GlobalRemove(id))
+                }
+                ChannelMessage::PipewireNodePropsChanged(id) => MockEvent::node_props(state, id),
+                ChannelMessage::PipewireInitialSyncDone => Some(MockEvent::InitialSyncDone),
+                _ => None,
+            })
+            .collect();
+        if !tick.is_empty() {
+            self.ticks.push(tick);
+        }
+    }
+
+    pub fn num_ticks(&self) -> usize {
+        self.ticks.len()
+    }
+
+    // one event per line to keep the file diffable
+    pub fn save(&self) -> Result<()> {
+        let mut out = String::from("[\n");
+        for (i, tick) in self.ticks.iter().enumerate() {
+            out.push_str("  [\n");
+            for (j, event) in tick.iter().enumerate() {
+                let separator = if j + 1 < tick.len() { "," } else { "" };
+                writeln!(out, "    {}{}", serde_json::to_string(event)?, separator)?;
+            }
+            let separator = if i + 1 < self.ticks.len() { "," } else { "" };
+            writeln!(out, "  ]{}", separator)?;
+        }
+        out.push_str("]\n");
+        std::fs::write(&self.path, out)?;
+        Ok(())
+    }
+}
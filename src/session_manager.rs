@@ -0,0 +1,140 @@
+use std::fmt::Write;
+
+use pipewire::{registry::GlobalObject, types::ObjectType};
+
+use crate::{
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    props::{PropKey, PropStore},
+};
+
+//
+// session manager detection and its policy on stream links
+//
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    WirePlumber,
+    MediaSession,
+}
+
+impl Kind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::WirePlumber => "WirePlumber",
+            Kind::MediaSession => "pipewire-media-session",
+        }
+    }
+}
+
+pub struct SessionManager {
+    pub kind: Kind,
+    pub client_id: u32,
+    pub version: Option<String>,
+}
+
+// from the clients it connects with (e.g. "WirePlumber" and "WirePlumber [export]")
+pub fn detect(state: &PipewireState) -> Option<SessionManager> {
+    state
+        .global_objects
+        .values()
+        .filter(|object| object.type_ == ObjectType::Client)
+        .find_map(|client| {
+            let props = client.props.as_ref()?;
+            let name = props.get_key(PropKey::AppName)?;
+            let kind = if name.starts_with("WirePlumber") {
+                Kind::WirePlumber
+            } else if name.starts_with("pipewire-media-session") {
+                Kind::MediaSession
+            } else {
+                return None;
+            };
+            Some(SessionManager {
+                kind,
+                client_id: client.id,
+                version: props.get_key(PropKey::AppVersion).map(str::to_owned),
+            })
+        })
+}
+
+// (stream node, node it is linked to) when the link belongs to a stream the policy routes
+pub fn policy_stream<'a>(
+    state: &'a PipewireState,
+    output_port: u32,
+    input_port: u32,
+) -> Option<(&'a GlobalObject<PropStore>, &'a GlobalObject<PropStore>)> {
+    let node = |port_id: u32| {
+        let port = state.global_objects.get(&port_id)?;
+        state
+            .global_objects
+            .get(&PipewireObject::get_parent_node_id(port)?)
+    };
+    let (output, input) = (node(output_port)?, node(input_port)?);
+    let is_stream = |node: &GlobalObject<PropStore>, class: &str| {
+        PipewireObject::get_media_class(node)
+            .map_or(false, |media_class| media_class.starts_with(class))
+            && node
+                .props
+                .as_ref()
+                .and_then(|props| props.get_key(PropKey::NodeAutoconnect))
+                != Some("false")
+    };
+    if is_stream(output, "Stream/Output") {
+        Some((output, input))
+    } else if is_stream(input, "Stream/Input") {
+        Some((input, output))
+    } else {
+        None
+    }
+}
+
+// the session manager links such a stream to its target and may undo manual links
+// (e.g. when the default device changes or the stream reconnects)
+pub fn link_warning(state: &PipewireState, output_port: u32, input_port: u32) -> Option<String> {
+    let session_manager = detect(state)?;
+    let (stream, _) = policy_stream(state, output_port, input_port)?;
+    Some(format!(
+        "{} may re-route \"{}\" and revert this link (see the Session manager window)",
+        session_manager.kind.label(),
+        PipewireObject::get_node_label(stream).unwrap_or("--")
+    ))
+}
+
+// WirePlumber 0.5 `stream.rules` snippet setting the target of the stream
+// (for ~/.config/wireplumber/wireplumber.conf.d/)
+pub fn wireplumber_rule(
+    stream: &GlobalObject<PropStore>,
+    target: &GlobalObject<PropStore>,
+) -> Option<String> {
+    let stream_props = stream.props.as_ref()?;
+    // application.name survives restarts of the application better than node.name
+    let (match_key, match_value) = match stream_props.get_key(PropKey::AppName) {
+        Some(name) => ("application.name", name),
+        None => ("node.name", stream_props.get_key(PropKey::NodeName)?),
+    };
+    let target_name = target.props.as_ref()?.get_key(PropKey::NodeName)?;
+    let mut out = String::new();
+    writeln!(out, "# {} -> {}", match_value, target_name).unwrap();
+    writeln!(out, "stream.rules = [").unwrap();
+    writeln!(out, "  {{").unwrap();
+    writeln!(out, "    matches = [").unwrap();
+    writeln!(
+        out,
+        "      {{ {} = \"{}\" }}",
+        match_key,
+        escape(match_value)
+    )
+    .unwrap();
+    writeln!(out, "    ]").unwrap();
+    writeln!(out, "    actions = {{").unwrap();
+    writeln!(out, "      update-props = {{").unwrap();
+    writeln!(out, "        target.object = \"{}\"", escape(target_name)).unwrap();
+    writeln!(out, "      }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "  }}").unwrap();
+    writeln!(out, "]").unwrap();
+    Some(out)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,339 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::pipewire_wrapper::{GlobalObjectDump, PipewireState};
+
+//
+// experimental read-only sharing of the registry snapshot over tcp
+// (newline delimited: token from the viewer, "ok" from the host, then json snapshots from the host)
+//
+// the token only guards against accidental connections since the stream is not encrypted,
+// so use it on a trusted network or through an ssh tunnel
+//
+
+// only this machine (e.g. the end of `ssh -L 7878:localhost:7878 host`) unless another
+// address is given
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+// connections still sending their token (more are closed right away)
+const MAX_PENDING_HANDSHAKES: usize = 8;
+// token and "ok" lines are short
+const MAX_HANDSHAKE_LINE: u64 = 256;
+// snapshots are written on the host loop, so a stalled viewer is dropped rather than waited for
+const VIEWER_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+// the viewer connects from the ui thread
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Redaction {
+    // application.process.* e.g. user, host and binary of clients
+    pub process: bool,
+    // media.name, media.title etc. e.g. what is being played
+    pub media: bool,
+    // serial numbers, bluetooth addresses, bus paths
+    pub device: bool,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            process: true,
+            media: true,
+            device: false,
+        }
+    }
+}
+
+impl Redaction {
+    fn is_redacted(&self, key: &str) -> bool {
+        const PROCESS: [&str; 2] = ["application.process.", "pipewire.sec."];
+        const MEDIA: [&str; 6] = [
+            "media.name",
+            "media.title",
+            "media.artist",
+            "media.album",
+            "media.filename",
+            "media.icon",
+        ];
+        const DEVICE: [&str; 6] = [
+            "device.serial",
+            "device.bus-path",
+            "device.string",
+            "api.bluez5.address",
+            "api.alsa.card.longname",
+            "object.path",
+        ];
+        (self.process && PROCESS.iter().any(|prefix| key.starts_with(prefix)))
+            || (self.media && MEDIA.contains(&key))
+            || (self.device && DEVICE.contains(&key))
+    }
+
+    fn apply(&self, mut objects: Vec<GlobalObjectDump>) -> Vec<GlobalObjectDump> {
+        for object in &mut objects {
+            object.props.retain(|key, _| !self.is_redacted(key));
+        }
+        objects
+    }
+}
+
+//
+// host
+//
+
+pub struct ShareHost {
+    pub token: String,
+    pub address: SocketAddr,
+    pub num_viewers: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl ShareHost {
+    pub fn new(
+        address: &str,
+        redaction: Redaction,
+        state: Arc<Mutex<PipewireState>>,
+    ) -> Result<Self> {
+        let listener =
+            TcpListener::bind(address).with_context(|| format!("failed to bind {}", address))?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let token = generate_token();
+        let num_viewers: Arc<AtomicUsize> = Default::default();
+        let stop: Arc<AtomicBool> = Default::default();
+
+        let token_ = token.clone();
+        let num_viewers_ = num_viewers.clone();
+        let stop_ = stop.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let mut viewers: Vec<TcpStream> = vec![];
+            let mut last_snapshot: Option<Instant> = None;
+            // viewers which sent the token
+            let (accepted_sender, accepted_receiver) = mpsc::channel();
+            let num_pending: Arc<AtomicUsize> = Default::default();
+            while !stop_.load(Ordering::Relaxed) {
+                while let Ok((stream, peer)) = listener.accept() {
+                    if num_pending.load(Ordering::Relaxed) >= MAX_PENDING_HANDSHAKES {
+                        tracing::warn!("share viewer rejected {} (too many handshakes)", peer);
+                        continue;
+                    }
+                    num_pending.fetch_add(1, Ordering::Relaxed);
+                    // on its own thread so that a silent connection doesn't hold up the others
+                    let (token, accepted_sender, num_pending) =
+                        (token_.clone(), accepted_sender.clone(), num_pending.clone());
+                    std::thread::spawn(move || {
+                        match handshake_host(&stream, &token) {
+                            Ok(()) => {
+                                tracing::info!("share viewer connected {}", peer);
+                                accepted_sender.send(stream).ok();
+                            }
+                            Err(e) => tracing::warn!("share viewer rejected {} ({:#})", peer, e),
+                        }
+                        num_pending.fetch_sub(1, Ordering::Relaxed);
+                    });
+                }
+                while let Ok(stream) = accepted_receiver.try_recv() {
+                    viewers.push(stream);
+                    // send the current snapshot right away
+                    last_snapshot = None;
+                }
+                if !viewers.is_empty()
+                    && last_snapshot.map_or(true, |time| time.elapsed() >= SNAPSHOT_INTERVAL)
+                {
+                    last_snapshot = Some(Instant::now());
+                    let snapshot = redaction.apply(state.lock().unwrap().dump());
+                    match serde_json::to_string(&snapshot) {
+                        Ok(json) => viewers.retain_mut(|stream| {
+                            writeln!(stream, "{}", json)
+                                .and_then(|_| stream.flush())
+                                .is_ok()
+                        }),
+                        Err(e) => tracing::error!("json serialization failed ({})", e),
+                    }
+                }
+                num_viewers_.store(viewers.len(), Ordering::Relaxed);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        Ok(Self {
+            token,
+            address,
+            num_viewers,
+            stop,
+            thread_handle: Some(thread_handle),
+        })
+    }
+}
+
+impl Drop for ShareHost {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread_handle) = self.thread_handle.take() {
+            thread_handle.join().ok();
+        }
+    }
+}
+
+fn handshake_host(stream: &TcpStream, token: &str) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    stream.set_write_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(stream.take(MAX_HANDSHAKE_LINE)).read_line(&mut line)?;
+    if !constant_time_eq(line.trim().as_bytes(), token.as_bytes()) {
+        bail!("invalid token");
+    }
+    let mut stream = stream;
+    writeln!(stream, "ok")?;
+    // don't let a stalled viewer block the others
+    stream.set_write_timeout(Some(VIEWER_WRITE_TIMEOUT))?;
+    Ok(())
+}
+
+// without an early return on the first different byte, which would tell how much of a guess
+// was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// not cryptographically strong but unpredictable enough for pairing
+fn generate_token() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    format!("{:016x}", hasher.finish())
+}
+
+//
+// viewer
+//
+
+#[derive(Default)]
+pub struct RemoteSnapshot {
+    pub objects: Vec<GlobalObjectDump>,
+    pub received: Option<Instant>,
+    // connection closed or rejected
+    pub error: Option<String>,
+}
+
+pub struct ShareViewer {
+    pub snapshot: Arc<Mutex<RemoteSnapshot>>,
+    stream: TcpStream,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl ShareViewer {
+    pub fn new(address: &str, token: &str) -> Result<Self> {
+        let mut stream =
+            connect(address).with_context(|| format!("failed to connect {}", address))?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        writeln!(stream, "{}", token.trim())?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader
+            .by_ref()
+            .take(MAX_HANDSHAKE_LINE)
+            .read_line(&mut line)?;
+        if line.trim() != "ok" {
+            bail!("rejected by the host (invalid token?)");
+        }
+        stream.set_read_timeout(None)?;
+
+        let snapshot: Arc<Mutex<RemoteSnapshot>> = Default::default();
+        let snapshot_ = snapshot.clone();
+        let thread_handle = std::thread::spawn(move || {
+            // ends when the stream is shut down
+            for line in reader.lines() {
+                let result = line
+                    .map_err(anyhow::Error::from)
+                    .and_then(|line| Ok(serde_json::from_str::<Vec<GlobalObjectDump>>(&line)?));
+                let mut snapshot = snapshot_.lock().unwrap();
+                match result {
+                    Ok(objects) => {
+                        snapshot.objects = objects;
+                        snapshot.received = Some(Instant::now());
+                    }
+                    Err(e) => {
+                        snapshot.error = Some(format!("{:#}", e));
+                        return;
+                    }
+                }
+            }
+            snapshot_.lock().unwrap().error = Some("disconnected".to_owned());
+        });
+
+        Ok(Self {
+            snapshot,
+            stream,
+            thread_handle: Some(thread_handle),
+        })
+    }
+}
+
+// first of the resolved addresses which accepts the connection in time
+fn connect(address: &str) -> Result<TcpStream> {
+    let mut error = None;
+    for address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => error = Some(e),
+        }
+    }
+    match error {
+        Some(e) => Err(e.into()),
+        None => bail!("no address resolved"),
+    }
+}
+
+impl Drop for ShareViewer {
+    fn drop(&mut self) {
+        self.stream.shutdown(Shutdown::Both).ok();
+        if let Some(thread_handle) = self.thread_handle.take() {
+            thread_handle.join().ok();
+        }
+    }
+}
+
+// "<node>:<port> -> <node>:<port>" of remote links
+pub fn remote_links(objects: &[GlobalObjectDump]) -> Vec<(String, String)> {
+    let find = |id: &str| objects.iter().find(|object| object.id.to_string() == id);
+    let port_label = |port_id: &str| -> Option<String> {
+        let port = find(port_id)?;
+        let node = find(port.props.get("node.id")?)?;
+        let node_name = node
+            .props
+            .get("node.description")
+            .or_else(|| node.props.get("node.name"))?;
+        Some(format!("{}:{}", node_name, port.props.get("port.name")?))
+    };
+    objects
+        .iter()
+        .filter_map(|object| {
+            let output = port_label(object.props.get("link.output.port")?)?;
+            let input = port_label(object.props.get("link.input.port")?)?;
+            Some((output, input))
+        })
+        .collect()
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Instant,
+};
+
+use pipewire::{prelude::ReadableDict, registry::GlobalObject, types::ObjectType};
+
+use crate::{
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    props::PropStore,
+};
+
+//
+// registry snapshots and their differences (nodes and links only)
+//
+
+pub struct Snapshot {
+    pub taken: Instant,
+    // keyed by global id
+    objects: BTreeMap<u32, SnapshotObject>,
+}
+
+#[derive(Clone)]
+struct SnapshotObject {
+    type_: &'static str,
+    serial: Option<String>,
+    label: String,
+    props: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    pub fn take(state: &PipewireState) -> Self {
+        let objects = state
+            .global_objects
+            .values()
+            .filter(|object| matches!(object.type_, ObjectType::Node | ObjectType::Link))
+            .map(|object| {
+                let snapshot_object = SnapshotObject {
+                    type_: if object.type_ == ObjectType::Node {
+                        "Node"
+                    } else {
+                        "Link"
+                    },
+                    serial: PipewireObject::get_serial(object).map(str::to_owned),
+                    label: label(state, object),
+                    props: object.props.as_ref().map_or_else(BTreeMap::new, |props| {
+                        props
+                            .iter()
+                            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                            .collect()
+                    }),
+                };
+                (object.id, snapshot_object)
+            })
+            .collect();
+        Self {
+            taken: Instant::now(),
+            objects,
+        }
+    }
+
+    pub fn num_objects(&self) -> usize {
+        self.objects.len()
+    }
+}
+
+// "node label" or "output node:port → input node:port"
+pub fn label(state: &PipewireState, object: &GlobalObject<PropStore>) -> String {
+    let node_label = |id: u32| {
+        state
+            .global_objects
+            .get(&id)
+            .and_then(PipewireObject::get_node_label)
+            .unwrap_or("--")
+            .to_owned()
+    };
+    let port_label = |id: u32| match state.global_objects.get(&id) {
+        Some(port) => format!(
+            "{}:{}",
+            PipewireObject::get_parent_node_id(port).map_or_else(|| "--".to_owned(), node_label),
+            PipewireObject::get_port_name(port).unwrap_or("--")
+        ),
+        None => "--".to_owned(),
+    };
+    match PipewireObject::get_link_ports(object) {
+        Some((output_port, input_port)) => {
+            format!("{} → {}", port_label(output_port), port_label(input_port))
+        }
+        None => node_label(object.id),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+pub struct Change {
+    pub id: u32,
+    pub type_: &'static str,
+    pub kind: ChangeKind,
+    pub label: String,
+    // (key, before, after) for `ChangeKind::Changed`
+    pub props: Vec<(String, Option<String>, Option<String>)>,
+}
+
+// an id reused by a new object (different `object.serial`) counts as removed and added
+pub fn diff(before: &Snapshot, after: &Snapshot) -> Vec<Change> {
+    let mut changes = vec![];
+    let change = |id: u32, object: &SnapshotObject, kind: ChangeKind| Change {
+        id,
+        type_: object.type_,
+        kind,
+        label: object.label.clone(),
+        props: vec![],
+    };
+    for (&id, old) in &before.objects {
+        match after.objects.get(&id) {
+            Some(new) if new.serial == old.serial && new.type_ == old.type_ => {
+                let keys: BTreeSet<&String> = old.props.keys().chain(new.props.keys()).collect();
+                let props: Vec<_> = keys
+                    .into_iter()
+                    .filter(|key| old.props.get(*key) != new.props.get(*key))
+                    .map(|key| {
+                        (
+                            key.clone(),
+                            old.props.get(key).cloned(),
+                            new.props.get(key).cloned(),
+                        )
+                    })
+                    .collect();
+                if !props.is_empty() {
+                    changes.push(Change {
+                        props,
+                        ..change(id, new, ChangeKind::Changed)
+                    });
+                }
+            }
+            _ => changes.push(change(id, old, ChangeKind::Removed)),
+        }
+    }
+    for (&id, new) in &after.objects {
+        let existed = before.objects.get(&id).map_or(false, |old| {
+            old.serial == new.serial && old.type_ == new.type_
+        });
+        if !existed {
+            changes.push(change(id, new, ChangeKind::Added));
+        }
+    }
+    // nodes before links, then by id
+    changes.sort_by_key(|change| (change.type_ == "Link", change.id));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(type_: &'static str, serial: &str, props: &[(&str, &str)]) -> SnapshotObject {
+        SnapshotObject {
+            type_,
+            serial: Some(serial.to_owned()),
+            label: format!("{} {}", type_, serial),
+            props: props
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+
+    fn snapshot(objects: &[(u32, SnapshotObject)]) -> Snapshot {
+        Snapshot {
+            taken: Instant::now(),
+            objects: objects.iter().cloned().collect(),
+        }
+    }
+
+    fn kinds(changes: &[Change]) -> Vec<(u32, &'static str, ChangeKind)> {
+        changes
+            .iter()
+            .map(|change| (change.id, change.type_, change.kind))
+            .collect()
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let before = snapshot(&[
+            (30, object("Node", "100", &[])),
+            (40, object("Link", "101", &[])),
+        ]);
+        let after = snapshot(&[
+            (31, object("Node", "102", &[])),
+            (40, object("Link", "101", &[])),
+            (41, object("Link", "103", &[])),
+        ]);
+        assert_eq!(
+            kinds(&diff(&before, &after)),
+            [
+                (30, "Node", ChangeKind::Removed),
+                (31, "Node", ChangeKind::Added),
+                (41, "Link", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(
+            kinds(&diff(&after, &before)),
+            [
+                (30, "Node", ChangeKind::Added),
+                (31, "Node", ChangeKind::Removed),
+                (41, "Link", ChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn reused_id_is_removed_and_added() {
+        let before = snapshot(&[(40, object("Link", "101", &[]))]);
+        let after = snapshot(&[(40, object("Link", "105", &[]))]);
+        let changes = diff(&before, &after);
+        assert_eq!(
+            kinds(&changes),
+            [
+                (40, "Link", ChangeKind::Removed),
+                (40, "Link", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(changes[0].label, "Link 101");
+        assert_eq!(changes[1].label, "Link 105");
+    }
+
+    #[test]
+    fn changed_props() {
+        let before = snapshot(&[
+            (30, object("Node", "100", &[("a", "1"), ("b", "2")])),
+            (40, object("Link", "101", &[("link.passive", "true")])),
+        ]);
+        let after = snapshot(&[
+            (
+                30,
+                object("Node", "100", &[("a", "1"), ("b", "3"), ("c", "4")]),
+            ),
+            (40, object("Link", "101", &[])),
+        ]);
+        let changes = diff(&before, &after);
+        assert_eq!(
+            kinds(&changes),
+            [
+                (30, "Node", ChangeKind::Changed),
+                (40, "Link", ChangeKind::Changed),
+            ]
+        );
+        let some = |value: &str| Some(value.to_owned());
+        assert_eq!(
+            changes[0].props,
+            [
+                ("b".to_owned(), some("2"), some("3")),
+                ("c".to_owned(), None, some("4")),
+            ]
+        );
+        assert_eq!(
+            changes[1].props,
+            [("link.passive".to_owned(), some("true"), None)]
+        );
+        // unchanged objects aren't listed
+        assert!(diff(&after, &after).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use eframe::egui;
+
+use crate::app::PipewireGraphApp;
+#[cfg(feature = "tray")]
+use crate::tray::{self, Tray};
+
+//
+// local graph and graphs of remote pipewire instances side by side
+//
+
+// `remote.name` of the open remote tabs (reconnected on startup)
+const PERSISTENCE_KEY: &str = concat!(env!("CARGO_PKG_NAME"), "_remotes");
+
+/// [`PipewireGraphApp`] per connection, one tab each.
+///
+/// Every tab runs its own pipewire thread. Tabs in the background keep handling their messages
+/// so they are up to date when switched to.
+pub struct PipewireGraphTabs {
+    local: PipewireGraphApp,
+    remotes: Vec<PipewireGraphApp>,
+    // 0 for `local` and i + 1 for `remotes[i]`
+    active: usize,
+    new_remote: String,
+    // shown while the local graph's "Minimize to tray" is on
+    #[cfg(feature = "tray")]
+    tray: Option<Tray>,
+}
+
+impl PipewireGraphTabs {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let local = PipewireGraphApp::new(cc);
+        let remotes: Vec<String> = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, PERSISTENCE_KEY))
+            .unwrap_or_default();
+        let remotes = remotes
+            .into_iter()
+            .map(|remote| local.open_remote(&cc.egui_ctx, remote))
+            .collect();
+        Self {
+            local,
+            remotes,
+            active: 0,
+            new_remote: String::new(),
+            #[cfg(feature = "tray")]
+            tray: None,
+        }
+    }
+
+    /// See [`PipewireGraphApp::record_session`] (only the local graph is recorded).
+    pub fn record_session(&mut self, path: String) {
+        self.local.record_session(path);
+    }
+
+    #[cfg(feature = "tray")]
+    fn update_tray(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let enabled = self.local.minimize_to_tray();
+        if enabled != self.tray.is_some() {
+            self.tray = enabled.then(|| Tray::spawn(ctx));
+        }
+        let new_nodes = self.local.take_new_nodes();
+        if let Some(tray) = &mut self.tray {
+            tray.update(frame);
+            // the graph shows them while the window is open
+            if tray.hidden {
+                for (summary, label) in new_nodes {
+                    tray::notify(summary, &label);
+                }
+            }
+        }
+    }
+
+    fn show_tab_bar(&mut self, ctx: &egui::Context) {
+        let mut close = None;
+        let mut open = None;
+        egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.active, 0, "Local");
+                for (i, tab) in self.remotes.iter().enumerate() {
+                    if !tab.initial_sync_done() {
+                        ui.spinner();
+                    }
+                    ui.selectable_value(&mut self.active, i + 1, tab.remote().unwrap_or("--"));
+                    if ui.small_button("✖").on_hover_text("disconnect").clicked() {
+                        close = Some(i);
+                    }
+                }
+                ui.separator();
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.new_remote)
+                        .hint_text("tcp:host:4656")
+                        .desired_width(150.0),
+                );
+                let remote = self.new_remote.trim();
+                let submit = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                let clicked = ui
+                    .add_enabled(!remote.is_empty(), egui::Button::new("Connect"))
+                    .on_hover_text(
+                        "remote.name of another pipewire instance \
+                         (a socket name or tcp:host:port of a server with TCP enabled)",
+                    )
+                    .clicked();
+                if (submit || clicked) && !remote.is_empty() {
+                    open = Some(remote.to_owned());
+                }
+            });
+        });
+        if let Some(remote) = open {
+            let tab = self.local.open_remote(ctx, remote);
+            self.remotes.push(tab);
+            self.active = self.remotes.len();
+            self.new_remote.clear();
+        }
+        if let Some(i) = close {
+            let mut tab = self.remotes.remove(i);
+            eframe::App::on_exit(&mut tab, None);
+            if self.active > i {
+                self.active -= 1;
+            }
+        }
+    }
+}
+
+impl eframe::App for PipewireGraphTabs {
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        for tab in &mut self.remotes {
+            tab.on_exit(gl);
+        }
+        self.local.on_exit(gl);
+    }
+
+    fn on_close_event(&mut self) -> bool {
+        #[cfg(feature = "tray")]
+        if let Some(tray) = &mut self.tray {
+            return tray.close_requested();
+        }
+        true
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.local.save(storage);
+        let remotes: Vec<&str> = self.remotes.iter().filter_map(|tab| tab.remote()).collect();
+        eframe::set_value(storage, PERSISTENCE_KEY, &remotes);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        #[cfg(feature = "tray")]
+        self.update_tray(ctx, frame);
+        self.show_tab_bar(ctx);
+        let native_pixels_per_point = frame.info().native_pixels_per_point.unwrap_or(1.0);
+        for (i, tab) in std::iter::once(&mut self.local)
+            .chain(&mut self.remotes)
+            .enumerate()
+        {
+            if i == self.active {
+                tab.show(ctx, native_pixels_per_point);
+            } else {
+                tab.poll();
+            }
+        }
+    }
+}
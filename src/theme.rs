@@ -0,0 +1,96 @@
+use eframe::egui::{self, Color32, Visuals};
+use serde::{Deserialize, Serialize};
+
+//
+// user adjustable look persisted in `ExtraState`
+//
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeMode {
+    Dark,
+    Light,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortColors {
+    pub audio: [u8; 3],
+    pub midi: [u8; 3],
+    pub video: [u8; 3],
+    pub other: [u8; 3],
+}
+
+impl PortColors {
+    pub const DEFAULT: PortColors = PortColors {
+        audio: [80, 180, 110],
+        midi: [200, 90, 90],
+        video: [80, 140, 210],
+        other: [150, 150, 150],
+    };
+
+    // Okabe-Ito palette which stays distinguishable with color vision deficiencies
+    pub const COLORBLIND: PortColors = PortColors {
+        audio: [0, 114, 178],
+        midi: [230, 159, 0],
+        video: [0, 158, 115],
+        other: [204, 121, 167],
+    };
+}
+
+impl Default for PortColors {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    // selection and hyperlinks (`None` keeps egui's blue)
+    pub accent: Option<[u8; 3]>,
+    pub port_colors: PortColors,
+    // of single channel wires (see `GraphNodeData::wire_style`)
+    pub wire_width: f32,
+    // relative to egui's default text sizes
+    pub font_scale: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::Dark,
+            accent: None,
+            port_colors: PortColors::DEFAULT,
+            wire_width: 5.0,
+            font_scale: 1.0,
+        }
+    }
+}
+
+pub fn rgb(color: [u8; 3]) -> Color32 {
+    Color32::from_rgb(color[0], color[1], color[2])
+}
+
+impl Theme {
+    pub fn apply(&self, ctx: &egui::Context) {
+        let mut visuals = match self.mode {
+            ThemeMode::Dark => Visuals::dark(),
+            ThemeMode::Light => Visuals::light(),
+        };
+        if let Some(accent) = self.accent {
+            let accent = rgb(accent);
+            visuals.selection.bg_fill = accent;
+            visuals.selection.stroke.color = visuals.strong_text_color();
+            visuals.hyperlink_color = accent;
+        }
+        let mut style = egui::Style {
+            visuals,
+            ..egui::Style::default()
+        };
+        for font_id in style.text_styles.values_mut() {
+            font_id.size *= self.font_scale;
+        }
+        ctx.set_style(style);
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{
+    channel::ChannelMessage,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+};
+
+//
+// timestamped registry events (to correlate glitches with devices or clients coming and going)
+//
+
+const MAX_EVENTS: usize = 10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Added,
+    // announced again (e.g. permission change)
+    Updated,
+    Removed,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Added => "added",
+            Action::Updated => "updated",
+            Action::Removed => "removed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimelineEvent {
+    // unix time in milliseconds (comparable with `journalctl -o short-unix`)
+    pub time_ms: u64,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub id: u32,
+    pub name: String,
+    pub action: Action,
+}
+
+#[derive(Default)]
+pub struct Timeline {
+    pub events: VecDeque<TimelineEvent>,
+    pub paused: bool,
+    // (type, name) of announced globals since they are gone from `PipewireState` on removal
+    known: HashMap<u32, (String, String)>,
+}
+
+impl Timeline {
+    // call for every registry message in order (like `PipewireRegistry::event`)
+    pub fn record(&mut self, state: &PipewireState, message: &ChannelMessage) {
+        let (id, action, (type_, name)) = match *message {
+            ChannelMessage::PipewireRegistryGlobal(id) => {
+                let object = match state.global_objects.get(&id) {
+                    Some(object) => object,
+                    None => return,
+                };
+                let entry = (
+                    PipewireObject::get_type_name(object).to_owned(),
+                    PipewireObject::get_name(object)
+                        .map_or_else(String::new, |(_key, value)| value.to_owned()),
+                );
+                let action = match self.known.insert(id, entry.clone()) {
+                    Some(_) => Action::Updated,
+                    None => Action::Added,
+                };
+                (id, action, entry)
+            }
+            ChannelMessage::PipewireRegistryGlobalRemove(id) => (
+                id,
+                Action::Removed,
+                self.known.remove(&id).unwrap_or_default(),
+            ),
+            _ => return,
+        };
+        if self.paused {
+            return;
+        }
+        if self.events.len() >= MAX_EVENTS {
+            self.events.pop_front();
+        }
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.events.push_back(TimelineEvent {
+            time_ms,
+            type_,
+            id,
+            name,
+            action,
+        });
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("time_ms,type,id,name,action\n");
+        for event in &self.events {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                event.time_ms,
+                escape_csv(&event.type_),
+                event.id,
+                escape_csv(&event.name),
+                event.action.as_str()
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.events)
+    }
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains(|c| matches!(c, ',' | '"' | '\n')) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+// "HH:MM:SS.mmm" (UTC)
+pub fn format_time(time_ms: u64) -> String {
+    let ms = time_ms % 1000;
+    let seconds = time_ms / 1000 % 86400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        ms
+    )
+}
@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+// short lived notifications rendered at the top-right corner
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+struct Toast {
+    text: String,
+    level: ToastLevel,
+    created: Instant,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    // failed operations (kept longer so that the reason can be read)
+    Error,
+}
+
+impl ToastLevel {
+    fn duration(self) -> Duration {
+        match self {
+            ToastLevel::Info | ToastLevel::Warning => Duration::from_secs(5),
+            ToastLevel::Error => Duration::from_secs(15),
+        }
+    }
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: ToastLevel, text: impl Into<String>) {
+        self.toasts.push(Toast {
+            text: text.into(),
+            level,
+            created: Instant::now(),
+        });
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Info, text);
+    }
+
+    pub fn warning(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Warning, text);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Error, text);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.created.elapsed() < toast.level.duration());
+        if self.toasts.is_empty() {
+            return;
+        }
+        let mut dismissed = None;
+        egui::Area::new("toasts")
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 40.0))
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let color = match toast.level {
+                        ToastLevel::Info => ui.visuals().text_color(),
+                        ToastLevel::Warning => ui.visuals().warn_fg_color,
+                        ToastLevel::Error => ui.visuals().error_fg_color,
+                    };
+                    let response = egui::Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_max_width(300.0);
+                            ui.colored_label(color, &toast.text);
+                        })
+                        .response;
+                    if response.interact(egui::Sense::click()).clicked() {
+                        dismissed = Some(i);
+                    }
+                }
+            });
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+}
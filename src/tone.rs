@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+//
+// tone generator node (see `ChannelMessage::ToneGeneratorCreate`)
+//
+
+pub const RATE: u32 = 48000;
+pub const MIN_FREQUENCY: u32 = 20;
+pub const MAX_FREQUENCY: u32 = 20000;
+pub const MIN_LEVEL_DB: i32 = -60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Waveform {
+    Sine,
+    WhiteNoise,
+}
+
+impl Waveform {
+    pub const ALL: [Waveform; 2] = [Waveform::Sine, Waveform::WhiteNoise];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Waveform::Sine => "Sine",
+            Waveform::WhiteNoise => "White noise",
+        }
+    }
+}
+
+// whole Hz and dB are enough for testing (and keep `GraphNodeResponse` comparable)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToneSettings {
+    pub waveform: Waveform,
+    pub frequency: u32,
+    pub level_db: i32,
+}
+
+impl Default for ToneSettings {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Sine,
+            frequency: 440,
+            level_db: -20,
+        }
+    }
+}
+
+// mono f32 at `RATE` (user data of the playback stream)
+pub struct ToneGenerator {
+    phase: f32,
+    // xorshift32 state
+    noise: u32,
+}
+
+impl Default for ToneGenerator {
+    fn default() -> Self {
+        Self {
+            phase: 0.0,
+            noise: 0x9e37_79b9,
+        }
+    }
+}
+
+impl ToneGenerator {
+    pub fn next_sample(&mut self, settings: &ToneSettings) -> f32 {
+        let amplitude = 10f32.powf(settings.level_db.min(0) as f32 / 20.0);
+        match settings.waveform {
+            Waveform::Sine => {
+                let frequency = settings.frequency.clamp(MIN_FREQUENCY, MAX_FREQUENCY);
+                let sample = amplitude * self.phase.sin();
+                let step = frequency as f32 / RATE as f32 * std::f32::consts::TAU;
+                self.phase = (self.phase + step) % std::f32::consts::TAU;
+                sample
+            }
+            Waveform::WhiteNoise => {
+                self.noise ^= self.noise << 13;
+                self.noise ^= self.noise >> 17;
+                self.noise ^= self.noise << 5;
+                amplitude * (self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0)
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use pipewire::spa::Direction;
+
+use crate::{
+    pipewire_registry::RegistryEvent,
+    pipewire_wrapper::{PipewireObject, PipewireState},
+    props::PropKey,
+};
+
+//
+// typed model of the nodes, ports and links maintained from the registry events
+// (the editor graph is rebuilt from it, and the monitor mode and the feedback check query it)
+//
+// only their relationships, props like object.serial or node.name are read from the registry
+// state (which is where the editor graph takes them from too)
+//
+
+pub struct Node {
+    pub id: u32,
+    pub device: Option<u32>,
+    pub client: Option<u32>,
+}
+
+pub struct Port {
+    pub id: u32,
+    pub node_id: u32,
+    pub direction: Option<Direction>,
+}
+
+pub struct Link {
+    pub id: u32,
+    pub output_port: u32,
+    pub input_port: u32,
+}
+
+// globals may be announced in any order, so relationships are resolved on lookup
+// (e.g. a port whose node is not known yet)
+#[derive(Default)]
+pub struct Topology {
+    nodes: BTreeMap<u32, Node>,
+    ports: BTreeMap<u32, Port>,
+    links: BTreeMap<u32, Link>,
+}
+
+impl Topology {
+    pub fn apply(&mut self, state: &PipewireState, event: &RegistryEvent) {
+        match *event {
+            RegistryEvent::NodeAdded(id) => self.update_node(state, id),
+            RegistryEvent::PortAdded { id, node_id } => {
+                let object = state.global_objects.get(&id);
+                let direction = match object {
+                    Some(object) if PipewireObject::is_input(object) => Some(Direction::Input),
+                    Some(object) if PipewireObject::is_output(object) => Some(Direction::Output),
+                    _ => None,
+                };
+                let port = Port {
+                    id,
+                    node_id,
+                    direction,
+                };
+                self.ports.insert(id, port);
+            }
+            RegistryEvent::LinkAdded {
+                id,
+                output_port,
+                input_port,
+            } => {
+                let link = Link {
+                    id,
+                    output_port,
+                    input_port,
+                };
+                self.links.insert(id, link);
+            }
+            RegistryEvent::NodeRemoved(id) => {
+                self.nodes.remove(&id);
+            }
+            RegistryEvent::PortRemoved(id) => {
+                self.ports.remove(&id);
+            }
+            RegistryEvent::LinkRemoved(id) => {
+                self.links.remove(&id);
+            }
+            RegistryEvent::GlobalAdded(..) | RegistryEvent::GlobalRemoved(_) => {}
+        }
+    }
+
+    // (re-)read the node from the registry state (e.g. after its props changed)
+    pub fn update_node(&mut self, state: &PipewireState, id: u32) {
+        let object = match state.global_objects.get(&id) {
+            Some(object) => object,
+            None => return,
+        };
+        let node = Node {
+            id,
+            device: PipewireObject::get_device_id(object),
+            client: object
+                .props
+                .as_ref()
+                .and_then(|props| props.get_key(PropKey::ClientId)?.parse().ok()),
+        };
+        self.nodes.insert(id, node);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    pub fn ports(&self) -> impl Iterator<Item = &Port> {
+        self.ports.values()
+    }
+
+    pub fn links(&self) -> impl Iterator<Item = &Link> {
+        self.links.values()
+    }
+
+    pub fn node(&self, id: u32) -> Option<&Node> {
+        self.nodes.get(&id)
+    }
+
+    pub fn port(&self, id: u32) -> Option<&Port> {
+        self.ports.get(&id)
+    }
+
+    pub fn ports_of(&self, node_id: u32) -> impl Iterator<Item = &Port> {
+        self.ports
+            .values()
+            .filter(move |port| port.node_id == node_id)
+    }
+
+    pub fn links_of(&self, port_id: u32) -> impl Iterator<Item = &Link> {
+        self.links
+            .values()
+            .filter(move |link| link.output_port == port_id || link.input_port == port_id)
+    }
+
+    // (output node, input node) once both ports are known
+    pub fn link_nodes(&self, link: &Link) -> Option<(u32, u32)> {
+        Some((
+            self.ports.get(&link.output_port)?.node_id,
+            self.ports.get(&link.input_port)?.node_id,
+        ))
+    }
+
+    // node -> nodes linked into it (`upstream`) or out of it
+    fn adjacency(&self, upstream: bool) -> BTreeMap<u32, BTreeSet<u32>> {
+        let mut adjacency: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+        for (output_node, input_node) in self.links().filter_map(|link| self.link_nodes(link)) {
+            let (from, to) = if upstream {
+                (input_node, output_node)
+            } else {
+                (output_node, input_node)
+            };
+            adjacency.entry(from).or_default().insert(to);
+        }
+        adjacency
+    }
+
+    // the node and every node routed into it directly or through other nodes
+    pub fn upstream(&self, node_id: u32) -> BTreeSet<u32> {
+        let upstream = self.adjacency(true);
+        let mut visited = BTreeSet::from([node_id]);
+        let mut queue = vec![node_id];
+        while let Some(node_id) = queue.pop() {
+            for &other in upstream.get(&node_id).into_iter().flatten() {
+                if visited.insert(other) {
+                    queue.push(other);
+                }
+            }
+        }
+        visited
+    }
+
+    // shortest chain of nodes routed from `from` to `to` (both included)
+    pub fn path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        let downstream = self.adjacency(false);
+        let mut previous = BTreeMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(node_id) = queue.pop_front() {
+            if node_id == to {
+                let mut path = vec![to];
+                let mut node_id = to;
+                while node_id != from {
+                    node_id = previous[&node_id];
+                    path.push(node_id);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &other in downstream.get(&node_id).into_iter().flatten() {
+                previous.entry(other).or_insert_with(|| {
+                    queue.push_back(other);
+                    node_id
+                });
+            }
+        }
+        None
+    }
+
+    // nodes of the loop which a new link from `output_node` to `input_node` would close
+    // (e.g. a sink monitor routed back into the same sink)
+    pub fn feedback_loop(&self, output_node: u32, input_node: u32) -> Option<Vec<u32>> {
+        self.path(input_node, output_node)
+    }
+}
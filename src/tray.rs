@@ -0,0 +1,165 @@
+use std::{
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use eframe::egui;
+
+//
+// system tray icon (StatusNotifierItem) for the "Minimize to tray" option
+//
+// closing the window hides it while the icon is shown, so the pipewire thread keeps running
+// (clicking the icon brings the window back and "Quit" of its menu really closes it)
+//
+
+// set by the tray thread and applied to the native window on the next frame
+#[derive(Default)]
+struct Requests {
+    show: AtomicBool,
+    quit: AtomicBool,
+    // a StatusNotifierWatcher shows the icon (e.g. not on GNOME without an extension)
+    online: AtomicBool,
+}
+
+struct TrayIcon {
+    requests: Arc<Requests>,
+    ctx: egui::Context,
+}
+
+impl TrayIcon {
+    fn request(&self, flag: &AtomicBool) {
+        flag.store(true, Ordering::Relaxed);
+        self.ctx.request_repaint();
+    }
+}
+
+impl ksni::Tray for TrayIcon {
+    fn id(&self) -> String {
+        env!("CARGO_PKG_NAME").to_owned()
+    }
+
+    fn title(&self) -> String {
+        env!("CARGO_PKG_NAME").to_owned()
+    }
+
+    fn icon_name(&self) -> String {
+        "audio-card".to_owned()
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.request(&self.requests.show);
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        use ksni::menu::StandardItem;
+        vec![
+            StandardItem {
+                label: "Show graph".to_owned(),
+                activate: Box::new(|tray: &mut Self| tray.request(&tray.requests.show)),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Quit".to_owned(),
+                icon_name: "application-exit".to_owned(),
+                activate: Box::new(|tray: &mut Self| tray.request(&tray.requests.quit)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+
+    fn watcher_online(&self) {
+        self.requests.online.store(true, Ordering::Relaxed);
+    }
+
+    // keep the service to show up again once a watcher starts
+    fn watcher_offine(&self) -> bool {
+        self.requests.online.store(false, Ordering::Relaxed);
+        true
+    }
+}
+
+/// Tray icon of the window, removed when dropped.
+pub struct Tray {
+    handle: ksni::Handle<TrayIcon>,
+    requests: Arc<Requests>,
+    /// The window is hidden in the tray.
+    pub hidden: bool,
+    hide_pending: bool,
+    quitting: bool,
+}
+
+impl Tray {
+    pub fn spawn(ctx: &egui::Context) -> Self {
+        let requests = Arc::<Requests>::default();
+        let service = ksni::TrayService::new(TrayIcon {
+            requests: requests.clone(),
+            ctx: ctx.clone(),
+        });
+        let handle = service.handle();
+        // e.g. no session bus
+        std::thread::spawn(move || {
+            if let Err(e) = service.run() {
+                tracing::error!("tray icon ({})", e);
+            }
+        });
+        Self {
+            handle,
+            requests,
+            hidden: false,
+            hide_pending: false,
+            quitting: false,
+        }
+    }
+
+    /// For `eframe::App::on_close_event`: hide the window instead unless there is no icon to
+    /// bring it back or "Quit" was chosen.
+    pub fn close_requested(&mut self) -> bool {
+        if self.quitting || !self.requests.online.load(Ordering::Relaxed) {
+            return true;
+        }
+        self.hide_pending = true;
+        false
+    }
+
+    /// Applies the tray menu and `close_requested` to the native window (call it every frame).
+    pub fn update(&mut self, frame: &mut eframe::Frame) {
+        if self.requests.quit.swap(false, Ordering::Relaxed) {
+            self.quitting = true;
+            frame.close();
+        }
+        if self.requests.show.swap(false, Ordering::Relaxed) {
+            self.hidden = false;
+            frame.set_visible(true);
+        }
+        if std::mem::take(&mut self.hide_pending) {
+            self.hidden = true;
+            frame.set_visible(false);
+        }
+    }
+}
+
+impl Drop for Tray {
+    fn drop(&mut self) {
+        self.handle.shutdown();
+    }
+}
+
+// desktop notification e.g. ("New device", "Built-in Audio Analog Stereo")
+pub fn notify(summary: &str, body: &str) {
+    let mut command = Command::new("notify-send");
+    command
+        .args(["--app-name", env!("CARGO_PKG_NAME"), summary, body])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // not waited for on the ui thread
+    std::thread::spawn(move || {
+        if let Err(e) = command.status() {
+            tracing::warn!("notify-send ({})", e);
+        }
+    });
+}
@@ -0,0 +1,110 @@
+use std::{
+    io::ErrorKind,
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+};
+
+//
+// guess why connecting to pipewire failed and what the user can do about it
+// (the connect error itself only says "failed to connect" without errno)
+//
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+    Container,
+}
+
+impl Sandbox {
+    pub fn detect() -> Option<Self> {
+        if Path::new("/.flatpak-info").exists() || std::env::var_os("FLATPAK_ID").is_some() {
+            Some(Sandbox::Flatpak)
+        } else if std::env::var_os("SNAP").is_some() {
+            Some(Sandbox::Snap)
+        } else if Path::new("/.dockerenv").exists()
+            || Path::new("/run/.containerenv").exists()
+            || std::env::var_os("container").is_some()
+        {
+            Some(Sandbox::Container)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sandbox::Flatpak => "Flatpak",
+            Sandbox::Snap => "Snap",
+            Sandbox::Container => "a container",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SocketStatus {
+    // neither PIPEWIRE_RUNTIME_DIR nor XDG_RUNTIME_DIR (e.g. started via sudo or a system service)
+    NoRuntimeDir,
+    Missing(PathBuf),
+    PermissionDenied(PathBuf),
+    // reachable, so the failure is elsewhere (e.g. the daemon refused the client)
+    Reachable(PathBuf),
+    Other(PathBuf, String),
+}
+
+// same lookup as libpipewire's default remote
+pub fn socket_path() -> Option<PathBuf> {
+    let runtime_dir =
+        std::env::var_os("PIPEWIRE_RUNTIME_DIR").or_else(|| std::env::var_os("XDG_RUNTIME_DIR"))?;
+    let remote = std::env::var("PIPEWIRE_REMOTE").unwrap_or_else(|_| "pipewire-0".to_owned());
+    Some(Path::new(&runtime_dir).join(remote))
+}
+
+pub fn probe_socket() -> SocketStatus {
+    let path = match socket_path() {
+        Some(path) => path,
+        None => return SocketStatus::NoRuntimeDir,
+    };
+    match UnixStream::connect(&path) {
+        Ok(_) => SocketStatus::Reachable(path),
+        Err(e) if e.kind() == ErrorKind::NotFound => SocketStatus::Missing(path),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => SocketStatus::PermissionDenied(path),
+        Err(e) => SocketStatus::Other(path, e.to_string()),
+    }
+}
+
+// actionable steps for the connection error screen (most likely first)
+pub fn remediation(sandbox: Option<Sandbox>, socket: &SocketStatus) -> Vec<String> {
+    let mut steps = vec![];
+    match (sandbox, socket) {
+        (_, SocketStatus::NoRuntimeDir) => {
+            steps.push("XDG_RUNTIME_DIR is not set. Run the app as your desktop user from your session (not via sudo or a system service).".to_owned());
+        }
+        (Some(Sandbox::Flatpak), SocketStatus::Missing(_) | SocketStatus::PermissionDenied(_)) => {
+            steps.push("The Flatpak sandbox has no access to the pipewire socket. Grant it with `flatpak override --user --filesystem=xdg-run/pipewire-0 <app id>` (the camera portal alone only exposes video nodes).".to_owned());
+        }
+        (Some(Sandbox::Snap), SocketStatus::Missing(_) | SocketStatus::PermissionDenied(_)) => {
+            steps.push("Connect the audio interfaces of the snap: `snap connect <snap>:audio-playback` and `snap connect <snap>:audio-record`.".to_owned());
+        }
+        (Some(Sandbox::Container), SocketStatus::Missing(path)) => {
+            steps.push(format!("Mount the host socket into the container (e.g. `-v $XDG_RUNTIME_DIR/pipewire-0:{}`) and set XDG_RUNTIME_DIR accordingly.", path.display()));
+        }
+        (_, SocketStatus::Missing(path)) => {
+            steps.push(format!(
+                "No pipewire socket at {}. Start the daemon with `systemctl --user start pipewire.socket pipewire wireplumber`.",
+                path.display()
+            ));
+        }
+        (_, SocketStatus::PermissionDenied(path)) => {
+            steps.push(format!("Access to {} is denied. Check that it is owned by the user running this app (`ls -l {}`).", path.display(), path.display()));
+        }
+        (_, SocketStatus::Reachable(path)) => {
+            steps.push(format!("{} is reachable but the connection was refused. Check the daemon log with `journalctl --user -u pipewire` (e.g. access module restrictions).", path.display()));
+        }
+        (_, SocketStatus::Other(path, e)) => {
+            steps.push(format!("Failed to open {} ({}).", path.display(), e));
+        }
+    }
+    steps.push("Check that `pw-cli info 0` works from the same environment.".to_owned());
+    steps
+}
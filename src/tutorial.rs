@@ -0,0 +1,96 @@
+//
+// guided walkthrough on a sandbox graph of fake nodes (nothing is sent to pipewire)
+//
+
+pub struct FakeNode {
+    pub label: &'static str,
+    pub media_class: &'static str,
+    pub inputs: &'static [&'static str],
+    pub outputs: &'static [&'static str],
+}
+
+pub const FAKE_NODES: [FakeNode; 4] = [
+    FakeNode {
+        label: "Music Player",
+        media_class: "Stream/Output/Audio",
+        inputs: &[],
+        outputs: &["output_FL", "output_FR"],
+    },
+    FakeNode {
+        label: "Microphone",
+        media_class: "Audio/Source",
+        inputs: &[],
+        outputs: &["capture_MONO"],
+    },
+    FakeNode {
+        label: "Speakers",
+        media_class: "Audio/Sink",
+        inputs: &["playback_FL", "playback_FR"],
+        outputs: &[],
+    },
+    FakeNode {
+        label: "Recorder",
+        media_class: "Stream/Input/Audio",
+        inputs: &["input_MONO"],
+        outputs: &[],
+    },
+];
+
+// ("<node>:<port>", "<node>:<port>") from output to input
+pub type FakeConnection = (String, String);
+
+const LEFT: (&str, &str) = ("Music Player:output_FL", "Speakers:playback_FL");
+const RIGHT: (&str, &str) = ("Music Player:output_FR", "Speakers:playback_FR");
+const RECORD: (&str, &str) = ("Microphone:capture_MONO", "Recorder:input_MONO");
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Step {
+    ConnectLeft,
+    ConnectRight,
+    ConnectRecorder,
+    Disconnect,
+    Done,
+}
+
+impl Step {
+    pub const FIRST: Step = Step::ConnectLeft;
+
+    pub fn instruction(self) -> &'static str {
+        match self {
+            Step::ConnectLeft => "Links carry audio between ports. Drag from \"output_FL\" of Music Player to \"playback_FL\" of Speakers to play the left channel (like choosing an output device in pavucontrol).",
+            Step::ConnectRight => "Now connect \"output_FR\" to \"playback_FR\" for the right channel. Each channel is a separate link.",
+            Step::ConnectRecorder => "Inputs work the same way: connect Microphone's \"capture_MONO\" to Recorder's \"input_MONO\".",
+            Step::Disconnect => "To remove a link, drag its end away from the input port and release it on empty space. Disconnect the left channel of Music Player.",
+            Step::Done => "That's it. In live mode these actions create and destroy real links of your session (Ctrl+Z undoes them).",
+        }
+    }
+
+    // advance once the goal of the step is met
+    pub fn next(self, connections: &[FakeConnection]) -> Step {
+        let has = |(output, input): (&str, &str)| {
+            connections
+                .iter()
+                .any(|connection| connection.0 == output && connection.1 == input)
+        };
+        match self {
+            Step::ConnectLeft if has(LEFT) => Step::ConnectRight,
+            Step::ConnectRight if has(RIGHT) => Step::ConnectRecorder,
+            Step::ConnectRecorder if has(RECORD) => Step::Disconnect,
+            Step::Disconnect if !has(LEFT) => Step::Done,
+            step => step,
+        }
+    }
+
+    // (current, total) for the progress label
+    pub fn progress(self) -> (usize, usize) {
+        let steps = [
+            Step::ConnectLeft,
+            Step::ConnectRight,
+            Step::ConnectRecorder,
+            Step::Disconnect,
+            Step::Done,
+        ];
+        let index = steps.iter().position(|&step| step == self).unwrap_or(0);
+        (index + 1, steps.len())
+    }
+}
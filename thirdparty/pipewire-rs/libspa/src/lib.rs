@@ -17,6 +17,7 @@ pub mod pod;
 pub mod utils;
 pub use direction::*;
 pub mod flags;
+pub use spa_sys as sys;
 
 /// prelude module re-exporing all the traits providing public API.
 pub mod prelude {
@@ -128,7 +128,7 @@ fn monitor(remote: Option<String>) -> Result<()> {
                             .info(|info| {
                                 dbg!(info);
                             })
-                            .param(|seq, id, index, next| {
+                            .param(|seq, id, index, next, _param| {
                                 dbg!((seq, id, index, next));
                             })
                             .register();
@@ -0,0 +1,187 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use libc::c_void;
+use std::mem;
+use std::pin::Pin;
+
+use crate::{
+    proxy::{Listener, Proxy, ProxyT},
+    registry::Permission,
+    types::ObjectType,
+};
+use spa::spa_interface_call_method;
+
+#[derive(Debug)]
+pub struct Client {
+    proxy: Proxy,
+}
+
+impl ProxyT for Client {
+    fn type_() -> ObjectType {
+        ObjectType::Client
+    }
+
+    fn upcast(self) -> Proxy {
+        self.proxy
+    }
+
+    fn upcast_ref(&self) -> &Proxy {
+        &self.proxy
+    }
+
+    unsafe fn from_proxy_unchecked(proxy: Proxy) -> Self
+    where
+        Self: Sized,
+    {
+        Self { proxy }
+    }
+}
+
+impl Client {
+    // TODO: add non-local version when we'll bind pw_thread_loop_start()
+    #[must_use]
+    pub fn add_listener_local(&self) -> ClientListenerLocalBuilder {
+        ClientListenerLocalBuilder {
+            client: self,
+            cbs: ListenerLocalCallbacks::default(),
+        }
+    }
+
+    /// Request the permissions of the client, reported through the `permissions` event.
+    pub fn get_permissions(&self, index: u32, num: u32) {
+        unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr(),
+                pw_sys::pw_client_methods,
+                get_permissions,
+                index,
+                num
+            );
+        }
+    }
+
+    /// Update the permissions of the client on the given global ids.
+    ///
+    /// `u32::MAX` (`PW_ID_ANY`) as id sets the default permissions of the client.
+    pub fn update_permissions(&self, permissions: &[(u32, Permission)]) {
+        let permissions: Vec<pw_sys::pw_permission> = permissions
+            .iter()
+            .map(|(id, permissions)| pw_sys::pw_permission {
+                id: *id,
+                permissions: permissions.bits(),
+            })
+            .collect();
+        unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr(),
+                pw_sys::pw_client_methods,
+                update_permissions,
+                permissions.len() as u32,
+                permissions.as_ptr()
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct ListenerLocalCallbacks {
+    #[allow(clippy::type_complexity)]
+    permissions: Option<Box<dyn Fn(u32, &[(u32, Permission)])>>,
+}
+
+pub struct ClientListenerLocalBuilder<'a> {
+    client: &'a Client,
+    cbs: ListenerLocalCallbacks,
+}
+
+pub struct ClientListener {
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_client_events>>,
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    #[allow(dead_code)]
+    data: Box<ListenerLocalCallbacks>,
+}
+
+impl Listener for ClientListener {}
+
+impl Drop for ClientListener {
+    fn drop(&mut self) {
+        spa::hook::remove(*self.listener);
+    }
+}
+
+impl<'a> ClientListenerLocalBuilder<'a> {
+    /// Called with the start index and the (global id, permissions) entries.
+    #[must_use]
+    pub fn permissions<F>(mut self, permissions: F) -> Self
+    where
+        F: Fn(u32, &[(u32, Permission)]) + 'static,
+    {
+        self.cbs.permissions = Some(Box::new(permissions));
+        self
+    }
+
+    #[must_use]
+    pub fn register(self) -> ClientListener {
+        unsafe extern "C" fn client_events_permissions(
+            data: *mut c_void,
+            index: u32,
+            n_permissions: u32,
+            permissions: *const pw_sys::pw_permission,
+        ) {
+            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+            let permissions = if permissions.is_null() {
+                vec![]
+            } else {
+                std::slice::from_raw_parts(permissions, n_permissions as usize)
+                    .iter()
+                    .map(|permission| {
+                        (
+                            permission.id,
+                            Permission::from_bits_truncate(permission.permissions),
+                        )
+                    })
+                    .collect()
+            };
+            callbacks.permissions.as_ref().unwrap()(index, &permissions);
+        }
+
+        let e = unsafe {
+            let mut e: Pin<Box<pw_sys::pw_client_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_CLIENT_EVENTS;
+
+            if self.cbs.permissions.is_some() {
+                e.permissions = Some(client_events_permissions);
+            }
+
+            e
+        };
+
+        let (listener, data) = unsafe {
+            let client = &self.client.proxy.as_ptr();
+
+            let data = Box::into_raw(Box::new(self.cbs));
+            let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
+
+            spa_interface_call_method!(
+                client,
+                pw_sys::pw_client_methods,
+                add_listener,
+                listener_ptr.cast(),
+                e.as_ref().get_ref(),
+                data as *mut _
+            );
+
+            (listener, Box::from_raw(data))
+        };
+
+        ClientListener {
+            events: e,
+            listener,
+            data,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::{os::unix::prelude::RawFd, ptr};
+use std::{ffi::CString, os::unix::prelude::RawFd, ptr};
 
 use crate::core_::Core;
 use crate::error::Error;
 use crate::loop_::LoopRef;
+use crate::module::ImplModule;
 use crate::properties::Properties;
 
 #[derive(Debug)]
@@ -63,6 +64,35 @@ impl<T: AsRef<LoopRef> + Clone> Context<T> {
             Ok(Core::from_ptr(ptr))
         }
     }
+
+    /// Load a module (e.g. `libpipewire-module-zeroconf-discover`) into this context.
+    ///
+    /// The module runs in this process and is unloaded when the returned value is dropped.
+    pub fn load_module(
+        &self,
+        name: &str,
+        args: Option<&str>,
+        properties: Option<Properties>,
+    ) -> Result<ImplModule, Error> {
+        let name = CString::new(name).map_err(|_| Error::CreationFailed)?;
+        let args = args
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| Error::CreationFailed)?;
+        let properties = properties.map_or(ptr::null_mut(), |p| p.into_raw());
+
+        unsafe {
+            let module = pw_sys::pw_context_load_module(
+                self.as_ptr(),
+                name.as_ptr(),
+                args.as_ref().map_or(ptr::null(), |args| args.as_ptr()),
+                properties,
+            );
+            let ptr = ptr::NonNull::new(module).ok_or(Error::CreationFailed)?;
+
+            Ok(ImplModule::from_ptr(ptr))
+        }
+    }
 }
 
 impl<T: AsRef<LoopRef> + Clone> Drop for Context<T> {
@@ -121,15 +121,18 @@ pub use spa;
 
 pub mod buffer;
 pub mod channel;
+pub mod client;
 pub mod constants;
 mod context;
 mod core_;
+pub mod device;
 mod error;
 pub mod keys;
 pub mod link;
 mod loop_;
 mod main_loop;
 pub mod metadata;
+pub mod module;
 pub mod node;
 pub mod port;
 mod properties;
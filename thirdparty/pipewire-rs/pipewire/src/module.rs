@@ -0,0 +1,267 @@
+use std::{
+    cell::Cell,
+    ffi::{c_void, CStr},
+    fmt, mem,
+    pin::Pin,
+    ptr,
+};
+
+use bitflags::bitflags;
+use spa::dict::ForeignDict;
+use spa::spa_interface_call_method;
+
+use crate::{
+    proxy::{Listener, Proxy, ProxyT},
+    types::ObjectType,
+};
+
+#[derive(Debug)]
+pub struct Module {
+    proxy: Proxy,
+}
+
+impl ProxyT for Module {
+    fn type_() -> ObjectType {
+        ObjectType::Module
+    }
+
+    fn upcast(self) -> Proxy {
+        self.proxy
+    }
+
+    fn upcast_ref(&self) -> &Proxy {
+        &self.proxy
+    }
+
+    unsafe fn from_proxy_unchecked(proxy: Proxy) -> Self
+    where
+        Self: Sized,
+    {
+        Self { proxy }
+    }
+}
+
+impl Module {
+    #[must_use]
+    pub fn add_listener_local(&self) -> ModuleListenerLocalBuilder {
+        ModuleListenerLocalBuilder {
+            module: self,
+            cbs: ListenerLocalCallbacks::default(),
+        }
+    }
+}
+
+pub struct ModuleListener {
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_module_events>>,
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    #[allow(dead_code)]
+    data: Box<ListenerLocalCallbacks>,
+}
+
+impl Listener for ModuleListener {}
+
+impl Drop for ModuleListener {
+    fn drop(&mut self) {
+        spa::hook::remove(*self.listener);
+    }
+}
+
+#[derive(Default)]
+struct ListenerLocalCallbacks {
+    #[allow(clippy::type_complexity)]
+    info: Option<Box<dyn Fn(&ModuleInfo)>>,
+}
+
+pub struct ModuleListenerLocalBuilder<'module> {
+    module: &'module Module,
+    cbs: ListenerLocalCallbacks,
+}
+
+impl<'a> ModuleListenerLocalBuilder<'a> {
+    #[must_use]
+    pub fn info<F>(mut self, info: F) -> Self
+    where
+        F: Fn(&ModuleInfo) + 'static,
+    {
+        self.cbs.info = Some(Box::new(info));
+        self
+    }
+
+    #[must_use]
+    pub fn register(self) -> ModuleListener {
+        unsafe extern "C" fn module_events_info(
+            data: *mut c_void,
+            info: *const pw_sys::pw_module_info,
+        ) {
+            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+            let info = ModuleInfo::new(ptr::NonNull::new(info as *mut _).expect("info is NULL"));
+            callbacks.info.as_ref().unwrap()(&info);
+        }
+
+        let e = unsafe {
+            let mut e: Pin<Box<pw_sys::pw_module_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_MODULE_EVENTS;
+
+            if self.cbs.info.is_some() {
+                e.info = Some(module_events_info);
+            }
+
+            e
+        };
+
+        let (listener, data) = unsafe {
+            let module = &self.module.proxy.as_ptr();
+
+            let data = Box::into_raw(Box::new(self.cbs));
+            let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
+
+            spa_interface_call_method!(
+                module,
+                pw_sys::pw_module_methods,
+                add_listener,
+                listener_ptr.cast(),
+                e.as_ref().get_ref(),
+                data as *mut _
+            );
+
+            (listener, Box::from_raw(data))
+        };
+
+        ModuleListener {
+            events: e,
+            listener,
+            data,
+        }
+    }
+}
+
+pub struct ModuleInfo {
+    ptr: ptr::NonNull<pw_sys::pw_module_info>,
+    props: Option<ForeignDict>,
+}
+
+impl ModuleInfo {
+    fn new(ptr: ptr::NonNull<pw_sys::pw_module_info>) -> Self {
+        let props_ptr = unsafe { ptr.as_ref().props };
+        let props = ptr::NonNull::new(props_ptr).map(|ptr| unsafe { ForeignDict::from_ptr(ptr) });
+        Self { ptr, props }
+    }
+
+    pub fn id(&self) -> u32 {
+        unsafe { self.ptr.as_ref().id }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        unsafe { optional_str(self.ptr.as_ref().name) }
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        unsafe { optional_str(self.ptr.as_ref().filename) }
+    }
+
+    pub fn args(&self) -> Option<&str> {
+        unsafe { optional_str(self.ptr.as_ref().args) }
+    }
+
+    pub fn change_mask(&self) -> ModuleChangeMask {
+        let mask = unsafe { self.ptr.as_ref().change_mask };
+        ModuleChangeMask::from_bits_truncate(mask)
+    }
+
+    pub fn props(&self) -> Option<&ForeignDict> {
+        self.props.as_ref()
+    }
+}
+
+unsafe fn optional_str<'a>(ptr: *const std::os::raw::c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        None
+    } else {
+        CStr::from_ptr(ptr).to_str().ok()
+    }
+}
+
+bitflags! {
+    pub struct ModuleChangeMask: u64 {
+        const PROPS = pw_sys::PW_MODULE_CHANGE_MASK_PROPS as u64;
+    }
+}
+
+impl fmt::Debug for ModuleInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModuleInfo")
+            .field("id", &self.id())
+            .field("name", &self.name())
+            .field("filename", &self.filename())
+            .field("args", &self.args())
+            .field("change-mask", &self.change_mask())
+            .field("props", &self.props())
+            .finish()
+    }
+}
+
+/// Module loaded into the local context (unloaded when dropped).
+///
+/// Some modules unload themselves (e.g. a tunnel whose server went away), see `is_destroyed`.
+pub struct ImplModule {
+    ptr: ptr::NonNull<pw_sys::pw_impl_module>,
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_impl_module_events>>,
+    #[allow(dead_code)]
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    destroyed: Box<Cell<bool>>,
+}
+
+impl ImplModule {
+    pub(crate) fn from_ptr(ptr: ptr::NonNull<pw_sys::pw_impl_module>) -> Self {
+        unsafe extern "C" fn impl_module_events_destroy(data: *mut c_void) {
+            (data as *const Cell<bool>).as_ref().unwrap().set(true);
+        }
+
+        unsafe {
+            let mut events: Pin<Box<pw_sys::pw_impl_module_events>> = Box::pin(mem::zeroed());
+            events.version = pw_sys::PW_VERSION_IMPL_MODULE_EVENTS;
+            events.destroy = Some(impl_module_events_destroy);
+
+            let destroyed = Box::new(Cell::new(false));
+            let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            pw_sys::pw_impl_module_add_listener(
+                ptr.as_ptr(),
+                listener.as_mut().get_unchecked_mut(),
+                events.as_ref().get_ref(),
+                destroyed.as_ref() as *const Cell<bool> as *mut c_void,
+            );
+            Self {
+                ptr,
+                events,
+                listener,
+                destroyed,
+            }
+        }
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.destroyed.get()
+    }
+}
+
+impl fmt::Debug for ImplModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImplModule")
+            .field("destroyed", &self.is_destroyed())
+            .finish()
+    }
+}
+
+impl Drop for ImplModule {
+    fn drop(&mut self) {
+        // the listener goes together with the module
+        if !self.is_destroyed() {
+            unsafe { pw_sys::pw_impl_module_destroy(self.ptr.as_ptr()) }
+        }
+    }
+}
@@ -49,6 +49,38 @@ impl Node {
             cbs: ListenerLocalCallbacks::default(),
         }
     }
+
+    /// Set a param from its serialized pod bytes (see [`spa::pod::serialize::PodSerializer`]).
+    pub fn set_param(&self, id: u32, flags: u32, pod: &[u8]) {
+        unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr(),
+                pw_sys::pw_node_methods,
+                set_param,
+                id,
+                flags,
+                pod.as_ptr() as *const spa_sys::spa_pod
+            );
+        }
+    }
+
+    /// Enumerate params of the given `id` (e.g. `SPA_PARAM_Props`).
+    ///
+    /// Results are delivered to the `param` listener callback.
+    pub fn enum_params(&self, seq: i32, id: u32, start: u32, num: u32) {
+        unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr(),
+                pw_sys::pw_node_methods,
+                enum_params,
+                seq,
+                id,
+                start,
+                num,
+                ptr::null()
+            );
+        }
+    }
 }
 
 #[derive(Default)]
@@ -56,7 +88,7 @@ struct ListenerLocalCallbacks {
     #[allow(clippy::type_complexity)]
     info: Option<Box<dyn Fn(&NodeInfo)>>,
     #[allow(clippy::type_complexity)]
-    param: Option<Box<dyn Fn(i32, u32, u32, u32)>>, // TODO: add params
+    param: Option<Box<dyn Fn(i32, u32, u32, u32, &[u8])>>,
 }
 
 pub struct NodeListenerLocalBuilder<'a> {
@@ -187,10 +219,12 @@ impl<'a> NodeListenerLocalBuilder<'a> {
         self
     }
 
+    /// The last argument is the raw pod (header included) which can be parsed with
+    /// [`spa::pod::deserialize::PodDeserializer`].
     #[must_use]
     pub fn param<F>(mut self, param: F) -> Self
     where
-        F: Fn(i32, u32, u32, u32) + 'static,
+        F: Fn(i32, u32, u32, u32, &[u8]) + 'static,
     {
         self.cbs.param = Some(Box::new(param));
         self
@@ -214,10 +248,17 @@ impl<'a> NodeListenerLocalBuilder<'a> {
             id: u32,
             index: u32,
             next: u32,
-            _param: *const spa_sys::spa_pod,
+            param: *const spa_sys::spa_pod,
         ) {
             let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.param.as_ref().unwrap()(seq, id, index, next);
+            let param = match param.as_ref() {
+                Some(pod) => std::slice::from_raw_parts(
+                    param as *const u8,
+                    mem::size_of::<spa_sys::spa_pod>() + pod.size as usize,
+                ),
+                None => &[],
+            };
+            callbacks.param.as_ref().unwrap()(seq, id, index, next, param);
         }
 
         let e = unsafe {